@@ -23,9 +23,9 @@ impl rubot::Game for Chess {
                 Outcome::Draw => 0,
                 Outcome::Decisive { winner } => {
                     if winner == player {
-                        std::i32::MAX
+                        i32::MAX
                     } else {
-                        std::i32::MIN
+                        i32::MIN
                     }
                 }
             }
@@ -54,12 +54,12 @@ impl rubot::Game for Chess {
 
     #[inline]
     fn is_upper_bound(&self, fitness: Self::Fitness, _: Self::Player) -> bool {
-        fitness == std::i32::MAX
+        fitness == i32::MAX
     }
 
     #[inline]
     fn is_lower_bound(&self, fitness: Self::Fitness, _: Self::Player) -> bool {
-        fitness == std::i32::MIN
+        fitness == i32::MIN
     }
 }
 
@@ -0,0 +1,46 @@
+use rubot::{Bot, Depth};
+
+#[path = "chess.rs"]
+mod chess;
+
+use chess::Chess;
+use shakmaty::Setup;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::Instant;
+
+fn hash(chess: &Chess) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    chess.0.board().hash(&mut hasher);
+    chess.0.turn().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Searches `fen` to a fixed depth with a growing amount of threads, which all share a single transposition table.
+///
+/// As the table is split into independently locked shards, additional threads should reduce the time
+/// until the current thread finished its search, as long as there are enough cores.
+fn search_threads(name: &str, fen: &str) {
+    let chess = Chess::from_fen(fen);
+    for &threads in &[1, 2, 4, 8] {
+        let mut bot = Bot::new(chess.0.turn())
+            .with_transposition_table(1 << 16, hash)
+            .with_threads(threads);
+        let start = Instant::now();
+        bot.select(&chess, Depth(4));
+        println!("{:050} {:7} {:10.3?}", name, threads, start.elapsed());
+    }
+    println!();
+}
+
+fn main() {
+    println!("{:050} {:>7} {:>10}", "game", "threads", "time");
+    search_threads(
+        "Initial position",
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    );
+    search_threads(
+        "Italian game",
+        "r1bqk1nr/pppp1ppp/2n5/2b1p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4",
+    );
+}
@@ -14,7 +14,7 @@
 //! ```
 //!
 //! - starting at any unit of the active player, he can take the next horizontal or vertical 2 blocks, as long as both are currently empty
-//!     (`*` marks possible spots for the unit of `x` marked with `#`)
+//!   (`*` marks possible spots for the unit of `x` marked with `#`)
 //!
 //! ```txt
 //! ...x..
@@ -26,8 +26,8 @@
 //! ```
 //!
 //! - or an empty horizontal or vertical block which is 3 steps away,
-//!     as long as the path to the block does not contain a unit owned by this player
-//!     (`*` marks possible spots for the unit of `x` marked with `#`)
+//!   as long as the path to the block does not contain a unit owned by this player
+//!   (`*` marks possible spots for the unit of `x` marked with `#`)
 //!
 //! ```txt
 //! ...o...
@@ -39,7 +39,7 @@
 //! ...*...
 //! ```
 //! - once both players are unable to do anything, the player with more units wins
-//!     (`x` wins this game with 7 to 5)
+//!   (`x` wins this game with 7 to 5)
 //!
 //! ```txt
 //! oxxo
@@ -289,39 +289,39 @@ fn prompt_move() -> Move {
 fn parse_move(input: &str) -> Result<Move, InvalidMove> {
     match input.len() {
         2 => {
-            let col = match input.chars().nth(0).unwrap() {
-                v if v >= 'a' && v <= 'z' => v as usize - 'a' as usize,
-                v if v >= 'A' && v <= 'Z' => v as usize - 'A' as usize,
+            let col = match input.chars().next().unwrap() {
+                v @ 'a'..='z' => v as usize - 'a' as usize,
+                v @ 'A'..='Z' => v as usize - 'A' as usize,
                 _ => return Err(InvalidMove(input.to_string())),
             };
 
             let row = match input.chars().nth(1).unwrap() {
-                v if v >= '1' && v <= '9' => v as usize - '1' as usize,
+                v @ '1'..='9' => v as usize - '1' as usize,
                 _ => return Err(InvalidMove(input.to_string())),
             };
 
             Ok(Move::Long(row, col))
         }
         4 => {
-            let b = match input.chars().nth(0).unwrap() {
-                v if v >= 'a' && v <= 'z' => v as usize - 'a' as usize,
-                v if v >= 'A' && v <= 'Z' => v as usize - 'A' as usize,
+            let b = match input.chars().next().unwrap() {
+                v @ 'a'..='z' => v as usize - 'a' as usize,
+                v @ 'A'..='Z' => v as usize - 'A' as usize,
                 _ => return Err(InvalidMove(input.to_string())),
             };
 
             let a = match input.chars().nth(1).unwrap() {
-                v if v >= '1' && v <= '9' => v as usize - '1' as usize,
+                v @ '1'..='9' => v as usize - '1' as usize,
                 _ => return Err(InvalidMove(input.to_string())),
             };
 
             let y = match input.chars().nth(2).unwrap() {
-                v if v >= 'a' && v <= 'z' => v as usize - 'a' as usize,
-                v if v >= 'A' && v <= 'Z' => v as usize - 'A' as usize,
+                v @ 'a'..='z' => v as usize - 'a' as usize,
+                v @ 'A'..='Z' => v as usize - 'A' as usize,
                 _ => return Err(InvalidMove(input.to_string())),
             };
 
             let x = match input.chars().nth(3).unwrap() {
-                v if v >= '1' && v <= '9' => v as usize - '1' as usize,
+                v @ '1'..='9' => v as usize - '1' as usize,
                 _ => return Err(InvalidMove(input.to_string())),
             };
 
@@ -317,7 +317,7 @@ impl rubot::Game for Game {
         if !self.is_finished() {
             for x in 0..3 {
                 for y in 0..3 {
-                    if let None = self.tiles()[x][y] {
+                    if self.tiles()[x][y].is_none() {
                         actions.push(Action(x, y));
                     }
                 }
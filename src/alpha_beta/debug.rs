@@ -2,7 +2,7 @@ use crate::Game;
 
 use std::fmt::{self, Debug};
 
-use super::{Action, Branch, Ctxt, MiniMax, State};
+use super::{Action, Branch, Ctxt, Iteration, MiniMax, Report, SearchNode, SearchTree, State};
 
 impl<T: Game> Debug for Action<T>
 where
@@ -12,7 +12,72 @@ where
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Action")
             .field("fitness", &self.fitness)
-            .field("path", &self.fitness)
+            .field("path", &self.path)
+            .field("proven", &self.proven)
+            .field("iterations", &self.iterations)
+            .field("reply", &self.reply)
+            .finish()
+    }
+}
+
+impl<T: Game> Debug for Iteration<T>
+where
+    T::Action: Debug,
+    T::Fitness: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Iteration")
+            .field("depth", &self.depth)
+            .field("action", &self.action)
+            .field("fitness", &self.fitness)
+            .field("nodes", &self.nodes)
+            .field("elapsed", &self.elapsed)
+            .finish()
+    }
+}
+
+impl<T: Game> Debug for Report<T>
+where
+    T::Action: Debug,
+    T::Fitness: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Report")
+            .field("action", &self.action)
+            .field("fitness", &self.fitness)
+            .field("depth", &self.depth)
+            .field("iterations", &self.iterations)
+            .field("stats", &self.stats)
+            .finish()
+    }
+}
+
+impl<T: Game> Debug for SearchTree<T>
+where
+    T::Action: Debug,
+    T::Fitness: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SearchTree")
+            .field("nodes", &self.nodes)
+            .field("truncated", &self.truncated)
+            .finish()
+    }
+}
+
+impl<T: Game> Debug for SearchNode<T>
+where
+    T::Action: Debug,
+    T::Fitness: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SearchNode")
+            .field("parent", &self.parent)
+            .field("action", &self.action)
+            .field("depth", &self.depth)
+            .field("fitness", &self.fitness)
+            .field("cutoff", &self.cutoff)
+            .field("pruned", &self.pruned)
             .finish()
     }
 }
@@ -46,6 +111,7 @@ where
             MiniMax::Terminated(path, branch) => write!(f, "Terminated({:?}, {:?})", path, branch),
             MiniMax::Open(path, branch) => write!(f, "Open({:?}, {:?})", path, branch),
             MiniMax::DeadEnd => write!(f, "DeadEnd"),
+            MiniMax::Repetition => write!(f, "Repetition"),
         }
     }
 }
@@ -0,0 +1,61 @@
+use crate::Game;
+
+use std::fmt::Debug;
+use std::io::{self, Write};
+
+use super::{Bound, SearchTree};
+
+impl<T: Game> SearchTree<T>
+where
+    T::Action: Debug,
+    T::Fitness: Debug,
+{
+    /// Writes this tree in the DOT format of [Graphviz][gv].
+    ///
+    /// Each edge is labeled with its action and the fitness of the reached state, where `>=` and `<=`
+    /// mark bounds. Edges to states which caused a cutoff are red, while skipped actions are shown
+    /// as dashed edges labeled with the responsible pruning rule.
+    ///
+    /// [gv]: https://graphviz.org
+    pub fn write_dot<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        writeln!(out, "digraph {{")?;
+        writeln!(out, "    node [shape=point];")?;
+        writeln!(out, "    n0 [shape=circle, label=\"\"];")?;
+        for (idx, node) in self.nodes.iter().enumerate() {
+            if let (Some(parent), Some(action)) = (node.parent, &node.action) {
+                let fitness = match node.fitness {
+                    Some(Bound::Exact(fitness)) => format!("= {:?}", fitness),
+                    Some(Bound::Lower(fitness)) => format!(">= {:?}", fitness),
+                    Some(Bound::Upper(fitness)) => format!("<= {:?}", fitness),
+                    None => String::from("?"),
+                };
+                let label = escape(&format!("{:?}: {}", action, fitness));
+                let color = if node.cutoff { ", color=red" } else { "" };
+                writeln!(
+                    out,
+                    "    n{} -> n{} [label=\"{}\"{}];",
+                    parent, idx, label, color
+                )?;
+            }
+
+            for (i, &(rule, skipped)) in node.pruned.iter().enumerate() {
+                writeln!(
+                    out,
+                    "    p{}_{} [shape=plaintext, label=\"{} skipped\"];",
+                    idx, i, skipped
+                )?;
+                writeln!(
+                    out,
+                    "    n{} -> p{}_{} [style=dashed, label=\"{:?}\"];",
+                    idx, idx, i, rule
+                )?;
+            }
+        }
+        writeln!(out, "}}")
+    }
+}
+
+/// Escapes `label` to be used inside of a quoted DOT string.
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
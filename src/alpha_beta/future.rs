@@ -0,0 +1,77 @@
+//! The future returned by `Bot::select_async`.
+use super::SearchHandle;
+use crate::Game;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+/// Whether the search finished, together with the waker of the task waiting for it.
+pub(super) type Completion = Arc<Mutex<(bool, Option<Waker>)>>;
+
+/// Marks the search as finished once dropped, which also happens in case the search panicked.
+pub(super) struct Finished(pub(super) Completion);
+
+impl Drop for Finished {
+    fn drop(&mut self) {
+        let waker = {
+            let mut completion = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+            completion.0 = true;
+            completion.1.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// A future resolving to the action selected by a search running on another thread,
+/// returned by [`Bot::select_async`][async].
+///
+/// Dropping this future cancels the search.
+///
+/// [async]: struct.Bot.html#method.select_async
+pub struct SelectFuture<T: Game> {
+    handle: Option<SearchHandle<T>>,
+}
+
+impl<T: Game> SelectFuture<T> {
+    pub(super) fn new(handle: SearchHandle<T>) -> Self {
+        Self {
+            handle: Some(handle),
+        }
+    }
+}
+
+impl<T: Game> Future for SelectFuture<T> {
+    type Output = Option<T::Action>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let handle = self
+            .handle
+            .as_ref()
+            .expect("`SelectFuture` polled after completion");
+        {
+            let mut completion = handle
+                .completion
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            if !completion.0 {
+                completion.1 = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+        }
+
+        // The search already finished, so `join` only waits for the thread to exit.
+        Poll::Ready(self.handle.take().unwrap().join())
+    }
+}
+
+impl<T: Game> Drop for SelectFuture<T> {
+    fn drop(&mut self) {
+        if let Some(handle) = &self.handle {
+            handle.cancel();
+        }
+    }
+}
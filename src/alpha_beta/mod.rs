@@ -1,16 +1,37 @@
 //! A deterministic game bot using alpha beta pruning.
-use crate::{Game, IntoRunCondition, RunCondition};
+use crate::rng::XorShiftRng;
+use crate::{
+    And, Clock, Depth, Game, GameBot, IntoRunCondition, Margin, OpponentModel, Policy,
+    RunCondition, SelectError, StopFlag, SystemClock, Tablebase, UnmakeGame, Veto,
+};
 
 use tapir::Tap;
 
 use std::cmp::{self, Reverse};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
 
 mod debug;
+mod dot;
+#[cfg(feature = "async")]
+mod future;
+#[cfg(feature = "serde")]
+mod persist;
+
+#[cfg(feature = "async")]
+pub use future::SelectFuture;
 
 /// A game bot which analyses its moves using [alpha beta pruning][ab_wiki] with [iterative deepening][id]. In case [`select`][sel] terminates
 /// before `condition` returned true, the result is always the best possible move. While this bot caches some data
-/// during computation, it does not require a lot of memory and will not store anything between different [`select`][sel] calls.
+/// during computation, it does not require a lot of memory. Between different [`select`][sel] calls, it only keeps the
+/// [statistics][stats] and [search tree][tree] of the last search, a [hint][hint] for the next one and,
+/// when using [`with_persistent_table`][keep], the transposition table.
 ///
 /// This bot requires [`Game`][game] to be implemented for your game.
 ///
@@ -47,665 +68,5518 @@ mod debug;
 /// ```
 /// Please visit [`select`][sel] for a simple example.
 ///
+/// # Serialization
+///
+/// With the `serde` feature, `Bot` implements `Serialize` and `Deserialize` in case both `Game::Player`
+/// and `Game::Action` do. This stores the player, the [hint][hint] and all options which do not contain
+/// functions or trait objects, like [`with_max_depth`][max] or [`with_search_widths`][widths].
+/// All other options, for example policies, the transposition table or helper threads, are not stored
+/// and have to be set again after deserializing a bot.
+///
 /// [id]:https://en.wikipedia.org/wiki/Iterative_deepening_depth-first_search
 /// [ab_wiki]:https://en.wikipedia.org/wiki/Alpha%E2%80%93beta_pruning
 /// [sel]: struct.Bot.html#method.select
+/// [stats]: struct.Bot.html#method.stats
+/// [tree]: struct.Bot.html#method.search_tree
+/// [keep]: struct.Bot.html#method.with_persistent_table
 /// [game]: ../trait.Game.html
+/// [hint]: struct.Bot.html#method.set_hint
+/// [max]: struct.Bot.html#method.with_max_depth
+/// [widths]: struct.Bot.html#method.with_search_widths
 pub struct Bot<T: Game> {
     player: T::Player,
+    policy: Option<Arc<dyn Policy<T> + Send + Sync>>,
+    opponent_model: Option<Arc<dyn OpponentModel<T> + Send + Sync>>,
+    veto: Option<Arc<dyn Veto<T> + Send + Sync>>,
+    tablebase: Option<Arc<dyn Tablebase<T> + Send + Sync>>,
+    max_depth: Option<u32>,
+    parity: Option<Parity>,
+    /// The depth of the first iteration and the depth added by each following one.
+    depth_schedule: (u32, u32),
+    cycle_detection: Option<CycleDetection<T>>,
+    contempt: Option<Contempt<T>>,
+    transposition_table: Option<TranspositionTable<T>>,
+    persistent_table: bool,
+    /// The transposition table of the previous search, only used with `persistent_table`.
+    kept_table: Option<Table<T>>,
+    volatility: Option<Volatility<T>>,
+    selective_deepening: Option<Volatility<T>>,
+    aspiration: Option<Aspiration<T>>,
+    mtdf: Option<ZeroWindow<T>>,
+    principal_variation: Option<ZeroWindow<T>>,
+    widths: Vec<usize>,
+    endgame_solver: Option<u64>,
+    /// The top level action which is searched first during the next call to `select`.
+    hint: Option<T::Action>,
+    killer_moves: bool,
+    /// Returns the index of an action in the history table.
+    history_heuristic: Option<fn(&T::Action) -> usize>,
+    null_move: Option<NullMove<T>>,
+    /// The maximum amount of additional plies searched after noisy actions at the lowest depth.
+    quiescence: Option<u32>,
+    undo: Option<Undo<T>>,
+    incremental_hashing: bool,
+    /// The amount of helper threads and the function used to run them.
+    threads: Option<(usize, Helpers<T>)>,
+    /// The maximum amount of bytes used by internal structures.
+    memory_limit: Option<usize>,
+    /// Used to measure time instead of the `SystemClock`.
+    clock: Option<Arc<dyn Clock + Send + Sync>>,
+    trace_pruning: bool,
+    /// Converts fitness values to compute `Pruned::margin`.
+    pruning_margins: Option<fn(T::Fitness) -> f64>,
+    /// The maximum amount of states recorded in `tree`.
+    search_tree: Option<usize>,
+    /// Whether the top level actions are shuffled before each search.
+    random_ties: bool,
+    /// Used to choose between actions by `Bot::select_with_temperature` and `Bot::with_random_ties`.
+    rng: XorShiftRng,
+    stats: SearchStats,
+    tree: Option<SearchTree<T>>,
 }
 
-impl<T: Game> Bot<T> {
-    /// Creates a new `Bot` for the given `player`.
-    pub fn new(player: T::Player) -> Self {
-        Self { player }
-    }
-
-    /// Returns a chosen action based on the given game state.
-    ///
-    /// Returns  `None` if no `Action` is possible or the bot is currently not the active player.
-    ///
-    /// This method runs until either the best possible action was found
-    /// or one of `RunCondition::depth` and `RunCondition::step` returned `false`.
-    pub fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action> {
-        self.inner_select(state, condition)
-            .map(|mut act| act.path.pop().unwrap())
-    }
-
-    /// Similar to `select`, except that this function also returns the principal variation and the
-    /// final evaluation of the given action.
-    ///
-    /// The actions are sorted in order they are executed, so
-    /// `action.path[0]` is always equal to the result of `select`.
-    ///
-    /// ```rust
-    /// use rubot::{Bot, ToCompletion, tree::Node};
-    ///
-    /// # #[rustfmt::skip]
-    /// let tree = Node::root().with_children(&[
-    ///     Node::new(true, 4),
-    ///     Node::new(true, 0).with_children(&[
-    ///         Node::new(true, 5), // This is the best possible result.
-    ///         Node::new(true, 3),
-    ///     ])
-    /// ]);
-    ///
-    /// assert_eq!(&Bot::new(true)
-    ///     .detailed_select(&tree, ToCompletion)
-    ///     .unwrap()
-    ///     .path, &[1, 0]);
-    /// ```
-    pub fn detailed_select<U: IntoRunCondition>(
-        &mut self,
-        state: &T,
-        condition: U,
-    ) -> Option<Action<T>> {
-        self.inner_select(state, condition)
-            .map(|act| act.tap(|act| act.path.reverse()))
-    }
+/// The parity of the number of plies searched by an iteration, used by [`Bot::with_depth_parity`][parity].
+///
+/// [parity]: struct.Bot.html#method.with_depth_parity
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Parity {
+    /// Only search an even number of plies, so leaves are evaluated after an action of the opponent.
+    Even,
+    /// Only search an odd number of plies, so leaves are evaluated after an action of the bot.
+    Odd,
+}
 
-    fn inner_select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<Action<T>> {
-        let mut condition = condition.into_run_condition();
+/// The function used to hash states and the fitness of repeated states.
+type CycleDetection<T> = (fn(&T) -> u64, <T as Game>::Fitness);
 
-        let (active, actions) = state.actions(self.player);
-        if !active {
-            return None;
-        }
+/// The fitness of even states and the fitness used for them instead, see `Bot::with_contempt`.
+type Contempt<T> = (<T as Game>::Fitness, <T as Game>::Fitness);
 
-        let actions: Vec<_> = actions
-            .into_iter()
-            .map(|action| Action {
-                fitness: state.look_ahead(&action, self.player),
-                path: vec![action],
-            })
-            .collect();
+/// The depth reduction of null moves and the function used to skip the turn of the active player.
+type NullMove<T> = (u32, fn(&mut T) -> bool);
 
-        if actions.is_empty() {
-            return None;
-        }
+/// The most recent and the second most recent killer move of a single ply.
+type Killers<T> = (Option<<T as Game>::Action>, Option<<T as Game>::Action>);
 
-        let mut ctxt = Ctxt::new(state, self.player, actions);
+/// `Margin::add_margin`, `Margin::sub_margin` and the initial margin of aspiration windows.
+type Aspiration<T> = (
+    fn(<T as Game>::Fitness, <T as Game>::Fitness) -> <T as Game>::Fitness,
+    fn(<T as Game>::Fitness, <T as Game>::Fitness) -> <T as Game>::Fitness,
+    <T as Game>::Fitness,
+);
 
-        for depth in 0.. {
-            if !condition.depth(depth) {
-                return Some(ctxt.cancel());
-            }
+/// `Margin::successor` and `Margin::predecessor`, used to create zero-width windows.
+type ZeroWindow<T> = (
+    fn(<T as Game>::Fitness) -> <T as Game>::Fitness,
+    fn(<T as Game>::Fitness) -> <T as Game>::Fitness,
+);
 
-            // Return early in case there is only one relevant action left.
-            // This is the case if we either only have one possible actions,
-            // or if all other possible actions are worse than the lower bound.
-            if let Some(exhausted) = ctxt.exhausted() {
-                return Some(exhausted);
-            }
+/// Runs the given amount of helper threads sharing the transposition table until the search of the current thread finished.
+type Helpers<T> = fn(&Bot<T>, &T, &SharedTable<T>, usize, Search<'_>);
 
-            let mut unfinished = mem::take(&mut ctxt.unfinished);
-            // Try unfinished actions with a high expected fitness first,
-            // as they are expected to give us a better alpha value.
-            unfinished.sort_by_key(|act| Reverse(act.fitness));
+/// The search of the current thread, which is called with a function that has to be called at the start of each iteration.
+type Search<'a> = &'a mut dyn FnMut(&mut dyn FnMut(u32));
 
-            if let Some(best) = ctxt.best.take() {
-                // If computation is cancelled here, we don't know anything new,
-                // so we can just return the previous best action.
-                if let Some(ret) = ctxt.try_action(best, depth, &mut condition, |_, act| act) {
-                    return Some(ret);
-                }
-            }
+/// The function used to hash states and the amount of entries of the transposition table.
+type TranspositionTable<T> = (fn(&T) -> u64, usize);
 
-            for action in unfinished.into_iter() {
-                // In case computation is cancelled here, we may not yet have computed the best action of
-                // the previous depth, to guard against this, we add the cancelled action back to `state.unfinished`
-                // in case it is still empty.
-                let on_cancel = |ctxt: &mut Ctxt<T>, act| {
-                    if ctxt.unfinished.is_empty() {
-                        ctxt.unfinished.push(act);
-                    }
-                    ctxt.cancel()
-                };
+/// Called with each completed iteration, see `Bot::select_with_progress`.
+type Progress<'a, T> = &'a mut dyn FnMut(&Iteration<T>);
 
-                if let Some(ret) = ctxt.try_action(action, depth, &mut condition, on_cancel) {
-                    return Some(ret);
-                }
-            }
+/// The best action and its fitness after the last completed iteration, see `SearchHandle::best_so_far`.
+type BestSoFar<T> = Arc<Mutex<Option<(<T as Game>::Action, <T as Game>::Fitness)>>>;
 
-            // We only test partially terminated action which may still be better than the best
-            // fitness at the current depth.
-            //
-            // As the current best fitness does not come from a terminated path,
-            // we still have to keep the other partially terminated actions around,
-            // in case the best fitness of a later depth is lower.
-            for action in ctxt.relevant_partials() {
-                // In case computation is cancelled here, we already tested at least some actions which were better than
-                // the cancelled partial action at the previous depth, so we can use `ctxt.cancel()` without any special
-                // considerations.
-                if let Some(ret) =
-                    ctxt.try_action(action, depth, &mut condition, |ctxt, _| ctxt.cancel())
-                {
-                    return Some(ret);
-                }
-            }
-        }
+/// Reverts an action and a pass, see `UnmakeGame::undo` and `UnmakeGame::undo_pass`.
+type Undo<T> = (
+    fn(&mut T, &<T as Game>::Action, <T as Game>::Player),
+    fn(&mut T, <T as Game>::Player),
+);
 
-        unreachable!();
-    }
+/// Statistics about the last call to [`Bot::select`][sel], returned by [`Bot::stats`][stats].
+///
+/// These are mostly useful to tune the order of actions, e.g. using [`Policy`][policy],
+/// as a good order causes most cutoffs to happen at the first searched action.
+/// All counters saturate at `u64::MAX` instead of overflowing.
+///
+/// [sel]: struct.Bot.html#method.select
+/// [stats]: struct.Bot.html#method.stats
+/// [policy]: ../trait.Policy.html
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct SearchStats {
+    /// The amount of searched states, excluding the initial state.
+    pub nodes: u64,
+    /// The amount of states in `nodes` which were searched by the quiescence search,
+    /// see [`Bot::with_quiescence_search`][quiescence].
+    ///
+    /// [quiescence]: struct.Bot.html#method.with_quiescence_search
+    pub quiescence_nodes: u64,
+    /// The amount of actions evaluated using `Game::look_ahead_batch` at the lowest depth.
+    pub leaves: u64,
+    /// The amount of cutoffs, where the remaining actions of a state did not have to be searched.
+    pub cutoffs: u64,
+    /// `cutoff_indices[i]` is the amount of cutoffs caused by the `i`-th searched action of a state.
+    pub cutoff_indices: Vec<u64>,
+    /// The highest amount of actions executed or evaluated on a single path.
+    pub max_ply: u32,
+    /// The amount of states whose result was taken from the transposition table,
+    /// see [`Bot::with_transposition_table`][tt].
+    ///
+    /// [tt]: struct.Bot.html#method.with_transposition_table
+    pub transpositions: u64,
+    /// The amount of times the transposition table was searched for a state, including the ones
+    /// where no usable result was found.
+    pub table_probes: u64,
+    /// The amount of entries of the transposition table which were replaced by the result of a different state.
+    pub table_replacements: u64,
+    /// Whether the transposition table has fewer entries than requested because of
+    /// [`Bot::with_memory_limit`][limit].
+    ///
+    /// [limit]: struct.Bot.html#method.with_memory_limit
+    pub table_shrunk: bool,
+    /// Whether the search stopped before an iteration because its estimated memory usage exceeded
+    /// [`Bot::with_memory_limit`][limit].
+    ///
+    /// [limit]: struct.Bot.html#method.with_memory_limit
+    pub memory_limit_reached: bool,
+    /// The amount of times the best action had to be searched again because its fitness was outside
+    /// of the aspiration window, see [`Bot::with_aspiration_windows`][asp].
+    ///
+    /// [asp]: struct.Bot.html#method.with_aspiration_windows
+    pub aspiration_researches: u64,
+    /// The amount of zero-width searches of top level actions, see [`Bot::with_mtdf`][mtdf].
+    ///
+    /// [mtdf]: struct.Bot.html#method.with_mtdf
+    pub zero_window_searches: u64,
+    /// The amount of states which had to be searched again with the complete window after failing
+    /// their zero-width search, see [`Bot::with_principal_variation_search`][pvs].
+    ///
+    /// [pvs]: struct.Bot.html#method.with_principal_variation_search
+    pub pvs_researches: u64,
+    /// The amount of states whose result was taken from the tablebase, see [`Bot::with_tablebase`][tb].
+    ///
+    /// [tb]: struct.Bot.html#method.with_tablebase
+    pub tablebase_hits: u64,
+    /// The amount of states where the active player did not have any actions to search.
+    ///
+    /// These states are treated like the end of the game, which hides bugs in `Game::actions` unless all
+    /// of their actions were vetoed. Use [`Checked::with_dead_end_checks`][checks] to find these states.
+    ///
+    /// [checks]: ../check/struct.Checked.html#method.with_dead_end_checks
+    pub dead_ends: u64,
+    /// The total duration of the search, measured using the clock set by [`Bot::with_clock`][clock].
+    ///
+    /// [clock]: struct.Bot.html#method.with_clock
+    pub duration: Duration,
+    /// Each state where a pruning rule skipped at least one action, in the order they were searched.
+    ///
+    /// This is only recorded when using [`Bot::with_pruning_trace`][trace].
+    ///
+    /// [trace]: struct.Bot.html#method.with_pruning_trace
+    pub pruned: Vec<Pruned>,
 }
 
-/// A top level action.
-pub struct Action<T: Game> {
-    /// The current fitness of a given action.
+/// A rule which caused actions to not be searched, see [`Pruned`][pruned].
+///
+/// [pruned]: struct.Pruned.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PruningRule {
+    /// An alpha-beta cutoff, the remaining actions can not change the result.
+    Cutoff,
+    /// The remaining actions were not searched because of [`Bot::with_search_widths`][widths].
     ///
-    /// This can mean one of the following things:
+    /// [widths]: struct.Bot.html#method.with_search_widths
+    SearchWidth,
+    /// Actions of the opponent were considered implausible by [`Bot::with_opponent_model`][model].
     ///
-    /// - For the best unfinished action, this is exact, but only for the current depth.
-    /// - For a terminated action, this is exact.
-    /// - For a partially terminated action, this is the upper limit.
-    pub fitness: T::Fitness,
-    /// The expected path taken during optimal play, when only  inspectnig up to the current depth.
+    /// [model]: struct.Bot.html#method.with_opponent_model
+    OpponentModel,
+    /// Actions were vetoed by [`Bot::with_veto`][veto].
     ///
-    /// This used as a stack, with `path.pop()` being the first action.
-    pub path: Vec<T::Action>,
+    /// [veto]: struct.Bot.html#method.with_veto
+    Veto,
+    /// All actions of a state were skipped because of [`Bot::with_null_move_pruning`][null].
+    ///
+    /// [null]: struct.Bot.html#method.with_null_move_pruning
+    NullMove,
+    /// All actions of a state were skipped as its result was taken from the transposition table,
+    /// see [`Bot::with_transposition_table`][tt].
+    ///
+    /// [tt]: struct.Bot.html#method.with_transposition_table
+    Transposition,
 }
 
-#[derive(Clone, Copy, Debug)]
-struct CancelledError;
-
-enum MiniMax<T: Game> {
-    /// No new elements were found in this branch
-    Terminated(Vec<T::Action>, Branch<T>),
-    /// New elements were found
-    Open(Vec<T::Action>, Branch<T>),
-    /// There are no possible actions for this state
-    DeadEnd,
+/// A single state at which a pruning rule fired, stored in [`SearchStats::pruned`][pruned].
+///
+/// [pruned]: struct.SearchStats.html#structfield.pruned
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pruned {
+    /// The pruning rule which fired.
+    pub rule: PruningRule,
+    /// The amount of actions executed to reach the state.
+    pub ply: u32,
+    /// The amount of actions which were skipped.
+    pub skipped: usize,
+    /// How far the fitness which caused the pruning was outside of the window of the state,
+    /// only recorded using [`Bot::with_pruning_margins`][margins].
+    ///
+    /// This is `None` for rules which do not depend on the window and for exact results,
+    /// for example cutoffs because the best possible fitness was already reached.
+    ///
+    /// [margins]: struct.Bot.html#method.with_pruning_margins
+    pub margin: Option<f64>,
 }
 
-impl<T: Game> MiniMax<T> {
-    /// Appends an action to self.
-    pub fn with(
-        self,
-        ctxt: &mut Ctxt<'_, T>,
-        action: T::Action,
-        fitness: T::Fitness,
-    ) -> MiniMax<T> {
-        match self {
-            MiniMax::DeadEnd => MiniMax::Terminated(
-                ctxt.new_path().tap(|p| p.push(action)),
-                Branch::Equal(fitness),
-            ),
-            MiniMax::Open(mut actions, branch) => {
-                actions.push(action);
-                MiniMax::Open(actions, branch)
-            }
-            MiniMax::Terminated(mut actions, branch) => {
-                actions.push(action);
-                MiniMax::Terminated(actions, branch)
-            }
-        }
-    }
+/// The combined effect of a single pruning rule, returned by [`SearchStats::pruning`][pruning].
+///
+/// [pruning]: struct.SearchStats.html#method.pruning
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PruningSummary {
+    /// The amount of states at which the rule fired.
+    pub states: u64,
+    /// The total amount of skipped actions.
+    pub skipped: u64,
+    /// The highest amount of actions skipped at a single state.
+    pub max_skipped: usize,
+    /// The smallest margin of all states, which shows how close the rule was to not firing.
+    pub min_margin: Option<f64>,
 }
 
-/// A fitness and how it was calculated,
-/// this is used if we want to know whether a cutoff occurred.
-enum Branch<T: Game> {
-    /// `actual_fitness <= fitness`.
+impl SearchStats {
+    /// Returns the amount of searched states per second, using the [`duration`][duration]
+    /// measured by the clock of the bot.
     ///
-    /// Used if the given branch is worse than the current `beta`.
-    /// Confusingly, this is also called an `alpha` cutoff.
-    Worse(T::Fitness),
-    /// `actual_fitness >= fitness`.
+    /// [duration]: #structfield.duration
+    pub fn nodes_per_second(&self) -> f64 {
+        self.nodes as f64 / self.duration.as_secs_f64()
+    }
+
+    /// Returns the effective branching factor, which is the amount of children of each state
+    /// required to reach [`nodes`][nodes] in a tree with a depth of [`max_ply`][ply].
     ///
-    /// Used if the given branch is better than the current `alpha`.
-    /// Confusingly, this is also called a `beta` cutoff.
-    Better(T::Fitness),
-    /// `actual_fitness == fitness`.
+    /// [nodes]: #structfield.nodes
+    /// [ply]: #structfield.max_ply
+    pub fn effective_branching_factor(&self) -> f64 {
+        ((self.nodes + self.leaves) as f64).powf(1.0 / f64::from(self.max_ply))
+    }
+
+    /// Combines all entries of [`pruned`][pruned] with the given `rule`.
     ///
-    /// Used if no cutoff occured.
-    Equal(T::Fitness),
-}
+    /// [pruned]: #structfield.pruned
+    pub fn pruning(&self, rule: PruningRule) -> PruningSummary {
+        self.pruned
+            .iter()
+            .filter(|pruned| pruned.rule == rule)
+            .fold(PruningSummary::default(), |summary, pruned| {
+                PruningSummary {
+                    states: summary.states.saturating_add(1),
+                    skipped: summary.skipped.saturating_add(pruned.skipped as u64),
+                    max_skipped: cmp::max(summary.max_skipped, pruned.skipped),
+                    min_margin: match (summary.min_margin, pruned.margin) {
+                        (Some(min), Some(margin)) => Some(min.min(margin)),
+                        (min, margin) => min.or(margin),
+                    },
+                }
+            })
+    }
 
-impl<T: Game> Clone for Branch<T> {
-    fn clone(&self) -> Branch<T> {
-        *self
+    fn add(&mut self, other: SearchStats) {
+        self.nodes = self.nodes.saturating_add(other.nodes);
+        self.quiescence_nodes = self.quiescence_nodes.saturating_add(other.quiescence_nodes);
+        self.leaves = self.leaves.saturating_add(other.leaves);
+        self.cutoffs = self.cutoffs.saturating_add(other.cutoffs);
+        if self.cutoff_indices.len() < other.cutoff_indices.len() {
+            self.cutoff_indices.resize(other.cutoff_indices.len(), 0);
+        }
+        for (count, other) in self.cutoff_indices.iter_mut().zip(other.cutoff_indices) {
+            *count = count.saturating_add(other);
+        }
+        self.max_ply = cmp::max(self.max_ply, other.max_ply);
+        self.transpositions = self.transpositions.saturating_add(other.transpositions);
+        self.table_probes = self.table_probes.saturating_add(other.table_probes);
+        self.table_replacements = self
+            .table_replacements
+            .saturating_add(other.table_replacements);
+        self.table_shrunk |= other.table_shrunk;
+        self.memory_limit_reached |= other.memory_limit_reached;
+        self.aspiration_researches = self
+            .aspiration_researches
+            .saturating_add(other.aspiration_researches);
+        self.zero_window_searches = self
+            .zero_window_searches
+            .saturating_add(other.zero_window_searches);
+        self.pvs_researches = self.pvs_researches.saturating_add(other.pvs_researches);
+        self.tablebase_hits = self.tablebase_hits.saturating_add(other.tablebase_hits);
+        self.dead_ends = self.dead_ends.saturating_add(other.dead_ends);
+        self.pruned.extend(other.pruned);
+    }
+
+    fn add_cutoff(&mut self, index: usize) {
+        self.cutoffs = self.cutoffs.saturating_add(1);
+        if self.cutoff_indices.len() <= index {
+            self.cutoff_indices.resize(index + 1, 0);
+        }
+        self.cutoff_indices[index] = self.cutoff_indices[index].saturating_add(1);
     }
 }
 
-impl<T: Game> Copy for Branch<T> {}
+/// The search depth of a single ply.
+///
+/// Depths during the search are fractional, allowing reductions and extensions
+/// which are smaller than a whole ply. This is internal only, all extensions and
+/// reductions which can currently be configured are whole plies.
+const PLY: u32 = 16;
 
-impl<T: Game> Branch<T> {
-    #[inline(always)]
-    fn fitness(self) -> T::Fitness {
-        match self {
-            Branch::Worse(fitness) | Branch::Better(fitness) | Branch::Equal(fitness) => fitness,
-        }
+/// The maximum factor by which the duration of an iteration is expected to exceed the previous one,
+/// which prevents very fast early iterations from predicting unreasonably slow ones.
+const MAX_ITERATION_GROWTH: f64 = 8.0;
+
+/// Returns whether the fitness changed by more than a margin, the margin and a limit on how often this is used.
+type Volatility<T> = (
+    fn(<T as Game>::Fitness, <T as Game>::Fitness, <T as Game>::Fitness) -> bool,
+    <T as Game>::Fitness,
+    u32,
+);
+
+fn swings<F: Margin>(previous: F, current: F, margin: F) -> bool {
+    current > previous.add_margin(margin) || current < previous.sub_margin(margin)
+}
+
+/// Removes all actions for which `keep` returns `false` and returns the amount of removed actions.
+///
+/// In case no action should be kept, all actions are kept. `kept` is an empty buffer.
+fn retain_actions<A>(
+    actions: &mut Vec<A>,
+    kept: &mut Vec<bool>,
+    keep: impl Fn(&A) -> bool,
+) -> usize {
+    kept.extend(actions.iter().map(keep));
+    let skipped = if kept.contains(&true) {
+        let mut kept_iter = kept.iter();
+        actions.retain(|_| *kept_iter.next().unwrap());
+        kept.len() - actions.len()
+    } else {
+        0
+    };
+    kept.clear();
+    skipped
+}
+
+/// Returns the fitness used for a state with the given `fitness`, see `Bot::with_contempt`.
+fn apply_contempt<T: Game>(contempt: Option<Contempt<T>>, fitness: T::Fitness) -> T::Fitness {
+    match contempt {
+        Some((even, score)) if fitness == even => score,
+        _ => fitness,
     }
 }
 
-/// The currently available data at the highest level, during minimax `State` is used instead.
-struct Ctxt<'a, T: Game> {
-    /// The initial gamestate.
-    state: &'a T,
-    /// The maximizing player.
-    player: T::Player,
-    /// The best unfinished action. This is not set if there is an already better terminated action.
-    best: Option<Action<T>>,
-    /// Actions which are both not yet finished and worse than `best_unfinished`.
-    unfinished: Vec<Action<T>>,
-    /// The best already completely terminated action. We keep the path for diagnostic
-    /// purposes only, as there is no reason to retry this.
-    terminated: Option<Action<T>>,
-    /// Partially terminated actions. These are paths which had a cutoff at the highest level.
-    ///
-    /// As these actions cannot have a fitness higher than this cutoff, we discard all partially terminated
-    /// actions which must be worse than `best_terminated`.
-    partially_terminated: Vec<Action<T>>,
-    /// In case all paths lead to defeat, we store the action which takes the longest,
-    /// so the bot doesn't start doing weird stuff once it realized it's lost.
-    losing_action: Option<Action<T>>,
-    /// We create and discard a lot of paths.
-    ///
-    /// As an optimization, we therefore can reuse these paths.
-    /// The paths stored here are always empty. This causes an about
-    /// 2% performance increase.
-    path_cache: Vec<Vec<T::Action>>,
+fn hash_state<T: Hash>(state: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    state.hash(&mut hasher);
+    hasher.finish()
 }
 
-impl<'a, T: Game> Ctxt<'a, T> {
-    fn new(state: &T, player: T::Player, unfinished: Vec<Action<T>>) -> Ctxt<T> {
-        Ctxt {
-            state,
+impl<T: Game> Bot<T> {
+    /// Creates a new `Bot` for the given `player`.
+    pub fn new(player: T::Player) -> Self {
+        Self {
             player,
-            best: None,
-            unfinished,
-            terminated: None,
-            losing_action: None,
-            partially_terminated: Vec::new(),
-            path_cache: Vec::new(),
+            policy: None,
+            opponent_model: None,
+            veto: None,
+            tablebase: None,
+            max_depth: None,
+            parity: None,
+            depth_schedule: (0, 1),
+            cycle_detection: None,
+            contempt: None,
+            transposition_table: None,
+            persistent_table: false,
+            kept_table: None,
+            volatility: None,
+            selective_deepening: None,
+            aspiration: None,
+            mtdf: None,
+            principal_variation: None,
+            widths: Vec::new(),
+            endgame_solver: None,
+            hint: None,
+            killer_moves: false,
+            history_heuristic: None,
+            null_move: None,
+            quiescence: None,
+            undo: None,
+            incremental_hashing: false,
+            threads: None,
+            memory_limit: None,
+            clock: None,
+            trace_pruning: false,
+            pruning_margins: None,
+            search_tree: None,
+            random_ties: false,
+            rng: XorShiftRng::from_seed_u64(0),
+            stats: SearchStats::default(),
+            tree: None,
         }
     }
 
-    /// Creates a new empty path, potentially reuse the cache.
+    /// Uses `policy` to order actions during the search.
+    ///
+    /// Without a policy, actions are ordered by the fitness after executing them.
+    /// With a policy, actions with a higher prior are searched first, only using
+    /// the fitness to break ties.
+    pub fn with_policy(mut self, policy: impl Policy<T> + Send + Sync + 'static) -> Self {
+        self.policy = Some(Arc::new(policy));
+        self
+    }
+
+    /// Uses `model` to predict the actions of the opponent.
+    ///
+    /// Instead of assuming that the opponent always chooses the action which is worst for this bot,
+    /// only actions considered plausible by `model` are searched when the opponent is active.
+    /// In case the model does not consider any action plausible, all actions are searched.
+    ///
+    /// This allows the bot to exploit known weaknesses of an opponent, but causes it to
+    /// play worse in case the model is wrong.
+    pub fn with_opponent_model(
+        mut self,
+        model: impl OpponentModel<T> + Send + Sync + 'static,
+    ) -> Self {
+        self.opponent_model = Some(Arc::new(model));
+        self
+    }
+
+    /// Never searches actions vetoed by `veto`, unless all actions of a state are vetoed.
+    ///
+    /// See [`Veto`][veto] for when this changes the result and an example.
+    ///
+    /// [veto]: ../trait.Veto.html
+    pub fn with_veto(mut self, veto: impl Veto<T> + Send + Sync + 'static) -> Self {
+        self.veto = Some(Arc::new(veto));
+        self
+    }
+
+    /// Probes `tablebase` at every searched state, using the result of known states
+    /// instead of searching their actions.
+    ///
+    /// In case the tablebase knows the best action of the initial state, it is selected without searching.
+    /// The amount of known states is stored in [`SearchStats::tablebase_hits`][hits].
+    /// See [`Tablebase`][tb] for an example.
+    ///
+    /// [hits]: struct.SearchStats.html#structfield.tablebase_hits
+    /// [tb]: ../trait.Tablebase.html
+    pub fn with_tablebase(mut self, tablebase: impl Tablebase<T> + Send + Sync + 'static) -> Self {
+        self.tablebase = Some(Arc::new(tablebase));
+        self
+    }
+
+    /// Never searches deeper than [`Depth`][depth]`(max_depth)` would, regardless of the used run condition.
+    ///
+    /// Without a maximum depth, [`ToCompletion`][tc] does not terminate for games which can
+    /// go on forever, for example if some actions can be undone by the opponent.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, Game, ToCompletion};
+    ///
+    /// /// Both players can endlessly move a token back and forth.
+    /// #[derive(Clone)]
+    /// struct BackAndForth(bool);
+    ///
+    /// impl Game for BackAndForth {
+    ///     type Player = ();
+    ///     type Action = bool;
+    ///     type Fitness = bool;
+    ///     type Actions = Vec<bool>;
+    ///
+    ///     fn actions(&self, _: ()) -> (bool, Vec<bool>) {
+    ///         (true, vec![!self.0])
+    ///     }
+    ///
+    ///     fn execute(&mut self, action: &bool, _: ()) -> bool {
+    ///         self.0 = *action;
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let mut bot = Bot::new(()).with_max_depth(20);
+    /// assert_eq!(bot.select(&BackAndForth(false), ToCompletion), Some(true));
+    /// ```
+    ///
+    /// [depth]: ../struct.Depth.html
+    /// [tc]: ../struct.ToCompletion.html
+    pub fn with_max_depth(mut self, max_depth: u32) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Skips all iterations of iterative deepening which do not search a number of plies with the given `parity`.
+    ///
+    /// Evaluation functions which favor the player who executed the last action cause the result to oscillate
+    /// between odd and even depths. Only using iterations of the same parity results in more stable scores
+    /// at the cost of searching less often. Here, a ply is a single action and
+    /// [`Depth`][depth]`(n)` searches `n + 1` plies, so [`Parity::Even`][even] only completes odd depths.
+    ///
+    /// This assumes that the players take turns, as the bot does not know who executes
+    /// the last action of a given path.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, Depth, alpha_beta::Parity, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 7).with_children(&[
+    ///         Node::new(true, 4),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 8).with_children(&[
+    ///             Node::new(false, 0),
+    ///         ]),
+    ///         Node::new(true, 9)
+    ///     ]),
+    /// ]);
+    ///
+    /// // `Depth(2)` searches 3 plies.
+    /// assert_eq!(Bot::new(true).select(&tree, Depth(2)), Some(0));
+    ///
+    /// // Only searching an even number of plies, `Depth(2)` is equivalent to `Depth(1)`.
+    /// let mut bot = Bot::new(true).with_depth_parity(Parity::Even);
+    /// assert_eq!(bot.select(&tree, Depth(2)), Some(1));
+    /// assert_eq!(bot.select(&tree, Depth(1)), Some(1));
+    /// ```
+    ///
+    /// [depth]: ../struct.Depth.html
+    /// [even]: enum.Parity.html#variant.Even
+    pub fn with_depth_parity(mut self, parity: Parity) -> Self {
+        self.parity = Some(parity);
+        self
+    }
+
+    /// Detects states which were already visited on the currently searched path and uses `fitness`
+    /// as their fitness instead of searching them again. This is usually the fitness of a draw.
+    ///
+    /// This prevents the bot from wasting time exploring loops in games with reversible actions
+    /// and, unlike [`with_max_depth`][max], also allows [`ToCompletion`][tc] to terminate for such games.
+    ///
+    /// States are compared using their hash, so in the rare case of a hash collision
+    /// a state is incorrectly considered to be repeated.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, Game, ToCompletion};
+    ///
+    /// /// Both players can endlessly move a token back and forth.
+    /// #[derive(Clone, Hash)]
+    /// struct BackAndForth(bool);
+    ///
+    /// impl Game for BackAndForth {
+    ///     type Player = ();
+    ///     type Action = bool;
+    ///     type Fitness = i8;
+    ///     type Actions = Vec<bool>;
+    ///
+    ///     fn actions(&self, _: ()) -> (bool, Vec<bool>) {
+    ///         (true, vec![!self.0])
+    ///     }
+    ///
+    ///     fn execute(&mut self, action: &bool, _: ()) -> i8 {
+    ///         self.0 = *action;
+    ///         self.0 as i8
+    ///     }
+    /// }
+    ///
+    /// let mut bot = Bot::new(()).with_cycle_detection(0);
+    /// let action = bot.detailed_select(&BackAndForth(false), ToCompletion).unwrap();
+    /// assert_eq!(action.fitness, 0);
+    /// assert_eq!(action.path, &[true, false]);
+    /// ```
+    ///
+    /// [max]: #method.with_max_depth
+    /// [tc]: ../struct.ToCompletion.html
+    pub fn with_cycle_detection(mut self, fitness: T::Fitness) -> Self
+    where
+        T: Hash,
+    {
+        self.cycle_detection = Some((hash_state::<T>, fitness));
+        self
+    }
+
+    /// Uses `contempt` less than `even` as the fitness of all states whose fitness is `even`,
+    /// where `even` is usually the fitness of a draw or of a balanced position.
+    ///
+    /// A positive `contempt` makes the bot avoid even states in favor of slightly worse ones
+    /// which keep the game going, which is useful against weaker opponents. A negative `contempt`
+    /// instead makes the bot prefer even states, e.g. against stronger opponents.
+    /// This only changes the fitness returned by `Game::execute` and `Game::look_ahead`,
+    /// so the fitness of [`with_cycle_detection`][cycle] and of a [`Tablebase`][tb] is used as is.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 0),
+    ///     Node::new(false, -1),
+    /// ]);
+    ///
+    /// assert_eq!(Bot::new(true).select(&tree, ToCompletion), Some(0));
+    ///
+    /// let mut bot = Bot::new(true).with_contempt(0, 2);
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(1));
+    /// ```
+    ///
+    /// [cycle]: #method.with_cycle_detection
+    /// [tb]: ../trait.Tablebase.html
+    pub fn with_contempt(mut self, even: T::Fitness, contempt: T::Fitness) -> Self
+    where
+        T::Fitness: Margin,
+    {
+        self.contempt = Some((even, even.sub_margin(contempt)));
+        self
+    }
+
+    /// Remembers the result of searched states in a transposition table with `entries` entries,
+    /// so states which can be reached using different orders of actions only have to be searched once
+    /// at each depth. The best action of a remembered state is also searched first during later iterations.
+    ///
+    /// States are identified using `hash`, which should be cheap to compute, e.g. using
+    /// [Zobrist hashing][zobrist]. The result of a state must only depend on the state itself,
+    /// so results influenced by [`with_cycle_detection`][cycle] may be reused incorrectly.
+    /// In the rare case of a hash collision, the result of a different state is used.
+    ///
+    /// Games with symmetries, e.g. reflected or rotated boards, can let symmetric states share entries by
+    /// returning the same hash for all of them, for example by hashing a canonical form of each state,
+    /// similar to [`Brute::with_canonical_form`][canonical]. The best action stored in an entry is only used
+    /// to order actions, so using the best action of a symmetric state does not change the result of the search.
+    ///
+    /// The table is cleared at the start of each call to `select`, unless [`with_persistent_table`][keep] is used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `entries` is `0`.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::{Hash, Hasher};
+    ///
+    /// fn hash(node: &Node) -> u64 {
+    ///     let mut hasher = DefaultHasher::new();
+    ///     node.hash(&mut hasher);
+    ///     hasher.finish()
+    /// }
+    ///
+    /// # #[rustfmt::skip]
+    /// let shared = Node::new(true, 4).with_children(&[
+    ///     Node::new(false, 1).with_children(&[
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 6).with_children(&[
+    ///         Node::new(true, 5),
+    ///     ]),
+    /// ]);
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 3).with_children(&[shared.clone()]),
+    ///     Node::new(false, 2).with_children(&[shared]),
+    /// ]);
+    ///
+    /// let mut bot = Bot::new(true).with_transposition_table(1 << 10, hash);
+    /// // The result of `shared` is only computed once.
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(0));
+    /// assert!(bot.stats().transpositions > 0);
+    /// ```
+    ///
+    /// [zobrist]: https://en.wikipedia.org/wiki/Zobrist_hashing
+    /// [keep]: #method.with_persistent_table
+    /// [cycle]: #method.with_cycle_detection
+    /// [canonical]: ../brute/struct.Brute.html#method.with_canonical_form
+    pub fn with_transposition_table(mut self, entries: usize, hash: fn(&T) -> u64) -> Self {
+        assert!(entries > 0, "transposition table must not be empty");
+        self.transposition_table = Some((hash, entries));
+        self
+    }
+
+    /// Keeps the transposition table of [`with_transposition_table`][tt] between calls to `select`,
+    /// instead of starting each search with an empty table.
+    ///
+    /// The states searched while selecting an action are usually also reached while selecting the next one,
+    /// so most of the previous search can be reused. The best action of the initial state is also searched
+    /// first in case it is known from a previous search, similar to [`set_hint`][hint].
+    /// Use [`clear_table`][clear] when starting a new game, as the table would otherwise only contain
+    /// irrelevant states. The table is also cleared when changing the player of the bot.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::{Hash, Hasher};
+    ///
+    /// fn hash(node: &Node) -> u64 {
+    ///     let mut hasher = DefaultHasher::new();
+    ///     node.hash(&mut hasher);
+    ///     hasher.finish()
+    /// }
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 3).with_children(&[
+    ///         Node::new(true, 1).with_children(&[
+    ///             Node::new(false, 1).with_children(&[
+    ///                 Node::new(true, 1),
+    ///                 Node::new(true, 0),
+    ///             ]),
+    ///             Node::new(false, 2).with_children(&[
+    ///                 Node::new(true, 2),
+    ///             ]),
+    ///         ]),
+    ///         Node::new(true, 4).with_children(&[
+    ///             Node::new(false, 5),
+    ///         ]),
+    ///     ]),
+    ///     Node::new(false, 1),
+    /// ]);
+    ///
+    /// let mut bot = Bot::new(true)
+    ///     .with_transposition_table(1 << 10, hash)
+    ///     .with_persistent_table();
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(0));
+    /// let nodes = bot.stats().nodes;
+    ///
+    /// // Most states are already known from the previous search.
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(0));
+    /// assert!(bot.stats().nodes < nodes);
+    /// ```
+    ///
+    /// [tt]: #method.with_transposition_table
+    /// [hint]: #method.set_hint
+    /// [clear]: #method.clear_table
+    pub fn with_persistent_table(mut self) -> Self {
+        self.persistent_table = true;
+        self
+    }
+
+    /// Hashes states for [`with_cycle_detection`][cycle] and [`with_transposition_table`][tt] using
+    /// [`Game::state_hash`][hash] for the initial state and [`Game::update_hash`][update] for all other states,
+    /// instead of hashing each state from scratch.
+    ///
+    /// Both cycle detection and the transposition table then use the same hash. In case `state_hash`
+    /// returns `None` for the initial state, the functions given to these methods are used instead.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, Game, ToCompletion};
+    ///
+    /// /// Both players add one or two to a sum, the player reaching ten wins.
+    /// #[derive(Clone)]
+    /// struct Sum {
+    ///     sum: u8,
+    ///     active: bool,
+    /// }
+    ///
+    /// impl Game for Sum {
+    ///     type Player = bool;
+    ///     type Action = u8;
+    ///     type Fitness = i8;
+    ///     type Actions = Vec<u8>;
+    ///
+    ///     fn actions(&self, player: bool) -> (bool, Vec<u8>) {
+    ///         let actions = (1..=2).filter(|add| self.sum + add <= 10).collect();
+    ///         (self.active == player, actions)
+    ///     }
+    ///
+    ///     fn execute(&mut self, &add: &u8, player: bool) -> i8 {
+    ///         self.sum += add;
+    ///         self.active = !self.active;
+    ///         match (self.sum, self.active == player) {
+    ///             (10, true) => -1,
+    ///             (10, false) => 1,
+    ///             _ => 0,
+    ///         }
+    ///     }
+    ///
+    ///     fn state_hash(&self) -> Option<u64> {
+    ///         Some(u64::from(self.sum) << 1 | self.active as u64)
+    ///     }
+    ///
+    ///     fn update_hash(&self, hash: u64, &add: &u8, _: bool) -> Option<u64> {
+    ///         Some((hash + (u64::from(add) << 1)) ^ 1)
+    ///     }
+    /// }
+    ///
+    /// let mut bot = Bot::new(true)
+    ///     .with_transposition_table(1 << 10, |state: &Sum| state.state_hash().unwrap())
+    ///     .with_incremental_hashing();
+    /// assert_eq!(bot.select(&Sum { sum: 0, active: true }, ToCompletion), Some(1));
+    /// assert!(bot.stats().transpositions > 0);
+    /// ```
+    ///
+    /// [cycle]: #method.with_cycle_detection
+    /// [tt]: #method.with_transposition_table
+    /// [hash]: ../trait.Game.html#method.state_hash
+    /// [update]: ../trait.Game.html#method.update_hash
+    pub fn with_incremental_hashing(mut self) -> Self {
+        self.incremental_hashing = true;
+        self
+    }
+
+    /// Starts iterative deepening at `start` and increases the depth by `step` after each iteration,
+    /// instead of starting at `0` and only increasing it by one.
+    ///
+    /// Depths are the ones passed to [`RunCondition::depth`][depth], where the iteration at depth `n`
+    /// returns the same result as [`Depth`][de]`(n + 1)`. Skipping shallow iterations saves time in case
+    /// they are cheap and therefore not useful to order the actions of later iterations.
+    /// The parity set using [`with_depth_parity`][parity] is applied afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is `0`.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, Logger, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 7).with_children(&[
+    ///         Node::new(true, 4),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 8),
+    ///         Node::new(true, 9)
+    ///     ]),
+    /// ]);
+    ///
+    /// let mut logger = Logger::new(ToCompletion);
+    /// let mut bot = Bot::new(true).with_depth_schedule(4, 2);
+    /// assert_eq!(bot.select(&tree, &mut logger), Some(1));
+    /// // Only depths 4, 6, 8, ... are searched.
+    /// assert_eq!(logger.depth() % 2, 0);
+    /// ```
+    ///
+    /// [depth]: ../trait.RunCondition.html#tymethod.depth
+    /// [de]: ../struct.Depth.html
+    /// [parity]: #method.with_depth_parity
+    pub fn with_depth_schedule(mut self, start: u32, step: u32) -> Self {
+        assert!(step > 0, "depth step must not be zero");
+        self.depth_schedule = (start, step);
+        self
+    }
+
+    /// Searches the best action one ply deeper than the current iteration in case its fitness changed by more than
+    /// `margin` between the previous two iterations. This is done at most `budget` times during each call to `select`.
+    ///
+    /// Scores which swing wildly between depths are often caused by the [horizon effect][horizon], where
+    /// a bad outcome is pushed beyond the search depth by delaying actions. Extending these lines helps
+    /// to detect this earlier, even for games without a natural definition of quiet positions.
+    ///
+    /// The extension is always exactly one ply. While the search tracks depths in fractions of a ply,
+    /// there is currently no way to extend by less.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, Depth, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 0).with_children(&[
+    ///         Node::new(true, 10).with_children(&[
+    ///             Node::new(false, 6).with_children(&[
+    ///                 Node::new(true, 9).with_children(&[
+    ///                     // This only gets found when searching 5 plies.
+    ///                     Node::new(false, 1),
+    ///                 ]),
+    ///             ]),
+    ///         ]),
+    ///     ]),
+    ///     Node::new(false, 5),
+    /// ]);
+    ///
+    /// assert_eq!(Bot::new(true).select(&tree, Depth(3)), Some(0));
+    /// assert_eq!(Bot::new(true).select(&tree, Depth(4)), Some(1));
+    ///
+    /// let mut bot = Bot::new(true).with_volatility_extension(2, 1);
+    /// assert_eq!(bot.select(&tree, Depth(3)), Some(1));
+    /// ```
+    ///
+    /// [horizon]: https://en.wikipedia.org/wiki/Horizon_effect
+    pub fn with_volatility_extension(mut self, margin: T::Fitness, budget: u32) -> Self
+    where
+        T::Fitness: Margin,
+    {
+        self.volatility = Some((swings::<T::Fitness>, margin, budget));
+        self
+    }
+
+    /// Searches up to `count` of the remaining top level actions one ply deeper than the current iteration
+    /// in case their fitness changed by more than `margin` since they were last searched.
+    ///
+    /// Unlike [`with_volatility_extension`][vol], which only extends the best action, this spends additional
+    /// effort on actions which are not yet refuted, instead of deepening all top level actions uniformly.
+    /// As with [`with_volatility_extension`][vol], each deepened action is searched a whole ply deeper.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, Depth, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 0).with_children(&[
+    ///         Node::new(true, 5).with_children(&[
+    ///             Node::new(false, 5).with_children(&[
+    ///                 Node::new(true, 5).with_children(&[
+    ///                     Node::new(false, 5).with_children(&[
+    ///                         Node::new(true, 5),
+    ///                     ]),
+    ///                 ]),
+    ///             ]),
+    ///         ]),
+    ///     ]),
+    ///     Node::new(false, 3).with_children(&[
+    ///         Node::new(true, 3).with_children(&[
+    ///             Node::new(false, 0).with_children(&[
+    ///                 Node::new(true, 4).with_children(&[
+    ///                     // This only gets found when searching 5 plies.
+    ///                     Node::new(false, 9),
+    ///                 ]),
+    ///             ]),
+    ///         ]),
+    ///     ]),
+    /// ]);
+    ///
+    /// assert_eq!(Bot::new(true).select(&tree, Depth(3)), Some(0));
+    /// assert_eq!(Bot::new(true).select(&tree, Depth(4)), Some(1));
+    ///
+    /// let mut bot = Bot::new(true).with_selective_deepening(2, 1);
+    /// assert_eq!(bot.select(&tree, Depth(3)), Some(1));
+    /// ```
+    ///
+    /// [vol]: #method.with_volatility_extension
+    pub fn with_selective_deepening(mut self, margin: T::Fitness, count: u32) -> Self
+    where
+        T::Fitness: Margin,
+    {
+        self.selective_deepening = Some((swings::<T::Fitness>, margin, count));
+        self
+    }
+
+    /// Searches the best action of the previous iteration using an [aspiration window][asp] of
+    /// `margin` around its previous fitness, instead of only using the fitness of the best terminated action.
+    ///
+    /// A narrow window causes more cutoffs, but in case the fitness of the best action is outside of it,
+    /// the action is searched again, doubling the margin on the side of the window which failed each time.
+    /// This does not change the result, but reduces the amount of searched states if the fitness of the
+    /// best action is stable between iterations.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 5).with_children(&[
+    ///             Node::new(false, 0),
+    ///         ]),
+    ///     ]),
+    ///     Node::new(false, 3).with_children(&[
+    ///         Node::new(true, 3),
+    ///     ]),
+    /// ]);
+    ///
+    /// let mut bot = Bot::new(true).with_aspiration_windows(1);
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(1));
+    /// // The fitness of the first action dropped from `5` to `0` when searching 3 plies.
+    /// assert!(bot.stats().aspiration_researches > 0);
+    /// ```
+    ///
+    /// [asp]: https://www.chessprogramming.org/Aspiration_Windows
+    pub fn with_aspiration_windows(mut self, margin: T::Fitness) -> Self
+    where
+        T::Fitness: Margin,
+    {
+        self.aspiration = Some((Margin::add_margin, Margin::sub_margin, margin));
+        self
+    }
+
+    /// Computes the fitness of top level actions using [MTD(f)][mtdf], which repeatedly searches each action
+    /// with a window of width zero, starting at its fitness from the previous iteration. Each of these searches
+    /// only tells whether the fitness is above or below the window, until its exact fitness is known.
+    ///
+    /// Zero-width windows cause a lot more cutoffs than the default search, which only uses a lower bound.
+    /// As the same states are searched again with different windows, this should be combined with
+    /// [`with_transposition_table`][tt]. Aspiration windows are not used when using MTD(f).
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 7).with_children(&[
+    ///         Node::new(true, 4),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 8),
+    ///         Node::new(true, 9),
+    ///     ]),
+    ///     Node::new(false, 6),
+    /// ]);
+    ///
+    /// let mut bot = Bot::new(true).with_mtdf();
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(1));
+    /// assert!(bot.stats().zero_window_searches > 0);
+    /// ```
+    ///
+    /// [mtdf]: https://en.wikipedia.org/wiki/MTD(f)
+    /// [tt]: #method.with_transposition_table
+    pub fn with_mtdf(mut self) -> Self
+    where
+        T::Fitness: Margin,
+    {
+        self.mtdf = Some((Margin::successor, Margin::predecessor));
+        self
+    }
+
+    /// Uses [principal variation search][pvs], which only searches the first child of each state with the
+    /// complete window. All other children are searched with an empty window, which only proves that they
+    /// are not better than the first one. In case this fails, the child is searched again with the complete window.
+    ///
+    /// This is faster than the default search if the first child is usually the best one, for example
+    /// when using a good [`Policy`][policy] or [`with_transposition_table`][tt], but slower otherwise.
+    /// The amount of repeated searches is stored in [`SearchStats::pvs_researches`][researches].
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 7).with_children(&[
+    ///         Node::new(true, 4),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 8),
+    ///         Node::new(true, 9),
+    ///     ]),
+    ///     Node::new(false, 6),
+    /// ]);
+    ///
+    /// let mut bot = Bot::new(true).with_principal_variation_search();
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(1));
+    /// ```
+    ///
+    /// [pvs]: https://en.wikipedia.org/wiki/Principal_variation_search
+    /// [policy]: ../trait.Policy.html
+    /// [tt]: #method.with_transposition_table
+    /// [researches]: struct.SearchStats.html#structfield.pvs_researches
+    pub fn with_principal_variation_search(mut self) -> Self
+    where
+        T::Fitness: Margin,
+    {
+        self.principal_variation = Some((Margin::successor, Margin::predecessor));
+        self
+    }
+
+    /// Only searches the first `widths[i]` actions of each state which is reached after `i + 1` actions,
+    /// in the order in which they would be searched. Actions after that are only evaluated using the fitness
+    /// returned by `Game::execute`, as if the game ended after executing them. States reached after more
+    /// than `widths.len()` actions are searched normally.
+    ///
+    /// This means that all actions are considered with a shallow search, while only the most promising
+    /// ones are searched to the full depth, which allows searching a lot deeper for games with a high branching
+    /// factor. As the result depends on the order of actions, this works best together with
+    /// [`with_policy`][policy]. Top level actions are always searched.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 6),
+    ///         // Looks bad for the opponent, so it is not searched with a width of `1`.
+    ///         Node::new(true, 9).with_children(&[
+    ///             Node::new(false, 9).with_children(&[
+    ///                 Node::new(true, 1),
+    ///             ]),
+    ///         ]),
+    ///     ]),
+    ///     Node::new(false, 4),
+    /// ]);
+    ///
+    /// assert_eq!(Bot::new(true).select(&tree, ToCompletion), Some(1));
+    /// // Only search the most promising reply of the opponent, missing the trap.
+    /// let mut bot = Bot::new(true).with_search_widths(vec![1]);
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(0));
+    /// ```
+    ///
+    /// [policy]: #method.with_policy
+    pub fn with_search_widths(mut self, widths: Vec<usize>) -> Self {
+        self.widths = widths;
+        self
+    }
+
+    /// Tries to completely solve the game using at most `max_steps` steps before searching normally.
+    ///
+    /// The attempt ignores [`RunCondition::depth`][depth], so small endgames are always played perfectly,
+    /// even when only searching up to a fixed [`Depth`][de]. In case the game is solved, the returned
+    /// action is marked as [`proven`][proven]. Otherwise the attempt is discarded, so this wastes up to `max_steps`
+    /// steps in positions which are too large to be solved.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, Depth, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 0).with_children(&[
+    ///         Node::new(true, 10).with_children(&[
+    ///             Node::new(false, 6).with_children(&[
+    ///                 Node::new(true, 9).with_children(&[
+    ///                     Node::new(false, 1),
+    ///                 ]),
+    ///             ]),
+    ///         ]),
+    ///     ]),
+    ///     Node::new(false, 5),
+    /// ]);
+    ///
+    /// let action = Bot::new(true).detailed_select(&tree, Depth(2)).unwrap();
+    /// assert_eq!((action.path[0], action.proven), (0, false));
+    ///
+    /// let mut bot = Bot::new(true).with_endgame_solver(100);
+    /// let action = bot.detailed_select(&tree, Depth(2)).unwrap();
+    /// assert_eq!((action.path[0], action.proven), (1, true));
+    /// ```
+    ///
+    /// [depth]: ../trait.RunCondition.html#tymethod.depth
+    /// [de]: ../struct.Depth.html
+    /// [proven]: struct.Action.html#structfield.proven
+    pub fn with_endgame_solver(mut self, max_steps: u64) -> Self {
+        self.endgame_solver = Some(max_steps);
+        self
+    }
+
+    /// Records each state at which a pruning rule skipped actions in [`SearchStats::pruned`][pruned].
+    ///
+    /// This is useful to check what is actually pruned when tuning options like
+    /// [`with_search_widths`][widths], but slows down the search and uses memory for each pruned state.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, alpha_beta::PruningRule, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 7).with_children(&[
+    ///         Node::new(true, 4),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 1),
+    ///         Node::new(true, 9),
+    ///     ]),
+    /// ]);
+    ///
+    /// let mut bot = Bot::new(true).with_pruning_trace();
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(0));
+    /// let cutoffs = bot.stats().pruning(PruningRule::Cutoff);
+    /// assert_eq!((cutoffs.states, cutoffs.skipped), (1, 1));
+    /// assert_eq!(bot.stats().pruned[0].ply, 1);
+    /// ```
+    ///
+    /// [pruned]: struct.SearchStats.html#structfield.pruned
+    /// [widths]: #method.with_search_widths
+    pub fn with_pruning_trace(mut self) -> Self {
+        self.trace_pruning = true;
+        self
+    }
+
+    /// Enables [`with_pruning_trace`][trace] and additionally records how far the fitness
+    /// causing a cutoff, null move or transposition was outside of the window in [`Pruned::margin`][margin].
+    ///
+    /// As the fitness does not have to be numeric, `value` is used to convert it first.
+    /// This is useful to tune margins which decide what is pruned, like the reduction of null moves.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, alpha_beta::PruningRule, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 7).with_children(&[
+    ///         Node::new(true, 4),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 1),
+    ///         Node::new(true, 9),
+    ///     ]),
+    /// ]);
+    ///
+    /// let mut bot = Bot::new(true).with_pruning_margins(f64::from);
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(0));
+    /// // The second action is worse by at least `2 - 1`.
+    /// assert_eq!(bot.stats().pruning(PruningRule::Cutoff).min_margin, Some(1.0));
+    /// ```
+    ///
+    /// [trace]: #method.with_pruning_trace
+    /// [margin]: struct.Pruned.html#structfield.margin
+    pub fn with_pruning_margins(mut self, value: fn(T::Fitness) -> f64) -> Self {
+        self.trace_pruning = true;
+        self.pruning_margins = Some(value);
+        self
+    }
+
+    /// Records the states explored by each call to [`select`][sel] in a [`SearchTree`][tree],
+    /// which can be written in the DOT format of [Graphviz][gv] to visualize small searches.
+    ///
+    /// At most `max_nodes` states are recorded, including the initial state. States reached afterwards are
+    /// omitted, so this can also be used to only look at the start of larger searches.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, alpha_beta::Bound, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 7).with_children(&[
+    ///         Node::new(true, 4),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 1),
+    ///         Node::new(true, 9),
+    ///     ]),
+    /// ]);
+    ///
+    /// let mut bot = Bot::new(true).with_search_tree(100);
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(0));
+    /// let searched = bot.search_tree().unwrap();
+    /// let first = &searched.nodes()[1];
+    /// assert_eq!((first.parent, first.action, first.fitness), (Some(0), Some(0), Some(Bound::Exact(2))));
+    ///
+    /// let mut dot = Vec::new();
+    /// searched.write_dot(&mut dot).unwrap();
+    /// assert!(String::from_utf8(dot).unwrap().starts_with("digraph {"));
+    /// ```
+    ///
+    /// [sel]: #method.select
+    /// [tree]: struct.SearchTree.html
+    /// [gv]: https://graphviz.org
+    pub fn with_search_tree(mut self, max_nodes: usize) -> Self {
+        self.search_tree = Some(max_nodes);
+        self
+    }
+
+    /// Remembers the last two actions at each ply which caused a cutoff, and searches them first
+    /// in other states at the same ply in case they are possible there.
+    ///
+    /// An action which refutes one action of the opponent often also refutes its other actions, so this
+    /// causes cutoffs to happen earlier for games where the same action is possible in many sibling states.
+    /// This is known as the [killer heuristic][killer]. Remembered actions are searched after the best action
+    /// of the previous iteration and before all other actions, which are otherwise ordered using
+    /// [`with_policy`][policy].
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 7).with_children(&[
+    ///         Node::new(true, 4),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 8),
+    ///         Node::new(true, 9)
+    ///     ]),
+    /// ]);
+    ///
+    /// let mut bot = Bot::new(true).with_killer_moves();
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(1));
+    /// ```
+    ///
+    /// [killer]: https://en.wikipedia.org/wiki/Killer_heuristic
+    /// [policy]: #method.with_policy
+    pub fn with_killer_moves(mut self) -> Self {
+        self.killer_moves = true;
+        self
+    }
+
+    /// Counts how often each action caused a cutoff, weighted by the remaining depth, and searches actions
+    /// with a higher count first. This is known as the [history heuristic][history].
+    ///
+    /// Unlike [`with_killer_moves`][killer], the count of an action is shared between all states, so this
+    /// works best for games where the same action is good in many different states, e.g. moving a piece
+    /// between two squares. Actions are identified using `key`, which should return small numbers as the
+    /// memory used by the table is proportional to the largest key. The actions of the bot and its opponents
+    /// are counted separately.
+    ///
+    /// Actions with the same count are ordered using [`with_policy`][policy] or their fitness,
+    /// so the count is only used at states which are not searched at the lowest depth.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 7).with_children(&[
+    ///         Node::new(true, 4),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 8),
+    ///         Node::new(true, 9)
+    ///     ]),
+    /// ]);
+    ///
+    /// // The actions of `Node` are their index.
+    /// let mut bot = Bot::new(true).with_history_heuristic(|&action| action);
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(1));
+    /// ```
+    ///
+    /// [history]: https://www.chessprogramming.org/History_Heuristic
+    /// [killer]: #method.with_killer_moves
+    /// [policy]: #method.with_policy
+    pub fn with_history_heuristic(mut self, key: fn(&T::Action) -> usize) -> Self {
+        self.history_heuristic = Some(key);
+        self
+    }
+
+    /// Before searching the actions of a state, first searches the state after the active player
+    /// skipped their turn, searching `reduction` fewer plies than usual. In case skipping the turn is already
+    /// good enough to cause a cutoff, all actions of the state are skipped. This is known as
+    /// [null-move pruning][null].
+    ///
+    /// `skip_turn` should skip the turn of the active player and return `true`, or return `false` without
+    /// modifying the state in case the turn must not be skipped. This assumes that executing an action is always
+    /// better than doing nothing, so `skip_turn` should return `false` if this is not the case, e.g. in endgames
+    /// where players are forced to weaken their position. Two turns are never skipped directly after each other.
+    ///
+    /// As this may prune good actions, results are never proven when using null-move pruning.
+    /// Cutoffs are kept once the search after skipping the turn reached the end of the game,
+    /// so [`ToCompletion`][tc] still terminates.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, Game, ToCompletion};
+    ///
+    /// /// Players alternately take up to three coins, every coin being worth
+    /// /// the amount of coins which were left before taking it.
+    /// #[derive(Clone)]
+    /// struct Coins { coins: u8, our_turn: bool, score: i16 }
+    ///
+    /// impl Game for Coins {
+    ///     type Player = ();
+    ///     type Action = u8;
+    ///     type Fitness = i16;
+    ///     type Actions = std::ops::RangeInclusive<u8>;
+    ///
+    ///     fn actions(&self, _: ()) -> (bool, Self::Actions) {
+    ///         (self.our_turn, 1..=self.coins.min(3))
+    ///     }
+    ///
+    ///     fn execute(&mut self, &take: &u8, _: ()) -> i16 {
+    ///         let value: i16 = (0..take).map(|i| i16::from(self.coins - i)).sum();
+    ///         self.score += if self.our_turn { value } else { -value };
+    ///         self.coins -= take;
+    ///         self.our_turn = !self.our_turn;
+    ///         self.score
+    ///     }
+    /// }
+    ///
+    /// fn skip_turn(coins: &mut Coins) -> bool {
+    ///     coins.our_turn = !coins.our_turn;
+    ///     true
+    /// }
+    ///
+    /// let coins = Coins { coins: 8, our_turn: true, score: 0 };
+    /// let mut bot = Bot::new(()).with_null_move_pruning(1, skip_turn);
+    /// assert_eq!(bot.select(&coins, ToCompletion), Some(3));
+    /// ```
+    ///
+    /// [null]: https://en.wikipedia.org/wiki/Null-move_heuristic
+    /// [tc]: ../struct.ToCompletion.html
+    pub fn with_null_move_pruning(mut self, reduction: u32, skip_turn: fn(&mut T) -> bool) -> Self {
+        self.null_move = Some((reduction, skip_turn));
+        self
+    }
+
+    /// Executes noisy actions at the lowest depth, which are actions for which `Game::is_quiet` returns `false`,
+    /// and searches up to `plies` further noisy actions after them. This is known as [quiescence search][qs].
+    ///
+    /// Evaluating a capture without considering the recapture of the opponent causes the bot to blunder,
+    /// as the search stops in the middle of an exchange. After a noisy action, the player to move may either
+    /// execute one of their noisy actions, or keep the current fitness by executing a quiet action.
+    /// Vetoes and opponent models are not used during the quiescence search.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, Depth, Game, tree::Node};
+    ///
+    /// /// The first action of each state is a capture.
+    /// #[derive(Clone)]
+    /// struct Captures(Node);
+    ///
+    /// impl Game for Captures {
+    ///     type Player = bool;
+    ///     type Action = usize;
+    ///     type Fitness = i8;
+    ///     type Actions = std::ops::Range<usize>;
+    ///
+    ///     fn actions(&self, player: bool) -> (bool, Self::Actions) {
+    ///         self.0.actions(player)
+    ///     }
+    ///
+    ///     fn execute(&mut self, action: &usize, player: bool) -> i8 {
+    ///         self.0.execute(action, player)
+    ///     }
+    ///
+    ///     fn is_quiet(&self, &action: &usize, _: bool) -> bool {
+    ///         action != 0
+    ///     }
+    /// }
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Captures(Node::root().with_children(&[
+    ///     Node::new(false, 4).with_children(&[
+    ///         // The opponent can capture, but we are able to recapture.
+    ///         Node::new(true, 2).with_children(&[
+    ///             Node::new(false, 9),
+    ///         ]),
+    ///         Node::new(true, 6),
+    ///     ]),
+    ///     Node::new(false, 4).with_children(&[
+    ///         Node::new(true, 3),
+    ///         Node::new(true, 4),
+    ///     ]),
+    /// ]));
+    ///
+    /// // `Depth(1)` searches 2 plies, so the bot does not see the recapture.
+    /// assert_eq!(Bot::new(true).select(&tree, Depth(1)), Some(1));
+    ///
+    /// let mut bot = Bot::new(true).with_quiescence_search(4);
+    /// assert_eq!(bot.select(&tree, Depth(1)), Some(0));
+    /// ```
+    ///
+    /// [qs]: https://en.wikipedia.org/wiki/Quiescence_search
+    pub fn with_quiescence_search(mut self, plies: u32) -> Self {
+        self.quiescence = Some(plies);
+        self
+    }
+
+    /// Limits the memory used by internal structures of the search to roughly `bytes`.
+    ///
+    /// The transposition table of [`with_transposition_table`][tt] is shrunk to at most half of `bytes`.
+    /// Before each iteration of iterative deepening, the memory used by the search is estimated and
+    /// in case it exceeds `bytes`, the search stops and returns the best action of the last iteration.
+    /// The estimate does not include memory owned by game states or actions, e.g. in a `Vec`.
+    /// Both are recorded in [`SearchStats::table_shrunk`][shrunk] and [`SearchStats::memory_limit_reached`][reached].
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 7).with_children(&[
+    ///         Node::new(true, 4),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 8),
+    ///         Node::new(true, 9),
+    ///     ]),
+    /// ]);
+    ///
+    /// // The search already stops before the first iteration.
+    /// let mut bot = Bot::new(true).with_memory_limit(0);
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(0));
+    ///
+    /// let mut bot = Bot::new(true).with_memory_limit(1 << 20);
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(1));
+    /// ```
+    ///
+    /// [tt]: #method.with_transposition_table
+    /// [shrunk]: struct.SearchStats.html#structfield.table_shrunk
+    /// [reached]: struct.SearchStats.html#structfield.memory_limit_reached
+    pub fn with_memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = Some(bytes);
+        self
+    }
+
+    /// Measures time using `clock` instead of the [`SystemClock`][system], which is used for
+    /// [`SearchStats::duration`][duration] and [`Iteration::elapsed`][elapsed].
+    ///
+    /// Together with a [`Timeout`][timeout] using the same clock, this allows searching on platforms
+    /// without `std::time::Instant`, like `wasm32-unknown-unknown`.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, Timeout, tree::Node};
+    /// use std::time::Duration;
+    ///
+    /// fn now() -> Duration {
+    ///     // On `wasm32`, this could convert the milliseconds of `performance.now()` instead.
+    ///     std::time::SystemTime::UNIX_EPOCH.elapsed().unwrap()
+    /// }
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 7).with_children(&[
+    ///         Node::new(true, 4),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 8),
+    ///         Node::new(true, 9),
+    ///     ]),
+    /// ]);
+    ///
+    /// let mut bot = Bot::new(true).with_clock(now);
+    /// assert_eq!(bot.select(&tree, Timeout::new(now, Duration::from_secs(1))), Some(1));
+    /// ```
+    ///
+    /// [system]: ../struct.SystemClock.html
+    /// [duration]: struct.SearchStats.html#structfield.duration
+    /// [elapsed]: struct.Iteration.html#structfield.elapsed
+    /// [timeout]: ../struct.Timeout.html
+    pub fn with_clock(mut self, clock: impl Clock + Send + Sync + 'static) -> Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
+    /// Randomly chooses between top level actions with the same fitness instead of always selecting
+    /// the first one returned by `Game::actions`, using the seed set by [`with_seed`][seed].
+    ///
+    /// This shuffles the top level actions before each search, so it only decides between actions
+    /// which are equally good at the searched depth, and the results stay reproducible for a given seed.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    ///
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 2),
+    ///     Node::new(false, 5),
+    ///     Node::new(false, 5),
+    ///     Node::new(false, 5),
+    /// ]);
+    ///
+    /// assert_eq!(Bot::new(true).select(&tree, ToCompletion), Some(1));
+    ///
+    /// let mut bot = Bot::new(true).with_random_ties().with_seed(3);
+    /// let selected: Vec<_> = (0..20).map(|_| bot.select(&tree, ToCompletion).unwrap()).collect();
+    /// assert!(selected.iter().all(|&action| action != 0));
+    /// assert!(selected.iter().any(|&action| action != 1));
+    /// ```
+    ///
+    /// [seed]: #method.with_seed
+    pub fn with_random_ties(mut self) -> Self {
+        self.random_ties = true;
+        self
+    }
+
+    /// Sets the seed used to randomly choose actions in [`select_with_temperature`][temp] and with
+    /// [`with_random_ties`][ties], so the selected actions are reproducible when using a deterministic
+    /// run condition.
+    ///
+    /// [temp]: #method.select_with_temperature
+    /// [ties]: #method.with_random_ties
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = XorShiftRng::from_seed_u64(seed);
+        self
+    }
+
+    /// Creates the transposition table of a new search, respecting the memory limit.
+    fn new_table(&self) -> Option<Table<T>> {
+        let (hash, entries) = self.transposition_table?;
+        let entries = match self.memory_limit {
+            Some(bytes) => {
+                let max = cmp::max(bytes / 2 / mem::size_of::<Option<Entry<T>>>(), 1);
+                // `Table::new` rounds up to a power of two, so round `max` down to one.
+                cmp::min(entries, 1 << (usize::BITS - 1 - max.leading_zeros()))
+            }
+            None => entries,
+        };
+        Some(Table::new(hash, entries))
+    }
+
+    /// Returns the clock set by `with_clock`, or the `SystemClock` if there is none.
+    fn clock(&self) -> &(dyn Clock + Send + Sync) {
+        self.clock.as_deref().unwrap_or(&SystemClock)
+    }
+
+    /// Returns statistics about the last call to `select`, `detailed_select` or `try_select`.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 7).with_children(&[
+    ///         Node::new(true, 4),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 8),
+    ///         Node::new(true, 9)
+    ///     ]),
+    /// ]);
+    ///
+    /// let mut bot = Bot::new(true);
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(1));
+    /// assert_eq!(bot.stats().max_ply, 2);
+    /// assert_eq!(bot.stats().leaves, 4);
+    /// ```
+    pub fn stats(&self) -> &SearchStats {
+        &self.stats
+    }
+
+    /// Returns the states explored during the last search, which are only recorded
+    /// when using [`with_search_tree`][tree].
+    ///
+    /// [tree]: #method.with_search_tree
+    pub fn search_tree(&self) -> Option<&SearchTree<T>> {
+        self.tree.as_ref()
+    }
+
+    /// Searches `action` first during the next call to [`select`][sel], for example the
+    /// expected action of the previous search or a suggestion of an opening book.
+    ///
+    /// The hint is searched at the full depth of each iteration and is only replaced once
+    /// a better action has been found, so an interrupted search returns the hint unless it was refuted.
+    /// A hint which is not a possible action is ignored.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, Steps, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 3),
+    ///     ]),
+    ///     Node::new(false, 4).with_children(&[
+    ///         Node::new(true, 3),
+    ///     ]),
+    /// ]);
+    ///
+    /// let mut bot = Bot::new(true);
+    /// assert_eq!(bot.select(&tree, Steps(0)), Some(0));
+    /// bot.set_hint(1);
+    /// assert_eq!(bot.select(&tree, Steps(0)), Some(1));
+    /// // The hint is only used once.
+    /// assert_eq!(bot.select(&tree, Steps(0)), Some(0));
+    /// ```
+    ///
+    /// [sel]: #method.select
+    pub fn set_hint(&mut self, action: T::Action) {
+        self.hint = Some(action);
+    }
+
+    /// Removes all states kept in the transposition table when using [`with_persistent_table`][keep].
+    ///
+    /// [keep]: #method.with_persistent_table
+    pub fn clear_table(&mut self) {
+        self.kept_table = None;
+    }
+
+    /// Returns the player this bot selects actions for.
+    pub fn player(&self) -> T::Player {
+        self.player
+    }
+
+    /// Changes the player this bot selects actions for, keeping all other options.
+    ///
+    /// This allows a single bot to play both sides of a game, or to evaluate positions from the
+    /// perspective of either player.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, Game, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let mut tree = Node::root().with_children(&[
+    ///     Node::new(false, 4).with_children(&[
+    ///         Node::new(true, 1),
+    ///         Node::new(true, 2),
+    ///     ]),
+    /// ]);
+    ///
+    /// let mut bot = Bot::new(true).with_max_depth(8);
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(0));
+    /// tree.execute(&0, true);
+    ///
+    /// // `true` is no longer the active player.
+    /// assert_eq!(bot.select(&tree, ToCompletion), None);
+    /// bot.set_player(false);
+    /// assert!(!bot.player());
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(1));
+    /// ```
+    pub fn set_player(&mut self, player: T::Player) {
+        self.player = player;
+        // The fitness of the kept states is from the perspective of the previous player.
+        self.kept_table = None;
+    }
+
+    /// Returns a chosen action based on the given game state.
+    ///
+    /// Returns  `None` if no `Action` is possible or the bot is currently not the active player.
+    ///
+    /// This method runs until either the best possible action was found
+    /// or one of `RunCondition::depth`, `RunCondition::step` and `RunCondition::check` returned `false`.
+    ///
+    /// The run condition is checked before each call to `Game::execute` and `Game::actions`,
+    /// so when using a deadline, this method returns at most the duration of the slowest of these calls
+    /// or of a single call to `Game::look_ahead_batch` after the deadline has passed,
+    /// ignoring a small constant overhead.
+    ///
+    /// If an iteration is interrupted, the top level actions which were already completely searched
+    /// during this iteration are still used, so only the work on the currently searched action is lost.
+    /// In case [`RunCondition::remaining`][rem] is known, e.g. when using a `Duration`, an iteration is not
+    /// started at all if the time spent on the previous iterations predicts that it takes longer than the remaining time.
+    ///
+    /// [rem]: ../trait.RunCondition.html#method.remaining
+    pub fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action> {
+        self.inner_select(state, condition, None)
+            .map(|mut act| act.path.pop().unwrap())
+    }
+
+    /// Similar to `select`, except that this function also returns the fitness of the given action,
+    /// which is the fitness expected after searching as deep as possible.
+    ///
+    /// This is cheaper than [`detailed_select`][detailed] in case the principal variation is not needed.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 7).with_children(&[
+    ///         Node::new(true, 4),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 8),
+    ///         Node::new(true, 9)
+    ///     ]),
+    /// ]);
+    ///
+    /// let mut bot = Bot::new(true);
+    /// assert_eq!(bot.select_with_fitness(&tree, ToCompletion), Some((1, 8)));
+    /// ```
+    ///
+    /// [detailed]: #method.detailed_select
+    pub fn select_with_fitness<U: IntoRunCondition>(
+        &mut self,
+        state: &T,
+        condition: U,
+    ) -> Option<(T::Action, T::Fitness)> {
+        self.inner_select(state, condition, None)
+            .map(|mut act| (act.path.pop().unwrap(), act.fitness))
+    }
+
+    /// Similar to `select`, except that this function also returns the principal variation and the
+    /// final evaluation of the given action.
+    ///
+    /// The actions are sorted in order they are executed, so
+    /// `action.path[0]` is always equal to the result of `select`.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(true, 4),
+    ///     Node::new(true, 0).with_children(&[
+    ///         Node::new(true, 5), // This is the best possible result.
+    ///         Node::new(true, 3),
+    ///     ])
+    /// ]);
+    ///
+    /// assert_eq!(&Bot::new(true)
+    ///     .detailed_select(&tree, ToCompletion)
+    ///     .unwrap()
+    ///     .path, &[1, 0]);
+    /// ```
+    pub fn detailed_select<U: IntoRunCondition>(
+        &mut self,
+        state: &T,
+        condition: U,
+    ) -> Option<Action<T>> {
+        self.inner_select(state, condition, None).map(|act| {
+            act.tap(|act| {
+                act.path.reverse();
+                if let Some(reply) = act.path.get(1) {
+                    let mut state = state.clone();
+                    state.execute(&act.path[0], self.player);
+                    if !state.actions(self.player).0 {
+                        act.reply = Some(reply.clone());
+                    }
+                }
+            })
+        })
+    }
+
+    /// Similar to `select`, except that this function also returns statistics about the search,
+    /// which are useful to tune the fitness function and the time spent on each move.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 7).with_children(&[
+    ///         Node::new(true, 4),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 8),
+    ///         Node::new(true, 9)
+    ///     ]),
+    /// ]);
+    ///
+    /// let mut bot = Bot::new(true);
+    /// let report = bot.select_verbose(&tree, ToCompletion).unwrap();
+    /// assert_eq!((report.action, report.fitness), (1, 8));
+    /// assert_eq!(report.depth, Some(1));
+    /// assert_eq!(report.stats.nodes, bot.stats().nodes);
+    /// assert_eq!(report.durations().count(), report.iterations.len());
+    /// ```
+    pub fn select_verbose<U: IntoRunCondition>(
+        &mut self,
+        state: &T,
+        condition: U,
+    ) -> Option<Report<T>> {
+        let mut act = self.inner_select(state, condition, None)?;
+        Some(Report {
+            action: act.path.pop().unwrap(),
+            fitness: act.fitness,
+            depth: act.iterations.last().map(|iteration| iteration.depth),
+            iterations: act.iterations,
+            stats: self.stats.clone(),
+        })
+    }
+
+    /// Similar to `select`, except that `progress` is called with the result of each completed iteration,
+    /// which allows showing the currently best action while the search is still running.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 7).with_children(&[
+    ///         Node::new(true, 4),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 8),
+    ///         Node::new(true, 9)
+    ///     ]),
+    /// ]);
+    ///
+    /// let mut considered = Vec::new();
+    /// let selected = Bot::new(true).select_with_progress(&tree, ToCompletion, |iteration| {
+    ///     considered.push((iteration.depth, iteration.action, iteration.fitness));
+    /// });
+    /// assert_eq!(selected, Some(1));
+    /// assert_eq!(considered, vec![(0, 1, 8), (1, 1, 8)]);
+    /// ```
+    pub fn select_with_progress<U: IntoRunCondition>(
+        &mut self,
+        state: &T,
+        condition: U,
+        mut progress: impl FnMut(&Iteration<T>),
+    ) -> Option<T::Action> {
+        self.inner_select(state, condition, Some(&mut progress))
+            .map(|mut act| act.path.pop().unwrap())
+    }
+
+    /// Returns every possible top level action together with its fitness, sorted from best to worst.
+    /// Returns an empty `Vec` if no action is possible or the bot is currently not the active player.
+    ///
+    /// Unlike `select`, which only proves that the other actions are worse than the selected one,
+    /// this computes the exact fitness of each action, which is a lot slower. All actions are searched
+    /// at the same depth, so if the search is cancelled, the results of the last completed iteration
+    /// are returned. Actions with the same fitness keep the order of `Game::actions`.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 7).with_children(&[
+    ///         Node::new(true, 4),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 8),
+    ///         Node::new(true, 9)
+    ///     ]),
+    ///     Node::new(false, 3),
+    /// ]);
+    ///
+    /// let mut bot = Bot::new(true);
+    /// assert_eq!(bot.rank_actions(&tree, ToCompletion), vec![(1, 8), (2, 3), (0, 2)]);
+    /// ```
+    pub fn rank_actions<U: IntoRunCondition>(
+        &mut self,
+        state: &T,
+        condition: U,
+    ) -> Vec<(T::Action, T::Fitness)> {
+        let start = self.clock().now();
+        self.stats = SearchStats::default();
+        self.tree = None;
+        let mut ranked = self.rank_with_stats(state, start, condition);
+        self.stats.duration = self.clock().now().saturating_sub(start);
+        ranked.sort_by_key(|&(_, fitness)| Reverse(fitness));
+        ranked
+    }
+
+    /// Returns a random action, where each action is chosen with a probability proportional to
+    /// `exp(fitness / temperature)` using the fitness computed by [`rank_actions`][rank].
+    ///
+    /// A high `temperature` chooses nearly uniformly between all actions, while a low one
+    /// nearly always chooses the best action. This is useful to create weaker opponents which
+    /// still play reasonable actions. Returns `None` if no action is possible or the bot
+    /// is currently not the active player.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `temperature` is not positive.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 3),
+    ///     Node::new(false, 2),
+    ///     Node::new(false, -20),
+    /// ]);
+    ///
+    /// let mut bot = Bot::new(true).with_seed(7);
+    /// let mut counts = [0; 3];
+    /// for _ in 0..100 {
+    ///     counts[bot.select_with_temperature(&tree, 1.0, ToCompletion).unwrap()] += 1;
+    /// }
+    /// assert!(counts[0] > counts[1] && counts[1] > 0);
+    /// assert_eq!(counts[2], 0);
+    /// ```
+    ///
+    /// [rank]: #method.rank_actions
+    pub fn select_with_temperature<U: IntoRunCondition>(
+        &mut self,
+        state: &T,
+        temperature: f64,
+        condition: U,
+    ) -> Option<T::Action>
+    where
+        T::Fitness: Into<f64>,
+    {
+        assert!(temperature > 0.0, "temperature must be positive");
+        let ranked = self.rank_actions(state, condition);
+        // `ranked` is sorted by fitness, so the first weight is `1` and no weight overflows.
+        let best = ranked.first()?.1.into();
+        let weights: Vec<_> = ranked
+            .iter()
+            .map(|&(_, fitness)| ((fitness.into() - best) / temperature).exp())
+            .collect();
+        let mut remaining = self.rng.next_f64() * weights.iter().sum::<f64>();
+        for ((action, _), weight) in ranked.iter().zip(&weights) {
+            remaining -= weight;
+            if remaining < 0.0 {
+                return Some(action.clone());
+            }
+        }
+        // Rounding errors may cause `remaining` to stay slightly positive.
+        ranked.into_iter().map(|(action, _)| action).next()
+    }
+
+    fn rank_with_stats<U: IntoRunCondition>(
+        &mut self,
+        state: &T,
+        start: Duration,
+        condition: U,
+    ) -> Vec<(T::Action, T::Fitness)> {
+        let mut condition = condition.into_run_condition();
+
+        let (active, actions) = state.actions(self.player);
+        if !active {
+            return Vec::new();
+        }
+
+        let actions: Vec<_> = actions.into_iter().collect();
+        let mut fitness = Vec::with_capacity(actions.len());
+        state.look_ahead_batch(&actions, self.player, &mut fitness);
+        debug_assert_eq!(actions.len(), fitness.len());
+        for fitness in &mut fitness {
+            *fitness = apply_contempt::<T>(self.contempt, *fitness);
+        }
+
+        // The fitness of each action, the path of its children and whether it is already terminated.
+        let mut ranked: Vec<_> = fitness
+            .into_iter()
+            .map(|fitness| (fitness, Vec::new(), false))
+            .collect();
+        let mut ctxt = Ctxt::new(state, self, start, Vec::new());
+        let (first, step) = self.depth_schedule;
+        'outer: for depth in (first..).step_by(step as usize) {
+            match self.parity {
+                Some(Parity::Even) if depth % 2 == 1 => continue,
+                Some(Parity::Odd) if depth % 2 == 0 => continue,
+                _ => (),
+            }
+
+            if ranked.iter().all(|&(_, _, terminated)| terminated)
+                || !condition.depth(depth)
+                || self.max_depth.is_some_and(|max| depth >= max)
+            {
+                break;
+            }
+
+            let mut next = Vec::with_capacity(ranked.len());
+            for (action, (fitness, path, terminated)) in actions.iter().zip(&ranked) {
+                if *terminated {
+                    next.push((*fitness, path.clone(), true));
+                    continue;
+                }
+
+                if !condition.check() {
+                    break 'outer;
+                }
+
+                let mut updated_state = state.clone();
+                let fitness = ctxt.contempt(updated_state.execute(action, self.player));
+                let replayed = cmp::min(path.len(), depth as usize);
+                let result = ctxt.minimax_with_path(
+                    path.iter().cloned().rev().take(replayed),
+                    updated_state,
+                    depth * PLY,
+                    None,
+                    None,
+                    &mut condition,
+                );
+                next.push(match result {
+                    Err(CancelledError) => break 'outer,
+                    Ok(MiniMax::DeadEnd) => (fitness, Vec::new(), true),
+                    Ok(MiniMax::Repetition) => (ctxt.repetition_fitness(), Vec::new(), true),
+                    Ok(MiniMax::Terminated(path, branch)) => (branch.fitness(), path, true),
+                    Ok(MiniMax::Open(path, branch)) => (branch.fitness(), path, false),
+                });
+            }
+            ranked = next;
+        }
+
+        let stats = ctxt.stats;
+        self.stats.add(stats);
+        actions
+            .into_iter()
+            .zip(ranked)
+            .map(|(action, (fitness, _, _))| (action, fitness))
+            .collect()
+    }
+
+    /// Similar to `select`, except that panics inside of the methods of [`Game`][game] during the search
+    /// are caught and returned as [`SelectError::Panicked`][panicked].
+    ///
+    /// The bot can still be used after a panic, which is useful in case a bug in some rarely reached
+    /// part of a game should not bring down the whole application. Note that the panic hook is still
+    /// called as usual, so the panic message is printed to stderr by default.
+    ///
+    /// As the panic may have happened while the state of the bot was updated, the table kept by
+    /// [`with_persistent_table`][keep], the hint of [`set_hint`][hint], the [`stats`][stats] and the
+    /// [`search_tree`][tree] are discarded in this case, so the next search starts from scratch.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, Game, SelectError, ToCompletion, tree::Node};
+    ///
+    /// #[derive(Clone)]
+    /// struct Buggy(Node);
+    ///
+    /// impl Game for Buggy {
+    ///     type Player = bool;
+    ///     type Action = usize;
+    ///     type Fitness = i8;
+    ///     type Actions = std::ops::Range<usize>;
+    ///
+    ///     fn actions(&self, player: bool) -> (bool, Self::Actions) {
+    ///         self.0.actions(player)
+    ///     }
+    ///
+    ///     fn execute(&mut self, action: &usize, player: bool) -> i8 {
+    ///         assert!(self.0.child_count() < 2, "oh no");
+    ///         self.0.execute(action, player)
+    ///     }
+    /// }
+    ///
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(true, 3),
+    ///     Node::new(true, 2),
+    /// ]);
+    /// # std::panic::set_hook(Box::new(|_| ()));
+    ///
+    /// let mut bot = Bot::new(true);
+    /// match bot.try_select(&Buggy(tree.clone()), ToCompletion) {
+    ///     Err(SelectError::Panicked(_)) => (),
+    ///     _ => unreachable!(),
+    /// }
+    ///
+    /// // The bot is still usable after a panic.
+    /// assert_eq!(bot.try_select(&Buggy(Node::root()), ToCompletion).unwrap(), None);
+    /// ```
+    ///
+    /// [game]: ../trait.Game.html
+    /// [panicked]: ../enum.SelectError.html#variant.Panicked
+    /// [keep]: #method.with_persistent_table
+    /// [hint]: #method.set_hint
+    /// [stats]: #method.stats
+    /// [tree]: #method.search_tree
+    pub fn try_select<U: IntoRunCondition>(
+        &mut self,
+        state: &T,
+        condition: U,
+    ) -> Result<Option<T::Action>, SelectError> {
+        panic::catch_unwind(AssertUnwindSafe(|| self.select(state, condition))).map_err(|err| {
+            // Everything kept between searches is reset, so the bot is in a consistent state again.
+            self.kept_table = None;
+            self.hint = None;
+            self.stats = SearchStats::default();
+            self.tree = None;
+            SelectError::Panicked(err)
+        })
+    }
+
+    fn inner_select<U: IntoRunCondition>(
+        &mut self,
+        state: &T,
+        condition: U,
+        progress: Option<Progress<'_, T>>,
+    ) -> Option<Action<T>> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("select").entered();
+        let start = self.clock().now();
+        self.stats = SearchStats::default();
+        self.tree = None;
+        let result = self.search_with_stats(state, start, condition, None, progress);
+        self.stats.duration = self.clock().now().saturating_sub(start);
+        event!(
+            debug,
+            nodes = self.stats.nodes,
+            cutoffs = self.stats.cutoffs,
+            duration = ?self.stats.duration,
+            proven = result.as_ref().is_some_and(|act| act.proven),
+            "selected action"
+        );
+        result
+    }
+
+    /// Searches `state`, using `shared` as the transposition table if it is set
+    /// and calling `progress` after each completed iteration.
+    fn search_with_stats<U: IntoRunCondition>(
+        &mut self,
+        state: &T,
+        start: Duration,
+        condition: U,
+        shared: Option<&SharedTable<T>>,
+        progress: Option<Progress<'_, T>>,
+    ) -> Option<Action<T>> {
+        let mut condition = condition.into_run_condition();
+        let hint = self.hint.take();
+
+        let (active, actions) = state.actions(self.player);
+        if !active {
+            return None;
+        }
+
+        let mut actions: Vec<_> = actions.into_iter().collect();
+        if actions.is_empty() {
+            return None;
+        }
+
+        if let Some(veto) = &self.veto {
+            let player = self.player;
+            let skipped = retain_actions(&mut actions, &mut Vec::new(), |action| {
+                !veto.vetoes(state, action, player)
+            });
+            if self.trace_pruning && skipped > 0 {
+                self.stats.pruned.push(Pruned {
+                    rule: PruningRule::Veto,
+                    ply: 0,
+                    skipped,
+                    margin: None,
+                });
+            }
+        }
+
+        if self.random_ties {
+            // Actions with the same fitness keep their order, so the first of them is selected.
+            for i in (1..actions.len()).rev() {
+                actions.swap(i, self.rng.below(i + 1));
+            }
+        }
+        // The sort is stable, so random ties are only broken between actions with the same order hint.
+        let player = self.player;
+        actions.sort_by_key(|action| Reverse(state.order_hint(action, player)));
+
+        let mut fitness = Vec::with_capacity(actions.len());
+        state.look_ahead_batch(&actions, self.player, &mut fitness);
+        debug_assert_eq!(actions.len(), fitness.len());
+        for fitness in &mut fitness {
+            *fitness = apply_contempt::<T>(self.contempt, *fitness);
+        }
+        let top_level = |actions: &[T::Action]| -> Vec<Action<T>> {
+            actions
+                .iter()
+                .zip(&fitness)
+                .map(|(action, &fitness)| Action {
+                    fitness,
+                    path: vec![action.clone()],
+                    proven: false,
+                    iterations: Vec::new(),
+                    reply: None,
+                })
+                .collect()
+        };
+
+        // Searches which restrict the possible actions can never prove anything.
+        let exact = self.opponent_model.is_none()
+            && self.veto.is_none()
+            && self.widths.is_empty()
+            && self.null_move.is_none();
+        // A known best action of the initial state does not have to be searched.
+        let probed = self
+            .tablebase
+            .as_ref()
+            .and_then(|tb| tb.probe(state, self.player));
+        if let Some((fitness, Some(action))) = probed {
+            if actions.contains(&action) {
+                self.stats.tablebase_hits = self.stats.tablebase_hits.saturating_add(1);
+                return Some(Action {
+                    fitness,
+                    path: vec![action],
+                    proven: exact,
+                    iterations: Vec::new(),
+                    reply: None,
+                });
+            }
+        }
+        // The states explored by the endgame solver are kept in the same tree as the ones of the normal search.
+        let mut tree = None;
+        if let Some(steps) = self.endgame_solver {
+            let mut ctxt = Ctxt::new(state, self, start, top_level(&actions));
+            ctxt.add_hint(hint.as_ref());
+            let mut solve = Solve {
+                condition: &mut condition,
+                steps,
+            };
+            let action = self.search(&mut ctxt, &mut solve);
+            let cancelled = ctxt.cancelled;
+            let iterations = ctxt.iterations;
+            let stats = ctxt.stats;
+            tree = ctxt.tree;
+            self.stats.add(stats);
+            if !cancelled {
+                self.tree = tree;
+                return Some(action.tap(|act| {
+                    act.proven = exact;
+                    act.iterations = iterations;
+                }));
+            }
+        }
+
+        // Helper threads do not start helper threads of their own.
+        let helpers = self
+            .threads
+            .filter(|&(threads, _)| threads > 1 && shared.is_none());
+        let mut kept = self.kept_table.take();
+        let table = helpers
+            .and_then(|_| kept.take().or_else(|| self.new_table()))
+            .map(SharedTable::new);
+
+        let mut ctxt = Ctxt::new(state, self, start, top_level(&actions));
+        if tree.is_some() {
+            ctxt.tree = tree;
+        }
+        if let Some(kept) = kept {
+            ctxt.table = Some(TableRef::Owned(kept));
+        }
+        if let Some(shared) = shared.or(table.as_ref()) {
+            ctxt.table = Some(TableRef::Shared(shared));
+        }
+        if let (Some(table), Some((_, entries))) = (&ctxt.table, self.transposition_table) {
+            ctxt.stats.table_shrunk = table.len() < entries;
+        }
+        // Shortens the lifetime of the closure to the one of `ctxt`.
+        ctxt.progress = progress.map(|progress| progress as Progress<'_, T>);
+        // With a kept table, the best action of the initial state may be known from the previous search.
+        let hint = hint.or_else(|| {
+            let key = ctxt.table_key(state, ctxt.root_hash)?;
+            ctxt.table.as_ref()?.probe(key, 0, None, None)?.1
+        });
+        ctxt.add_hint(hint.as_ref());
+        let action = match (helpers, &table) {
+            (Some((threads, run_helpers)), Some(table)) => {
+                let mut action = None;
+                run_helpers(self, state, table, threads - 1, &mut |iteration| {
+                    let mut condition = Synced(&mut condition, iteration);
+                    action = Some(self.search(&mut ctxt, &mut condition));
+                });
+                action.expect("search did not finish")
+            }
+            _ => self.search(&mut ctxt, &mut condition),
+        };
+        let proven = exact && !ctxt.cancelled;
+        let iterations = ctxt.iterations;
+        let stats = ctxt.stats;
+        let tree = ctxt.tree;
+        let owned = match ctxt.table {
+            Some(TableRef::Owned(table)) => Some(table),
+            _ => None,
+        };
+        self.stats.add(stats);
+        self.tree = tree;
+        if self.persistent_table {
+            self.kept_table = table.map(SharedTable::into_table).or(owned);
+        }
+        Some(action.tap(|act| {
+            act.proven = proven;
+            act.iterations = iterations;
+        }))
+    }
+
+    /// Searches using iterative deepening until either `condition` cancels the search
+    /// or the best action was found.
+    fn search<U: RunCondition>(&self, ctxt: &mut Ctxt<'_, T>, condition: &mut U) -> Action<T> {
+        // The fitness of the best action at the previous iteration and the amount of used extensions.
+        let mut previous_best = None;
+        let mut extensions = 0;
+        // The fitness of each top level action when it was last searched, only used with `selective_deepening`.
+        let mut root_fitness: Vec<(T::Action, T::Fitness)> = Vec::new();
+        let (start, step) = self.depth_schedule;
+        for depth in (start..).step_by(step as usize) {
+            // Iteration `depth` searches `depth + 2` plies.
+            match self.parity {
+                Some(Parity::Even) if depth % 2 == 1 => continue,
+                Some(Parity::Odd) if depth % 2 == 0 => continue,
+                _ => (),
+            }
+
+            if !condition.depth(depth) || self.max_depth.is_some_and(|max| depth >= max) {
+                return ctxt.cancel();
+            }
+
+            if let Some(remaining) = condition.remaining() {
+                if let Some(predicted) = ctxt.predict_iteration(remaining) {
+                    if predicted >= remaining {
+                        event!(debug, depth, ?predicted, ?remaining, "skipped iteration");
+                        return ctxt.cancel();
+                    }
+                }
+                ctxt.remaining.push(remaining);
+            }
+
+            if self.memory_limit.is_some_and(|bytes| ctxt.memory() > bytes) {
+                event!(debug, depth, "reached memory limit");
+                ctxt.stats.memory_limit_reached = true;
+                return ctxt.cancel();
+            }
+
+            #[cfg(feature = "tracing")]
+            let _iteration = tracing::debug_span!("iteration", depth).entered();
+
+            // Return early in case there is only one relevant action left.
+            // This is the case if we either only have one possible actions,
+            // or if all other possible actions are worse than the lower bound.
+            if let Some(exhausted) = ctxt.exhausted() {
+                return exhausted;
+            }
+
+            let mut unfinished = mem::take(&mut ctxt.unfinished);
+            // Try unfinished actions with a high expected fitness first,
+            // as they are expected to give us a better alpha value.
+            unfinished.sort_by_key(|act| Reverse(act.fitness));
+
+            if let Some(best) = ctxt.best.take() {
+                let mut best_depth = depth * PLY;
+                if let Some((swings, margin, budget)) = self.volatility {
+                    let fitness = previous_best.replace(best.fitness);
+                    if extensions < budget
+                        && fitness.is_some_and(|fitness| swings(fitness, best.fitness, margin))
+                    {
+                        extensions += 1;
+                        best_depth += PLY;
+                    }
+                }
+
+                // If computation is cancelled here, we don't know anything new,
+                // so we can just return the previous best action.
+                let ret = match self.aspiration {
+                    Some(aspiration) if self.mtdf.is_none() => {
+                        ctxt.try_aspiration(best, best_depth, aspiration, condition)
+                    }
+                    _ => ctxt.try_action(best, best_depth, condition, |_, act| act),
+                };
+                if let Some(ret) = ret {
+                    return ret;
+                }
+            }
+
+            let mut deepened = 0;
+            for action in unfinished.into_iter() {
+                let mut action_depth = depth * PLY;
+                if let Some((swings, margin, count)) = self.selective_deepening {
+                    let root = action.path.last().expect("unexpected empty path");
+                    match root_fitness.iter_mut().find(|(act, _)| act == root) {
+                        Some((_, fitness)) => {
+                            if deepened < count && swings(*fitness, action.fitness, margin) {
+                                deepened += 1;
+                                action_depth += PLY;
+                            }
+                            *fitness = action.fitness;
+                        }
+                        None => root_fitness.push((root.clone(), action.fitness)),
+                    }
+                }
+
+                // In case computation is cancelled here, we may not yet have computed the best action of
+                // the previous depth, to guard against this, we add the cancelled action back to `state.unfinished`
+                // in case it is still empty.
+                let on_cancel = |ctxt: &mut Ctxt<T>, act| {
+                    if ctxt.unfinished.is_empty() {
+                        ctxt.unfinished.push(act);
+                    }
+                    ctxt.cancel()
+                };
+
+                if let Some(ret) = ctxt.try_action(action, action_depth, condition, on_cancel) {
+                    return ret;
+                }
+            }
+
+            // We only test partially terminated action which may still be better than the best
+            // fitness at the current depth.
+            //
+            // As the current best fitness does not come from a terminated path,
+            // we still have to keep the other partially terminated actions around,
+            // in case the best fitness of a later depth is lower.
+            for action in ctxt.relevant_partials() {
+                // In case computation is cancelled here, we already tested at least some actions which were better than
+                // the cancelled partial action at the previous depth, so we can use `ctxt.cancel()` without any special
+                // considerations.
+                if let Some(ret) =
+                    ctxt.try_action(action, depth * PLY, condition, |ctxt, _| ctxt.cancel())
+                {
+                    return ret;
+                }
+            }
+
+            ctxt.complete_iteration(depth);
+            event!(
+                debug,
+                depth,
+                nodes = ctxt.stats.nodes,
+                "completed iteration"
+            );
+        }
+
+        unreachable!();
+    }
+}
+
+impl<T: UnmakeGame> Bot<T> {
+    /// Searches all states using a single copy of the state, executing actions on it and reverting
+    /// them using [`UnmakeGame::undo`][undo] and [`UnmakeGame::undo_pass`][undo_pass]
+    /// instead of executing them on clones of the state.
+    ///
+    /// The state is only cloned to search the top level actions and for null moves, see
+    /// [`Bot::with_null_move_pruning`][null]. Searched actions are executed twice, once to
+    /// compute their fitness and once to search their state. This does not change the result of the search.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, Game, ToCompletion, UnmakeGame};
+    ///
+    /// /// Both players take one or two sticks, the player taking the last one wins.
+    /// #[derive(Clone)]
+    /// struct Nim {
+    ///     sticks: u8,
+    ///     active: bool,
+    /// }
+    ///
+    /// impl Game for Nim {
+    ///     type Player = bool;
+    ///     type Action = u8;
+    ///     type Fitness = i8;
+    ///     type Actions = Vec<u8>;
+    ///
+    ///     fn actions(&self, player: bool) -> (bool, Vec<u8>) {
+    ///         (self.active == player, (1..=self.sticks.min(2)).collect())
+    ///     }
+    ///
+    ///     fn execute(&mut self, &take: &u8, player: bool) -> i8 {
+    ///         self.sticks -= take;
+    ///         self.active = !self.active;
+    ///         match (self.sticks, self.active == player) {
+    ///             (0, true) => -1,
+    ///             (0, false) => 1,
+    ///             _ => 0,
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// impl UnmakeGame for Nim {
+    ///     fn undo(&mut self, &take: &u8, _: bool) {
+    ///         self.sticks += take;
+    ///         self.active = !self.active;
+    ///     }
+    /// }
+    ///
+    /// let state = Nim { sticks: 7, active: true };
+    /// let mut bot = Bot::new(true).with_undo();
+    /// assert_eq!(bot.select(&state, ToCompletion), Some(1));
+    /// ```
+    ///
+    /// [undo]: ../trait.UnmakeGame.html#tymethod.undo
+    /// [undo_pass]: ../trait.UnmakeGame.html#method.undo_pass
+    /// [null]: struct.Bot.html#method.with_null_move_pruning
+    pub fn with_undo(mut self) -> Self {
+        self.undo = Some((T::undo, T::undo_pass));
+        self
+    }
+}
+
+impl<T> Bot<T>
+where
+    T: Game + Send + Sync,
+    T::Player: Send,
+    T::Action: Send,
+    T::Fitness: Send,
+{
+    /// Searches using `threads` threads which share a single transposition table, which is known as Lazy SMP.
+    ///
+    /// The current thread searches as usual, while helper threads search the same state, starting at deeper
+    /// iterations. This fills the transposition table with results which are used by the current thread.
+    /// Helper threads stop once the search of the current thread finished, their statistics are not
+    /// included in [`stats`][stats].
+    ///
+    /// Helper threads use the same options as the current thread, including [`with_policy`][policy],
+    /// [`with_opponent_model`][model], [`with_veto`][veto] and [`with_tablebase`][tb].
+    /// The transposition table is split into independently locked shards, so threads rarely wait for each other.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threads` is zero or if the bot does not use [`with_transposition_table`][tt],
+    /// which therefore has to be called first.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::{Hash, Hasher};
+    ///
+    /// fn hash(node: &Node) -> u64 {
+    ///     let mut hasher = DefaultHasher::new();
+    ///     node.hash(&mut hasher);
+    ///     hasher.finish()
+    /// }
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 7).with_children(&[
+    ///         Node::new(true, 4),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 8),
+    ///         Node::new(true, 9),
+    ///     ]),
+    ///     Node::new(false, 6),
+    /// ]);
+    ///
+    /// let mut bot = Bot::new(true)
+    ///     .with_transposition_table(1 << 10, hash)
+    ///     .with_threads(4);
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(1));
+    /// ```
+    ///
+    /// [stats]: #method.stats
+    /// [tt]: #method.with_transposition_table
+    /// [policy]: #method.with_policy
+    /// [model]: #method.with_opponent_model
+    /// [veto]: #method.with_veto
+    /// [tb]: #method.with_tablebase
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        assert!(threads > 0, "at least one thread is required");
+        assert!(
+            self.transposition_table.is_some(),
+            "helper threads require a transposition table"
+        );
+        self.threads = Some((threads, run_helpers::<T>));
+        self
+    }
+
+    /// Searches using `threads` threads like [`with_threads`][threads], but always returns the same results
+    /// for the same amount of threads, which makes bugs of multithreaded searches reproducible.
+    ///
+    /// At the start of each iteration of the current thread, every helper thread searches a single iteration
+    /// which is either one or two iterations deeper, using its own transposition table. At the start of the
+    /// following iteration, the current thread waits for all helper threads and adds their results to its
+    /// transposition table in a fixed order. This is slower than [`with_threads`][threads] and uses
+    /// one transposition table for each thread.
+    ///
+    /// Results only depend on the time spent searching when using a run condition which does so,
+    /// for example a `Duration`.
+    ///
+    /// # Panics
+    ///
+    /// Panics in the same cases as [`with_threads`][threads].
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::{Hash, Hasher};
+    ///
+    /// fn hash(node: &Node) -> u64 {
+    ///     let mut hasher = DefaultHasher::new();
+    ///     node.hash(&mut hasher);
+    ///     hasher.finish()
+    /// }
+    ///
+    /// let tree = Node::from_bytes(&[7, 3, 11, 5, 2, 9, 4, 8, 6, 1, 10, 12]);
+    /// let search = || {
+    ///     let mut bot = Bot::new(true)
+    ///         .with_transposition_table(1 << 10, hash)
+    ///         .with_deterministic_threads(4);
+    ///     let action = bot.detailed_select(&tree, ToCompletion).map(|act| act.path);
+    ///     (action, bot.stats().nodes)
+    /// };
+    /// assert_eq!(search(), search());
+    /// ```
+    ///
+    /// [threads]: #method.with_threads
+    pub fn with_deterministic_threads(self, threads: usize) -> Self {
+        let mut bot = self.with_threads(threads);
+        bot.threads = Some((threads, run_deterministic_helpers::<T>));
+        bot
+    }
+}
+
+impl<T> Bot<T>
+where
+    T: Game + Send,
+    T::Player: Send,
+    T::Action: Send,
+    T::Fitness: Send,
+{
+    /// Starts searching a clone of this bot on a new thread and returns a handle to the running search,
+    /// so the current thread is not blocked while the bot is thinking.
+    ///
+    /// The search behaves like [`select_with_progress`][progress], where the result of each completed
+    /// iteration is available using [`SearchHandle::best_so_far`][best]. Statistics of the search are
+    /// not included in [`stats`][stats] and a hint set using [`set_hint`][hint] is used by the spawned search.
+    /// All options are shared with the spawned search, including [`with_policy`][policy],
+    /// [`with_opponent_model`][model], [`with_veto`][veto] and [`with_tablebase`][tb], except for:
+    ///
+    /// - [`with_pruning_trace`][trace], [`with_pruning_margins`][margins] and [`with_search_tree`][tree],
+    ///   which are ignored as the statistics and the search tree of the spawned search are not returned.
+    ///   The [`search_tree`][searched] of the previous search is kept.
+    /// - [`with_persistent_table`][keep], as the spawned search uses a new transposition table, which is
+    ///   discarded afterwards. The table kept by this bot is neither used nor modified.
+    ///
+    /// Random choices, for example with [`with_random_ties`][ties], are the same as the ones of the next call
+    /// to `select`, after which this bot uses different random choices for following searches.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 7).with_children(&[
+    ///         Node::new(true, 4),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 8),
+    ///         Node::new(true, 9),
+    ///     ]),
+    /// ]);
+    ///
+    /// let mut bot = Bot::new(true);
+    /// let search = bot.spawn_select(tree, ToCompletion);
+    /// while !search.poll() {
+    ///     // Keep the game responsive, for example by rendering `search.best_so_far()`.
+    ///     std::thread::yield_now();
+    /// }
+    /// assert_eq!(search.best_so_far(), Some((1, 8)));
+    /// assert_eq!(search.join(), Some(1));
+    /// ```
+    ///
+    /// [progress]: #method.select_with_progress
+    /// [best]: struct.SearchHandle.html#method.best_so_far
+    /// [stats]: #method.stats
+    /// [hint]: #method.set_hint
+    /// [policy]: #method.with_policy
+    /// [model]: #method.with_opponent_model
+    /// [veto]: #method.with_veto
+    /// [tb]: #method.with_tablebase
+    /// [trace]: #method.with_pruning_trace
+    /// [margins]: #method.with_pruning_margins
+    /// [tree]: #method.with_search_tree
+    /// [searched]: #method.search_tree
+    /// [keep]: #method.with_persistent_table
+    /// [ties]: #method.with_random_ties
+    pub fn spawn_select<U>(&mut self, state: T, condition: U) -> SearchHandle<T>
+    where
+        T: 'static,
+        T::Player: 'static,
+        T::Action: 'static,
+        T::Fitness: 'static,
+        U: IntoRunCondition + Send + 'static,
+    {
+        let settings = Settings::new(self);
+        let hint = self.hint.take();
+        let (endgame_solver, threads, memory_limit, rng) = (
+            self.endgame_solver,
+            self.threads,
+            self.memory_limit,
+            self.rng,
+        );
+        // The spawned search uses the current rng, so following searches must not repeat its choices.
+        self.rng = XorShiftRng::from_seed_u64(self.rng.next_u64());
+
+        let stop = StopFlag::new();
+        let best = Arc::new(Mutex::new(None));
+        #[cfg(feature = "async")]
+        let completion = future::Completion::default();
+        let thread = {
+            let stop = stop.clone();
+            let best = Arc::clone(&best);
+            #[cfg(feature = "async")]
+            let completion = Arc::clone(&completion);
+            thread::spawn(move || {
+                #[cfg(feature = "async")]
+                let _finished = future::Finished(completion);
+                let mut bot = Bot {
+                    endgame_solver,
+                    hint,
+                    threads,
+                    memory_limit,
+                    rng,
+                    ..settings.into_bot()
+                };
+                bot.select_with_progress(&state, And(condition, stop), |iteration| {
+                    let mut best = best.lock().unwrap_or_else(PoisonError::into_inner);
+                    *best = Some((iteration.action.clone(), iteration.fitness));
+                })
+            })
+        };
+        SearchHandle {
+            stop,
+            best,
+            thread,
+            #[cfg(feature = "async")]
+            completion,
+        }
+    }
+
+    /// Returns a future resolving to the action selected by a search running on another thread,
+    /// which does not block the executor while the bot is thinking.
+    ///
+    /// The search is started using [`spawn_select`][spawn] and is cancelled once the returned future is dropped,
+    /// for example when it loses a race against a timeout.
+    ///
+    /// Only available with the `async` feature.
+    ///
+    /// [spawn]: #method.spawn_select
+    #[cfg(feature = "async")]
+    pub fn select_async<U>(&mut self, state: T, condition: U) -> SelectFuture<T>
+    where
+        T: 'static,
+        T::Player: 'static,
+        T::Action: 'static,
+        T::Fitness: 'static,
+        U: IntoRunCondition + Send + 'static,
+    {
+        SelectFuture::new(self.spawn_select(state, condition))
+    }
+}
+
+/// A search running on another thread, started by [`Bot::spawn_select`][spawn].
+///
+/// [spawn]: struct.Bot.html#method.spawn_select
+pub struct SearchHandle<T: Game> {
+    stop: StopFlag,
+    best: BestSoFar<T>,
+    thread: thread::JoinHandle<Option<T::Action>>,
+    #[cfg(feature = "async")]
+    completion: future::Completion,
+}
+
+impl<T: Game> SearchHandle<T> {
+    /// Returns `true` once the search finished, in which case [`join`][join] does not block.
+    ///
+    /// [join]: #method.join
+    pub fn poll(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    /// Returns the best action and its fitness after the last completed iteration,
+    /// or `None` if the first iteration is not yet completed.
+    pub fn best_so_far(&self) -> Option<(T::Action, T::Fitness)> {
+        self.best
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Stops the search as soon as possible, after which [`join`][join] returns the currently best action.
+    ///
+    /// [join]: #method.join
+    pub fn cancel(&self) {
+        self.stop.stop();
+    }
+
+    /// Waits until the search finished and returns the selected action, the same as `Bot::select`.
+    ///
+    /// In case the search panicked, the panic is resumed on the current thread.
+    pub fn join(self) -> Option<T::Action> {
+        self.thread
+            .join()
+            .unwrap_or_else(|payload| panic::resume_unwind(payload))
+    }
+}
+
+/// Runs `helpers` helper threads searching `state` using the shared `table` until `search` returns.
+fn run_helpers<T>(
+    bot: &Bot<T>,
+    state: &T,
+    table: &SharedTable<T>,
+    helpers: usize,
+    search: Search<'_>,
+) where
+    T: Game + Send + Sync,
+    T::Player: Send,
+    T::Action: Send,
+    T::Fitness: Send,
+{
+    let stop = AtomicBool::new(false);
+    thread::scope(|scope| {
+        for i in 0..helpers {
+            let mut settings = Settings::new(bot);
+            // Half of the helpers start one iteration deeper than the others.
+            settings.depth_schedule.0 += 1 + (i % 2) as u32;
+            let stop = &stop;
+            scope.spawn(move || {
+                let mut helper = settings.into_bot();
+                let start = helper.clock().now();
+                helper.search_with_stats(state, start, Stop(stop), Some(table), None);
+            });
+        }
+
+        // Helper threads have to be stopped even if the search panics, as `thread::scope` waits for them.
+        let result = panic::catch_unwind(AssertUnwindSafe(|| search(&mut |_| ())));
+        stop.store(true, Ordering::Relaxed);
+        if let Err(payload) = result {
+            panic::resume_unwind(payload);
+        }
+    });
+}
+
+/// Runs `helpers` helper threads for `Bot::with_deterministic_threads`, which search fixed depths using their
+/// own transposition tables, until `search` returns.
+///
+/// The results of the helpers are added to `table` at the start of the following iteration of the current thread.
+fn run_deterministic_helpers<T>(
+    bot: &Bot<T>,
+    state: &T,
+    table: &SharedTable<T>,
+    helpers: usize,
+    search: Search<'_>,
+) where
+    T: Game + Send + Sync,
+    T::Player: Send,
+    T::Action: Send,
+    T::Fitness: Send,
+{
+    let stop = AtomicBool::new(false);
+    let tables: Vec<_> = (0..helpers)
+        .filter_map(|_| bot.new_table().map(SharedTable::new))
+        .collect();
+    thread::scope(|scope| {
+        let mut workers = Vec::with_capacity(helpers);
+        for (i, private) in tables.iter().enumerate() {
+            let (jobs, received) = mpsc::channel::<u32>();
+            let (finished, done) = mpsc::channel();
+            let mut helper = Settings::new(bot).into_bot();
+            let stop = &stop;
+            scope.spawn(move || {
+                for depth in received {
+                    // Half of the helpers search one iteration deeper than the others.
+                    let depth = depth + 1 + (i % 2) as u32;
+                    helper.depth_schedule = (depth, 1);
+                    let condition = And(Depth(depth + 1), Stop(stop));
+                    let start = helper.clock().now();
+                    helper.search_with_stats(state, start, condition, Some(private), None);
+                    if finished.send(()).is_err() {
+                        break;
+                    }
+                }
+            });
+            workers.push((jobs, done));
+        }
+
+        let mut pending = false;
+        let mut iteration = |depth| {
+            for ((_, done), private) in workers.iter().zip(&tables) {
+                if pending && done.recv().is_ok() {
+                    table.merge(private);
+                }
+            }
+            for (jobs, _) in &workers {
+                let _ = jobs.send(depth);
+            }
+            pending = true;
+        };
+        let result = panic::catch_unwind(AssertUnwindSafe(|| search(&mut iteration)));
+        // Stopping the helpers and closing their channels ends their threads.
+        stop.store(true, Ordering::Relaxed);
+        drop(workers);
+        if let Err(payload) = result {
+            panic::resume_unwind(payload);
+        }
+    });
+}
+
+/// The configuration of a `Bot` used by helper threads and `Bot::spawn_select`, which excludes the results
+/// of previous searches and the options only affecting these results, like the pruning trace, the search tree
+/// and the persistent table.
+struct Settings<T: Game> {
+    player: T::Player,
+    policy: Option<Arc<dyn Policy<T> + Send + Sync>>,
+    opponent_model: Option<Arc<dyn OpponentModel<T> + Send + Sync>>,
+    veto: Option<Arc<dyn Veto<T> + Send + Sync>>,
+    tablebase: Option<Arc<dyn Tablebase<T> + Send + Sync>>,
+    clock: Option<Arc<dyn Clock + Send + Sync>>,
+    max_depth: Option<u32>,
+    parity: Option<Parity>,
+    depth_schedule: (u32, u32),
+    cycle_detection: Option<CycleDetection<T>>,
+    contempt: Option<Contempt<T>>,
+    transposition_table: Option<TranspositionTable<T>>,
+    volatility: Option<Volatility<T>>,
+    selective_deepening: Option<Volatility<T>>,
+    aspiration: Option<Aspiration<T>>,
+    mtdf: Option<ZeroWindow<T>>,
+    principal_variation: Option<ZeroWindow<T>>,
+    widths: Vec<usize>,
+    killer_moves: bool,
+    history_heuristic: Option<fn(&T::Action) -> usize>,
+    null_move: Option<NullMove<T>>,
+    quiescence: Option<u32>,
+    undo: Option<Undo<T>>,
+    incremental_hashing: bool,
+    random_ties: bool,
+}
+
+impl<T: Game> Settings<T> {
+    fn new(bot: &Bot<T>) -> Self {
+        Self {
+            player: bot.player,
+            policy: bot.policy.clone(),
+            opponent_model: bot.opponent_model.clone(),
+            veto: bot.veto.clone(),
+            tablebase: bot.tablebase.clone(),
+            clock: bot.clock.clone(),
+            max_depth: bot.max_depth,
+            parity: bot.parity,
+            depth_schedule: bot.depth_schedule,
+            cycle_detection: bot.cycle_detection,
+            contempt: bot.contempt,
+            transposition_table: bot.transposition_table,
+            volatility: bot.volatility,
+            selective_deepening: bot.selective_deepening,
+            aspiration: bot.aspiration,
+            mtdf: bot.mtdf,
+            principal_variation: bot.principal_variation,
+            widths: bot.widths.clone(),
+            killer_moves: bot.killer_moves,
+            history_heuristic: bot.history_heuristic,
+            null_move: bot.null_move,
+            quiescence: bot.quiescence,
+            undo: bot.undo,
+            incremental_hashing: bot.incremental_hashing,
+            random_ties: bot.random_ties,
+        }
+    }
+
+    fn into_bot(self) -> Bot<T> {
+        Bot {
+            policy: self.policy,
+            opponent_model: self.opponent_model,
+            veto: self.veto,
+            tablebase: self.tablebase,
+            clock: self.clock,
+            max_depth: self.max_depth,
+            parity: self.parity,
+            depth_schedule: self.depth_schedule,
+            cycle_detection: self.cycle_detection,
+            contempt: self.contempt,
+            transposition_table: self.transposition_table,
+            volatility: self.volatility,
+            selective_deepening: self.selective_deepening,
+            aspiration: self.aspiration,
+            mtdf: self.mtdf,
+            principal_variation: self.principal_variation,
+            widths: self.widths,
+            killer_moves: self.killer_moves,
+            history_heuristic: self.history_heuristic,
+            null_move: self.null_move,
+            quiescence: self.quiescence,
+            undo: self.undo,
+            incremental_hashing: self.incremental_hashing,
+            random_ties: self.random_ties,
+            ..Bot::new(self.player)
+        }
+    }
+}
+
+/// Calls the given function at the start of each iteration which is not stopped by the inner run condition.
+struct Synced<'a, U>(&'a mut U, &'a mut dyn FnMut(u32));
+
+impl<U: RunCondition> RunCondition for Synced<'_, U> {
+    fn step(&mut self) -> bool {
+        self.0.step()
+    }
+
+    fn depth(&mut self, depth: u32) -> bool {
+        let run = self.0.depth(depth);
+        if run {
+            (self.1)(depth);
+        }
+        run
+    }
+
+    fn check(&mut self) -> bool {
+        self.0.check()
+    }
+
+    fn remaining(&self) -> Option<Duration> {
+        self.0.remaining()
+    }
+}
+
+/// The run condition of helper threads, which stops once the search of the current thread finished.
+struct Stop<'a>(&'a AtomicBool);
+
+impl RunCondition for Stop<'_> {
+    fn step(&mut self) -> bool {
+        !self.0.load(Ordering::Relaxed)
+    }
+
+    fn depth(&mut self, _: u32) -> bool {
+        !self.0.load(Ordering::Relaxed)
+    }
+
+    fn check(&mut self) -> bool {
+        !self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A top level action.
+pub struct Action<T: Game> {
+    /// The current fitness of a given action.
+    ///
+    /// This can mean one of the following things:
+    ///
+    /// - For the best unfinished action, this is exact, but only for the current depth.
+    /// - For a terminated action, this is exact.
+    /// - For a partially terminated action, this is the upper limit.
+    pub fitness: T::Fitness,
+    /// The expected path taken during optimal play, when only  inspectnig up to the current depth.
+    ///
+    /// This used as a stack, with `path.pop()` being the first action.
+    pub path: Vec<T::Action>,
+    /// Whether this action is guaranteed to be the best possible action, in which case `fitness` is exact.
+    ///
+    /// This is the case once the game was completely searched, it is never set when using
+    /// [`Bot::with_opponent_model`][model], [`Bot::with_veto`][veto] or [`Bot::with_search_widths`][widths].
+    ///
+    /// [model]: struct.Bot.html#method.with_opponent_model
+    /// [veto]: struct.Bot.html#method.with_veto
+    /// [widths]: struct.Bot.html#method.with_search_widths
+    pub proven: bool,
+    /// The results of all completed iterations of iterative deepening, in the order they were completed.
+    ///
+    /// This is only set for the action returned by [`Bot::detailed_select`][detailed].
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 3).with_children(&[
+    ///         Node::new(true, 4),
+    ///     ]),
+    ///     Node::new(false, 2).with_children(&[
+    ///         Node::new(true, 1).with_children(&[
+    ///             Node::new(false, 9),
+    ///         ]),
+    ///     ]),
+    /// ]);
+    ///
+    /// let action = Bot::new(true).detailed_select(&tree, ToCompletion).unwrap();
+    /// let first = action.iterations.first().unwrap();
+    /// assert_eq!((first.action, first.fitness), (0, 4));
+    /// let last = action.iterations.last().unwrap();
+    /// assert_eq!((last.action, last.fitness), (1, 9));
+    /// assert!(action.iterations.windows(2).all(|w| w[0].nodes <= w[1].nodes));
+    /// ```
+    ///
+    /// [detailed]: struct.Bot.html#method.detailed_select
+    pub iterations: Vec<Iteration<T>>,
+    /// The expected reply of the opponent, which is `path[1]` in case the opponent is active after `path[0]`.
+    ///
+    /// This is only set for the action returned by [`Bot::detailed_select`][detailed] and can be used
+    /// to prepare for the next move, e.g. using [`Bot::set_hint`][hint].
+    ///
+    /// ```rust
+    /// use rubot::{Bot, ToCompletion, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 4).with_children(&[
+    ///         Node::new(true, 2),
+    ///         Node::new(true, 1),
+    ///     ]),
+    ///     Node::new(true, 0).with_children(&[
+    ///         Node::new(true, 0),
+    ///     ]),
+    /// ]);
+    ///
+    /// let action = Bot::new(true).detailed_select(&tree, ToCompletion).unwrap();
+    /// assert_eq!(action.path, &[0, 1]);
+    /// assert_eq!(action.reply, Some(1));
+    /// ```
+    ///
+    /// [detailed]: struct.Bot.html#method.detailed_select
+    /// [hint]: struct.Bot.html#method.set_hint
+    pub reply: Option<T::Action>,
+}
+
+/// The result of a completed iteration of iterative deepening, see [`Action::iterations`][iter].
+///
+/// [iter]: struct.Action.html#structfield.iterations
+pub struct Iteration<T: Game> {
+    /// The depth of this iteration, as passed to [`RunCondition::depth`][depth].
+    ///
+    /// [depth]: ../trait.RunCondition.html#tymethod.depth
+    pub depth: u32,
+    /// The best action after this iteration.
+    pub action: T::Action,
+    /// The fitness of `action` after this iteration.
+    pub fitness: T::Fitness,
+    /// The amount of states searched up to and including this iteration.
+    pub nodes: u64,
+    /// The time since the start of the search.
+    pub elapsed: Duration,
+}
+
+/// The result of [`Bot::select_verbose`][verbose].
+///
+/// [verbose]: struct.Bot.html#method.select_verbose
+pub struct Report<T: Game> {
+    /// The selected action, which is the same as the result of `select`.
+    pub action: T::Action,
+    /// The fitness of `action` after searching as deep as possible.
+    pub fitness: T::Fitness,
+    /// The depth of the last completed iteration, or `None` if the search was cancelled during the first one.
+    pub depth: Option<u32>,
+    /// The results of all completed iterations, in the order they were completed.
+    pub iterations: Vec<Iteration<T>>,
+    /// Statistics about the search, the same as the ones returned by [`Bot::stats`][stats].
+    ///
+    /// [stats]: struct.Bot.html#method.stats
+    pub stats: SearchStats,
+}
+
+impl<T: Game> Report<T> {
+    /// Returns the depth of each completed iteration together with the time spent on it.
+    pub fn durations(&self) -> impl Iterator<Item = (u32, Duration)> + '_ {
+        let starts = std::iter::once(Duration::default())
+            .chain(self.iterations.iter().map(|iteration| iteration.elapsed));
+        self.iterations
+            .iter()
+            .zip(starts)
+            .map(|(iteration, start)| (iteration.depth, iteration.elapsed - start))
+    }
+}
+
+/// The states explored by a search, recorded when using [`Bot::with_search_tree`][tree].
+///
+/// The first node is the initial state, every top level action searched by an iteration adds
+/// a new child to it, so the same action is usually contained multiple times with increasing depths.
+///
+/// [tree]: struct.Bot.html#method.with_search_tree
+pub struct SearchTree<T: Game> {
+    nodes: Vec<SearchNode<T>>,
+    max_nodes: usize,
+    truncated: bool,
+}
+
+/// A single state of a [`SearchTree`][tree].
+///
+/// [tree]: struct.SearchTree.html
+pub struct SearchNode<T: Game> {
+    /// The index of the parent state in [`SearchTree::nodes`][nodes], `None` for the initial state.
+    ///
+    /// [nodes]: struct.SearchTree.html#method.nodes
+    pub parent: Option<usize>,
+    /// The action executed in the parent state to reach this state, `None` for the initial state.
+    pub action: Option<T::Action>,
+    /// The remaining depth in plies when this state was searched, rounded down.
+    pub depth: u32,
+    /// The fitness of this state, or `None` if it is not known because the search was cancelled.
+    pub fitness: Option<Bound<T::Fitness>>,
+    /// Whether this state caused a cutoff, so its remaining siblings were not searched.
+    pub cutoff: bool,
+    /// Each pruning rule which skipped actions of this state, together with the amount of skipped actions.
+    pub pruned: Vec<(PruningRule, usize)>,
+}
+
+/// The fitness of a state in a [`SearchTree`][tree], which may only be a bound in case of a cutoff.
+///
+/// [tree]: struct.SearchTree.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Bound<F> {
+    /// The exact fitness.
+    Exact(F),
+    /// The fitness is at least the given value.
+    Lower(F),
+    /// The fitness is at most the given value.
+    Upper(F),
+}
+
+impl<T: Game> SearchTree<T> {
+    fn new(max_nodes: usize) -> Self {
+        let mut tree = SearchTree {
+            nodes: Vec::new(),
+            max_nodes,
+            truncated: false,
+        };
+        tree.push(None, None, 0, None);
+        tree
+    }
+
+    /// Returns all recorded states in the order they were reached, the first one is the initial state.
+    pub fn nodes(&self) -> &[SearchNode<T>] {
+        &self.nodes
+    }
+
+    /// Returns whether states were omitted because more than `max_nodes` states were reached.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Adds a new state, returning its index unless the tree is already full.
+    fn push(
+        &mut self,
+        parent: Option<usize>,
+        action: Option<T::Action>,
+        depth: u32,
+        fitness: Option<Bound<T::Fitness>>,
+    ) -> Option<usize> {
+        if self.nodes.len() >= self.max_nodes {
+            self.truncated = true;
+            return None;
+        }
+
+        self.nodes.push(SearchNode {
+            parent,
+            action,
+            depth,
+            fitness,
+            cutoff: false,
+            pruned: Vec::new(),
+        });
+        Some(self.nodes.len() - 1)
+    }
+}
+
+impl<T: Game> Clone for Iteration<T> {
+    fn clone(&self) -> Self {
+        Self {
+            depth: self.depth,
+            action: self.action.clone(),
+            fitness: self.fitness,
+            nodes: self.nodes,
+            elapsed: self.elapsed,
+        }
+    }
+}
+
+/// The run condition used while trying to solve the game using `Bot::with_endgame_solver`.
+struct Solve<'a, U> {
+    condition: &'a mut U,
+    steps: u64,
+}
+
+impl<U: RunCondition> RunCondition for Solve<'_, U> {
+    fn step(&mut self) -> bool {
+        if self.steps == 0 {
+            false
+        } else {
+            self.steps -= 1;
+            self.condition.step()
+        }
+    }
+
+    fn depth(&mut self, _: u32) -> bool {
+        true
+    }
+
+    fn check(&mut self) -> bool {
+        self.condition.check()
+    }
+}
+
+impl<T: Game> GameBot<T> for Bot<T> {
+    fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action> {
+        Bot::select(self, state, condition)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct CancelledError;
+
+enum MiniMax<T: Game> {
+    /// No new elements were found in this branch
+    Terminated(Vec<T::Action>, Branch<T>),
+    /// New elements were found
+    Open(Vec<T::Action>, Branch<T>),
+    /// There are no possible actions for this state
+    DeadEnd,
+    /// This state was already visited on the current path.
+    Repetition,
+}
+
+impl<T: Game> MiniMax<T> {
+    /// Appends an action to self.
+    pub fn with(
+        self,
+        ctxt: &mut Ctxt<'_, T>,
+        action: T::Action,
+        fitness: T::Fitness,
+    ) -> MiniMax<T> {
+        match self {
+            MiniMax::DeadEnd => MiniMax::Terminated(
+                ctxt.new_path().tap(|p| p.push(action)),
+                Branch::Equal(fitness),
+            ),
+            MiniMax::Repetition => MiniMax::Terminated(
+                ctxt.new_path().tap(|p| p.push(action)),
+                Branch::Equal(ctxt.repetition_fitness()),
+            ),
+            MiniMax::Open(mut actions, branch) => {
+                actions.push(action);
+                MiniMax::Open(actions, branch)
+            }
+            MiniMax::Terminated(mut actions, branch) => {
+                actions.push(action);
+                MiniMax::Terminated(actions, branch)
+            }
+        }
+    }
+
+    fn branch(&self) -> Option<Branch<T>> {
+        match self {
+            MiniMax::Terminated(_, branch) | MiniMax::Open(_, branch) => Some(*branch),
+            MiniMax::DeadEnd | MiniMax::Repetition => None,
+        }
+    }
+}
+
+/// A fitness and how it was calculated,
+/// this is used if we want to know whether a cutoff occurred.
+enum Branch<T: Game> {
+    /// `actual_fitness <= fitness`.
+    ///
+    /// Used if the given branch is worse than the current `beta`.
+    /// Confusingly, this is also called an `alpha` cutoff.
+    Worse(T::Fitness),
+    /// `actual_fitness >= fitness`.
+    ///
+    /// Used if the given branch is better than the current `alpha`.
+    /// Confusingly, this is also called a `beta` cutoff.
+    Better(T::Fitness),
+    /// `actual_fitness == fitness`.
+    ///
+    /// Used if no cutoff occured.
+    Equal(T::Fitness),
+}
+
+impl<T: Game> Clone for Branch<T> {
+    fn clone(&self) -> Branch<T> {
+        *self
+    }
+}
+
+impl<T: Game> Copy for Branch<T> {}
+
+impl<T: Game> Branch<T> {
+    fn bound(self) -> Bound<T::Fitness> {
+        match self {
+            Branch::Equal(fitness) => Bound::Exact(fitness),
+            Branch::Better(fitness) => Bound::Lower(fitness),
+            Branch::Worse(fitness) => Bound::Upper(fitness),
+        }
+    }
+
+    #[inline(always)]
+    fn fitness(self) -> T::Fitness {
+        match self {
+            Branch::Worse(fitness) | Branch::Better(fitness) | Branch::Equal(fitness) => fitness,
+        }
+    }
+
+    /// Returns the fitness of this branch and the bound of the window `(alpha, beta)` it exceeds,
+    /// the larger one first, or `None` for exact results.
+    fn exceeded(
+        self,
+        alpha: Option<T::Fitness>,
+        beta: Option<T::Fitness>,
+    ) -> Option<(T::Fitness, T::Fitness)> {
+        match self {
+            Branch::Better(fitness) => Some((fitness, beta?)),
+            Branch::Worse(fitness) => Some((alpha?, fitness)),
+            Branch::Equal(_) => None,
+        }
+    }
+}
+
+/// A child state, the action leading to it and its fitness.
+///
+/// The state is `None` with `Bot::with_undo`, as the action is executed on the state of its parent instead.
+type GameState<T> = (Option<T>, <T as Game>::Action, <T as Game>::Fitness);
+type GameStates<T> = Vec<GameState<T>>;
+
+/// The currently available data at the highest level, during minimax `State` is used instead.
+struct Ctxt<'a, T: Game> {
+    /// The initial gamestate.
+    state: &'a T,
+    /// The maximizing player.
+    player: T::Player,
+    /// Used to order actions at interior nodes.
+    policy: Option<&'a (dyn Policy<T> + Send + Sync)>,
+    /// Used to restrict the actions of the opponent.
+    opponent_model: Option<&'a (dyn OpponentModel<T> + Send + Sync)>,
+    /// Used to skip actions.
+    veto: Option<&'a (dyn Veto<T> + Send + Sync)>,
+    /// Exact results of known states.
+    tablebase: Option<&'a (dyn Tablebase<T> + Send + Sync)>,
+    /// Used to detect repeated states.
+    cycle_detection: Option<CycleDetection<T>>,
+    contempt: Option<Contempt<T>>,
+    /// The hashes of all states on the currently searched path, only used with `cycle_detection`.
+    history: Vec<u64>,
+    /// Results of already searched states.
+    table: Option<TableRef<'a, T>>,
+    /// The maximum amount of actions searched at each ply.
+    widths: &'a [usize],
+    trace_pruning: bool,
+    pruning_margins: Option<fn(T::Fitness) -> f64>,
+    /// The explored states, only used with `Bot::with_search_tree`.
+    tree: Option<SearchTree<T>>,
+    /// The index of the currently entered state in `tree`.
+    node: Option<usize>,
+    /// The last two actions which caused a cutoff at each ply, the most recent one first,
+    /// only used with `Bot::with_killer_moves`.
+    killers: Option<Vec<Killers<T>>>,
+    /// Used to order actions by how often they caused a cutoff.
+    history_table: Option<HistoryTable<T>>,
+    null_move: Option<NullMove<T>>,
+    quiescence: Option<u32>,
+    undo: Option<Undo<T>>,
+    /// The amount of states on the current path whose turn was passed using `Game::pass`.
+    passes: usize,
+    /// The hash of the initial state using `Game::state_hash`, only used with `Bot::with_incremental_hashing`.
+    root_hash: Option<u64>,
+    /// The hash of the next entered state, computed from the hash of its parent.
+    next_hash: Option<u64>,
+    mtdf: Option<ZeroWindow<T>>,
+    principal_variation: Option<ZeroWindow<T>>,
+    /// The ply of the state reached by the most recent null move on the current path.
+    null_move_ply: Option<usize>,
+    /// The ply of the actions of the currently searched state, the top level actions are at ply `0`.
+    ply: usize,
+    /// Whether the search was cancelled by the run condition.
+    cancelled: bool,
+    /// Called after each completed iteration.
+    progress: Option<Progress<'a, T>>,
+    stats: SearchStats,
+    /// Used to measure the time since the start of the search.
+    clock: &'a (dyn Clock + Send + Sync),
+    /// The start of the search and the results of all completed iterations.
+    start: Duration,
+    iterations: Vec<Iteration<T>>,
+    /// The time left according to the run condition at the start of each iteration,
+    /// which is used instead of `start` to predict the duration of the next one.
+    remaining: Vec<Duration>,
+    /// The best unfinished action. This is not set if there is an already better terminated action.
+    best: Option<Action<T>>,
+    /// Actions which are both not yet finished and worse than `best_unfinished`.
+    unfinished: Vec<Action<T>>,
+    /// The best already completely terminated action. We keep the path for diagnostic
+    /// purposes only, as there is no reason to retry this.
+    terminated: Option<Action<T>>,
+    /// Partially terminated actions. These are paths which had a cutoff at the highest level.
+    ///
+    /// As these actions cannot have a fitness higher than this cutoff, we discard all partially terminated
+    /// actions which must be worse than `best_terminated`.
+    partially_terminated: Vec<Action<T>>,
+    /// In case all paths lead to defeat, we store the action which takes the longest,
+    /// so the bot doesn't start doing weird stuff once it realized it's lost.
+    losing_action: Option<Action<T>>,
+    /// We create and discard a lot of paths.
+    ///
+    /// As an optimization, we therefore can reuse these paths.
+    /// The paths stored here are always empty. This causes an about
+    /// 2% performance increase.
+    path_cache: Vec<Vec<T::Action>>,
+    /// Reused buffers for the actions and fitness values of leaf nodes,
+    /// which are evaluated using `Game::look_ahead_batch`.
+    leaf_actions: Vec<T::Action>,
+    leaf_fitness: Vec<T::Fitness>,
+    /// Reused buffer for the priors returned by `policy`.
+    priors: Vec<f64>,
+    /// Reused buffer for the results of `opponent_model` and `veto`.
+    kept: Vec<bool>,
+    /// Reused stack of the states whose children are currently searched.
+    frames: Vec<Frame<T>>,
+}
+
+impl<'a, T: Game> Ctxt<'a, T> {
+    fn new(
+        state: &'a T,
+        bot: &'a Bot<T>,
+        start: Duration,
+        unfinished: Vec<Action<T>>,
+    ) -> Ctxt<'a, T> {
+        let root_hash = if bot.incremental_hashing {
+            state.state_hash()
+        } else {
+            None
+        };
+        Ctxt {
+            state,
+            player: bot.player,
+            policy: bot.policy.as_deref(),
+            opponent_model: bot.opponent_model.as_deref(),
+            veto: bot.veto.as_deref(),
+            tablebase: bot.tablebase.as_deref(),
+            cycle_detection: bot.cycle_detection,
+            contempt: bot.contempt,
+            history: bot
+                .cycle_detection
+                .map(|(hash, _)| vec![root_hash.unwrap_or_else(|| hash(state))])
+                .unwrap_or_default(),
+            table: bot.new_table().map(TableRef::Owned),
+            widths: &bot.widths,
+            trace_pruning: bot.trace_pruning,
+            pruning_margins: bot.pruning_margins,
+            tree: bot.search_tree.map(SearchTree::new),
+            node: None,
+            killers: if bot.killer_moves {
+                Some(Vec::new())
+            } else {
+                None
+            },
+            history_table: bot.history_heuristic.map(|key| HistoryTable {
+                key,
+                scores: [Vec::new(), Vec::new()],
+            }),
+            null_move: bot.null_move,
+            quiescence: bot.quiescence,
+            undo: bot.undo,
+            passes: 0,
+            root_hash,
+            next_hash: None,
+            mtdf: bot.mtdf,
+            principal_variation: bot.principal_variation,
+            null_move_ply: None,
+            ply: 1,
+            cancelled: false,
+            progress: None,
+            stats: SearchStats::default(),
+            clock: bot.clock(),
+            start,
+            iterations: Vec::new(),
+            remaining: Vec::new(),
+            best: None,
+            unfinished,
+            terminated: None,
+            losing_action: None,
+            partially_terminated: Vec::new(),
+            path_cache: Vec::new(),
+            leaf_actions: Vec::new(),
+            leaf_fitness: Vec::new(),
+            priors: Vec::new(),
+            kept: Vec::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Creates a new empty path, potentially reuse the cache.
     #[inline(always)]
     pub fn new_path(&mut self) -> Vec<T::Action> {
         // While it would be possible to create new paths using `Vec::with_capacity(depth)`
         // here, this does not actually influence the benchmarks so I decided against it.
-        self.path_cache.pop().unwrap_or_else(Vec::new)
+        self.path_cache.pop().unwrap_or_default()
+    }
+
+    /// Discards a path, storing it in the cache.
+    #[inline(always)]
+    pub fn discard_path(&mut self, mut path: Vec<T::Action>) {
+        // Note that `path.clear()` does not free the allocated storage.
+        path.clear();
+        self.path_cache.push(path);
+    }
+
+    fn repetition_fitness(&self) -> T::Fitness {
+        self.cycle_detection
+            .expect("repetition without cycle detection")
+            .1
+    }
+
+    /// Returns the hash of `game_state` if cycle detection is enabled, using `incremental`
+    /// instead of hashing `game_state` if it is set.
+    ///
+    /// Returns `Err` in case `game_state` is a repetition.
+    fn check_repetition(
+        &self,
+        game_state: &T,
+        incremental: Option<u64>,
+    ) -> Result<Option<u64>, ()> {
+        match self.cycle_detection {
+            Some((hash, _)) => {
+                let hash = incremental.unwrap_or_else(|| hash(game_state));
+                if self.history.contains(&hash) {
+                    Err(())
+                } else {
+                    Ok(Some(hash))
+                }
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Returns all partially terminated actions may be better than `self.best_unfinished`,
+    /// and should therefore be retried at the current depth.
+    fn relevant_partials(&mut self) -> impl IntoIterator<Item = Action<T>> {
+        self.partially_terminated.sort_by_key(|act| act.fitness);
+
+        if let Some(ref best) = self.best {
+            // We only care about partially terminated paths which may be better than the current best.
+            let pos = self
+                .partially_terminated
+                .iter()
+                .position(|act| act.fitness > best.fitness)
+                .unwrap_or(self.partially_terminated.len());
+            self.partially_terminated.split_off(pos)
+        } else {
+            mem::take(&mut self.partially_terminated)
+        }
+    }
+
+    /// Updates `self.terminated` in case the new action has a higher fitness.
+    ///
+    /// This also removes all partially terminated actions with a worse maximum fitness,
+    /// as they are now irrelevant.
+    fn add_terminated(&mut self, act: Action<T>) {
+        if self
+            .terminated
+            .as_ref()
+            .is_none_or(|best| best.fitness < act.fitness)
+        {
+            // Remove a partially terminated which are worse than the new best terminated action.
+            //
+            // This pretty much a manual reimplementation of `Vec::drain_filter`, which is currently unstable.
+            for i in (0..self.partially_terminated.len()).rev() {
+                if self.partially_terminated[i].fitness <= act.fitness {
+                    let act = self.partially_terminated.swap_remove(i);
+                    self.discard_path(act.path);
+                }
+            }
+
+            // `best` is expected to always be better than `terminated`.
+            if let Some(best) = self.best.take() {
+                if best.fitness > act.fitness {
+                    // Still relevant, put it back in.
+                    self.best = Some(best);
+                } else {
+                    // Not relevant, add it to the other unfinished actions.
+                    self.unfinished.push(best);
+                }
+            }
+
+            if let Some(term) = self.terminated.replace(act) {
+                self.discard_path(term.path);
+            }
+        } else {
+            self.discard_path(act.path);
+        }
+    }
+
+    /// Adds a new partially finished action in case its maximum fitness is
+    /// greater than the fitness of the best completely terminated action.
+    fn add_partially_terminated(&mut self, act: Action<T>) {
+        if self
+            .terminated
+            .as_ref()
+            .is_none_or(|best| best.fitness < act.fitness)
+        {
+            self.partially_terminated.push(act);
+        } else {
+            self.discard_path(act.path);
+        }
+    }
+
+    fn add_best(&mut self, act: Action<T>) {
+        if self
+            .best
+            .as_ref()
+            .or(self.terminated.as_ref())
+            .is_none_or(|best| best.fitness < act.fitness)
+        {
+            // Move the previous best action back into `unfinished`.
+            self.unfinished.extend(self.best.replace(act));
+        } else {
+            self.unfinished.push(act);
+        }
+    }
+
+    /// Records that `rule` skipped `skipped` actions at the state `node` reached after `ply` actions.
+    ///
+    /// `exceeded` is the fitness which caused the pruning and the bound it exceeded, the larger one first.
+    fn trace_pruning(
+        &mut self,
+        rule: PruningRule,
+        node: Option<usize>,
+        ply: usize,
+        skipped: usize,
+        exceeded: Option<(T::Fitness, T::Fitness)>,
+    ) {
+        if skipped == 0 {
+            return;
+        }
+
+        if self.trace_pruning {
+            let margin = self
+                .pruning_margins
+                .zip(exceeded)
+                .map(|(value, (larger, smaller))| value(larger) - value(smaller));
+            self.stats.pruned.push(Pruned {
+                rule,
+                ply: ply as u32,
+                skipped,
+                margin,
+            });
+        }
+        if let (Some(tree), Some(node)) = (&mut self.tree, node) {
+            tree.nodes[node].pruned.push((rule, skipped));
+        }
+    }
+
+    /// Returns the exact result of `game_state` in case it is known by the tablebase.
+    fn probe_tablebase(&mut self, game_state: &T) -> Option<MiniMax<T>> {
+        let (fitness, action) = self.tablebase?.probe(game_state, self.player)?;
+        self.stats.tablebase_hits = self.stats.tablebase_hits.saturating_add(1);
+        let mut path = self.new_path();
+        path.extend(action);
+        Some(MiniMax::Terminated(path, Branch::Equal(fitness)))
+    }
+
+    /// Returns the hash of `game_state` using `Game::state_hash`, only used with `Bot::with_incremental_hashing`.
+    ///
+    /// This uses the hash computed from the parent of `game_state` if it exists.
+    fn incremental_hash(&mut self, game_state: &T) -> Option<u64> {
+        let next_hash = self.next_hash.take();
+        if self.root_hash.is_some() {
+            next_hash.or_else(|| game_state.state_hash())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the key of `game_state` in the transposition table, using `incremental` if it is set.
+    fn table_key(&self, game_state: &T, incremental: Option<u64>) -> Option<u64> {
+        let table = self.table.as_ref()?;
+        Some(incremental.unwrap_or_else(|| table.hash()(game_state)))
+    }
+
+    /// Returns the hash of the child of `frame` reached using `action`, computed from the hash of `frame`.
+    fn child_hash(&self, frame: &Frame<T>, game_state: &T, action: &T::Action) -> Option<u64> {
+        game_state.update_hash(frame.incremental?, action, self.player)
+    }
+
+    /// Adds the state reached by executing `action` in `parent` to the search tree, returning its index.
+    fn record_child(
+        &mut self,
+        parent: Option<usize>,
+        action: &T::Action,
+        depth: u32,
+    ) -> Option<usize> {
+        let parent = parent?;
+        self.tree
+            .as_mut()?
+            .push(Some(parent), Some(action.clone()), depth / PLY, None)
+    }
+
+    /// Stores the result of searching the state `node`, which was reached with the given `fitness`.
+    fn record_result(&mut self, node: Option<usize>, value: &MiniMax<T>, fitness: T::Fitness) {
+        let node = match (node, &self.tree) {
+            (Some(node), Some(_)) => node,
+            _ => return,
+        };
+
+        let branch = match value {
+            MiniMax::Open(_, branch) | MiniMax::Terminated(_, branch) => *branch,
+            MiniMax::DeadEnd => Branch::Equal(fitness),
+            MiniMax::Repetition => Branch::Equal(self.repetition_fitness()),
+        };
+        if let Some(tree) = &mut self.tree {
+            tree.nodes[node].fitness = Some(branch.bound());
+        }
+    }
+
+    /// Returns an estimate of the amount of bytes used by this search, excluding memory owned by game states
+    /// and actions.
+    fn memory(&self) -> usize {
+        use std::mem::size_of;
+
+        let paths = |actions: &[Action<T>]| -> usize {
+            actions
+                .iter()
+                .map(|act| size_of::<Action<T>>() + act.path.capacity() * size_of::<T::Action>())
+                .sum()
+        };
+        let table = self.table.as_ref().map_or(0, TableRef::len);
+        let history_table = self
+            .history_table
+            .as_ref()
+            .map_or(0, |table| table.scores.iter().map(Vec::capacity).sum());
+        let cached_paths: usize = self.path_cache.iter().map(Vec::capacity).sum();
+
+        table * size_of::<Option<Entry<T>>>()
+            + self.history.capacity() * size_of::<u64>()
+            + history_table * size_of::<u64>()
+            + self.killers.as_ref().map_or(0, Vec::capacity) * size_of::<Killers<T>>()
+            + self.frames.capacity() * size_of::<Frame<T>>()
+            + self.path_cache.capacity() * size_of::<Vec<T::Action>>()
+            + cached_paths * size_of::<T::Action>()
+            + paths(&self.unfinished)
+            + paths(&self.partially_terminated)
+            + self.leaf_actions.capacity() * size_of::<T::Action>()
+            + self.leaf_fitness.capacity() * size_of::<T::Fitness>()
+            + self.stats.pruned.capacity() * size_of::<Pruned>()
+            + self.stats.cutoff_indices.capacity() * size_of::<u64>()
+    }
+
+    /// Searches the top level action `hint` first, until a better action is found.
+    fn add_hint(&mut self, hint: Option<&T::Action>) {
+        let hint = match hint {
+            Some(hint) => hint,
+            None => return,
+        };
+
+        if let Some(idx) = self.unfinished.iter().position(|act| act.path[0] == *hint) {
+            self.best = Some(self.unfinished.remove(idx));
+        }
+    }
+
+    /// Stores the currently best action as the result of the iteration at `depth`.
+    fn complete_iteration(&mut self, depth: u32) {
+        let best = self
+            .best
+            .as_ref()
+            .or(self.terminated.as_ref())
+            .or_else(|| self.unfinished.iter().max_by_key(|act| act.fitness))
+            .or(self.losing_action.as_ref());
+        if let Some(best) = best {
+            self.iterations.push(Iteration {
+                depth,
+                action: best.path.last().expect("unexpected empty path").clone(),
+                fitness: best.fitness,
+                nodes: self.stats.nodes,
+                elapsed: self.clock.now().saturating_sub(self.start),
+            });
+            if let Some(progress) = &mut self.progress {
+                progress(self.iterations.last().unwrap());
+            }
+        }
+    }
+
+    /// Returns the expected duration of the next iteration, assuming that it grows by the same
+    /// factor as the last one, or `None` if no iteration was started yet.
+    ///
+    /// Durations are measured using the `remaining` time of the run condition, so
+    /// simulated clocks also result in the same predictions each time.
+    fn predict_iteration(&self, remaining: Duration) -> Option<Duration> {
+        let mut starts = self.remaining.iter().rev().copied();
+        let start = starts.next()?;
+        let last = start.saturating_sub(remaining);
+        let growth = match starts.next() {
+            Some(before) if before > start => {
+                let previous = before - start;
+                (last.as_secs_f64() / previous.as_secs_f64()).clamp(1.0, MAX_ITERATION_GROWTH)
+            }
+            _ => 1.0,
+        };
+        Some(last.mul_f64(growth))
+    }
+
+    /// Returns the fitness used for a state with the given `fitness`, see `Bot::with_contempt`.
+    fn contempt(&self, fitness: T::Fitness) -> T::Fitness {
+        apply_contempt::<T>(self.contempt, fitness)
+    }
+
+    /// Stop computing and return the currently best action.
+    fn cancel(&mut self) -> Action<T> {
+        self.cancelled = true;
+        self.best
+            .take()
+            .or(self.terminated.take())
+            .or_else(|| {
+                mem::take(&mut self.unfinished)
+                    .into_iter()
+                    .max_by_key(|act| act.fitness)
+            })
+            .unwrap_or_else(|| {
+                // In case no other action exists,
+                // we need at least one guaranteed losing action.
+                self.losing_action.take().unwrap()
+            })
+    }
+
+    fn exhausted(&mut self) -> Option<Action<T>> {
+        if self.best.is_none() && self.unfinished.is_empty() {
+            // We can only get partially terminated actions in
+            // case there is a better non terminated one.
+            assert!(self.partially_terminated.is_empty());
+
+            Some(
+                self.terminated
+                    .take()
+                    .unwrap_or_else(|| self.losing_action.take().unwrap()),
+            )
+        } else {
+            None
+        }
+    }
+
+    /// Tests the given action at the current depth, returns `Some`
+    /// once we are finished.
+    fn try_action<U: RunCondition>(
+        &mut self,
+        action: Action<T>,
+        depth: u32,
+        condition: &mut U,
+        on_cancel: impl FnOnce(&mut Self, Action<T>) -> Action<T>,
+    ) -> Option<Action<T>> {
+        let alpha = self
+            .best
+            .as_ref()
+            .or(self.terminated.as_ref())
+            .map(|act| act.fitness);
+        let searched = match self.mtdf {
+            Some(mtdf) => self.search_mtdf(&action, depth, alpha, mtdf, condition),
+            None => self.search_action(&action, depth, alpha, None, condition),
+        };
+        match searched {
+            Err(CancelledError) => {
+                self.cancelled = true;
+                Some(on_cancel(self, action))
+            }
+            Ok((fitness, minimax)) => self.add_result(action, fitness, minimax),
+        }
+    }
+
+    /// Searches `action` using zero-width windows until either its exact fitness is known
+    /// or it is known to not be better than `alpha`.
+    fn search_mtdf<U: RunCondition>(
+        &mut self,
+        action: &Action<T>,
+        depth: u32,
+        alpha: Option<T::Fitness>,
+        (successor, predecessor): ZeroWindow<T>,
+        condition: &mut U,
+    ) -> Result<(T::Fitness, MiniMax<T>), CancelledError> {
+        // The result of the search which proved the highest lower bound
+        // and the lowest proven upper bound together with whether it is independent of `depth`.
+        let mut lower: Option<(T::Fitness, MiniMax<T>)> = None;
+        let mut upper: Option<(T::Fitness, bool)> = None;
+        let mut guess = action.fitness;
+        loop {
+            if let Some((fitness, _)) = lower {
+                if successor(fitness) == fitness || upper.is_some_and(|(upper, _)| upper <= fitness)
+                {
+                    let (fitness, minimax) = lower.take().unwrap();
+                    let terminated = upper.is_none_or(|(_, terminated)| terminated);
+                    return Ok(match minimax {
+                        MiniMax::Terminated(path, _) if terminated => {
+                            (fitness, MiniMax::Terminated(path, Branch::Equal(fitness)))
+                        }
+                        MiniMax::Terminated(path, _) | MiniMax::Open(path, _) => {
+                            (fitness, MiniMax::Open(path, Branch::Equal(fitness)))
+                        }
+                        MiniMax::DeadEnd | MiniMax::Repetition => {
+                            unreachable!("lower bound without path")
+                        }
+                    });
+                }
+            }
+
+            // Test whether the fitness is at least `beta`, which must be better than all known lower bounds.
+            let floor = match (&lower, alpha) {
+                (Some((lower, _)), Some(alpha)) => Some(cmp::max(*lower, alpha)),
+                (Some((lower, _)), None) => Some(*lower),
+                (None, alpha) => alpha,
+            };
+            let mut beta = match floor {
+                Some(floor) if guess <= floor => successor(floor),
+                _ => guess,
+            };
+            if let Some((upper, _)) = upper {
+                beta = cmp::min(beta, upper);
+            }
+
+            let window_alpha = Some(predecessor(beta)).filter(|&window_alpha| window_alpha < beta);
+            self.stats.zero_window_searches = self.stats.zero_window_searches.saturating_add(1);
+            let (fitness, minimax) =
+                self.search_action(action, depth, window_alpha, Some(beta), condition)?;
+            match minimax {
+                MiniMax::Open(_, Branch::Better(value))
+                | MiniMax::Terminated(_, Branch::Better(value)) => {
+                    if let Some((_, MiniMax::Open(path, _)))
+                    | Some((_, MiniMax::Terminated(path, _))) = lower.replace((value, minimax))
+                    {
+                        self.discard_path(path);
+                    }
+                    guess = value;
+                }
+                MiniMax::Open(path, Branch::Worse(value))
+                    if alpha.is_none_or(|alpha| value > alpha) =>
+                {
+                    self.discard_path(path);
+                    upper = Some((value, false));
+                    guess = value;
+                }
+                MiniMax::Terminated(path, Branch::Worse(value))
+                    if alpha.is_none_or(|alpha| value > alpha) =>
+                {
+                    self.discard_path(path);
+                    upper = Some((value, true));
+                    guess = value;
+                }
+                minimax => {
+                    // The action is not better than `alpha` or its fitness is exact.
+                    if let Some((_, MiniMax::Open(path, _)))
+                    | Some((_, MiniMax::Terminated(path, _))) = lower
+                    {
+                        self.discard_path(path);
+                    }
+                    return Ok((fitness, minimax));
+                }
+            }
+        }
+    }
+
+    /// Tests the best action of the previous iteration using an aspiration window around its fitness,
+    /// searching it again with a wider window until its fitness is inside of the window.
+    ///
+    /// In case the search is cancelled, the unchanged `action` is returned.
+    fn try_aspiration<U: RunCondition>(
+        &mut self,
+        action: Action<T>,
+        depth: u32,
+        (add_margin, sub_margin, margin): Aspiration<T>,
+        condition: &mut U,
+    ) -> Option<Action<T>> {
+        // `self.best` was taken, so the actual lower bound is the best terminated action.
+        let bound = self.terminated.as_ref().map(|act| act.fitness);
+        // The window is opened completely once it does not get wider, e.g. for a margin of `0`.
+        let lower = |fitness, margin| {
+            Some(sub_margin(fitness, margin))
+                .filter(|&alpha| alpha < fitness && bound.is_none_or(|bound| alpha > bound))
+        };
+        let upper =
+            |fitness, margin| Some(add_margin(fitness, margin)).filter(|&beta| beta > fitness);
+
+        let (mut alpha_margin, mut beta_margin) = (margin, margin);
+        let mut alpha = lower(action.fitness, alpha_margin);
+        let mut beta = upper(action.fitness, beta_margin);
+        loop {
+            let searched = self.search_action(&action, depth, alpha.or(bound), beta, condition);
+            match searched {
+                Err(CancelledError) => {
+                    self.cancelled = true;
+                    return Some(action);
+                }
+                Ok((_, MiniMax::Open(path, Branch::Better(fitness))))
+                | Ok((_, MiniMax::Terminated(path, Branch::Better(fitness)))) => {
+                    self.discard_path(path);
+                    self.stats.aspiration_researches =
+                        self.stats.aspiration_researches.saturating_add(1);
+                    beta_margin = add_margin(beta_margin, beta_margin);
+                    beta = upper(fitness, beta_margin);
+                }
+                Ok((_, MiniMax::Open(path, Branch::Worse(fitness))))
+                | Ok((_, MiniMax::Terminated(path, Branch::Worse(fitness))))
+                    if alpha.is_some() =>
+                {
+                    self.discard_path(path);
+                    self.stats.aspiration_researches =
+                        self.stats.aspiration_researches.saturating_add(1);
+                    alpha_margin = add_margin(alpha_margin, alpha_margin);
+                    alpha = lower(fitness, alpha_margin);
+                }
+                Ok((fitness, minimax)) => return self.add_result(action, fitness, minimax),
+            }
+        }
+    }
+
+    /// Searches `action` at `depth`, returning the fitness after its first action and the result of the search.
+    fn search_action<U: RunCondition>(
+        &mut self,
+        action: &Action<T>,
+        depth: u32,
+        alpha: Option<T::Fitness>,
+        beta: Option<T::Fitness>,
+        condition: &mut U,
+    ) -> Result<(T::Fitness, MiniMax<T>), CancelledError> {
+        if !condition.check() {
+            return Err(CancelledError);
+        }
+
+        let mut updated_state = self.state.clone();
+        let (start, rest) = action.path.split_last().expect("unexpected empty path");
+
+        let fitness = self.contempt(updated_state.execute(start, self.player));
+        let node = self.record_child(Some(0), start, depth);
+        self.node = node;
+        self.next_hash = self
+            .root_hash
+            .and_then(|hash| self.state.update_hash(hash, start, self.player));
+        // Actions which were previously searched deeper than `depth` have paths which are
+        // too long to be replayed completely.
+        let replayed = cmp::min(rest.len(), (depth / PLY) as usize);
+        let minimax = self.minimax_with_path(
+            rest.iter().cloned().rev().take(replayed),
+            updated_state,
+            depth,
+            alpha,
+            beta,
+            condition,
+        )?;
+        self.record_result(node, &minimax, fitness);
+        Ok(match minimax {
+            MiniMax::Repetition => (
+                fitness,
+                MiniMax::Terminated(self.new_path(), Branch::Equal(self.repetition_fitness())),
+            ),
+            minimax => (fitness, minimax),
+        })
+    }
+
+    /// Stores the result of searching the top level `action`, returns `Some` once we are finished.
+    fn add_result(
+        &mut self,
+        mut action: Action<T>,
+        fitness: T::Fitness,
+        minimax: MiniMax<T>,
+    ) -> Option<Action<T>> {
+        match minimax {
+            MiniMax::DeadEnd => {
+                if self.state.is_upper_bound(fitness, self.player) {
+                    Some(action)
+                } else if self.state.is_lower_bound(fitness, self.player) {
+                    if self
+                        .losing_action
+                        .as_ref()
+                        .is_none_or(|act| act.path.len() < action.path.len())
+                    {
+                        if let Some(act) = self.losing_action.replace(action) {
+                            self.discard_path(act.path);
+                        }
+                    }
+                    None
+                } else {
+                    self.add_terminated(action);
+                    None
+                }
+            }
+            MiniMax::Terminated(mut path, Branch::Equal(fitness)) => {
+                path.push(action.path.pop().unwrap());
+                self.discard_path(action.path);
+                let action = Action {
+                    fitness,
+                    path,
+                    proven: false,
+                    iterations: Vec::new(),
+                    reply: None,
+                };
+                if self.state.is_upper_bound(fitness, self.player) {
+                    Some(action)
+                } else if self.state.is_lower_bound(fitness, self.player) {
+                    if self
+                        .losing_action
+                        .as_ref()
+                        .is_none_or(|act| act.path.len() < action.path.len())
+                    {
+                        if let Some(act) = self.losing_action.replace(action) {
+                            self.discard_path(act.path);
+                        }
+                    }
+                    None
+                } else {
+                    self.add_terminated(action);
+                    None
+                }
+            }
+            MiniMax::Terminated(mut path, Branch::Worse(fitness)) => {
+                path.push(action.path.pop().unwrap());
+                self.discard_path(action.path);
+                let action = Action {
+                    fitness,
+                    path,
+                    proven: false,
+                    iterations: Vec::new(),
+                    reply: None,
+                };
+                self.add_partially_terminated(action);
+                None
+            }
+            MiniMax::Open(mut path, Branch::Worse(fitness)) => {
+                path.push(action.path.pop().unwrap());
+                self.discard_path(action.path);
+                let action = Action {
+                    fitness,
+                    path,
+                    proven: false,
+                    iterations: Vec::new(),
+                    reply: None,
+                };
+                self.unfinished.push(action);
+                None
+            }
+            MiniMax::Open(mut path, Branch::Equal(fitness)) => {
+                path.push(action.path.pop().unwrap());
+                self.discard_path(action.path);
+                let action = Action {
+                    fitness,
+                    path,
+                    proven: false,
+                    iterations: Vec::new(),
+                    reply: None,
+                };
+                self.add_best(action);
+                None
+            }
+            MiniMax::Terminated(_, Branch::Better(_)) | MiniMax::Open(_, Branch::Better(_)) => {
+                unreachable!("beta cutoff at highest depth");
+            }
+            MiniMax::Repetition => unreachable!("repetitions are replaced above"),
+        }
+    }
+
+    /// Computes the next possible steps and sorts them to maximize
+    /// cutoffs.
+    ///
+    /// In case the opponent is active without any possible actions, `game_state`
+    /// is replaced by the state after passing the turn using `Game::pass`.
+    /// Returns the children of `game_state`, passing the turn if the opponent does not have any actions.
+    ///
+    /// `hash` is cleared in case the turn had to be passed, as it no longer matches `game_state` afterwards.
+    fn generate_game_states<U: RunCondition>(
+        &mut self,
+        game_state: &mut T,
+        hash: &mut Option<u64>,
+        condition: &mut U,
+    ) -> Result<(bool, GameStates<T>), CancelledError> {
+        let (active, game_states) = self.expand(game_state, condition)?;
+        if !active && game_states.is_empty() && self.pass(game_state) {
+            *hash = None;
+            if !condition.check() {
+                return Err(CancelledError);
+            }
+
+            self.expand(game_state, condition)
+        } else {
+            Ok((active, game_states))
+        }
+    }
+
+    /// Passes the turn using `Game::pass`, which is reverted by `unpass` once the state is left.
+    fn pass(&mut self, game_state: &mut T) -> bool {
+        let passed = game_state.pass(self.player);
+        self.passes += passed as usize;
+        passed
+    }
+
+    /// Reverts the most recent pass with `Bot::with_undo`, without it the state is simply dropped afterwards.
+    fn unpass(&mut self, game_state: &mut T) {
+        self.passes -= 1;
+        if let Some((_, undo_pass)) = self.undo {
+            undo_pass(game_state, self.player);
+        }
+    }
+
+    fn expand<U: RunCondition>(
+        &mut self,
+        game_state: &mut T,
+        condition: &mut U,
+    ) -> Result<(bool, GameStates<T>), CancelledError> {
+        let (active, actions) = game_state.actions(self.player);
+
+        if self.policy.is_none() && self.veto.is_none() && (active || self.opponent_model.is_none())
+        {
+            let mut game_states = self.execute_all(game_state, actions, condition)?;
+            self.sort_by_fitness(game_state, active, &mut game_states);
+            return Ok((active, game_states));
+        }
+
+        let mut actions: Vec<_> = actions.into_iter().collect();
+        self.apply_veto(game_state, &mut actions);
+        if !active {
+            self.restrict_opponent(game_state, &mut actions);
+        }
+
+        if let Some(policy) = self.policy {
+            let mut priors = mem::take(&mut self.priors);
+            policy.priors(game_state, &actions, self.player, &mut priors);
+            debug_assert_eq!(actions.len(), priors.len());
+
+            let game_states = self.execute_all(game_state, actions, condition);
+            let mut game_states: Vec<_> = priors.drain(..).zip(game_states?).collect();
+            self.priors = priors;
+
+            // Sort by prior first, only using the order hint and the fitness to break ties.
+            let player = self.player;
+            game_states.sort_by(|(a_prior, (_, a_action, a)), (b_prior, (_, b_action, b))| {
+                b_prior
+                    .total_cmp(a_prior)
+                    .then_with(|| {
+                        let a_hint = game_state.order_hint(a_action, player);
+                        game_state.order_hint(b_action, player).cmp(&a_hint)
+                    })
+                    .then_with(|| if active { b.cmp(a) } else { a.cmp(b) })
+            });
+
+            Ok((
+                active,
+                game_states.into_iter().map(|(_, state)| state).collect(),
+            ))
+        } else {
+            let mut game_states = self.execute_all(game_state, actions, condition)?;
+            self.sort_by_fitness(game_state, active, &mut game_states);
+            Ok((active, game_states))
+        }
     }
 
-    /// Discards a path, storing it in the cache.
-    #[inline(always)]
-    pub fn discard_path(&mut self, mut path: Vec<T::Action>) {
-        // Note that `path.clear()` does not free the allocated storage.
-        path.clear();
-        self.path_cache.push(path);
+    /// Executes all `actions`, checking `condition` before each call to `execute` as
+    /// it may be fairly expensive.
+    ///
+    /// With `Bot::with_undo`, the actions are executed and reverted on `game_state` itself.
+    fn execute_all<U: RunCondition>(
+        &self,
+        game_state: &mut T,
+        actions: impl IntoIterator<Item = T::Action>,
+        condition: &mut U,
+    ) -> Result<GameStates<T>, CancelledError> {
+        actions
+            .into_iter()
+            .map(|action| {
+                if !condition.check() {
+                    return Err(CancelledError);
+                }
+
+                if let Some((undo, _)) = self.undo {
+                    let fitness = self.contempt(game_state.execute(&action, self.player));
+                    undo(game_state, &action, self.player);
+                    Ok((None, action, fitness))
+                } else {
+                    let mut game_state = game_state.clone();
+                    let fitness = self.contempt(game_state.execute(&action, self.player));
+                    Ok((Some(game_state), action, fitness))
+                }
+            })
+            .collect()
     }
 
-    /// Returns all partially terminated actions may be better than `self.best_unfinished`,
-    /// and should therefore be retried at the current depth.
-    fn relevant_partials(&mut self) -> impl IntoIterator<Item = Action<T>> {
-        self.partially_terminated.sort_by_key(|act| act.fitness);
+    fn sort_by_fitness(&self, game_state: &T, active: bool, game_states: &mut GameStates<T>) {
+        // Sort the actions so the most probable one is checked first.
+        // This allows for faster cutoffs. Note that depending on the fitness
+        // function, this can hit some fairly bad cases.
+        //
+        // Order hints are compared first, so domain knowledge can override the fitness.
+        let player = self.player;
+        let hint = |action: &T::Action| game_state.order_hint(action, player);
+        game_states.sort_by(|(_, a_action, a), (_, b_action, b)| {
+            hint(b_action)
+                .cmp(&hint(a_action))
+                .then_with(|| if active { b.cmp(a) } else { a.cmp(b) })
+        });
+    }
 
-        if let Some(ref best) = self.best {
-            // We only care about partially terminated paths which may be better than the current best.
-            let pos = self
-                .partially_terminated
-                .iter()
-                .position(|act| act.fitness > best.fitness)
-                .unwrap_or(self.partially_terminated.len());
-            self.partially_terminated.split_off(pos)
-        } else {
-            mem::take(&mut self.partially_terminated)
+    /// Removes all actions of the opponent which are considered implausible by the opponent model.
+    ///
+    /// In case no action is plausible, all actions are kept.
+    fn restrict_opponent(&mut self, game_state: &T, actions: &mut Vec<T::Action>) {
+        if let Some(model) = self.opponent_model {
+            let player = self.player;
+            let mut kept = mem::take(&mut self.kept);
+            let skipped = retain_actions(actions, &mut kept, |action| {
+                model.is_plausible(game_state, action, player)
+            });
+            self.kept = kept;
+            self.trace_pruning(
+                PruningRule::OpponentModel,
+                self.node,
+                self.ply,
+                skipped,
+                None,
+            );
         }
     }
 
-    /// Updates `self.terminated` in case the new action has a higher fitness.
+    /// Removes all vetoed actions, in case all actions are vetoed, all actions are kept.
+    fn apply_veto(&mut self, game_state: &T, actions: &mut Vec<T::Action>) {
+        if let Some(veto) = self.veto {
+            let player = self.player;
+            let mut kept = mem::take(&mut self.kept);
+            let skipped = retain_actions(actions, &mut kept, |action| {
+                !veto.vetoes(game_state, action, player)
+            });
+            self.kept = kept;
+            self.trace_pruning(PruningRule::Veto, self.node, self.ply, skipped, None);
+        }
+    }
+
+    /// As we want to ignore as many possible subtrees as possible,
+    /// we start each depth by taking the best possible path of the
+    /// previous depth.
     ///
-    /// This also removes all partially terminated actions with a worse maximum fitness,
-    /// as they are now irrelevant.
-    fn add_terminated(&mut self, act: Action<T>) {
-        if self
-            .terminated
-            .as_ref()
-            .map_or(true, |best| best.fitness < act.fitness)
-        {
-            // Remove a partially terminated which are worse than the new best terminated action.
-            //
-            // This pretty much a manual reimplementation of `Vec::drain_filter`, which is currently unstable.
-            for i in (0..self.partially_terminated.len()).rev() {
-                if self.partially_terminated[i].fitness <= act.fitness {
-                    let act = self.partially_terminated.swap_remove(i);
-                    self.discard_path(act.path);
+    /// As this path is hopefully also a good choice at this depth,
+    /// we very quickly get a good alpha/lower limit.
+    ///
+    /// The search uses an explicit stack of `Frame`s instead of recursion,
+    /// so very deep searches do not overflow the stack. All frames share `game_state`,
+    /// which always is the currently entered state.
+    fn minimax_with_path<U: RunCondition>(
+        &mut self,
+        mut path: impl Iterator<Item = T::Action>,
+        mut game_state: T,
+        depth: u32,
+        alpha: Option<T::Fitness>,
+        beta: Option<T::Fitness>,
+        condition: &mut U,
+    ) -> Result<MiniMax<T>, CancelledError> {
+        let mut frames = mem::take(&mut self.frames);
+        let passes = self.passes;
+        let result = self.run_frames(
+            &mut frames,
+            &mut path,
+            &mut game_state,
+            depth,
+            alpha,
+            beta,
+            condition,
+        );
+        // Frames are only left behind if the search was cancelled.
+        while let Some(frame) = frames.pop() {
+            self.leave(frame.hash);
+        }
+        self.frames = frames;
+        self.passes = passes;
+        result
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn run_frames<U: RunCondition>(
+        &mut self,
+        frames: &mut Vec<Frame<T>>,
+        path: &mut impl Iterator<Item = T::Action>,
+        game_state: &mut T,
+        depth: u32,
+        alpha: Option<T::Fitness>,
+        beta: Option<T::Fitness>,
+        condition: &mut U,
+    ) -> Result<MiniMax<T>, CancelledError> {
+        let mut next = Next::Enter(depth, alpha, beta, true);
+        loop {
+            let mut value = match next {
+                Next::Enter(depth, alpha, beta, follow_path) => {
+                    // The initial state is recorded by the caller.
+                    if let Some(frame) = frames.last() {
+                        self.node = frame.child;
+                        self.next_hash = frame.child_hash;
+                    }
+                    let entered = if follow_path {
+                        self.enter_path(path, game_state, depth, alpha, condition)?
+                    } else {
+                        self.enter(game_state, depth, alpha, beta, condition)?
+                    };
+
+                    match entered {
+                        Entered::Done(value) => value,
+                        Entered::Expanded(mut frame) => {
+                            next = self.next_child(&mut frame, game_state);
+                            frames.push(frame);
+                            continue;
+                        }
+                    }
                 }
-            }
+                Next::Return(value) => value,
+            };
 
-            // `best` is expected to always be better than `terminated`.
-            if let Some(best) = self.best.take() {
-                if best.fitness > act.fitness {
-                    // Still relevant, put it back in.
-                    self.best = Some(best);
-                } else {
-                    // Not relevant, add it to the other unfinished actions.
-                    self.unfinished.push(best);
+            // Bind `value` to its parent until a frame has further children to search.
+            loop {
+                let frame = match frames.last_mut() {
+                    Some(frame) => frame,
+                    None => return Ok(value),
+                };
+
+                let (action, fitness) = frame.current.take().expect("no child searched");
+                self.record_result(frame.child, &value, fitness);
+                if mem::take(&mut frame.research) && frame.state.fails_zero_window(&value) {
+                    self.stats.pvs_researches = self.stats.pvs_researches.saturating_add(1);
+                    if let MiniMax::Open(path, _) | MiniMax::Terminated(path, _) = value {
+                        self.discard_path(path);
+                    }
+                    // The child is still entered, so only a pass of its turn has to be reverted.
+                    if self.passes > frame.passes {
+                        self.unpass(game_state);
+                        if let Some(parent) = &frame.parent {
+                            *game_state = parent.clone();
+                            game_state.execute(&action, self.player);
+                        }
+                    }
+                    let depth = frame.depth - PLY;
+                    frame.child = self.record_child(frame.node, &action, depth);
+                    frame.current = Some((action, fitness));
+                    let (alpha, beta) = (frame.state.alpha, frame.state.beta);
+                    next = Next::Enter(depth, alpha, beta, false);
+                    break;
                 }
-            }
 
-            if let Some(term) = self.terminated.replace(act) {
-                self.discard_path(term.path);
+                self.unmake(frame, game_state, &action);
+                let minimax = value.with(self, action, fitness);
+                if let Some(cutoff) = frame.state.bind(self, game_state, minimax) {
+                    self.stats.add_cutoff(frame.index);
+                    self.add_refutation(&cutoff, frame.state.active, frame.depth);
+                    let skipped = frame.game_states.len();
+                    event!(
+                        trace,
+                        ply = self.ply - 1,
+                        index = frame.index,
+                        skipped,
+                        "cutoff"
+                    );
+                    if let (Some(tree), Some(child)) = (&mut self.tree, frame.child) {
+                        tree.nodes[child].cutoff = true;
+                    }
+                    let exceeded = cutoff
+                        .branch()
+                        .and_then(|branch| branch.exceeded(frame.state.alpha, frame.state.beta));
+                    self.trace_pruning(
+                        PruningRule::Cutoff,
+                        frame.node,
+                        self.ply - 1,
+                        skipped,
+                        exceeded,
+                    );
+                    let frame = frames.pop().unwrap();
+                    self.leave(frame.hash);
+                    self.remember(frame.key, frame.depth, &cutoff);
+                    value = cutoff;
+                } else if !frame.game_states.as_slice().is_empty() {
+                    frame.index += 1;
+                    next = self.next_child(frame, game_state);
+                    break;
+                } else {
+                    let frame = frames.pop().unwrap();
+                    self.leave(frame.hash);
+                    value = frame.state.consume();
+                    self.remember(frame.key, frame.depth, &value);
+                }
             }
-        } else {
-            self.discard_path(act.path);
         }
     }
 
-    /// Adds a new partially finished action in case its maximum fitness is
-    /// greater than the fitness of the best completely terminated action.
-    fn add_partially_terminated(&mut self, act: Action<T>) {
-        if self
-            .terminated
-            .as_ref()
-            .map_or(true, |best| best.fitness < act.fitness)
-        {
-            self.partially_terminated.push(act);
-        } else {
-            self.discard_path(act.path);
+    /// Enters a state while following the path of a previous iteration.
+    fn enter_path<U: RunCondition>(
+        &mut self,
+        path: &mut impl Iterator<Item = T::Action>,
+        game_state: &mut T,
+        depth: u32,
+        alpha: Option<T::Fitness>,
+        condition: &mut U,
+    ) -> Result<Entered<T>, CancelledError> {
+        if !condition.step() {
+            return Err(CancelledError);
         }
-    }
+        self.stats.nodes = self.stats.nodes.saturating_add(1);
 
-    fn add_best(&mut self, act: Action<T>) {
-        if self
-            .best
-            .as_ref()
-            .or(self.terminated.as_ref())
-            .map_or(true, |best| best.fitness < act.fitness)
-        {
-            // Move the previous best action back into `unfinished`.
-            self.unfinished.extend(self.best.replace(act));
+        let action = if let Some(action) = path.next() {
+            action
         } else {
-            self.unfinished.push(act);
+            return self.enter(game_state, depth, alpha, None, condition);
+        };
+
+        if depth < PLY {
+            unreachable!("lowest depth with non empty path");
+        }
+
+        let mut incremental = self.incremental_hash(game_state);
+        let hash = match self.check_repetition(game_state, incremental) {
+            Ok(hash) => hash,
+            Err(()) => return Ok(Entered::Done(MiniMax::Repetition)),
+        };
+
+        if let Some(value) = self.probe_tablebase(game_state) {
+            return Ok(Entered::Done(value));
         }
+
+        let key = self.table_key(game_state, incremental);
+        let (active, mut game_states) =
+            self.generate_game_states(game_state, &mut incremental, condition)?;
+
+        let state = State::new(self.new_path(), self.player, alpha, None, active);
+
+        let first = match game_states.iter().position(|(_, a, _)| *a == action) {
+            Some(idx) => game_states.remove(idx),
+            None => unreachable!("path segment not found"),
+        };
+        self.order_game_states(&mut game_states, active, None);
+
+        let mut frame = self.push_frame(state, Some(first), game_states, depth, hash, key);
+        frame.incremental = incremental;
+        Ok(Entered::Expanded(frame))
     }
 
-    /// Stop computing and return the currently best action.
-    fn cancel(&mut self) -> Action<T> {
-        self.best
-            .take()
-            .or(self.terminated.take())
-            .or_else(|| {
-                mem::take(&mut self.unfinished)
-                    .into_iter()
-                    .max_by_key(|act| act.fitness)
-            })
-            .unwrap_or_else(|| {
-                // In case no other action exists,
-                // we need at least one guaranteed losing action.
-                self.losing_action.take().unwrap()
-            })
+    /// Enters a state, evaluating it directly in case it is a leaf.
+    fn enter<U: RunCondition>(
+        &mut self,
+        game_state: &mut T,
+        depth: u32,
+        alpha: Option<T::Fitness>,
+        beta: Option<T::Fitness>,
+        condition: &mut U,
+    ) -> Result<Entered<T>, CancelledError> {
+        if !condition.step() {
+            return Err(CancelledError);
+        }
+        self.stats.nodes = self.stats.nodes.saturating_add(1);
+
+        let mut incremental = self.incremental_hash(game_state);
+        let hash = match self.check_repetition(game_state, incremental) {
+            Ok(hash) => hash,
+            Err(()) => return Ok(Entered::Done(MiniMax::Repetition)),
+        };
+
+        if let Some(value) = self.probe_tablebase(game_state) {
+            return Ok(Entered::Done(value));
+        }
+
+        if depth < PLY {
+            return self.leaf(game_state, condition).map(Entered::Done);
+        }
+
+        let key = self.table_key(game_state, incremental);
+        let mut best = None;
+        let probed = key.and_then(|key| {
+            let table = self.table.as_ref()?;
+            self.stats.table_probes = self.stats.table_probes.saturating_add(1);
+            table.probe(key, depth, alpha, beta)
+        });
+        if let Some((result, entry_best)) = probed {
+            if let Some((branch, terminated)) = result {
+                self.stats.transpositions = self.stats.transpositions.saturating_add(1);
+                if self.trace_pruning || (self.tree.is_some() && self.node.is_some()) {
+                    let skipped = game_state.actions(self.player).1.into_iter().count();
+                    let exceeded = branch.exceeded(alpha, beta);
+                    let rule = PruningRule::Transposition;
+                    self.trace_pruning(rule, self.node, self.ply, skipped, exceeded);
+                }
+                let path = self.new_path();
+                return Ok(Entered::Done(if terminated {
+                    MiniMax::Terminated(path, branch)
+                } else {
+                    MiniMax::Open(path, branch)
+                }));
+            }
+            best = entry_best;
+        }
+
+        let (active, mut game_states) =
+            self.generate_game_states(game_state, &mut incremental, condition)?;
+
+        if game_states.is_empty() {
+            if active {
+                self.stats.dead_ends = self.stats.dead_ends.saturating_add(1);
+            }
+            return Ok(Entered::Done(MiniMax::DeadEnd));
+        }
+
+        let window = (alpha, beta);
+        if let Some(cutoff) = self.null_move(game_state, active, depth, window, hash, condition)? {
+            let exceeded = cutoff
+                .branch()
+                .and_then(|branch| branch.exceeded(alpha, beta));
+            self.trace_pruning(
+                PruningRule::NullMove,
+                self.node,
+                self.ply,
+                game_states.len(),
+                exceeded,
+            );
+            return Ok(Entered::Done(cutoff));
+        }
+
+        self.order_game_states(&mut game_states, active, best);
+
+        let state = State::new(self.new_path(), self.player, alpha, beta, active);
+
+        let mut frame = self.push_frame(state, None, game_states, depth, hash, key);
+        frame.incremental = incremental;
+        Ok(Entered::Expanded(frame))
     }
 
-    fn exhausted(&mut self) -> Option<Action<T>> {
-        if self.best.is_none() && self.unfinished.is_empty() {
-            // We can only get partially terminated actions in
-            // case there is a better non terminated one.
-            assert!(self.partially_terminated.is_empty());
+    /// Evaluates all actions of a state at the lowest depth using `Game::look_ahead_batch`.
+    fn leaf<U: RunCondition>(
+        &mut self,
+        game_state: &mut T,
+        condition: &mut U,
+    ) -> Result<MiniMax<T>, CancelledError> {
+        let (mut active, actions) = game_state.actions(self.player);
+        if !condition.check() {
+            return Err(CancelledError);
+        }
 
-            Some(
-                self.terminated
-                    .take()
-                    .unwrap_or_else(|| self.losing_action.take().unwrap()),
-            )
+        let mut leaf_actions = mem::take(&mut self.leaf_actions);
+        let mut leaf_fitness = mem::take(&mut self.leaf_fitness);
+        leaf_actions.extend(actions);
+        if !active && leaf_actions.is_empty() && self.pass(game_state) {
+            if !condition.check() {
+                self.leaf_actions = leaf_actions;
+                self.leaf_fitness = leaf_fitness;
+                return Err(CancelledError);
+            }
+
+            let (now_active, actions) = game_state.actions(self.player);
+            active = now_active;
+            leaf_actions.extend(actions);
+        }
+        self.apply_veto(game_state, &mut leaf_actions);
+        if !active {
+            self.restrict_opponent(game_state, &mut leaf_actions);
+        }
+        if active && leaf_actions.is_empty() {
+            self.stats.dead_ends = self.stats.dead_ends.saturating_add(1);
+        }
+        game_state.look_ahead_batch(&leaf_actions, self.player, &mut leaf_fitness);
+        debug_assert_eq!(leaf_actions.len(), leaf_fitness.len());
+        for fitness in &mut leaf_fitness {
+            *fitness = self.contempt(*fitness);
+        }
+        self.stats.leaves = self.stats.leaves.saturating_add(leaf_actions.len() as u64);
+        if !leaf_actions.is_empty() {
+            self.stats.max_ply = cmp::max(self.stats.max_ply, self.ply as u32 + 1);
+        }
+
+        if let Some(plies) = self.quiescence.filter(|&plies| plies > 0) {
+            let ply = self.ply as u32;
+            for (action, fitness) in leaf_actions.iter().zip(leaf_fitness.iter_mut()) {
+                if game_state.is_quiet(action, self.player) {
+                    continue;
+                }
+
+                match self.quiesce(game_state, action, plies, ply, condition) {
+                    Ok(value) => *fitness = value,
+                    Err(CancelledError) => {
+                        leaf_actions.clear();
+                        leaf_fitness.clear();
+                        self.leaf_actions = leaf_actions;
+                        self.leaf_fitness = leaf_fitness;
+                        return Err(CancelledError);
+                    }
+                }
+            }
+        }
+
+        if let (Some(tree), Some(node)) = (&mut self.tree, self.node) {
+            for (action, &fitness) in leaf_actions.iter().zip(&leaf_fitness) {
+                let fitness = Some(Bound::Exact(fitness));
+                tree.push(Some(node), Some(action.clone()), 0, fitness);
+            }
+        }
+
+        let fitness = leaf_fitness.iter().copied().enumerate();
+        let selected = if active {
+            fitness.max_by_key(|&(_, fitness)| fitness)
         } else {
-            None
+            fitness.min_by_key(|&(_, fitness)| fitness)
+        };
+
+        let result = selected.map_or(MiniMax::DeadEnd, |(idx, fitness)| {
+            let mut path = self.new_path();
+            path.push(leaf_actions.swap_remove(idx));
+            MiniMax::Open(path, Branch::Equal(fitness))
+        });
+
+        leaf_actions.clear();
+        leaf_fitness.clear();
+        self.leaf_actions = leaf_actions;
+        self.leaf_fitness = leaf_fitness;
+        Ok(result)
+    }
+
+    /// Returns the fitness of the noisy `action` at `ply`, searching up to `plies` further noisy actions.
+    ///
+    /// The player to move after `action` can also execute a quiet action instead,
+    /// which is expected to keep the fitness after `action`.
+    fn quiesce<U: RunCondition>(
+        &mut self,
+        game_state: &mut T,
+        action: &T::Action,
+        plies: u32,
+        ply: u32,
+        condition: &mut U,
+    ) -> Result<T::Fitness, CancelledError> {
+        if !condition.step() {
+            return Err(CancelledError);
+        }
+        // With `Bot::with_undo`, `action` is executed on `game_state` itself and reverted afterwards.
+        let mut cloned;
+        let game_state = if self.undo.is_some() {
+            game_state
+        } else {
+            cloned = game_state.clone();
+            &mut cloned
+        };
+        let mut best = self.contempt(game_state.execute(action, self.player));
+        self.stats.nodes = self.stats.nodes.saturating_add(1);
+        self.stats.quiescence_nodes = self.stats.quiescence_nodes.saturating_add(1);
+
+        if !condition.check() {
+            return Err(CancelledError);
+        }
+        let (mut active, actions) = game_state.actions(self.player);
+        let mut actions: Vec<_> = actions.into_iter().collect();
+        let passed = !active && actions.is_empty() && self.pass(game_state);
+        if passed {
+            if !condition.check() {
+                return Err(CancelledError);
+            }
+            let (now_active, now_actions) = game_state.actions(self.player);
+            active = now_active;
+            actions = now_actions.into_iter().collect();
+        }
+
+        for action in actions {
+            if game_state.is_quiet(&action, self.player) {
+                continue;
+            }
+
+            let fitness = if plies > 1 {
+                self.quiesce(game_state, &action, plies - 1, ply + 1, condition)?
+            } else {
+                self.stats.leaves = self.stats.leaves.saturating_add(1);
+                self.stats.max_ply = cmp::max(self.stats.max_ply, ply + 2);
+                self.contempt(game_state.look_ahead(&action, self.player))
+            };
+
+            best = if active {
+                cmp::max(best, fitness)
+            } else {
+                cmp::min(best, fitness)
+            };
         }
+
+        if passed {
+            self.unpass(game_state);
+        }
+        if let Some((undo, _)) = self.undo {
+            undo(game_state, action, self.player);
+        }
+        Ok(best)
     }
 
-    /// Tests the given action at the current depth, returns `Some`
-    /// once we are finished.
-    fn try_action<U: RunCondition>(
+    /// Searches `game_state` after skipping the turn of the active player with a reduced depth,
+    /// returning a cutoff in case the result is already outside of the window `(alpha, beta)`.
+    fn null_move<U: RunCondition>(
         &mut self,
-        mut action: Action<T>,
+        game_state: &T,
+        active: bool,
         depth: u32,
+        (alpha, beta): (Option<T::Fitness>, Option<T::Fitness>),
+        hash: Option<u64>,
         condition: &mut U,
-        on_cancel: impl FnOnce(&mut Self, Action<T>) -> Action<T>,
-    ) -> Option<Action<T>> {
-        let mut updated_state = self.state.clone();
-        let (start, rest) = action.path.split_last().expect("unexpected empty path");
+    ) -> Result<Option<MiniMax<T>>, CancelledError> {
+        let (reduction, skip_turn) = match self.null_move {
+            Some(null_move) => null_move,
+            None => return Ok(None),
+        };
 
-        let fitness = updated_state.execute(start, self.player);
-        match self.minimax_with_path(
-            rest.iter().cloned().rev(),
-            updated_state,
+        let bound = if active { beta } else { alpha };
+        let depth = match (bound, depth.checked_sub((reduction + 1) * PLY)) {
+            (Some(_), Some(depth)) if self.null_move_ply != Some(self.ply) => depth,
+            _ => return Ok(None),
+        };
+
+        let mut null_state = game_state.clone();
+        if !skip_turn(&mut null_state) {
+            return Ok(None);
+        }
+
+        self.history.extend(hash);
+        self.ply += 1;
+        let null_move_ply = self.null_move_ply.replace(self.ply);
+        // The states searched after the null move are not recorded in the search tree.
+        let node = self.node.take();
+        let result = self.minimax_with_path(
+            std::iter::empty(),
+            null_state,
             depth,
-            self.best
-                .as_ref()
-                .or(self.terminated.as_ref())
-                .map(|act| act.fitness),
-            None,
+            alpha,
+            beta,
             condition,
-        ) {
-            Err(CancelledError) => Some(on_cancel(self, action)),
-            Ok(MiniMax::DeadEnd) => {
-                if self.state.is_upper_bound(fitness, self.player) {
-                    Some(action)
-                } else if self.state.is_lower_bound(fitness, self.player) {
-                    if self
-                        .losing_action
-                        .as_ref()
-                        .map_or(true, |act| act.path.len() < action.path.len())
-                    {
-                        let act = self.losing_action.replace(action);
-                        act.map(|act| self.discard_path(act.path));
-                    }
-                    None
-                } else {
-                    self.add_terminated(action);
-                    None
-                }
-            }
-            Ok(MiniMax::Terminated(mut path, Branch::Equal(fitness))) => {
-                path.push(action.path.pop().unwrap());
-                self.discard_path(action.path);
-                let action = Action { fitness, path };
-                if self.state.is_upper_bound(fitness, self.player) {
-                    Some(action)
-                } else if self.state.is_lower_bound(fitness, self.player) {
-                    if self
-                        .losing_action
-                        .as_ref()
-                        .map_or(true, |act| act.path.len() < action.path.len())
-                    {
-                        let act = self.losing_action.replace(action);
-                        act.map(|act| self.discard_path(act.path));
-                    }
-                    None
-                } else {
-                    self.add_terminated(action);
-                    None
-                }
+        );
+        self.node = node;
+        self.null_move_ply = null_move_ply;
+        self.ply -= 1;
+        if hash.is_some() {
+            self.history.pop();
+        }
+
+        let (branch, terminated) = match result? {
+            MiniMax::Terminated(path, branch) => {
+                self.discard_path(path);
+                (branch, true)
             }
-            Ok(MiniMax::Terminated(mut path, Branch::Worse(fitness))) => {
-                path.push(action.path.pop().unwrap());
-                self.discard_path(action.path);
-                let action = Action { fitness, path };
-                self.add_partially_terminated(action);
-                None
+            MiniMax::Open(path, branch) => {
+                self.discard_path(path);
+                (branch, false)
             }
-            Ok(MiniMax::Open(mut path, Branch::Worse(fitness))) => {
-                path.push(action.path.pop().unwrap());
-                self.discard_path(action.path);
-                let action = Action { fitness, path };
-                self.unfinished.push(action);
-                None
+            MiniMax::DeadEnd | MiniMax::Repetition => return Ok(None),
+        };
+
+        let bound = bound.unwrap();
+        let branch = match branch {
+            Branch::Equal(fitness) | Branch::Better(fitness) if active && fitness >= bound => {
+                Branch::Better(fitness)
             }
-            Ok(MiniMax::Open(mut path, Branch::Equal(fitness))) => {
-                path.push(action.path.pop().unwrap());
-                self.discard_path(action.path);
-                let action = Action { fitness, path };
-                self.add_best(action);
-                None
+            Branch::Equal(fitness) | Branch::Worse(fitness) if !active && fitness <= bound => {
+                Branch::Worse(fitness)
             }
-            Ok(MiniMax::Terminated(_, Branch::Better(_)))
-            | Ok(MiniMax::Open(_, Branch::Better(_))) => {
-                unreachable!("beta cutoff at highest depth");
+            _ => return Ok(None),
+        };
+
+        // The cutoff is kept once the search after the null move reached the end of the game,
+        // as deeper iterations would otherwise never terminate.
+        let path = self.new_path();
+        Ok(Some(if terminated {
+            MiniMax::Terminated(path, branch)
+        } else {
+            MiniMax::Open(path, branch)
+        }))
+    }
+
+    /// Sorts `game_states` using the history table and moves `best` to the front,
+    /// followed by the killer moves of the current ply.
+    fn order_game_states(
+        &self,
+        game_states: &mut GameStates<T>,
+        active: bool,
+        best: Option<T::Action>,
+    ) {
+        if let Some(table) = &self.history_table {
+            game_states.sort_by_key(|(_, action, _)| Reverse(table.score(action, active)));
+        }
+
+        let killers = self
+            .killers
+            .as_ref()
+            .and_then(|killers| killers.get(self.ply));
+        let (first, second) = match killers {
+            Some((first, second)) => (first.as_ref(), second.as_ref()),
+            None => (None, None),
+        };
+
+        let mut front = 0;
+        for action in best.as_ref().into_iter().chain(first).chain(second) {
+            let rest = &mut game_states[front..];
+            if let Some(idx) = rest.iter().position(|(_, a, _)| a == action) {
+                rest[..=idx].rotate_right(1);
+                front += 1;
             }
         }
     }
 
-    /// Computes the next possible steps and sorts them to maximize
-    /// cutoffs.
-    fn generate_game_states(&self, game_state: &T) -> (bool, Vec<(T, T::Action, T::Fitness)>) {
-        let (active, actions) = game_state.actions(self.player);
+    /// Remembers the action which caused the `cutoff` of the innermost frame at the given `depth`,
+    /// both as a killer move and in the history table.
+    fn add_refutation(&mut self, cutoff: &MiniMax<T>, active: bool, depth: u32) {
+        let ply = self.ply - 1;
+        let action = match cutoff {
+            MiniMax::Terminated(path, _) | MiniMax::Open(path, _) => match path.last() {
+                Some(action) => action,
+                None => return,
+            },
+            MiniMax::DeadEnd | MiniMax::Repetition => return,
+        };
 
-        let mut game_states: Vec<_> = actions
-            .into_iter()
-            .map(|action| {
-                let mut game_state = game_state.clone();
-                let fitness = game_state.execute(&action, self.player);
-                (game_state, action, fitness)
-            })
-            .collect();
+        if let Some(killers) = &mut self.killers {
+            if killers.len() <= ply {
+                killers.resize_with(ply + 1, || (None, None));
+            }
+            let (first, second) = &mut killers[ply];
+            if first.as_ref() != Some(action) {
+                *second = first.replace(action.clone());
+            }
+        }
 
-        // Sort the actions so the most probable one is checked first.
-        // This allows for faster cutoffs. Note that depending on the fitness
-        // function, this can hit some fairly bad cases.
-        if active {
-            game_states.sort_by(|(_, _, a), (_, _, b)| b.cmp(a));
-        } else {
-            game_states.sort_by(|(_, _, a), (_, _, b)| a.cmp(b));
+        if let Some(table) = &mut self.history_table {
+            let plies = u64::from(depth / PLY);
+            table.add(action, active, plies * plies);
         }
+    }
+
+    /// Stores the result of a completely searched state with the given `key` in the transposition table.
+    fn remember(&mut self, key: Option<u64>, depth: u32, value: &MiniMax<T>) {
+        if let (Some(table), Some(key)) = (&mut self.table, key) {
+            let (path, branch, terminated) = match value {
+                MiniMax::Terminated(path, branch) => (path, *branch, true),
+                MiniMax::Open(path, branch) => (path, *branch, false),
+                MiniMax::DeadEnd | MiniMax::Repetition => return,
+            };
 
-        (active, game_states)
+            let replaced = table.insert(Entry {
+                key,
+                depth,
+                branch,
+                terminated,
+                best: path.last().cloned(),
+            });
+            if replaced {
+                self.stats.table_replacements = self.stats.table_replacements.saturating_add(1);
+            }
+        }
     }
 
-    /// As we want to ignore as many possible subtrees as possible,
-    /// we start each depth by taking the best possible path of the
-    /// previous depth.
-    ///
-    /// As this path is hopefully also a good choice at this depth,
-    /// we very quickly get a good alpha/lower limit.
-    fn minimax_with_path<U: RunCondition>(
+    /// Starts searching the children of `state`, starting with `first` which is searched
+    /// while following the path of the previous iteration.
+    fn push_frame(
         &mut self,
-        mut path: impl Iterator<Item = T::Action>,
-        game_state: T,
+        state: State<T>,
+        first: Option<GameState<T>>,
+        game_states: GameStates<T>,
         depth: u32,
-        alpha: Option<T::Fitness>,
-        beta: Option<T::Fitness>,
-        condition: &mut U,
-    ) -> Result<MiniMax<T>, CancelledError> {
-        if !condition.step() {
-            return Err(CancelledError);
+        hash: Option<u64>,
+        key: Option<u64>,
+    ) -> Frame<T> {
+        self.history.extend(hash);
+        let width = self.widths.get(self.ply - 1).copied();
+        self.ply += 1;
+        Frame {
+            node: self.node,
+            child: None,
+            state,
+            first,
+            game_states: game_states.into_iter(),
+            current: None,
+            depth,
+            width,
+            hash,
+            key,
+            index: 0,
+            searched: 0,
+            width_pruned: false,
+            research: false,
+            incremental: None,
+            child_hash: None,
+            parent: None,
+            made: false,
+            passes: 0,
         }
+    }
 
-        let action = if let Some(action) = path.next() {
-            action
-        } else {
-            return self.minimax(game_state, depth, alpha, beta, condition);
-        };
-
-        if depth == 0 {
-            unreachable!("lowest depth with non empty path");
+    /// Stops searching the children of a frame with the given `hash`.
+    fn leave(&mut self, hash: Option<u64>) {
+        self.ply -= 1;
+        if hash.is_some() {
+            self.history.pop();
         }
+    }
 
-        let (active, mut game_states) = self.generate_game_states(&game_state);
-
-        let mut state = State::new(
-            self.new_path(),
-            game_state,
-            self.player,
-            alpha,
-            None,
-            active,
-        );
-        match game_states.iter().position(|(_, a, _)| *a == action) {
-            Some(idx) => {
-                let (game_state, action, fitness) = game_states.remove(idx);
+    /// Selects the next child of `frame`, which must have children left, and enters it in `game_state`.
+    ///
+    /// Only the first `width` children are searched, all remaining children are treated
+    /// as if they do not have any possible actions.
+    fn next_child(&mut self, frame: &mut Frame<T>, game_state: &mut T) -> Next<T> {
+        let depth = frame.depth - PLY;
+        if let Some((child, action, fitness)) = frame.first.take() {
+            self.make(frame, game_state, child, &action);
+            frame.searched += 1;
+            frame.child = self.record_child(frame.node, &action, depth);
+            frame.current = Some((action, fitness));
+            return Next::Enter(depth, frame.state.alpha, frame.state.beta, true);
+        }
 
-                let minimax = self
-                    .minimax_with_path(
-                        path,
-                        game_state,
-                        depth - 1,
-                        state.alpha,
-                        state.beta,
-                        condition,
-                    )?
-                    .with(self, action, fitness);
+        let (child, action, fitness) = frame.game_states.next().expect("no children left");
+        if frame.width.is_some_and(|width| frame.searched >= width) {
+            frame.child = None;
+            frame.current = Some((action, fitness));
+            // Record all remaining actions once, at the first skipped one.
+            if !frame.width_pruned {
+                frame.width_pruned = true;
+                let skipped = frame.game_states.len() + 1;
+                self.trace_pruning(
+                    PruningRule::SearchWidth,
+                    frame.node,
+                    self.ply - 1,
+                    skipped,
+                    None,
+                );
+            }
+            return Next::Return(MiniMax::DeadEnd);
+        }
 
-                if let Some(cutoff) = state.bind(self, minimax) {
-                    return Ok(cutoff);
+        self.make(frame, game_state, child, &action);
+        frame.searched += 1;
+        frame.child = self.record_child(frame.node, &action, depth);
+        frame.current = Some((action, fitness));
+        let (alpha, beta) = match self.principal_variation {
+            Some(zero_window) => match frame.state.zero_window(zero_window) {
+                Some((alpha, beta)) => {
+                    frame.research = true;
+                    (Some(alpha), Some(beta))
                 }
+                None => (frame.state.alpha, frame.state.beta),
+            },
+            None => (frame.state.alpha, frame.state.beta),
+        };
+        Next::Enter(depth, alpha, beta, false)
+    }
+
+    /// Replaces `game_state`, which is the state of `frame`, with its `child` reached using `action`.
+    ///
+    /// With `Bot::with_undo`, `child` is `None` and `action` is executed on `game_state` instead.
+    fn make(&self, frame: &mut Frame<T>, game_state: &mut T, child: Option<T>, action: &T::Action) {
+        frame.child_hash = self.child_hash(frame, game_state, action);
+        frame.passes = self.passes;
+        frame.made = true;
+        match child {
+            Some(child) => frame.parent = Some(mem::replace(game_state, child)),
+            None => {
+                game_state.execute(action, self.player);
             }
-            None => unreachable!("path segment not found"),
         }
+    }
 
-        for (game_state, action, fitness) in game_states {
-            let minimax = self
-                .minimax(game_state, depth - 1, state.alpha, state.beta, condition)?
-                .with(self, action, fitness);
-            if let Some(cutoff) = state.bind(self, minimax) {
-                return Ok(cutoff);
-            }
+    /// Reverts `make` after the child of `frame` reached using `action` was searched.
+    fn unmake(&mut self, frame: &mut Frame<T>, game_state: &mut T, action: &T::Action) {
+        if !mem::take(&mut frame.made) {
+            return;
+        }
+
+        if self.passes > frame.passes {
+            self.unpass(game_state);
+        }
+        match (frame.parent.take(), self.undo) {
+            (Some(parent), _) => *game_state = parent,
+            (None, Some((undo, _))) => undo(game_state, action, self.player),
+            (None, None) => unreachable!("child state without undo"),
         }
+    }
+}
+
+/// A state whose children are currently being searched.
+struct Frame<T: Game> {
+    /// The index of this state and the currently searched child in the search tree.
+    node: Option<usize>,
+    child: Option<usize>,
+    state: State<T>,
+    /// The child which is searched first while following the path of the previous iteration.
+    first: Option<GameState<T>>,
+    game_states: std::vec::IntoIter<GameState<T>>,
+    /// The action and fitness leading to the currently searched child.
+    current: Option<(T::Action, T::Fitness)>,
+    depth: u32,
+    width: Option<usize>,
+    hash: Option<u64>,
+    /// The key of this state in the transposition table.
+    key: Option<u64>,
+    /// The index of the currently searched child.
+    index: usize,
+    /// The amount of children which were not skipped because of `width`.
+    searched: usize,
+    width_pruned: bool,
+    /// Whether the currently searched child is searched using a zero-width window,
+    /// only used with `Bot::with_principal_variation_search`.
+    research: bool,
+    /// The hash of this state and of the currently searched child, only used with `Bot::with_incremental_hashing`.
+    incremental: Option<u64>,
+    child_hash: Option<u64>,
+    /// The state of this frame while a child is searched, only used without `Bot::with_undo`.
+    parent: Option<T>,
+    /// Whether the currently searched child was entered using `Ctxt::make`.
+    made: bool,
+    /// The value of `Ctxt::passes` before the currently searched child was entered.
+    passes: usize,
+}
+
+/// How often actions caused a cutoff, see `Bot::with_history_heuristic`.
+struct HistoryTable<T: Game> {
+    key: fn(&T::Action) -> usize,
+    /// The scores of the actions of the opponents and the ones of the bot.
+    scores: [Vec<u64>; 2],
+}
 
-        Ok(state.consume())
+impl<T: Game> HistoryTable<T> {
+    fn score(&self, action: &T::Action, active: bool) -> u64 {
+        self.scores[active as usize]
+            .get((self.key)(action))
+            .copied()
+            .unwrap_or(0)
     }
 
-    fn minimax<U: RunCondition>(
-        &mut self,
-        game_state: T,
+    fn add(&mut self, action: &T::Action, active: bool, score: u64) {
+        let key = (self.key)(action);
+        let scores = &mut self.scores[active as usize];
+        if scores.len() <= key {
+            scores.resize(key + 1, 0);
+        }
+        scores[key] = scores[key].saturating_add(score);
+    }
+}
+
+/// The results of already searched states, see `Bot::with_transposition_table`.
+struct Table<T: Game> {
+    hash: fn(&T) -> u64,
+    /// Each key is stored at index `key % entries.len()`, where `entries.len()` is a power of two.
+    entries: Vec<Option<Entry<T>>>,
+}
+
+struct Entry<T: Game> {
+    key: u64,
+    depth: u32,
+    branch: Branch<T>,
+    /// Whether the state was searched completely, in which case `branch` is also valid at larger depths.
+    terminated: bool,
+    /// The first action of the best path, which is searched first when the state is searched again.
+    best: Option<T::Action>,
+}
+
+/// The transposition table shared between threads when using `Bot::with_threads`.
+///
+/// The entries are split into shards of consecutive entries, each of which is locked separately.
+struct SharedTable<T: Game> {
+    hash: fn(&T) -> u64,
+    /// The total amount of entries, which is a power of two.
+    len: usize,
+    /// The amount of entries of each shard, which is a power of two.
+    shard_len: usize,
+    shards: Vec<Mutex<Vec<Option<Entry<T>>>>>,
+}
+
+/// The amount of shards of a `SharedTable`, unless the table has fewer entries.
+const SHARDS: usize = 256;
+
+/// The transposition table of a single search, which is shared between threads when using `Bot::with_threads`.
+enum TableRef<'a, T: Game> {
+    Owned(Table<T>),
+    Shared(&'a SharedTable<T>),
+}
+
+/// The result of a state stored in the transposition table, if it is usable,
+/// and the first action of its best path.
+type Probed<T> = (Option<(Branch<T>, bool)>, Option<<T as Game>::Action>);
+
+impl<T: Game> TableRef<'_, T> {
+    fn hash(&self) -> fn(&T) -> u64 {
+        match self {
+            TableRef::Owned(table) => table.hash,
+            TableRef::Shared(table) => table.hash,
+        }
+    }
+
+    /// Returns the entry of `key` in case it exists, see `Entry::result`.
+    fn probe(
+        &self,
+        key: u64,
         depth: u32,
         alpha: Option<T::Fitness>,
         beta: Option<T::Fitness>,
-        condition: &mut U,
-    ) -> Result<MiniMax<T>, CancelledError> {
-        if !condition.step() {
-            return Err(CancelledError);
+    ) -> Option<Probed<T>> {
+        let probe = |entry: &Entry<T>| (entry.result(depth, alpha, beta), entry.best.clone());
+        match self {
+            TableRef::Owned(table) => table.get(key).map(probe),
+            TableRef::Shared(table) => table.probe(key, probe),
         }
+    }
 
-        if depth == 0 {
-            let (active, actions) = game_state.actions(self.player);
-            let actions = actions.into_iter().map(|action| {
-                let fitness = game_state.look_ahead(&action, self.player);
-                (action, fitness)
-            });
-            let selected = if active {
-                actions.max_by_key(|(_, fitness)| *fitness)
-            } else {
-                actions.min_by_key(|(_, fitness)| *fitness)
-            };
+    /// Stores `entry`, returning whether it replaced the entry of a different state.
+    fn insert(&mut self, entry: Entry<T>) -> bool {
+        match self {
+            TableRef::Owned(table) => table.insert(entry),
+            TableRef::Shared(table) => table.insert(entry),
+        }
+    }
 
-            return Ok(selected.map_or(MiniMax::DeadEnd, |(action, fitness)| {
-                let mut path = self.new_path();
-                path.push(action);
-                MiniMax::Open(path, Branch::Equal(fitness))
-            }));
+    /// The total amount of entries.
+    fn len(&self) -> usize {
+        match self {
+            TableRef::Owned(table) => table.entries.len(),
+            TableRef::Shared(table) => table.len,
         }
+    }
+}
 
-        let (active, game_states) = self.generate_game_states(&game_state);
+impl<T: Game> Table<T> {
+    fn new(hash: fn(&T) -> u64, entries: usize) -> Self {
+        Self {
+            hash,
+            entries: (0..entries.next_power_of_two()).map(|_| None).collect(),
+        }
+    }
 
-        if game_states.is_empty() {
-            return Ok(MiniMax::DeadEnd);
+    fn get(&self, key: u64) -> Option<&Entry<T>> {
+        let idx = key as usize & (self.entries.len() - 1);
+        self.entries[idx].as_ref().filter(|entry| entry.key == key)
+    }
+
+    /// Replaces the entry at the index of `entry.key`, see `Entry::replaces`.
+    ///
+    /// Returns whether `entry` replaced the entry of a different state.
+    fn insert(&mut self, entry: Entry<T>) -> bool {
+        let idx = entry.key as usize & (self.entries.len() - 1);
+        entry.store(&mut self.entries[idx])
+    }
+}
+
+impl<T: Game> SharedTable<T> {
+    fn new(table: Table<T>) -> Self {
+        let mut entries = table.entries;
+        let len = entries.len();
+        let shard_len = cmp::max(len / SHARDS, 1);
+        // Splitting off the last shard first only moves each entry once.
+        let mut shards = Vec::with_capacity(len / shard_len);
+        while !entries.is_empty() {
+            shards.push(Mutex::new(entries.split_off(entries.len() - shard_len)));
+        }
+        shards.reverse();
+        Self {
+            hash: table.hash,
+            len,
+            shard_len,
+            shards,
         }
+    }
 
-        let mut state = State::new(
-            self.new_path(),
-            game_state,
-            self.player,
-            alpha,
-            beta,
-            active,
-        );
-        for (game_state, action, fitness) in game_states {
-            let minimax = self
-                .minimax(game_state, depth - 1, state.alpha, state.beta, condition)?
-                .with(self, action, fitness);
-            if let Some(cutoff) = state.bind(self, minimax) {
-                return Ok(cutoff);
+    /// Moves all entries of `other` into this table, see `Entry::replaces`.
+    fn merge(&self, other: &SharedTable<T>) {
+        for shard in &other.shards {
+            let mut shard = shard.lock().unwrap_or_else(PoisonError::into_inner);
+            for entry in shard.iter_mut().filter_map(Option::take) {
+                self.insert(entry);
             }
         }
+    }
+
+    fn into_table(self) -> Table<T> {
+        Table {
+            hash: self.hash,
+            entries: self
+                .shards
+                .into_iter()
+                .flat_map(|shard| shard.into_inner().unwrap_or_else(PoisonError::into_inner))
+                .collect(),
+        }
+    }
+
+    /// Returns the shard containing `key` and the index of `key` in this shard.
+    fn shard(&self, key: u64) -> (&Mutex<Vec<Option<Entry<T>>>>, usize) {
+        let idx = key as usize & (self.len - 1);
+        (
+            &self.shards[idx / self.shard_len],
+            idx & (self.shard_len - 1),
+        )
+    }
+
+    fn probe<R>(&self, key: u64, f: impl FnOnce(&Entry<T>) -> R) -> Option<R> {
+        let (shard, idx) = self.shard(key);
+        let shard = shard.lock().unwrap_or_else(PoisonError::into_inner);
+        shard[idx].as_ref().filter(|entry| entry.key == key).map(f)
+    }
+
+    /// Returns whether `entry` replaced the entry of a different state, see `Table::insert`.
+    fn insert(&self, entry: Entry<T>) -> bool {
+        let (shard, idx) = self.shard(entry.key);
+        let mut shard = shard.lock().unwrap_or_else(PoisonError::into_inner);
+        entry.store(&mut shard[idx])
+    }
+}
+
+impl<T: Game> Entry<T> {
+    /// Returns whether this entry replaces `old`, which is the case unless `old` contains a deeper search
+    /// of the same state.
+    fn replaces(&self, old: Option<&Entry<T>>) -> bool {
+        old.is_none_or(|old| old.key != self.key || old.depth <= self.depth || self.terminated)
+    }
+
+    /// Stores this entry in `slot` if it replaces the previous one, returning whether that one
+    /// was the entry of a different state.
+    fn store(self, slot: &mut Option<Entry<T>>) -> bool {
+        if !self.replaces(slot.as_ref()) {
+            return false;
+        }
+        let other = slot.as_ref().is_some_and(|old| old.key != self.key);
+        *slot = Some(self);
+        other
+    }
+
+    /// Returns the stored result in case it can be used when searching at `depth` with the given window.
+    ///
+    /// Exact results can always be used, while bounds are only useful if they cause a cutoff.
+    fn result(
+        &self,
+        depth: u32,
+        alpha: Option<T::Fitness>,
+        beta: Option<T::Fitness>,
+    ) -> Option<(Branch<T>, bool)> {
+        if !self.terminated && self.depth < depth {
+            return None;
+        }
 
-        Ok(state.consume())
+        match self.branch {
+            Branch::Equal(_) => Some((self.branch, self.terminated)),
+            Branch::Worse(fitness) if alpha.is_some_and(|alpha| fitness <= alpha) => {
+                Some((self.branch, self.terminated))
+            }
+            Branch::Better(fitness) if beta.is_some_and(|beta| fitness >= beta) => {
+                Some((self.branch, self.terminated))
+            }
+            _ => None,
+        }
     }
 }
 
+/// The next step of `Ctxt::run_frames`.
+enum Next<T: Game> {
+    /// Enters the current child with the given depth, alpha and beta, following the path of the previous
+    /// iteration if the last field is `true`.
+    Enter(u32, Option<T::Fitness>, Option<T::Fitness>, bool),
+    /// Binds the fitness of the currently searched child of the innermost frame.
+    Return(MiniMax<T>),
+}
+
+/// The result of entering a state.
+enum Entered<T: Game> {
+    /// The state does not have to be expanded, for example because it is a leaf.
+    Done(MiniMax<T>),
+    /// The children of this state have to be searched.
+    Expanded(Frame<T>),
+}
+
 struct State<T: Game> {
-    state: T,
     player: T::Player,
     alpha: Option<T::Fitness>,
     beta: Option<T::Fitness>,
@@ -716,16 +5590,53 @@ struct State<T: Game> {
 }
 
 impl<T: Game> State<T> {
+    /// Returns the zero-width window used to prove that a child is not better than the current best child,
+    /// or `None` if there is no current best child or the window is already as small as possible.
+    fn zero_window(
+        &self,
+        (successor, predecessor): ZeroWindow<T>,
+    ) -> Option<(T::Fitness, T::Fitness)> {
+        if self.active {
+            let alpha = self.alpha?;
+            let beta = successor(alpha);
+            if beta > alpha && self.beta.is_none_or(|old| beta < old) {
+                return Some((alpha, beta));
+            }
+        } else {
+            let beta = self.beta?;
+            let alpha = predecessor(beta);
+            if alpha < beta && self.alpha.is_none_or(|old| alpha > old) {
+                return Some((alpha, beta));
+            }
+        }
+        None
+    }
+
+    /// Returns whether the result of a zero-width search of a child shows that it may be better
+    /// than the current best child, in which case it has to be searched again.
+    fn fails_zero_window(&self, value: &MiniMax<T>) -> bool {
+        match value {
+            MiniMax::Open(_, branch) | MiniMax::Terminated(_, branch) => match *branch {
+                Branch::Better(fitness) if self.active => {
+                    self.beta.is_none_or(|beta| fitness < beta)
+                }
+                Branch::Worse(fitness) if !self.active => {
+                    self.alpha.is_none_or(|alpha| fitness > alpha)
+                }
+                _ => false,
+            },
+            MiniMax::DeadEnd | MiniMax::Repetition => false,
+        }
+    }
+
     fn new(
         path: Vec<T::Action>,
-        state: T,
         player: T::Player,
         alpha: Option<T::Fitness>,
         beta: Option<T::Fitness>,
         active: bool,
     ) -> Self {
         Self {
-            state,
             player,
             alpha,
             beta,
@@ -747,11 +5658,17 @@ impl<T: Game> State<T> {
         self.best_fitness = Some(fitness);
     }
 
-    fn bind(&mut self, ctxt: &mut Ctxt<'_, T>, value: MiniMax<T>) -> Option<MiniMax<T>> {
+    /// Binds the `value` of a child of `game_state`, returning the value of `game_state` once it is known.
+    fn bind(
+        &mut self,
+        ctxt: &mut Ctxt<'_, T>,
+        game_state: &T,
+        value: MiniMax<T>,
+    ) -> Option<MiniMax<T>> {
         match value {
-            MiniMax::DeadEnd => unreachable!(),
+            MiniMax::DeadEnd | MiniMax::Repetition => unreachable!(),
             MiniMax::Terminated(path, Branch::Equal(fitness)) => {
-                self.bind_equal(ctxt, path, fitness, true);
+                self.bind_equal(ctxt, game_state, path, fitness, true);
             }
             MiniMax::Terminated(path, Branch::Better(fitness)) => {
                 self.bind_better(ctxt, path, fitness, true);
@@ -760,7 +5677,7 @@ impl<T: Game> State<T> {
                 self.bind_worse(ctxt, path, fitness, true);
             }
             MiniMax::Open(path, Branch::Equal(fitness)) => {
-                self.bind_equal(ctxt, path, fitness, false);
+                self.bind_equal(ctxt, game_state, path, fitness, false);
             }
             MiniMax::Open(path, Branch::Better(fitness)) => {
                 self.bind_better(ctxt, path, fitness, false);
@@ -772,12 +5689,12 @@ impl<T: Game> State<T> {
 
         let branch = match self.best_fitness {
             Some(Branch::Equal(fitness)) | Some(Branch::Better(fitness))
-                if self.active && self.state.is_upper_bound(fitness, self.player) =>
+                if self.active && game_state.is_upper_bound(fitness, self.player) =>
             {
                 Branch::Equal(fitness)
             }
             Some(Branch::Equal(fitness)) | Some(Branch::Worse(fitness))
-                if !self.active && self.state.is_lower_bound(fitness, self.player) =>
+                if !self.active && game_state.is_lower_bound(fitness, self.player) =>
             {
                 Branch::Equal(fitness)
             }
@@ -794,28 +5711,23 @@ impl<T: Game> State<T> {
         };
 
         if self.terminated {
-            Some(MiniMax::Terminated(
-                mem::replace(&mut self.path, Vec::new()),
-                branch,
-            ))
+            Some(MiniMax::Terminated(mem::take(&mut self.path), branch))
         } else {
-            Some(MiniMax::Open(
-                mem::replace(&mut self.path, Vec::new()),
-                branch,
-            ))
+            Some(MiniMax::Open(mem::take(&mut self.path), branch))
         }
     }
 
     fn bind_equal(
         &mut self,
         ctxt: &mut Ctxt<'_, T>,
+        game_state: &T,
         path: Vec<T::Action>,
         fitness: T::Fitness,
         terminated: bool,
     ) {
         self.terminated &= terminated;
         if self.active {
-            if terminated && self.state.is_upper_bound(fitness, self.player) {
+            if terminated && game_state.is_upper_bound(fitness, self.player) {
                 self.update_best_action(ctxt, path, Branch::Equal(fitness));
                 self.terminated = true;
             } else {
@@ -823,14 +5735,14 @@ impl<T: Game> State<T> {
                 if self
                     .best_fitness
                     .as_ref()
-                    .map_or(true, |old| old.fitness() <= fitness)
+                    .is_none_or(|old| old.fitness() <= fitness)
                 {
                     self.update_best_action(ctxt, path, Branch::Equal(fitness));
                 } else {
                     ctxt.discard_path(path);
                 }
             }
-        } else if terminated && self.state.is_lower_bound(fitness, self.player) {
+        } else if terminated && game_state.is_lower_bound(fitness, self.player) {
             self.update_best_action(ctxt, path, Branch::Equal(fitness));
             self.terminated = true;
         } else {
@@ -838,7 +5750,7 @@ impl<T: Game> State<T> {
             if self
                 .best_fitness
                 .as_ref()
-                .map_or(true, |old| old.fitness() >= fitness)
+                .is_none_or(|old| old.fitness() >= fitness)
             {
                 self.update_best_action(ctxt, path, Branch::Equal(fitness));
             } else {
@@ -856,18 +5768,18 @@ impl<T: Game> State<T> {
     ) {
         self.terminated &= terminated;
         if self.active {
-            debug_assert!(self.alpha.map_or(true, |value| value <= fitness));
+            debug_assert!(self.alpha.is_none_or(|value| value <= fitness));
             debug_assert!(self
                 .best_fitness
                 .as_ref()
-                .map_or(true, |value| value.fitness() <= fitness));
+                .is_none_or(|value| value.fitness() <= fitness));
 
             self.alpha = Some(fitness);
             self.update_best_action(ctxt, path, Branch::Better(fitness));
         } else if self
             .best_fitness
             .as_ref()
-            .map_or(true, |old| old.fitness() > fitness)
+            .is_none_or(|old| old.fitness() > fitness)
         {
             self.update_best_action(ctxt, path, Branch::Better(fitness));
         } else {
@@ -884,17 +5796,17 @@ impl<T: Game> State<T> {
     ) {
         self.terminated &= terminated;
         if !self.active {
-            debug_assert!(self.beta.map_or(true, |value| value >= fitness));
+            debug_assert!(self.beta.is_none_or(|value| value >= fitness));
             self.beta = Some(fitness);
             debug_assert!(self
                 .best_fitness
                 .as_ref()
-                .map_or(true, |value| value.fitness() >= fitness));
+                .is_none_or(|value| value.fitness() >= fitness));
             self.update_best_action(ctxt, path, Branch::Worse(fitness));
         } else if self
             .best_fitness
             .as_ref()
-            .map_or(true, |old| old.fitness() < fitness)
+            .is_none_or(|old| old.fitness() < fitness)
         {
             self.update_best_action(ctxt, path, Branch::Worse(fitness));
         } else {
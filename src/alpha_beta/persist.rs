@@ -0,0 +1,87 @@
+use crate::Game;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{Bot, Parity};
+
+/// The parts of a `Bot` which are stored when serializing it.
+#[derive(Serialize, Deserialize)]
+struct Persisted<P, A> {
+    player: P,
+    hint: Option<A>,
+    max_depth: Option<u32>,
+    parity: Option<Parity>,
+    depth_schedule: (u32, u32),
+    widths: Vec<usize>,
+    persistent_table: bool,
+    endgame_solver: Option<u64>,
+    killer_moves: bool,
+    quiescence: Option<u32>,
+    incremental_hashing: bool,
+    random_ties: bool,
+    memory_limit: Option<usize>,
+    trace_pruning: bool,
+    search_tree: Option<usize>,
+}
+
+impl<P: Copy, A: Clone> Persisted<P, A> {
+    fn new<T: Game<Player = P, Action = A>>(bot: &Bot<T>) -> Self {
+        Persisted {
+            player: bot.player,
+            hint: bot.hint.clone(),
+            max_depth: bot.max_depth,
+            parity: bot.parity,
+            depth_schedule: bot.depth_schedule,
+            widths: bot.widths.clone(),
+            persistent_table: bot.persistent_table,
+            endgame_solver: bot.endgame_solver,
+            killer_moves: bot.killer_moves,
+            quiescence: bot.quiescence,
+            incremental_hashing: bot.incremental_hashing,
+            random_ties: bot.random_ties,
+            memory_limit: bot.memory_limit,
+            trace_pruning: bot.trace_pruning,
+            search_tree: bot.search_tree,
+        }
+    }
+
+    fn into_bot<T: Game<Player = P, Action = A>>(self) -> Bot<T> {
+        Bot {
+            hint: self.hint,
+            max_depth: self.max_depth,
+            parity: self.parity,
+            depth_schedule: self.depth_schedule,
+            widths: self.widths,
+            persistent_table: self.persistent_table,
+            endgame_solver: self.endgame_solver,
+            killer_moves: self.killer_moves,
+            quiescence: self.quiescence,
+            incremental_hashing: self.incremental_hashing,
+            random_ties: self.random_ties,
+            memory_limit: self.memory_limit,
+            trace_pruning: self.trace_pruning,
+            search_tree: self.search_tree,
+            ..Bot::new(self.player)
+        }
+    }
+}
+
+impl<T: Game> Serialize for Bot<T>
+where
+    T::Player: Serialize,
+    T::Action: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Persisted::new(self).serialize(serializer)
+    }
+}
+
+impl<'de, T: Game> Deserialize<'de> for Bot<T>
+where
+    T::Player: Deserialize<'de>,
+    T::Action: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Persisted::deserialize(deserializer).map(Persisted::into_bot)
+    }
+}
@@ -0,0 +1,190 @@
+//! A game bot for single player games with a huge amount of actions, using beam search.
+use crate::{Game, GameBot, IntoRunCondition, RunCondition};
+
+use std::cmp::Reverse;
+
+/// A game bot which only keeps the states with the highest fitness at each depth.
+///
+/// Starting at the current state, all actions of the best [`width`][width] states are executed,
+/// after which only the best `width` of the resulting states are kept for the next depth. This makes
+/// the cost of each depth independent of the amount of actions per state, so it gives usable results
+/// for optimization problems where an exhaustive search is impossible. Important actions which only
+/// pay off after more than one action might get discarded though.
+///
+/// All actions are chosen to maximize the fitness of the bot, so this bot should only be used for games
+/// with a single player. `RunCondition::depth` is called before searching one additional action and
+/// `RunCondition::step` before each executed action. The search stops once no kept state has any actions.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{ToCompletion, beam, Game};
+///
+/// /// Pick up to three of the given numbers, trying to reach the highest sum.
+/// #[derive(Clone)]
+/// struct Pick {
+///     left: Vec<u8>,
+///     sum: u8,
+///     picked: u8,
+/// }
+///
+/// impl Game for Pick {
+///     type Player = ();
+///     type Action = usize;
+///     type Fitness = u8;
+///     type Actions = std::ops::Range<usize>;
+///
+///     fn actions(&self, _: ()) -> (bool, Self::Actions) {
+///         if self.picked < 3 {
+///             (true, 0..self.left.len())
+///         } else {
+///             (true, 0..0)
+///         }
+///     }
+///
+///     fn execute(&mut self, &action: &usize, _: ()) -> u8 {
+///         self.sum += self.left.remove(action);
+///         self.picked += 1;
+///         self.sum
+///     }
+/// }
+///
+/// let state = Pick { left: vec![3, 9, 1, 7, 4], sum: 0, picked: 0 };
+/// let mut bot = beam::Bot::new(()).with_width(2);
+/// assert_eq!(bot.select(&state, ToCompletion), Some(1));
+/// ```
+///
+/// [width]: #method.with_width
+pub struct Bot<T: Game> {
+    player: T::Player,
+    width: usize,
+}
+
+/// A kept state together with the index of the first action leading to it.
+struct Candidate<T: Game> {
+    state: T,
+    first: usize,
+    fitness: T::Fitness,
+}
+
+impl<T: Game> Bot<T> {
+    /// Creates a new `Bot` for the given `player`, keeping `16` states at each depth.
+    pub fn new(player: T::Player) -> Self {
+        Self { player, width: 16 }
+    }
+
+    /// Sets the amount of states kept at each depth.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is `0`.
+    pub fn with_width(mut self, width: usize) -> Self {
+        assert!(width > 0, "beam width must not be zero");
+        self.width = width;
+        self
+    }
+
+    /// Returns the first action leading to the state with the highest fitness, or `None` if no action is
+    /// possible or the bot is currently not the active player.
+    ///
+    /// The highest fitness is taken from the states of the deepest completed depth and all states
+    /// without possible actions which were found during the search.
+    pub fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action> {
+        let mut condition = condition.into_run_condition();
+
+        let (active, actions) = state.actions(self.player);
+        if !active {
+            return None;
+        }
+
+        let mut actions: Vec<_> = actions.into_iter().collect();
+        if actions.is_empty() {
+            return None;
+        }
+
+        let mut beam = Vec::with_capacity(actions.len());
+        for (first, action) in actions.iter().enumerate() {
+            if !condition.step() {
+                break;
+            }
+            let mut state = state.clone();
+            let fitness = state.execute(action, self.player);
+            beam.push(Candidate {
+                state,
+                first,
+                fitness,
+            });
+        }
+        self.prune(&mut beam);
+
+        let mut finished: Option<(usize, T::Fitness)> = None;
+        let mut depth = 0;
+        while !beam.is_empty() && condition.depth(depth) {
+            depth += 1;
+            match self.expand(&beam, &mut finished, &mut condition) {
+                Some(mut children) => {
+                    self.prune(&mut children);
+                    beam = children;
+                }
+                None => break,
+            }
+        }
+
+        let mut best = finished;
+        for candidate in beam {
+            if best.is_none_or(|(_, best)| candidate.fitness > best) {
+                best = Some((candidate.first, candidate.fitness));
+            }
+        }
+        let first = best.map_or(0, |(first, _)| first);
+        Some(actions.swap_remove(first))
+    }
+
+    /// Returns all children of `beam`, or `None` if the search was cancelled.
+    ///
+    /// States without actions are compared with `finished` instead.
+    fn expand<U: RunCondition>(
+        &self,
+        beam: &[Candidate<T>],
+        finished: &mut Option<(usize, T::Fitness)>,
+        condition: &mut U,
+    ) -> Option<Vec<Candidate<T>>> {
+        let mut children = Vec::new();
+        for candidate in beam {
+            let (_, actions) = candidate.state.actions(self.player);
+            let mut actions = actions.into_iter().peekable();
+            if actions.peek().is_none() {
+                if finished.is_none_or(|(_, best)| candidate.fitness > best) {
+                    *finished = Some((candidate.first, candidate.fitness));
+                }
+                continue;
+            }
+
+            for action in actions {
+                if !condition.step() {
+                    return None;
+                }
+                let mut state = candidate.state.clone();
+                let fitness = state.execute(&action, self.player);
+                children.push(Candidate {
+                    state,
+                    first: candidate.first,
+                    fitness,
+                });
+            }
+        }
+        Some(children)
+    }
+
+    /// Only keeps the best `width` states, preferring earlier states in case of equal fitness.
+    fn prune(&self, beam: &mut Vec<Candidate<T>>) {
+        beam.sort_by_key(|candidate| Reverse(candidate.fitness));
+        beam.truncate(self.width);
+    }
+}
+
+impl<T: Game> GameBot<T> for Bot<T> {
+    fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action> {
+        Bot::select(self, state, condition)
+    }
+}
@@ -0,0 +1,146 @@
+//! A benchmark suite measuring how fast bots search a fixed set of states.
+use crate::rng::XorShiftRng;
+use crate::tree::Node;
+use crate::{Depth, Game, GameBot, Logger};
+
+use std::time::Duration;
+
+/// The sizes of the trees in [`Suite::standard`][standard].
+///
+/// [standard]: struct.Suite.html#method.standard
+const STANDARD_SIZES: &[usize] = &[100, 1_000, 10_000];
+
+/// The result of searching a single state of a [`Suite`][suite] up to a given depth.
+///
+/// [suite]: struct.Suite.html
+#[derive(Clone, Debug)]
+pub struct Measurement {
+    /// The name of the searched state.
+    pub name: String,
+    /// The depth passed to [`Depth`][depth].
+    ///
+    /// [depth]: ../struct.Depth.html
+    pub depth: u32,
+    /// The amount of search steps, as counted by [`Logger::steps`][steps].
+    ///
+    /// [steps]: ../struct.Logger.html#method.steps
+    pub nodes: u64,
+    /// The time until the search finished.
+    pub time: Duration,
+    /// Whether the search finished before reaching `depth`, in which case deeper measurements are skipped.
+    pub completed: bool,
+}
+
+impl Measurement {
+    /// Returns the amount of search steps per second.
+    pub fn nodes_per_second(&self) -> f64 {
+        self.nodes as f64 / self.time.as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+}
+
+/// A fixed set of states, each of which is searched with increasing depths.
+///
+/// [`standard`][standard] contains generated trees of different sizes, while user provided states can be added
+/// using [`with_state`][with]. As all measurements use [`Depth`][depth], the amount of searched nodes does not
+/// depend on the machine, so it can be compared between different versions of a bot.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{Bot, bench::Suite};
+///
+/// let measurements = Suite::standard().with_max_depth(3).run(|| Bot::new(true));
+/// for measurement in &measurements {
+///     println!(
+///         "{} depth {}: {} nodes in {:?}, {:.0} nodes/s",
+///         measurement.name,
+///         measurement.depth,
+///         measurement.nodes,
+///         measurement.time,
+///         measurement.nodes_per_second(),
+///     );
+/// }
+/// ```
+///
+/// [standard]: #method.standard
+/// [with]: #method.with_state
+/// [depth]: ../struct.Depth.html
+pub struct Suite<T> {
+    states: Vec<(String, T)>,
+    max_depth: u32,
+}
+
+impl Suite<Node> {
+    /// Creates a suite containing generated trees with `100`, `1000` and `10000` nodes.
+    ///
+    /// The trees are generated using [`Node::from_bytes`][bytes], so they may change between versions.
+    ///
+    /// [bytes]: ../tree/struct.Node.html#method.from_bytes
+    pub fn standard() -> Self {
+        let mut rng = XorShiftRng::from_seed_u64(0);
+        let mut suite = Self::new();
+        for &size in STANDARD_SIZES {
+            let bytes: Vec<u8> = (0..size + 4).map(|_| rng.next_u64() as u8).collect();
+            suite = suite.with_state(&format!("tree-{}", size), Node::from_bytes(&bytes));
+        }
+        suite
+    }
+}
+
+impl<T: Game> Suite<T> {
+    /// Creates an empty suite, measuring depths up to `8` by default.
+    pub fn new() -> Self {
+        Self {
+            states: Vec::new(),
+            max_depth: 8,
+        }
+    }
+
+    /// Adds a state to this suite, identified by `name`.
+    pub fn with_state(mut self, name: &str, state: T) -> Self {
+        self.states.push((name.to_string(), state));
+        self
+    }
+
+    /// Sets the deepest measured depth.
+    pub fn with_max_depth(mut self, max_depth: u32) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Returns all states of this suite.
+    pub fn states(&self) -> &[(String, T)] {
+        &self.states
+    }
+
+    /// Searches each state using a new bot created by `bot` for each depth up to the maximum depth.
+    ///
+    /// Measurements are returned ordered by state and depth.
+    pub fn run<B: GameBot<T>>(&self, mut bot: impl FnMut() -> B) -> Vec<Measurement> {
+        let mut measurements = Vec::new();
+        for (name, state) in &self.states {
+            for depth in 0..=self.max_depth {
+                let mut logger = Logger::new(Depth(depth));
+                bot().select(state, &mut logger);
+                let completed = logger.completed();
+                measurements.push(Measurement {
+                    name: name.clone(),
+                    depth,
+                    nodes: logger.steps(),
+                    time: logger.duration(),
+                    completed,
+                });
+                if completed {
+                    break;
+                }
+            }
+        }
+        measurements
+    }
+}
+
+impl<T: Game> Default for Suite<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,20 +1,140 @@
-//! This module contains a bot which simply brute forces every possible action, this bot should only be used for testing.
-use crate::Game;
+//! An exact solver which simply brute forces every possible action.
+//!
+//! This is far too slow to actually play most games, but as it does not prune anything, it is
+//! useful as a correctness oracle when testing other bots, for example using [`Brute::check_if_best`][check].
+//!
+//! # Examples
+//!
+//! ```rust
+//! use rubot::{Bot, ToCompletion, brute::Brute, tree::Node};
+//!
+//! # #[rustfmt::skip]
+//! let tree = Node::root().with_children(&[
+//!     Node::new(false, 7).with_children(&[
+//!         Node::new(true, 4),
+//!         Node::new(true, 2),
+//!     ]),
+//!     Node::new(false, 5).with_children(&[
+//!         Node::new(true, 8),
+//!         Node::new(true, 9),
+//!     ]),
+//! ]);
+//!
+//! let selected = Bot::new(true).select(&tree, ToCompletion);
+//! assert!(Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX));
+//! ```
+//!
+//! [check]: struct.Brute.html#method.check_if_best
+use crate::{Depth, Game, GameBot, IntoRunCondition, RunCondition};
 
 use std::cmp::{self, Ordering};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
 
-/// A bot which uses brute force to calculate the optimal move
+/// A bot which uses brute force to calculate the optimal move.
+///
+/// All methods take a `depth`, which is the amount of actions executed after the first one
+/// before using `Game::look_ahead`. Use `u32::MAX` to search the complete game.
 pub struct Brute<T: Game> {
     player: T::Player,
+    memo: Option<Memo<T>>,
+}
+
+/// Already computed fitness values, see `Brute::with_memoization`.
+struct Memo<T: Game> {
+    hash: fn(&T) -> u64,
+    eq: fn(&T, &T) -> bool,
+    canonical: Option<fn(&T) -> T>,
+    entries: HashMap<u64, Vec<Entry<T>>>,
+}
+
+struct Entry<T: Game> {
+    state: T,
+    depth: u32,
+    fitness: T::Fitness,
+    /// Whether `Game::look_ahead` was never used, in which case `fitness` is also exact for all larger depths.
+    complete: bool,
+}
+
+fn hash_state<T: Hash>(state: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    state.hash(&mut hasher);
+    hasher.finish()
 }
 
 impl<T: Game> Brute<T> {
+    /// Creates a new `Brute` for the given `player`.
     pub fn new(player: T::Player) -> Self {
-        Self { player }
+        Self { player, memo: None }
+    }
+
+    /// Remembers the fitness of each searched state, so states which can be reached
+    /// using different orders of actions are only searched once.
+    ///
+    /// This requires the fitness after an action to only depend on the resulting state, as
+    /// the transposition table is keyed by the resulting state instead of the previous state and action.
+    /// Remembered values are also used by later calls.
+    ///
+    /// ```rust
+    /// use rubot::{brute::Brute, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 7).with_children(&[
+    ///         Node::new(true, 4),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 8),
+    ///         Node::new(true, 9),
+    ///     ]),
+    /// ]);
+    ///
+    /// let mut brute = Brute::new(true).with_memoization();
+    /// assert_eq!(brute.select(&tree, u32::MAX), Some(1));
+    /// ```
+    pub fn with_memoization(mut self) -> Self
+    where
+        T: Hash + Eq,
+    {
+        self.memo.get_or_insert_with(|| Memo {
+            hash: hash_state::<T>,
+            eq: T::eq,
+            canonical: None,
+            entries: HashMap::new(),
+        });
+        self
+    }
+
+    /// Replaces each state with `canonical(state)` before remembering it, so symmetric states,
+    /// e.g. mirrored boards, are only searched once. All states which are mapped to the same state
+    /// must have the same fitness.
+    ///
+    /// This also enables [`with_memoization`][memo].
+    ///
+    /// [memo]: #method.with_memoization
+    pub fn with_canonical_form(mut self, canonical: fn(&T) -> T) -> Self
+    where
+        T: Hash + Eq,
+    {
+        self = self.with_memoization();
+        if let Some(memo) = &mut self.memo {
+            memo.canonical = Some(canonical);
+        }
+        self
     }
 
+    /// Returns the best action for `state`, or `None` if no action is possible or the bot is
+    /// currently not the active player. In case multiple actions are equally good, the first one is returned.
     pub fn select(&mut self, state: &T, depth: u32) -> Option<T::Action> {
+        self.best_action(state, depth).map(|(action, _)| action)
+    }
+
+    /// Returns the best action and whether the complete game was searched.
+    fn best_action(&mut self, state: &T, depth: u32) -> Option<(T::Action, bool)> {
         let (active, actions) = state.actions(self.player);
         if !active {
             return None;
@@ -22,22 +142,25 @@ impl<T: Game> Brute<T> {
 
         let mut actions = actions.into_iter();
 
-        let mut best = {
+        let (mut best, mut complete) = {
             let action = actions.next()?;
-            let value = self.minimax(state, &action, depth);
-            (action, value)
+            let (value, complete) = self.search(state, &action, depth);
+            ((action, value), complete)
         };
 
         for action in actions {
-            let new = self.minimax(state, &action, depth);
+            let (new, new_complete) = self.search(state, &action, depth);
+            complete &= new_complete;
             if new > best.1 {
                 best = (action, new);
             }
         }
 
-        Some(best.0)
+        Some((best.0, complete))
     }
 
+    /// Returns whether `best` is one of the best actions for `state`, where `None` means that
+    /// no action is possible or the bot is currently not the active player.
     pub fn check_if_best(&mut self, state: &T, best: Option<&T::Action>, depth: u32) -> bool {
         let (active, actions) = state.actions(self.player);
         if !active {
@@ -49,7 +172,7 @@ impl<T: Game> Brute<T> {
             return actions.next().is_none();
         }
 
-        let mut best = self.minimax(state, best.unwrap(), depth);
+        let best = self.minimax(state, best.unwrap(), depth);
 
         for action in actions {
             let new = self.minimax(state, &action, depth);
@@ -61,8 +184,10 @@ impl<T: Game> Brute<T> {
         true
     }
 
-    /// lists all actions with a fitness at `completed_depth + 1` which is better than the worst action
-    /// of all best actions at `completed_depth`
+    /// Lists all actions with a fitness at `completed_depth + 1` which is better than the worst action
+    /// of all best actions at `completed_depth`.
+    ///
+    /// These are the actions which may be returned by a bot which was interrupted after `completed_depth`.
     pub fn allowed_actions(&mut self, state: &T, completed_depth: u32) -> Vec<Option<T::Action>> {
         let (active, actions) = state.actions(self.player);
         if !active {
@@ -99,30 +224,117 @@ impl<T: Game> Brute<T> {
             .min()
             .unwrap();
 
-        let mut actions: Vec<_> = state
+        state
             .actions(self.player)
             .1
             .into_iter()
             .filter(|action| self.minimax(state, action, completed_depth + 1) >= worst_allowed)
             .map(Some)
-            .collect();
-        actions
+            .collect()
+    }
+
+    /// Returns the fitness of `action` in `state`, when searching `depth` additional actions.
+    pub fn fitness(&mut self, state: &T, action: &T::Action, depth: u32) -> T::Fitness {
+        self.minimax(state, action, depth)
     }
 
     fn minimax(&mut self, state: &T, action: &T::Action, depth: u32) -> T::Fitness {
+        self.search(state, action, depth).0
+    }
+
+    /// Returns the fitness of `action` and whether it was computed without using `Game::look_ahead`.
+    fn search(&mut self, state: &T, action: &T::Action, depth: u32) -> (T::Fitness, bool) {
         if depth == 0 {
-            state.look_ahead(&action, self.player)
-        } else {
-            let mut state = state.clone();
-            let fitness = state.execute(&action, self.player);
-            let (active, actions) = state.actions(self.player);
+            return (state.look_ahead(action, self.player), false);
+        }
+
+        let mut state = state.clone();
+        let fitness = state.execute(action, self.player);
+        let (mut active, actions) = state.actions(self.player);
+        let mut actions: Vec<_> = actions.into_iter().collect();
+        if !active && actions.is_empty() && state.pass(self.player) {
+            let (now_active, now_actions) = state.actions(self.player);
+            active = now_active;
+            actions = now_actions.into_iter().collect();
+        }
+
+        if actions.is_empty() {
+            return (fitness, true);
+        }
+
+        if let Some(result) = self.recall(&state, depth) {
+            return result;
+        }
+
+        let mut complete = true;
+        let mut best = None;
+        for action in actions {
+            let (fitness, child_complete) = self.search(&state, &action, depth - 1);
+            complete &= child_complete;
+            best = Some(match best {
+                Some(best) if active => cmp::max(best, fitness),
+                Some(best) => cmp::min(best, fitness),
+                None => fitness,
+            });
+        }
+
+        let best = best.unwrap();
+        self.remember(state, depth, best, complete);
+        (best, complete)
+    }
+
+    fn recall(&self, state: &T, depth: u32) -> Option<(T::Fitness, bool)> {
+        let memo = self.memo.as_ref()?;
+        let canonical = memo.canonical.map(|canonical| canonical(state));
+        let state = canonical.as_ref().unwrap_or(state);
+        memo.entries
+            .get(&(memo.hash)(state))?
+            .iter()
+            .find(|entry| {
+                (memo.eq)(&entry.state, state)
+                    && (entry.depth == depth || (entry.complete && entry.depth <= depth))
+            })
+            .map(|entry| (entry.fitness, entry.complete))
+    }
 
-            let iter = actions
-                .into_iter()
-                .map(|action| self.minimax(&state, &action, depth - 1));
+    fn remember(&mut self, state: T, depth: u32, fitness: T::Fitness, complete: bool) {
+        if let Some(memo) = &mut self.memo {
+            let state = match memo.canonical {
+                Some(canonical) => canonical(&state),
+                None => state,
+            };
+            memo.entries
+                .entry((memo.hash)(&state))
+                .or_default()
+                .push(Entry {
+                    state,
+                    depth,
+                    fitness,
+                    complete,
+                });
+        }
+    }
+}
 
-            if active { iter.max() } else { iter.min() }.unwrap_or(fitness)
+/// Searches one additional action each time `RunCondition::depth` returns `true`,
+/// stopping once the complete game was searched.
+///
+/// Like for [`alpha_beta::Bot`][ab], [`Depth`][depth]`(n)` searches `n + 1` actions.
+///
+/// [ab]: ../alpha_beta/struct.Bot.html
+/// [depth]: ../struct.Depth.html
+impl<T: Game> GameBot<T> for Brute<T> {
+    fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action> {
+        let mut condition = condition.into_run_condition();
+        let (mut best, mut complete) = self.best_action(state, 0)?;
+        let mut depth = 0;
+        while !complete && condition.depth(depth) {
+            depth += 1;
+            let (action, now_complete) = self.best_action(state, depth)?;
+            best = action;
+            complete = now_complete;
         }
+        Some(best)
     }
 }
 
@@ -131,7 +343,12 @@ where
     T::Fitness: fmt::Debug,
     T::Action: fmt::Debug,
 {
-    pub fn print_best(&mut self, state: &T, depth: u32) {
+    /// Writes the best action for `state` and its fitness to `out`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `state` has no possible actions for the player of this bot.
+    pub fn print_best(&mut self, mut out: impl Write, state: &T, depth: u32) -> io::Result<()> {
         let (active, actions) = state.actions(self.player);
         assert!(active);
 
@@ -150,6 +367,48 @@ where
             }
         }
 
-        println!("best: {:?}, fitness: {:?}", best.0, best.1);
+        writeln!(out, "best: {:?}, fitness: {:?}", best.0, best.1)
+    }
+
+    /// Asserts that `bot` selects one of the best actions for `state` when using [`Depth`][depth]`(depth)`,
+    /// which searches the same amount of actions as `Brute` does for `depth`.
+    ///
+    /// This is meant to be used in property tests of custom games and bots, where `bot` has to select
+    /// actions for the same player as this `Brute`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the fitness of the selected action is worse than the fitness of the best action,
+    /// with a message containing both actions and their fitness.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, brute::Brute, tree::Node};
+    ///
+    /// for seed in 0..100u8 {
+    ///     let tree = Node::from_bytes(&[seed, 7, 3, 9, 1, 4, 2, 8, 6, 5, 0, 3, 7, 2, 9]);
+    ///     for depth in 0..4 {
+    ///         Brute::new(true).assert_bot(&mut Bot::new(true), &tree, depth);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [depth]: ../struct.Depth.html
+    pub fn assert_bot<B: GameBot<T>>(&mut self, bot: &mut B, state: &T, depth: u32) {
+        let selected = bot.select(state, Depth(depth));
+        if self.check_if_best(state, selected.as_ref(), depth) {
+            return;
+        }
+
+        let best = self.select(state, depth);
+        let fitness = |brute: &mut Self, action: &Option<T::Action>| {
+            action
+                .as_ref()
+                .map(|action| brute.minimax(state, action, depth))
+        };
+        let (selected_fitness, best_fitness) = (fitness(self, &selected), fitness(self, &best));
+        panic!(
+            "selected {:?} with fitness {:?} at depth {}, but the best action is {:?} with fitness {:?}",
+            selected, selected_fitness, depth, best, best_fitness
+        );
     }
 }
@@ -0,0 +1,598 @@
+//! Wrappers which validate that a [`Game`][game] upholds the contracts required by bots.
+//!
+//! Violating these contracts does not cause a bot to panic, it just causes it to play badly,
+//! which can be really hard to debug. It is recommended to only use these wrappers during
+//! testing, as they are a lot slower than using the game directly.
+//!
+//! [game]: ../trait.Game.html
+use crate::rng::XorShiftRng;
+use crate::{Game, Negate, UnmakeGame};
+use std::cell::Cell;
+use std::fmt::{self, Debug, Display};
+
+/// A game wrapper which panics as soon as it detects a violated contract of [`Game`][game].
+///
+/// Always checks that clones of a game state return the actions in the same order.
+/// Iterating over a `HashMap` or `HashSet` inside of `actions` can easily break
+/// this without being noticed.
+///
+/// Using [`with_look_ahead_checks`][la], `look_ahead` can additionally be compared with `execute`,
+/// and for zero-sum games, [`with_zero_sum_checks`][zs] validates that the fitness is always from
+/// the perspective of the queried player. [`with_dead_end_checks`][de] rejects active players
+/// without any possible actions.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{Bot, ToCompletion, check::Checked, tree::Node};
+///
+/// # #[rustfmt::skip]
+/// let tree = Node::root().with_children(&[
+///     Node::new(false, 4),
+///     Node::new(false, 7),
+/// ]);
+///
+/// let mut bot = Bot::new(true);
+/// assert_eq!(bot.select(&Checked::new(tree), ToCompletion), Some(1));
+/// ```
+///
+/// [game]: ../trait.Game.html
+/// [la]: #method.with_look_ahead_checks
+/// [zs]: #method.with_zero_sum_checks
+/// [de]: #method.with_dead_end_checks
+#[derive(Debug, Clone)]
+pub struct Checked<T: Game> {
+    game: T,
+    look_ahead_rate: f64,
+    rng: Cell<XorShiftRng>,
+    zero_sum: Option<ZeroSum<T>>,
+    dead_end: bool,
+}
+
+/// Returns the opponent of a player and negates fitness values.
+type ZeroSum<T> = (
+    fn(<T as Game>::Player) -> <T as Game>::Player,
+    fn(<T as Game>::Fitness) -> <T as Game>::Fitness,
+);
+
+impl<T: Game> Checked<T> {
+    /// Wraps `game`.
+    pub fn new(game: T) -> Self {
+        Self {
+            game,
+            look_ahead_rate: 0.0,
+            rng: Cell::new(XorShiftRng::from_seed(0)),
+            zero_sum: None,
+            dead_end: false,
+        }
+    }
+
+    /// Compares `look_ahead` and `look_ahead_batch` with `execute` for roughly a fraction `rate`
+    /// of all evaluated actions, panicking with the offending action in case they differ.
+    ///
+    /// Actions are sampled using a fixed seed, so repeated searches check the same actions.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, Game, SelectError, ToCompletion, check::Checked, tree::Node};
+    ///
+    /// #[derive(Clone)]
+    /// struct Optimistic(Node);
+    ///
+    /// impl Game for Optimistic {
+    ///     type Player = bool;
+    ///     type Action = usize;
+    ///     type Fitness = i8;
+    ///     type Actions = std::ops::Range<usize>;
+    ///
+    ///     fn actions(&self, player: bool) -> (bool, Self::Actions) {
+    ///         self.0.actions(player)
+    ///     }
+    ///
+    ///     fn execute(&mut self, action: &usize, player: bool) -> i8 {
+    ///         self.0.execute(action, player)
+    ///     }
+    ///
+    ///     fn look_ahead(&self, action: &usize, player: bool) -> i8 {
+    ///         self.0.look_ahead(action, player) + 1
+    ///     }
+    /// }
+    ///
+    /// let tree = Node::root().with_children(&[Node::new(false, 4)]);
+    /// let game = Checked::new(Optimistic(tree)).with_look_ahead_checks(1.0);
+    /// # std::panic::set_hook(Box::new(|_| ()));
+    /// match Bot::new(true).try_select(&game, ToCompletion) {
+    ///     Err(SelectError::Panicked(_)) => (),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn with_look_ahead_checks(mut self, rate: f64) -> Self {
+        self.look_ahead_rate = rate;
+        self
+    }
+
+    /// Checks that executing an action returns the negated fitness when queried for `opponent(player)`
+    /// instead of `player`, panicking with the offending action otherwise.
+    ///
+    /// This only holds for zero-sum games, and catches the most common mistake when implementing
+    /// [`Game`][game]: returning the fitness from the perspective of the active player, or of a fixed player,
+    /// instead of the queried one. As `i32::MIN` cannot be negated, use `-i32::MAX` for lost games.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, SelectError, ToCompletion, check::Checked, tree::Node};
+    ///
+    /// // `Node` always returns the fitness from the perspective of the tested player.
+    /// let tree = Node::root().with_children(&[Node::new(false, 4)]);
+    /// let game = Checked::new(tree).with_zero_sum_checks(|player| !player);
+    /// # std::panic::set_hook(Box::new(|_| ()));
+    /// match Bot::new(true).try_select(&game, ToCompletion) {
+    ///     Err(SelectError::Panicked(_)) => (),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    ///
+    /// [game]: ../trait.Game.html
+    pub fn with_zero_sum_checks(mut self, opponent: fn(T::Player) -> T::Player) -> Self
+    where
+        T::Fitness: Negate,
+    {
+        self.zero_sum = Some((opponent, Negate::negate));
+        self
+    }
+
+    /// Panics if `actions` reports the queried player as active without returning any actions.
+    ///
+    /// Bots treat such a state as the end of the game and use the fitness of the previous action, which
+    /// silently hides bugs in `actions`, e.g. forgetting to switch the active player. When using this check,
+    /// finished games must report the queried player as inactive. Without this check, `alpha_beta::Bot`
+    /// counts these states in [`SearchStats::dead_ends`][dead_ends].
+    ///
+    /// ```rust
+    /// use rubot::{Bot, SelectError, ToCompletion, check::Checked, tree::Node};
+    ///
+    /// // The leaf is owned by the bot, so it is active without any possible actions.
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(true, 4),
+    ///     Node::new(false, 2),
+    /// ]);
+    /// let game = Checked::new(tree).with_dead_end_checks();
+    /// # std::panic::set_hook(Box::new(|_| ()));
+    /// match Bot::new(true).try_select(&game, ToCompletion) {
+    ///     Err(SelectError::Panicked(_)) => (),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    ///
+    /// [dead_ends]: ../alpha_beta/struct.SearchStats.html#structfield.dead_ends
+    pub fn with_dead_end_checks(mut self) -> Self {
+        self.dead_end = true;
+        self
+    }
+
+    /// Returns a reference to the wrapped game.
+    pub fn get_ref(&self) -> &T {
+        &self.game
+    }
+
+    /// Unwraps this `Checked`, returning the wrapped game.
+    pub fn into_inner(self) -> T {
+        self.game
+    }
+}
+
+impl<T: Game> Checked<T>
+where
+    T::Action: Debug,
+    T::Fitness: Debug,
+{
+    fn sample(&self) -> bool {
+        if self.look_ahead_rate <= 0.0 {
+            return false;
+        }
+
+        let mut rng = self.rng.get();
+        let sampled = rng.next_f64() < self.look_ahead_rate;
+        self.rng.set(rng);
+        sampled
+    }
+
+    fn check_look_ahead(&self, action: &T::Action, player: T::Player, look_ahead: T::Fitness) {
+        let execute = self.game.clone().execute(action, player);
+        assert_eq!(
+            look_ahead, execute,
+            "`Game::look_ahead` and `Game::execute` returned different fitness values for `{:?}`",
+            action
+        );
+    }
+
+    fn opponent_fitness(&self, action: &T::Action, player: T::Player) -> Option<T::Fitness> {
+        self.zero_sum
+            .map(|(opponent, _)| self.game.clone().execute(action, opponent(player)))
+    }
+
+    fn check_perspective(
+        &self,
+        action: &T::Action,
+        fitness: T::Fitness,
+        opponent_fitness: Option<T::Fitness>,
+    ) {
+        if let (Some((_, negate)), Some(opponent_fitness)) = (self.zero_sum, opponent_fitness) {
+            assert_eq!(
+                fitness,
+                negate(opponent_fitness),
+                "the fitness after `{:?}` is not negated for the opponent, \
+                 it must always be from the perspective of the queried player",
+                action
+            );
+        }
+    }
+}
+
+impl<T: Game> Game for Checked<T>
+where
+    T::Action: Debug,
+    T::Fitness: Debug,
+{
+    type Player = T::Player;
+    type Action = T::Action;
+    type Fitness = T::Fitness;
+    type Actions = Vec<T::Action>;
+
+    fn actions(&self, player: Self::Player) -> (bool, Self::Actions) {
+        let (active, actions) = self.game.actions(player);
+        let actions: Vec<_> = actions.into_iter().collect();
+
+        let (clone_active, clone_actions) = self.game.clone().actions(player);
+        assert_eq!(
+            active, clone_active,
+            "`Game::actions` returned a different active player for a clone of the same state"
+        );
+        let mut clone_actions = clone_actions.into_iter();
+        for (idx, action) in actions.iter().enumerate() {
+            match clone_actions.next() {
+                Some(ref clone_action) if clone_action == action => (),
+                Some(clone_action) => panic!(
+                    "`Game::actions` returned a different order for a clone of the same state: \
+                     expected `{:?}` at index {}, found `{:?}`",
+                    action, idx, clone_action
+                ),
+                None => panic!(
+                    "`Game::actions` returned fewer actions for a clone of the same state: \
+                     missing `{:?}` at index {}",
+                    action, idx
+                ),
+            }
+        }
+        if let Some(clone_action) = clone_actions.next() {
+            panic!(
+                "`Game::actions` returned more actions for a clone of the same state: \
+                 found additional `{:?}` at index {}",
+                clone_action,
+                actions.len()
+            );
+        }
+
+        assert!(
+            !(self.dead_end && active && actions.is_empty()),
+            "`Game::actions` returned no actions for an active player"
+        );
+
+        (active, actions)
+    }
+
+    fn execute(&mut self, action: &Self::Action, player: Self::Player) -> Self::Fitness {
+        // `self.game` is modified by `execute`, so query the opponent fitness beforehand.
+        let opponent_fitness = self.opponent_fitness(action, player);
+        if self.sample() {
+            let look_ahead = self.game.look_ahead(action, player);
+            self.check_look_ahead(action, player, look_ahead);
+        }
+        let fitness = self.game.execute(action, player);
+        self.check_perspective(action, fitness, opponent_fitness);
+        fitness
+    }
+
+    fn look_ahead(&self, action: &Self::Action, player: Self::Player) -> Self::Fitness {
+        let fitness = self.game.look_ahead(action, player);
+        if self.sample() {
+            self.check_look_ahead(action, player, fitness);
+        }
+        self.check_perspective(action, fitness, self.opponent_fitness(action, player));
+        fitness
+    }
+
+    fn look_ahead_batch(
+        &self,
+        actions: &[Self::Action],
+        player: Self::Player,
+        fitness: &mut Vec<Self::Fitness>,
+    ) {
+        let start = fitness.len();
+        self.game.look_ahead_batch(actions, player, fitness);
+        assert_eq!(
+            fitness.len() - start,
+            actions.len(),
+            "`Game::look_ahead_batch` returned the wrong amount of fitness values"
+        );
+        for (action, &fitness) in actions.iter().zip(&fitness[start..]) {
+            if self.sample() {
+                self.check_look_ahead(action, player, fitness);
+            }
+            self.check_perspective(action, fitness, self.opponent_fitness(action, player));
+        }
+    }
+
+    fn pass(&mut self, player: Self::Player) -> bool {
+        self.game.pass(player)
+    }
+
+    fn is_quiet(&self, action: &Self::Action, player: Self::Player) -> bool {
+        self.game.is_quiet(action, player)
+    }
+
+    fn order_hint(&self, action: &Self::Action, player: Self::Player) -> i32 {
+        self.game.order_hint(action, player)
+    }
+
+    fn state_hash(&self) -> Option<u64> {
+        self.game.state_hash()
+    }
+
+    fn update_hash(&self, hash: u64, action: &Self::Action, player: Self::Player) -> Option<u64> {
+        self.game.update_hash(hash, action, player)
+    }
+
+    fn is_upper_bound(&self, fitness: Self::Fitness, player: Self::Player) -> bool {
+        self.game.is_upper_bound(fitness, player)
+    }
+
+    fn is_lower_bound(&self, fitness: Self::Fitness, player: Self::Player) -> bool {
+        self.game.is_lower_bound(fitness, player)
+    }
+}
+
+impl<T: UnmakeGame> UnmakeGame for Checked<T>
+where
+    T::Action: Debug,
+    T::Fitness: Debug,
+{
+    fn undo(&mut self, action: &Self::Action, player: Self::Player) {
+        self.game.undo(action, player)
+    }
+
+    fn undo_pass(&mut self, player: Self::Player) {
+        self.game.undo_pass(player)
+    }
+}
+
+/// A violated contract found by [`check_game`][check].
+///
+/// [check]: fn.check_game.html
+pub struct Violation<T: Game> {
+    /// The actions executed after the checked state to reach the state violating the contract.
+    pub path: Vec<T::Action>,
+    /// A description of the violated contract.
+    pub message: String,
+}
+
+impl<T: Game> Debug for Violation<T>
+where
+    T::Action: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Violation")
+            .field("path", &self.path)
+            .field("message", &self.message)
+            .finish()
+    }
+}
+
+impl<T: Game> Display for Violation<T>
+where
+    T::Action: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} after {:?}", self.message, self.path)
+    }
+}
+
+/// Checks the implementation of [`Game`][game] for `state` and all states reachable using
+/// at most `depth` actions, returning all violated contracts.
+///
+/// `players` must contain every player of the game. For each state, this checks that
+///
+/// - clones return the same actions in the same order and the same active player,
+/// - the actions do not depend on the queried player and at most one player is active,
+/// - some player is active if there are possible actions,
+/// - `look_ahead` and `look_ahead_batch` return the same fitness as `execute` for each player,
+/// - executing an action on two clones results in the same fitness and the same actions,
+/// - `update_hash` returns the `state_hash` of the resulting state, if the game implements hashing.
+///
+/// Unlike [`Checked`][checked], which panics during a search, this is meant to be called directly
+/// while testing a new game.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{Game, check::check_game, tree::Node};
+///
+/// /// Always claims that the next action is played by `player`.
+/// #[derive(Clone)]
+/// struct Everyone(Node);
+///
+/// impl Game for Everyone {
+///     type Player = bool;
+///     type Action = usize;
+///     type Fitness = i8;
+///     type Actions = std::ops::Range<usize>;
+///
+///     fn actions(&self, player: bool) -> (bool, Self::Actions) {
+///         let (_, actions) = self.0.actions(player);
+///         (!actions.is_empty(), actions)
+///     }
+///
+///     fn execute(&mut self, action: &usize, player: bool) -> i8 {
+///         self.0.execute(action, player)
+///     }
+/// }
+///
+/// let tree = Node::from_bytes(&[3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8]);
+/// assert!(check_game(&tree, &[true, false], 3).is_empty());
+///
+/// let violations = check_game(&Everyone(tree), &[true, false], 3);
+/// assert!(violations[0].message.contains("more than one player"));
+/// assert!(violations[0].path.is_empty());
+/// ```
+///
+/// [game]: ../trait.Game.html
+/// [checked]: struct.Checked.html
+pub fn check_game<T: Game>(state: &T, players: &[T::Player], depth: u32) -> Vec<Violation<T>>
+where
+    T::Action: Debug,
+    T::Fitness: Debug,
+{
+    let mut violations = Vec::new();
+    let mut path = Vec::new();
+    check_state(state, players, depth, &mut path, &mut violations);
+    violations
+}
+
+/// Checks a single state of `check_game` and recursively checks all states reachable from it.
+fn check_state<T: Game>(
+    state: &T,
+    players: &[T::Player],
+    depth: u32,
+    path: &mut Vec<T::Action>,
+    violations: &mut Vec<Violation<T>>,
+) where
+    T::Action: Debug,
+    T::Fitness: Debug,
+{
+    let mut report = |message: String| {
+        violations.push(Violation {
+            path: path.clone(),
+            message,
+        })
+    };
+
+    let mut actions: Option<Vec<T::Action>> = None;
+    let mut active = None;
+    for &player in players {
+        let (is_active, player_actions) = state.actions(player);
+        let player_actions: Vec<_> = player_actions.into_iter().collect();
+        let (clone_active, clone_actions) = state.clone().actions(player);
+        let clone_actions: Vec<_> = clone_actions.into_iter().collect();
+        if is_active != clone_active || player_actions != clone_actions {
+            report(format!(
+                "`Game::actions` returned `({}, {:?})` for a clone of a state which returned `({}, {:?})`",
+                clone_active, clone_actions, is_active, player_actions
+            ));
+        }
+
+        if is_active && !player_actions.is_empty() {
+            if active.is_some() {
+                report(String::from(
+                    "`Game::actions` reported more than one player as active",
+                ));
+            }
+            active = Some(player);
+        }
+
+        match &actions {
+            Some(actions) if *actions != player_actions => report(format!(
+                "`Game::actions` returned `{:?}` and `{:?}` for different players, \
+                 the actions must not depend on the queried player",
+                actions, player_actions
+            )),
+            Some(_) => (),
+            None => actions = Some(player_actions),
+        }
+    }
+
+    let actions = actions.unwrap_or_default();
+    if active.is_none() && !actions.is_empty() {
+        report(format!(
+            "`Game::actions` returned `{:?}` without an active player",
+            actions
+        ));
+    }
+
+    for &player in players {
+        let mut batch = Vec::new();
+        state.look_ahead_batch(&actions, player, &mut batch);
+        if batch.len() != actions.len() {
+            report(format!(
+                "`Game::look_ahead_batch` returned {} fitness values for {} actions",
+                batch.len(),
+                actions.len()
+            ));
+        }
+
+        for (i, action) in actions.iter().enumerate() {
+            let execute = state.clone().execute(action, player);
+            let look_ahead = state.look_ahead(action, player);
+            if look_ahead != execute {
+                report(format!(
+                    "`Game::look_ahead` returned `{:?}` for `{:?}`, but `Game::execute` returned `{:?}`",
+                    look_ahead, action, execute
+                ));
+            }
+            if let Some(&batch) = batch.get(i).filter(|&&batch| batch != execute) {
+                report(format!(
+                    "`Game::look_ahead_batch` returned `{:?}` for `{:?}`, but `Game::execute` returned `{:?}`",
+                    batch, action, execute
+                ));
+            }
+        }
+    }
+
+    let active = match active {
+        Some(active) => active,
+        None => return,
+    };
+    let mut children = Vec::new();
+    for action in actions {
+        let (mut first, mut second) = (state.clone(), state.clone());
+        let (fitness, clone_fitness) = (
+            first.execute(&action, active),
+            second.execute(&action, active),
+        );
+        if fitness != clone_fitness {
+            report(format!(
+                "`Game::execute` returned `{:?}` and `{:?}` for `{:?}` on two clones of the same state",
+                fitness, clone_fitness, action
+            ));
+        }
+        for &player in players {
+            let first_actions: Vec<_> = first.actions(player).1.into_iter().collect();
+            let second_actions: Vec<_> = second.actions(player).1.into_iter().collect();
+            if first_actions != second_actions {
+                report(format!(
+                    "executing `{:?}` on two clones of the same state resulted in the actions `{:?}` and `{:?}`",
+                    action, first_actions, second_actions
+                ));
+                break;
+            }
+        }
+
+        if let Some(hash) = state.state_hash() {
+            let updated = state.update_hash(hash, &action, active);
+            let expected = first.state_hash();
+            if updated.is_some() && updated != expected {
+                report(format!(
+                    "`Game::update_hash` returned `{:?}` for `{:?}`, but `Game::state_hash` of the resulting state is `{:?}`",
+                    updated, action, expected
+                ));
+            }
+        }
+
+        if depth > 0 {
+            children.push((action, first));
+        }
+    }
+
+    for (action, child) in children {
+        path.push(action);
+        check_state(&child, players, depth - 1, path, violations);
+        path.pop();
+    }
+}
@@ -0,0 +1,223 @@
+//! A game bot for games with random events, using [expectimax][em].
+//!
+//! [em]: https://en.wikipedia.org/wiki/Expectiminimax
+use crate::{Game, GameBot, IntoRunCondition, RunCondition};
+
+/// A [`Game`][game] with random events, e.g. rolling dice.
+///
+/// A state either has a random event, in which case `Game::actions` is not used, or it has
+/// actions of the players. The outcomes of random events are executed using `Game::execute`.
+///
+/// [game]: ../trait.Game.html
+pub trait Chance: Game {
+    /// Returns each possible outcome of the random event of this state together with its probability,
+    /// or an empty `Vec` in case the next action is chosen by a player.
+    ///
+    /// The probabilities of all outcomes should add up to `1.0`.
+    fn outcomes(&self) -> Vec<(Self::Action, f64)>;
+}
+
+/// A game bot which maximizes the expected fitness for games with random events.
+///
+/// Unlike [`alpha_beta::Bot`][ab], which would assume that every random event has the worst
+/// possible outcome, this bot weights each outcome by its probability. Opponents are still
+/// expected to choose the action which is worst for the bot.
+///
+/// Random events do not count towards the search depth, so [`Depth`][depth]`(n)` searches `n + 1`
+/// actions of the players like [`brute::Brute`][brute]. As the expected fitness is computed using `f64`,
+/// `T::Fitness` must be convertible to `f64`.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{Game, ToCompletion, expectimax::{self, Chance}};
+///
+/// /// The bot either takes `3` points or gambles by rolling a die,
+/// /// getting `8` points for each even number and nothing otherwise.
+/// #[derive(Clone)]
+/// enum Gamble {
+///     Start,
+///     Rolling,
+///     Done,
+/// }
+///
+/// impl Game for Gamble {
+///     type Player = ();
+///     type Action = u8;
+///     type Fitness = u8;
+///     type Actions = Vec<u8>;
+///
+///     fn actions(&self, _: ()) -> (bool, Vec<u8>) {
+///         match self {
+///             // `0` takes the points, `1` gambles.
+///             Gamble::Start => (true, vec![0, 1]),
+///             _ => (false, Vec::new()),
+///         }
+///     }
+///
+///     fn execute(&mut self, &action: &u8, _: ()) -> u8 {
+///         match (&self, action) {
+///             (Gamble::Start, 0) => {
+///                 *self = Gamble::Done;
+///                 3
+///             }
+///             (Gamble::Start, _) => {
+///                 *self = Gamble::Rolling;
+///                 0
+///             }
+///             (_, roll) => {
+///                 *self = Gamble::Done;
+///                 if roll % 2 == 0 { 8 } else { 0 }
+///             }
+///         }
+///     }
+/// }
+///
+/// impl Chance for Gamble {
+///     fn outcomes(&self) -> Vec<(u8, f64)> {
+///         match self {
+///             Gamble::Rolling => (1..=6).map(|roll| (roll, 1.0 / 6.0)).collect(),
+///             _ => Vec::new(),
+///         }
+///     }
+/// }
+///
+/// // The expected fitness of gambling is `4`.
+/// assert_eq!(expectimax::Bot::new(()).select(&Gamble::Start, ToCompletion), Some(1));
+/// ```
+///
+/// [ab]: ../alpha_beta/struct.Bot.html
+/// [depth]: ../struct.Depth.html
+/// [brute]: ../brute/struct.Brute.html
+pub struct Bot<T: Game> {
+    player: T::Player,
+}
+
+impl<T: Chance> Bot<T>
+where
+    T::Fitness: Into<f64>,
+{
+    /// Creates a new `Bot` for the given `player`.
+    pub fn new(player: T::Player) -> Self {
+        Self { player }
+    }
+
+    /// Returns the action with the highest expected fitness, or `None` if no action is possible,
+    /// the bot is currently not the active player or the next event is random.
+    ///
+    /// `RunCondition::depth` is called before searching one additional action and
+    /// `RunCondition::step` before each searched state. In case the search is cancelled,
+    /// the best action of the last completed depth is returned.
+    pub fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action> {
+        let mut condition = condition.into_run_condition();
+
+        if !state.outcomes().is_empty() {
+            return None;
+        }
+
+        let (active, actions) = state.actions(self.player);
+        if !active {
+            return None;
+        }
+
+        let actions: Vec<_> = actions.into_iter().collect();
+        let (mut best, mut complete) = self.best_action(state, &actions, 0, &mut condition)?;
+        let mut depth = 0;
+        while !complete && condition.depth(depth) {
+            depth += 1;
+            match self.best_action(state, &actions, depth, &mut condition) {
+                Some((action, now_complete)) => {
+                    best = action;
+                    complete = now_complete;
+                }
+                None => break,
+            }
+        }
+        Some(best)
+    }
+
+    /// Returns the best action of `actions` and whether the complete game was searched,
+    /// or `None` if there are no actions or the search was cancelled.
+    fn best_action<U: RunCondition>(
+        &self,
+        state: &T,
+        actions: &[T::Action],
+        depth: u32,
+        condition: &mut U,
+    ) -> Option<(T::Action, bool)> {
+        let mut best: Option<(&T::Action, f64)> = None;
+        let mut complete = true;
+        for action in actions {
+            let (value, action_complete) = self.value(state, action, depth, condition)?;
+            complete &= action_complete;
+            if best.is_none_or(|(_, best)| value > best) {
+                best = Some((action, value));
+            }
+        }
+        best.map(|(action, _)| (action.clone(), complete))
+    }
+
+    /// Returns the expected fitness of `action` when searching `depth` additional actions
+    /// and whether this is exact, or `None` if the search was cancelled.
+    fn value<U: RunCondition>(
+        &self,
+        state: &T,
+        action: &T::Action,
+        depth: u32,
+        condition: &mut U,
+    ) -> Option<(f64, bool)> {
+        if depth == 0 {
+            return Some((state.look_ahead(action, self.player).into(), false));
+        }
+
+        if !condition.step() {
+            return None;
+        }
+
+        let mut state = state.clone();
+        let fitness = state.execute(action, self.player);
+
+        let outcomes = state.outcomes();
+        if !outcomes.is_empty() {
+            let mut expected = 0.0;
+            let mut complete = true;
+            for (outcome, probability) in outcomes {
+                let (value, outcome_complete) = self.value(&state, &outcome, depth, condition)?;
+                expected += probability * value;
+                complete &= outcome_complete;
+            }
+            return Some((expected, complete));
+        }
+
+        let (mut active, actions) = state.actions(self.player);
+        let mut actions: Vec<_> = actions.into_iter().collect();
+        if !active && actions.is_empty() && state.pass(self.player) {
+            let (now_active, now_actions) = state.actions(self.player);
+            active = now_active;
+            actions = now_actions.into_iter().collect();
+        }
+
+        let mut best: Option<f64> = None;
+        let mut complete = true;
+        for action in actions {
+            let (value, action_complete) = self.value(&state, &action, depth - 1, condition)?;
+            complete &= action_complete;
+            best = Some(match best {
+                Some(best) if active => best.max(value),
+                Some(best) => best.min(value),
+                None => value,
+            });
+        }
+
+        Some(best.map_or((fitness.into(), true), |best| (best, complete)))
+    }
+}
+
+impl<T: Chance> GameBot<T> for Bot<T>
+where
+    T::Fitness: Into<f64>,
+{
+    fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action> {
+        Bot::select(self, state, condition)
+    }
+}
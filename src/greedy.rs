@@ -0,0 +1,65 @@
+//! A game bot which only looks at the immediate result of its actions.
+use crate::{Game, GameBot, IntoRunCondition};
+
+/// A game bot which chooses the action with the highest fitness according to `Game::look_ahead`,
+/// ignoring all replies of the opponent.
+///
+/// This is similar to [`alpha_beta::Bot`][ab] searching with [`Depth`][depth]`(0)`, but without any overhead,
+/// which makes it useful as an easy opponent or as a baseline in benchmarks.
+/// In case multiple actions have the same fitness, the first one is chosen.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{Bot, ToCompletion, greedy, tree::Node};
+///
+/// # #[rustfmt::skip]
+/// let tree = Node::root().with_children(&[
+///     Node::new(false, 7).with_children(&[
+///         Node::new(true, 2),
+///     ]),
+///     Node::new(false, 5),
+/// ]);
+///
+/// assert_eq!(greedy::Bot::new(true).select(&tree), Some(0));
+/// // The opponent can reply to the first action.
+/// assert_eq!(Bot::new(true).select(&tree, ToCompletion), Some(1));
+/// ```
+///
+/// [ab]: ../alpha_beta/struct.Bot.html
+/// [depth]: ../struct.Depth.html
+pub struct Bot<T: Game> {
+    player: T::Player,
+}
+
+impl<T: Game> Bot<T> {
+    /// Creates a new `Bot` for the given `player`.
+    pub fn new(player: T::Player) -> Self {
+        Self { player }
+    }
+
+    /// Returns the action with the highest fitness after executing it, or `None` if no action
+    /// is possible or the bot is currently not the active player.
+    pub fn select(&mut self, state: &T) -> Option<T::Action> {
+        let (active, actions) = state.actions(self.player);
+        if !active {
+            return None;
+        }
+
+        let mut best: Option<(T::Action, T::Fitness)> = None;
+        for action in actions {
+            let fitness = state.look_ahead(&action, self.player);
+            if best.as_ref().is_none_or(|&(_, best)| fitness > best) {
+                best = Some((action, fitness));
+            }
+        }
+        best.map(|(action, _)| action)
+    }
+}
+
+/// The run condition is ignored, as only a single action is looked at.
+impl<T: Game> GameBot<T> for Bot<T> {
+    fn select<U: IntoRunCondition>(&mut self, state: &T, _: U) -> Option<T::Action> {
+        Bot::select(self, state)
+    }
+}
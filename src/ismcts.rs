@@ -0,0 +1,341 @@
+//! A game bot for games with hidden information, using information set Monte Carlo tree search.
+use crate::rng::XorShiftRng;
+use crate::{Game, GameBot, IntoRunCondition, RunCondition};
+
+use std::cmp::Ordering;
+
+/// A [`Game`][game] where players do not know the complete state, e.g. the cards in the hand of an opponent.
+///
+/// The state given to the bot may contain the actual hidden information, which must only be
+/// accessed by [`determinize`][det].
+///
+/// [game]: ../trait.Game.html
+/// [det]: #tymethod.determinize
+pub trait HiddenInformationGame: Game {
+    /// Returns a state which is consistent with everything `player` is able to observe,
+    /// choosing all hidden information randomly using `seed`.
+    ///
+    /// The possible actions of `player` must be the same for all determinizations of a state.
+    fn determinize(&self, player: Self::Player, seed: u64) -> Self;
+}
+
+/// A game bot which uses single observer information set Monte Carlo tree search.
+///
+/// Like [`mcts::Bot`][mcts], this bot estimates the value of actions using random playouts. Each playout
+/// starts at a new determinization of the current state, so the bot is not able to cheat by looking at hidden
+/// information. All determinizations share a single search tree, in which the children of a node are
+/// chosen using UCT among the actions which are possible in the current determinization.
+///
+/// The reward of a playout is `1.0` if its final fitness is an upper bound, `0.0` if it is a lower bound
+/// and `0.5` otherwise. Use [`with_reward`][reward] to use the fitness values directly.
+///
+/// As the search never fully explores the game, `select` stops only once its run condition does,
+/// so it must be used with a condition like [`Steps`][steps] or a `Duration` instead of `ToCompletion`.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{Game, Steps, ismcts::{self, HiddenInformationGame}};
+///
+/// /// The bot either guesses the hidden side of a coin, getting `10` points
+/// /// if it is correct, or takes `6` points without guessing.
+/// #[derive(Clone)]
+/// struct Coin {
+///     hidden: bool,
+///     fitness: Option<u8>,
+/// }
+///
+/// impl Game for Coin {
+///     type Player = ();
+///     type Action = u8;
+///     type Fitness = u8;
+///     type Actions = Vec<u8>;
+///
+///     fn actions(&self, _: ()) -> (bool, Vec<u8>) {
+///         match self.fitness {
+///             // `0` and `1` are guesses, `2` takes the safe points.
+///             None => (true, vec![0, 1, 2]),
+///             Some(_) => (false, Vec::new()),
+///         }
+///     }
+///
+///     fn execute(&mut self, &action: &u8, _: ()) -> u8 {
+///         let fitness = match action {
+///             2 => 6,
+///             guess if (guess == 1) == self.hidden => 10,
+///             _ => 0,
+///         };
+///         self.fitness = Some(fitness);
+///         fitness
+///     }
+/// }
+///
+/// impl HiddenInformationGame for Coin {
+///     fn determinize(&self, _: (), seed: u64) -> Self {
+///         Coin { hidden: seed % 2 == 0, fitness: self.fitness }
+///     }
+/// }
+///
+/// let state = Coin { hidden: true, fitness: None };
+/// let mut bot = ismcts::Bot::new(()).with_reward(|fitness| f64::from(fitness) / 10.0);
+/// // Guessing only results in `5` points on average.
+/// assert_eq!(bot.select(&state, Steps(1000)), Some(2));
+/// ```
+///
+/// [mcts]: ../mcts/struct.Bot.html
+/// [reward]: #method.with_reward
+/// [steps]: ../struct.Steps.html
+pub struct Bot<T: Game> {
+    player: T::Player,
+    exploration: f64,
+    playout_depth: u32,
+    reward: Option<fn(T::Fitness) -> f64>,
+    rng: XorShiftRng,
+}
+
+/// A node of the search tree shared by all determinizations.
+struct Node<T: Game> {
+    /// The action leading to this node, `None` for the root.
+    action: Option<T::Action>,
+    children: Vec<usize>,
+    visits: u64,
+    reward: f64,
+    /// How often this node was a possible choice while selecting a child of its parent.
+    availability: u64,
+}
+
+impl<T: Game> Node<T> {
+    fn new(action: Option<T::Action>) -> Self {
+        Self {
+            action,
+            children: Vec::new(),
+            visits: 0,
+            reward: 0.0,
+            availability: 0,
+        }
+    }
+}
+
+impl<T: HiddenInformationGame> Bot<T>
+where
+    T::Action: PartialEq,
+{
+    /// Creates a new `Bot` for the given `player`.
+    pub fn new(player: T::Player) -> Self {
+        Self {
+            player,
+            exploration: std::f64::consts::SQRT_2,
+            playout_depth: 64,
+            reward: None,
+            rng: XorShiftRng::from_seed_u64(0),
+        }
+    }
+
+    /// Sets the exploration constant of UCT, which is `sqrt(2)` by default.
+    pub fn with_exploration(mut self, exploration: f64) -> Self {
+        self.exploration = exploration;
+        self
+    }
+
+    /// Sets the maximum amount of random actions executed during a single playout, which is `64` by default.
+    pub fn with_playout_depth(mut self, playout_depth: u32) -> Self {
+        self.playout_depth = playout_depth;
+        self
+    }
+
+    /// Uses `reward` to convert the fitness at the end of a playout to a reward, which should be in `[0, 1]`.
+    pub fn with_reward(mut self, reward: fn(T::Fitness) -> f64) -> Self {
+        self.reward = Some(reward);
+        self
+    }
+
+    /// Sets the seed used to choose determinizations and random actions, results are reproducible when
+    /// using the same seed and a deterministic run condition like [`Steps`][steps].
+    ///
+    /// [steps]: ../struct.Steps.html
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = XorShiftRng::from_seed_u64(seed);
+        self
+    }
+
+    /// Returns the action with the most playouts, or `None` if no action is possible or the bot is
+    /// currently not the active player.
+    ///
+    /// `RunCondition::step` is called before each playout and `RunCondition::depth` each time the search tree
+    /// grows deeper, with the new depth of the tree.
+    pub fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action> {
+        let mut condition = condition.into_run_condition();
+
+        let (active, actions) = state.actions(self.player);
+        if !active {
+            return None;
+        }
+
+        let mut actions = actions.into_iter();
+        let first = actions.next()?;
+
+        let mut tree = vec![Node::<T>::new(None)];
+        let mut tree_depth = 0;
+        let mut path = Vec::new();
+        while condition.step() {
+            let mut game_state = state.determinize(self.player, self.rng.next_u64());
+            path.clear();
+            path.push(0);
+            let mut idx = 0;
+            let mut fitness = None;
+            loop {
+                let (active, actions) = self.actions(&mut game_state);
+                if actions.is_empty() {
+                    break;
+                }
+
+                let untried: Vec<_> = actions
+                    .iter()
+                    .filter(|&action| {
+                        !tree[idx]
+                            .children
+                            .iter()
+                            .any(|&child| tree[child].action.as_ref() == Some(action))
+                    })
+                    .collect();
+                if !untried.is_empty() {
+                    // Expand a random untried action.
+                    let action = untried[self.rng.below(untried.len())].clone();
+                    fitness = Some(game_state.execute(&action, self.player));
+                    let child = tree.len();
+                    tree[idx].children.push(child);
+                    tree.push(Node::new(Some(action)));
+                    path.push(child);
+                    break;
+                }
+
+                let available: Vec<_> = tree[idx]
+                    .children
+                    .iter()
+                    .copied()
+                    .filter(|&child| {
+                        actions
+                            .iter()
+                            .any(|action| tree[child].action.as_ref() == Some(action))
+                    })
+                    .collect();
+                for &child in &available {
+                    tree[child].availability += 1;
+                }
+                idx = self.uct(&tree, &available, active);
+                fitness = Some(game_state.execute(tree[idx].action.as_ref().unwrap(), self.player));
+                path.push(idx);
+            }
+
+            if path.len() > tree_depth {
+                tree_depth = path.len();
+                if !condition.depth(tree_depth as u32) {
+                    break;
+                }
+            }
+
+            // The determinization does not have any actions, which can only happen
+            // if `determinize` does not keep the actions of the bot.
+            let fitness = match fitness {
+                Some(fitness) => fitness,
+                None => continue,
+            };
+
+            let reward = match self.playout(&mut game_state, fitness, &mut condition) {
+                Some(reward) => reward,
+                None => break,
+            };
+
+            for &idx in &path {
+                let node = &mut tree[idx];
+                node.visits += 1;
+                node.reward += reward;
+            }
+        }
+
+        let mean = |node: &Node<T>| node.reward / node.visits as f64;
+        let best = tree[0].children.iter().copied().max_by(|&a, &b| {
+            let (a, b) = (&tree[a], &tree[b]);
+            a.visits
+                .cmp(&b.visits)
+                .then_with(|| mean(a).partial_cmp(&mean(b)).unwrap_or(Ordering::Equal))
+        });
+
+        match best {
+            Some(best) => tree.swap_remove(best).action,
+            // The search was cancelled before the first action was expanded.
+            None => Some(first),
+        }
+    }
+
+    /// Returns whether the bot is active and all possible actions, passing the turn of the opponent if necessary.
+    fn actions(&self, game_state: &mut T) -> (bool, Vec<T::Action>) {
+        let (active, actions) = game_state.actions(self.player);
+        let actions: Vec<_> = actions.into_iter().collect();
+        if !active && actions.is_empty() && game_state.pass(self.player) {
+            let (active, actions) = game_state.actions(self.player);
+            (active, actions.into_iter().collect())
+        } else {
+            (active, actions)
+        }
+    }
+
+    /// Returns the child in `available` with the highest upper confidence bound, using the availability
+    /// of each child instead of the visits of the parent.
+    fn uct(&self, tree: &[Node<T>], available: &[usize], active: bool) -> usize {
+        let score = |child: &Node<T>| {
+            let mean = child.reward / child.visits as f64;
+            let mean = if active { mean } else { 1.0 - mean };
+            mean + self.exploration
+                * ((child.availability as f64).ln() / child.visits as f64).sqrt()
+        };
+
+        available
+            .iter()
+            .copied()
+            .max_by(|&a, &b| {
+                score(&tree[a])
+                    .partial_cmp(&score(&tree[b]))
+                    .unwrap_or(Ordering::Equal)
+            })
+            .expect("no available child")
+    }
+
+    /// Executes random actions starting at `game_state`, returning the reward of the final fitness
+    /// or `None` if the search was cancelled.
+    fn playout<U: RunCondition>(
+        &mut self,
+        game_state: &mut T,
+        mut fitness: T::Fitness,
+        condition: &mut U,
+    ) -> Option<f64> {
+        for _ in 0..self.playout_depth {
+            let (_, actions) = self.actions(game_state);
+            if actions.is_empty() {
+                break;
+            }
+
+            if !condition.check() {
+                return None;
+            }
+            let action = &actions[self.rng.below(actions.len())];
+            fitness = game_state.execute(action, self.player);
+        }
+
+        Some(match self.reward {
+            Some(reward) => reward(fitness),
+            None if game_state.is_upper_bound(fitness, self.player) => 1.0,
+            None if game_state.is_lower_bound(fitness, self.player) => 0.0,
+            None => 0.5,
+        })
+    }
+}
+
+impl<T: HiddenInformationGame> GameBot<T> for Bot<T>
+where
+    T::Action: PartialEq,
+{
+    fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action> {
+        Bot::select(self, state, condition)
+    }
+}
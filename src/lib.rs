@@ -45,21 +45,75 @@
 //! Please visit the [examples folder][ex] or the [`trait Game`][game] documentation
 //! for more realistic examples.
 //!
+//! # Features
+//!
+//! - `tracing`: [`alpha_beta::Bot`][ab] emits [`tracing`][tracing] spans for each call to `select` and each
+//!   iteration of iterative deepening at the `debug` level, together with events for completed iterations
+//!   and the selected action. Cutoffs are emitted at the `trace` level.
+//! - `serde`: [`alpha_beta::Bot`][ab] implements `Serialize` and `Deserialize`, storing its player and
+//!   all options which do not contain functions or trait objects.
+//! - `async`: adds [`alpha_beta::Bot::select_async`][async], which returns a future resolving to the
+//!   selected action while the search runs on another thread.
+//! - `protocol`: adds the module [`protocol`][protocol], which drives bots using text protocols like
+//!   the Universal Chess Interface and the Go Text Protocol.
+//!
 //! [ab]:alpha_beta/struct.Bot.html
+//! [async]:alpha_beta/struct.Bot.html#method.select_async
+//! [protocol]:protocol/index.html
 //! [ex]:https://github.com/lcnr/rubot/tree/master/examples
 //! [game]:trait.Game.html
+//! [tracing]:https://docs.rs/tracing
+
+/// Emits a `tracing` event at the given level if the `tracing` feature is enabled.
+#[cfg(feature = "tracing")]
+macro_rules! event {
+    ($level:ident, $($arg:tt)*) => {
+        tracing::$level!($($arg)*)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! event {
+    ($level:ident, $($arg:tt)*) => {};
+}
+
 pub mod alpha_beta;
+pub mod beam;
+pub mod bench;
+pub mod brute;
+pub mod check;
+pub mod expectimax;
+pub mod greedy;
+pub mod ismcts;
+pub mod maxn;
+pub mod mcts;
+pub mod openings;
+#[cfg(feature = "protocol")]
+pub mod protocol;
+pub mod random;
+pub mod record;
+pub mod selfplay;
+pub mod simultaneous;
+pub mod sss;
+pub mod tournament;
+pub mod training;
 pub mod tree;
+pub mod tune;
 
-#[allow(unused)]
-#[doc(hidden)]
-pub mod brute;
+mod rng;
 #[cfg(test)]
 mod tests;
 
+use std::any::Any;
 use std::cmp::PartialEq;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::error::Error;
 use std::fmt::{self, Debug};
+use std::hash::Hash;
 use std::ops::Drop;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant};
 
 /// An interface required to interact with [`GameBot`s][bot].
@@ -202,6 +256,8 @@ pub trait Game: Clone {
     type Actions: IntoIterator<Item = Self::Action>;
 
     /// Returns all currently possible actions and if they are executed by the given `player`.
+    ///
+    /// Once the game has ended, this should return no actions and report `player` as inactive.
     fn actions(&self, player: Self::Player) -> (bool, Self::Actions);
 
     /// Execute a given `action`, returning the new `fitness` for the given `player`.
@@ -254,169 +310,275 @@ pub trait Game: Clone {
         self.clone().execute(action, player)
     }
 
-    /// Returns `true` if the given `fitness` is one of the best currently possible outcomes for the given `player`.
+    /// Appends the fitness after each of the given `actions` to `fitness`, in the same order.
     ///
-    /// A good example is a checkmate in chess, as there does not exist a better game state than having won.
+    /// [`alpha_beta::Bot`][ab] uses this instead of repeatedly calling [`look_ahead`][la] to evaluate all leaves
+    /// of a node at once, which is useful in case evaluating many positions together is a lot cheaper, e.g. when
+    /// using a neural net or a SIMD heavy evaluation function. [`mcts::Bot`][mcts] uses this to evaluate all possible
+    /// last actions of each playout.
+    ///
+    /// This function should always push the same fitness values as calling [`look_ahead`][la] for each action.
+    ///
+    /// ```rust
+    /// # use rubot::Game;
+    /// # #[derive(Clone)]
+    /// # struct GameState;
+    /// # impl rubot::Game for GameState {
+    /// #     type Player = ();
+    /// #     type Action = u8;
+    /// #     type Fitness = u8;
+    /// #     type Actions = Vec<u8>;
+    /// #
+    /// #     fn actions(&self, _player: Self::Player) -> (bool, Self::Actions) {
+    /// #         (true, vec![1, 2, 3])
+    /// #     }
+    /// #
+    /// #     fn execute(&mut self, action: &Self::Action, _player: Self::Player) -> Self::Fitness {
+    /// #         *action * 2
+    /// #     }
+    /// # }
+    /// # let player = ();
+    /// let state = GameState;
+    /// let actions = state.actions(player).1;
+    ///
+    /// let mut batch = Vec::new();
+    /// state.look_ahead_batch(&actions, player, &mut batch);
+    /// let look_ahead: Vec<_> = actions.iter().map(|a| state.look_ahead(a, player)).collect();
+    ///
+    /// assert_eq!(batch, look_ahead);
+    /// ```
+    /// [la]: trait.Game.html#method.look_ahead
+    /// [ab]: alpha_beta/struct.Bot.html
+    /// [mcts]: mcts/struct.Bot.html
     #[inline]
-    fn is_upper_bound(&self, fitness: Self::Fitness, player: Self::Player) -> bool {
-        let _ = (fitness, player);
-        false
+    fn look_ahead_batch(
+        &self,
+        actions: &[Self::Action],
+        player: Self::Player,
+        fitness: &mut Vec<Self::Fitness>,
+    ) {
+        fitness.extend(actions.iter().map(|action| self.look_ahead(action, player)));
     }
 
-    /// Returns `true` if the given `fitness` is one of the worst currently possible outcomes for the given `player`.
+    /// Passes the turn back to `player`, which is called in case the opponent of `player`
+    /// is active but does not have any possible actions.
     ///
-    /// A good example is a checkmate in chess, as there does not exist a worse game state than having lost.
+    /// Returns `true` if the turn was passed, after which `player` should be active.
+    /// Returns `false` if the game has ended instead, in which case `self` must not be modified.
+    ///
+    /// By default the game always ends if the opponent has no possible actions.
+    /// Games like Reversi, where a player without actions has to skip their turn,
+    /// should implement this method to get correct evaluations after such a forced pass.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, Game, ToCompletion};
+    ///
+    /// /// Both players take one stone at a time, the player who takes the last stone wins.
+    /// /// The opponent is not allowed to take the last stone and has to pass instead.
+    /// /// We are also able to give up, which is represented by the action `false`.
+    /// #[derive(Clone)]
+    /// struct Stones { stones: u8, our_turn: bool }
+    ///
+    /// impl Game for Stones {
+    ///     type Player = ();
+    ///     type Action = bool;
+    ///     type Fitness = bool;
+    ///     type Actions = Vec<bool>;
+    ///
+    ///     fn actions(&self, _: ()) -> (bool, Vec<bool>) {
+    ///         let actions = match self.stones {
+    ///             0 => vec![],
+    ///             _ if self.our_turn => vec![true, false],
+    ///             1 => vec![],
+    ///             _ => vec![true],
+    ///         };
+    ///         (self.our_turn, actions)
+    ///     }
+    ///
+    ///     fn execute(&mut self, take: &bool, _: ()) -> bool {
+    ///         if !take {
+    ///             self.stones = 0;
+    ///             return false;
+    ///         }
+    ///
+    ///         self.stones -= 1;
+    ///         self.our_turn = !self.our_turn;
+    ///         // We won if we took the last stone.
+    ///         self.stones == 0 && !self.our_turn
+    ///     }
+    ///
+    ///     fn pass(&mut self, _: ()) -> bool {
+    ///         if self.stones > 0 {
+    ///             self.our_turn = true;
+    ///             true
+    ///         } else {
+    ///             false
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// // After taking a stone, the opponent has to pass and we can take the last one.
+    /// let mut bot = Bot::new(());
+    /// let action = bot.detailed_select(&Stones { stones: 2, our_turn: true }, ToCompletion).unwrap();
+    /// assert_eq!(action.path, &[true, true]);
+    /// assert!(action.fitness);
+    /// ```
     #[inline]
-    fn is_lower_bound(&self, fitness: Self::Fitness, player: Self::Player) -> bool {
-        let _ = (fitness, player);
+    fn pass(&mut self, player: Self::Player) -> bool {
+        let _ = player;
         false
     }
-}
-
-/// Converts a type into a [`RunCondition`][rc] used by [`Bot::select`][sel].
-/// It is recommended to mostly use [`Duration`][dur].
-///
-/// # Examples
-///
-/// ```rust
-/// # struct Game;
-/// # struct Bot;
-/// # impl Bot {
-/// #   fn select<U: rubot::IntoRunCondition>(&mut self, state: &Game, condition: U) -> Option<()> {
-/// #       Some(())
-/// #   }
-/// # }
-/// use std::time::Duration;
-///
-/// let available_time = Duration::from_secs(2);
-///
-/// let game: Game = // ...
-/// # Game;
-/// let mut bot: Bot = // ...
-/// # Bot;
-/// assert!(bot.select(&game, available_time).is_some())
-/// ```
-///
-/// # Implementations
-///
-/// - [`Duration`][dur]: `select` runs for the specified duration
-/// - [`ToCompletion`][complete]: `select` runs until it found the perfect action
-/// - [`Depth`][depth]: `select` analyses up the to given depth and returns to best action at that depth
-/// - [`Instant`][instant]: `select` runs until the given `Instant` is in the past
-/// - [`Logger`][logger]: takes another run condition and stores information about the last call to `select`
-///
-/// [rc]: trait.RunCondition.html
-/// [dur]: https://doc.rust-lang.org/std/time/struct.Duration.html
-/// [complete]: struct.ToCompletion.html
-/// [depth]: struct.Depth.html
-/// [instant]: https://doc.rust-lang.org/std/time/struct.Instant.html
-/// [logger]: struct.Logger.html
-/// [sel]: alpha_beta/struct.Bot.html#method.select
-///
-pub trait IntoRunCondition {
-    type RunCondition: RunCondition;
 
-    /// consumes `self` and returns a `RunCondition`.
+    /// Returns `false` if executing `action` is expected to drastically change the fitness,
+    /// e.g. captures in chess. These are called noisy actions.
     ///
-    /// [rc]: trait.RunCondition.html
-    fn into_run_condition(self) -> Self::RunCondition;
-}
-
-impl<T> IntoRunCondition for T
-where
-    T: RunCondition,
-{
-    type RunCondition = Self;
-
-    fn into_run_condition(self) -> Self {
-        self
+    /// Bots using [`alpha_beta::Bot::with_quiescence_search`][qs] execute noisy actions at the lowest depth
+    /// instead of evaluating them using `look_ahead`, and continue searching the noisy actions of the
+    /// resulting states. By default all actions are quiet.
+    ///
+    /// [qs]: alpha_beta/struct.Bot.html#method.with_quiescence_search
+    #[inline]
+    fn is_quiet(&self, action: &Self::Action, player: Self::Player) -> bool {
+        let _ = (action, player);
+        true
     }
-}
-
-/// Can be converted into [`RunCondition`][rc] which returns `true` for the first `self.0` steps.
-/// This should only be used for debugging and testing as unlike `Duration`, `ToCompletion` or `Depth`,
-/// the total amount of steps needed is not directly indicative of search depth and can change between minor versions.
-///
-/// [rc]: trait.RunCondition.html
-#[derive(Clone, Copy, Debug)]
-pub struct Steps(pub u32);
-
-/// The [`RunCondition`][rc] created by [`Steps`][steps]`::into_run_condition`
-///
-/// [rc]: trait.RunCondition.html
-/// [steps]: struct.Steps.html
-#[doc(hidden)]
-#[derive(Debug)]
-pub struct InnerSteps(u32, u32);
 
-impl IntoRunCondition for Steps {
-    type RunCondition = InnerSteps;
-
-    fn into_run_condition(self) -> InnerSteps {
-        InnerSteps(0, self.0)
+    /// Returns a static estimate of how promising `action` is for the player executing it, e.g. `1` for
+    /// captures in chess and `0` otherwise.
+    ///
+    /// `alpha_beta::Bot` searches actions with a higher hint first, only using the fitness after executing
+    /// each action to order actions with the same hint. Trying good actions first results in more cutoffs, but
+    /// does not change the result of a search. By default all actions have the same hint.
+    ///
+    /// This is called while sorting the actions of each searched state, so it should be cheap.
+    ///
+    /// ```rust
+    /// use rubot::{Bot, Game, ToCompletion};
+    ///
+    /// /// Take any amount of points, where taking `3` points ends the game.
+    /// #[derive(Clone)]
+    /// struct Points(u8);
+    ///
+    /// impl Game for Points {
+    ///     type Player = ();
+    ///     type Action = u8;
+    ///     type Fitness = u8;
+    ///     type Actions = Vec<u8>;
+    ///
+    ///     fn actions(&self, _: ()) -> (bool, Vec<u8>) {
+    ///         (true, if self.0 < 3 { vec![1, 2, 3] } else { Vec::new() })
+    ///     }
+    ///
+    ///     fn execute(&mut self, &action: &u8, _: ()) -> u8 {
+    ///         self.0 += action;
+    ///         self.0.min(3)
+    ///     }
+    ///
+    ///     fn order_hint(&self, &action: &u8, _: ()) -> i32 {
+    ///         // Ending the game is usually a good idea.
+    ///         i32::from(self.0 + action >= 3)
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(Bot::new(()).select(&Points(0), ToCompletion), Some(3));
+    /// ```
+    #[inline]
+    fn order_hint(&self, action: &Self::Action, player: Self::Player) -> i32 {
+        let _ = (action, player);
+        0
     }
-}
 
-impl RunCondition for InnerSteps {
+    /// Returns a hash identifying this state, or `None` if this is not supported.
+    ///
+    /// Together with [`update_hash`][update], this allows bots using [`alpha_beta::Bot::with_incremental_hashing`][inc]
+    /// to compute the hash of each state from the hash of its parent, e.g. using [Zobrist hashing][zobrist],
+    /// instead of hashing every searched state from scratch. By default no hash is returned.
+    ///
+    /// [update]: #method.update_hash
+    /// [inc]: alpha_beta/struct.Bot.html#method.with_incremental_hashing
+    /// [zobrist]: https://en.wikipedia.org/wiki/Zobrist_hashing
     #[inline]
-    fn step(&mut self) -> bool {
-        self.0 += 1;
-        self.0 < self.1
+    fn state_hash(&self) -> Option<u64> {
+        None
     }
 
+    /// Returns the [`state_hash`][hash] of the state after executing `action`, where `hash` is
+    /// the hash of the current state.
+    ///
+    /// This should be a lot cheaper than executing `action` and hashing the resulting state,
+    /// which is what this does by default.
+    ///
+    /// [hash]: #method.state_hash
     #[inline]
-    fn depth(&mut self, _: u32) -> bool {
-        true
+    fn update_hash(&self, hash: u64, action: &Self::Action, player: Self::Player) -> Option<u64> {
+        let _ = hash;
+        let mut state = self.clone();
+        state.execute(action, player);
+        state.state_hash()
     }
-}
 
-/// Creates a [`RunCondition`][rc] which returns `true` until this `Duration` has passed.
-///
-/// [rc]: trait.RunCondition.html
-impl IntoRunCondition for Duration {
-    type RunCondition = Instant;
+    /// Returns `true` if the given `fitness` is one of the best currently possible outcomes for the given `player`.
+    ///
+    /// A good example is a checkmate in chess, as there does not exist a better game state than having won.
+    #[inline]
+    fn is_upper_bound(&self, fitness: Self::Fitness, player: Self::Player) -> bool {
+        let _ = (fitness, player);
+        false
+    }
 
-    fn into_run_condition(self) -> Instant {
-        Instant::now() + self
+    /// Returns `true` if the given `fitness` is one of the worst currently possible outcomes for the given `player`.
+    ///
+    /// A good example is a checkmate in chess, as there does not exist a worse game state than having lost.
+    #[inline]
+    fn is_lower_bound(&self, fitness: Self::Fitness, player: Self::Player) -> bool {
+        let _ = (fitness, player);
+        false
     }
 }
 
-/// A condition which indicates if [`Bot::select`][sel] should keep on running.
-/// It is recommended to use [`Duration`][dur] for nearly all use cases.
+/// A game whose actions can be reverted, which allows bots to avoid cloning the game state.
 ///
-/// A list of all already implemented `RunCondition`s can be found [here][into].
+/// [`alpha_beta::Bot::with_undo`][undo] uses this to compute the fitness of all actions of a state
+/// by executing and reverting them in place, and to search all states using a single copy of the state.
+/// This is useful for games with large states, where cloning them dominates the time spent searching.
 ///
-/// [sel]: alpha_beta/struct.Bot.html#method.select
-/// [dur]: https://doc.rust-lang.org/std/time/struct.Duration.html
-/// [into]: trait.IntoRunCondition.html#implementations-1
-pub trait RunCondition {
-    /// Called at each search step, instantly stops all calculations by returning `false`.
-    fn step(&mut self) -> bool;
-    /// Called after every finished search depth, instantly stops all calculations by returning `false`.
-    fn depth(&mut self, depth: u32) -> bool;
-}
-
-/// Returns `true` while the `Instant` is still in the future
-impl RunCondition for Instant {
-    #[inline]
-    fn step(&mut self) -> bool {
-        Instant::now() < *self
-    }
+/// [undo]: alpha_beta/struct.Bot.html#method.with_undo
+pub trait UnmakeGame: Game {
+    /// Reverts `action`, which is the action most recently executed by `player` using [`Game::execute`][exe].
+    ///
+    /// The state afterwards must be identical to the state before `action` was executed,
+    /// including the order of the actions returned by [`Game::actions`][actions].
+    ///
+    /// [exe]: trait.Game.html#tymethod.execute
+    /// [actions]: trait.Game.html#tymethod.actions
+    fn undo(&mut self, action: &Self::Action, player: Self::Player);
 
+    /// Reverts the most recent call to [`Game::pass`][pass] with the given `player` which returned `true`.
+    ///
+    /// This only has to be implemented by games which implement `Game::pass`.
+    ///
+    /// [pass]: trait.Game.html#method.pass
     #[inline]
-    fn depth(&mut self, _: u32) -> bool {
-        Instant::now() < *self
+    fn undo_pass(&mut self, player: Self::Player) {
+        let _ = player;
     }
 }
 
-/// A struct implementing [`RunCondition`][rc] which always returns `true`.
+/// A bot which selects actions for a [`Game`][game], implemented by all bots of this crate.
 ///
-/// This means that the bot will always run until the best action was found.
+/// This allows writing code which works with any bot, for example to let different bots
+/// play against each other.
 ///
 /// # Examples
 ///
 /// ```rust
-/// # use rubot::{Bot, tree::Node, ToCompletion};
+/// use rubot::{Bot, GameBot, ToCompletion, brute::Brute, mcts, tree::Node};
+///
+/// fn select<B: GameBot<Node>>(mut bot: B, tree: &Node) -> Option<usize> {
+///     bot.select(tree, ToCompletion)
+/// }
+///
+/// # #[rustfmt::skip]
 /// let tree = Node::root().with_children(&[
 ///     Node::new(false, 7).with_children(&[
 ///         Node::new(true, 4),
@@ -428,32 +590,48 @@ impl RunCondition for Instant {
 ///     ]),
 /// ]);
 ///
-/// let mut bot = Bot::new(true);
-/// assert_eq!(bot.select(&tree, ToCompletion), Some(1));
+/// assert_eq!(select(Bot::new(true), &tree), Some(1));
+/// assert_eq!(select(Brute::new(true), &tree), Some(1));
+/// assert_eq!(select(mcts::Bot::new(true), &tree), Some(1));
 /// ```
-/// [rc]: trait.RunCondition.html
-#[derive(Clone, Copy, Debug)]
-pub struct ToCompletion;
-
-impl RunCondition for ToCompletion {
-    #[inline]
-    fn step(&mut self) -> bool {
-        true
-    }
-
-    #[inline]
-    fn depth(&mut self, _: u32) -> bool {
-        true
-    }
+///
+/// [game]: trait.Game.html
+pub trait GameBot<T: Game> {
+    /// Returns the action the bot wants to execute in `state`, or `None` if no action is possible
+    /// or the bot is currently not the active player.
+    ///
+    /// The search stops once `condition` is no longer true, see [`IntoRunCondition`][cond].
+    ///
+    /// [cond]: trait.IntoRunCondition.html
+    fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action>;
 }
 
-/// A struct implementing [`RunCondition`][rc] returning cancelling the computation once the depth `self.0`
-/// is reached.
+/// A source of prior probabilities for actions, for example a cheap learned policy.
+///
+/// Bots use these priors to decide which actions should be looked at first.
+/// [`alpha_beta::Bot`][ab] only uses them to order actions, so even a bad `Policy`
+/// does not change the result of [`Bot::select`][sel] once the search terminates.
+/// [`mcts::Bot`][mcts] also uses them to bias its exploration using PUCT, so a bad `Policy`
+/// may cause it to select worse actions.
+///
+/// There is no method like `Game::action_priors`, priors are always provided using this trait so that a
+/// learned policy can be plugged in without changing the game. Games which know good priors themselves
+/// can implement `Policy<Self>` for a separate, usually empty, type as shown below.
 ///
 /// # Examples
 ///
 /// ```rust
-/// # use rubot::{Bot, tree::Node, Depth};
+/// use rubot::{Bot, Policy, ToCompletion, tree::Node};
+///
+/// /// Prefer actions with a small index.
+/// struct FirstFirst;
+///
+/// impl Policy<Node> for FirstFirst {
+///     fn priors(&self, _: &Node, actions: &[usize], _: bool, priors: &mut Vec<f64>) {
+///         priors.extend(actions.iter().map(|&a| 1.0 / (a + 1) as f64));
+///     }
+/// }
+///
 /// let tree = Node::root().with_children(&[
 ///     Node::new(false, 7).with_children(&[
 ///         Node::new(true, 4),
@@ -465,33 +643,960 @@ impl RunCondition for ToCompletion {
 ///     ]),
 /// ]);
 ///
-/// let mut bot = Bot::new(true);
-/// assert_eq!(bot.select(&tree, Depth(0)), Some(0));
-/// assert_eq!(bot.select(&tree, Depth(1)), Some(1));
+/// let mut bot = Bot::new(true).with_policy(FirstFirst);
+/// assert_eq!(bot.select(&tree, ToCompletion), Some(1));
 /// ```
-/// [rc]: trait.RunCondition.html
-#[derive(Clone, Copy, Debug)]
-pub struct Depth(pub u32);
-
-impl RunCondition for Depth {
-    #[inline]
-    fn step(&mut self) -> bool {
-        true
-    }
-
-    #[inline]
-    fn depth(&mut self, depth: u32) -> bool {
-        self.0 > depth
-    }
+/// [ab]: alpha_beta/struct.Bot.html#method.with_policy
+/// [sel]: alpha_beta/struct.Bot.html#method.select
+/// [mcts]: mcts/struct.Bot.html#method.with_policy
+pub trait Policy<T: Game> {
+    /// Appends the prior of each action in `actions` to `priors`, in the same order.
+    ///
+    /// `player` is the player the bot is searching for, which is not necessarily the player
+    /// executing `actions`. Actions with a higher prior are expected to be better for the player executing them.
+    fn priors(&self, state: &T, actions: &[T::Action], player: T::Player, priors: &mut Vec<f64>);
 }
 
-/// A struct implementing [`IntoRunCondition`] which can be used to log a call to [`select`][sel].
-/// For more details you can visit the individual methods.
+/// A model of the opponent, used to predict which actions the opponent may choose.
+///
+/// By default, bots assume that the opponent always chooses the action which is worst for the bot.
+/// An `OpponentModel` can be used to exploit weaknesses of a known opponent, for example by ignoring
+/// actions this opponent never chooses. To completely replace the choice of the opponent, only
+/// consider the predicted action plausible.
 ///
 /// # Examples
 ///
 /// ```rust
-/// # use rubot::{Bot, tree::Node, ToCompletion, Logger};
+/// use rubot::{Bot, Game, OpponentModel, ToCompletion, tree::Node};
+///
+/// /// The opponent never chooses actions which are really bad for us.
+/// struct Friendly;
+///
+/// impl OpponentModel<Node> for Friendly {
+///     fn is_plausible(&self, state: &Node, action: &usize, player: bool) -> bool {
+///         state.look_ahead(action, player) > 0
+///     }
+/// }
+///
+/// let tree = Node::root().with_children(&[
+///     Node::new(false, 0).with_children(&[
+///         Node::new(true, 10),
+///         Node::new(true, -5),
+///     ]),
+///     Node::new(false, 0).with_children(&[
+///         Node::new(true, 3),
+///     ]),
+/// ]);
+///
+/// assert_eq!(Bot::new(true).select(&tree, ToCompletion), Some(1));
+/// assert_eq!(Bot::new(true).with_opponent_model(Friendly).select(&tree, ToCompletion), Some(0));
+/// ```
+pub trait OpponentModel<T: Game> {
+    /// Returns `true` if the opponent may choose `action` in `state`.
+    ///
+    /// `player` is the player the bot is searching for, not the opponent. This should
+    /// always return the same result for the same state and action.
+    fn is_plausible(&self, state: &T, action: &T::Action, player: T::Player) -> bool;
+}
+
+/// A domain specific rule which prevents bots from searching some actions, for example
+/// actions which are possible but never worth considering.
+///
+/// Unlike removing these actions in [`Game::actions`][actions], this only affects bots using this rule.
+///
+/// Vetoing actions of the bot itself only restricts which actions it may choose, so the result is
+/// the best action among the remaining ones. Vetoing actions of the opponent assumes that the opponent
+/// never chooses them, similar to [`OpponentModel`][model], which causes the bot to play worse in case
+/// this is wrong. If all actions of a state are vetoed, all of them are searched, so a veto never
+/// ends the game early.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{Bot, ToCompletion, Veto, tree::Node};
+///
+/// /// Never choose the first action.
+/// struct NotFirst;
+///
+/// impl Veto<Node> for NotFirst {
+///     fn vetoes(&self, _: &Node, &action: &usize, _: bool) -> bool {
+///         action == 0
+///     }
+/// }
+///
+/// let tree = Node::root().with_children(&[
+///     Node::new(false, 9),
+///     Node::new(false, 3),
+///     Node::new(false, 5),
+/// ]);
+///
+/// assert_eq!(Bot::new(true).select(&tree, ToCompletion), Some(0));
+/// assert_eq!(Bot::new(true).with_veto(NotFirst).select(&tree, ToCompletion), Some(2));
+/// ```
+///
+/// [actions]: trait.Game.html#tymethod.actions
+/// [model]: trait.OpponentModel.html
+pub trait Veto<T: Game> {
+    /// Returns `true` if `action` should not be searched in `state`.
+    ///
+    /// `player` is the player the bot is searching for, which is not necessarily the player
+    /// executing `action`. This should always return the same result for the same state and action.
+    fn vetoes(&self, state: &T, action: &T::Action, player: T::Player) -> bool;
+}
+
+/// Precomputed exact results of states, usually endgames with few remaining pieces.
+///
+/// [`alpha_beta::Bot::with_tablebase`][with] probes the tablebase at every searched state.
+/// In case the state is known, its result is used directly and its actions are not searched.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{Bot, Game, HashTablebase, ToCompletion, tree::Node};
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::{Hash, Hasher};
+///
+/// fn hash(node: &Node) -> u64 {
+///     let mut hasher = DefaultHasher::new();
+///     node.hash(&mut hasher);
+///     hasher.finish()
+/// }
+///
+/// # #[rustfmt::skip]
+/// let tree = Node::root().with_children(&[
+///     Node::new(false, 3).with_children(&[
+///         Node::new(true, 1),
+///         Node::new(true, 2),
+///     ]),
+///     Node::new(false, 2),
+/// ]);
+///
+/// assert_eq!(Bot::new(true).select(&tree, ToCompletion), Some(1));
+///
+/// // The tablebase claims that the opponent can not prevent a fitness of `5` after the first action.
+/// let mut after = tree.clone();
+/// after.execute(&0, true);
+/// let mut tablebase = HashTablebase::new(hash);
+/// tablebase.insert(&after, true, 5, None);
+/// let mut bot = Bot::new(true).with_tablebase(tablebase);
+/// assert_eq!(bot.select(&tree, ToCompletion), Some(0));
+/// assert_eq!(bot.stats().tablebase_hits, 1);
+/// ```
+///
+/// [with]: alpha_beta/struct.Bot.html#method.with_tablebase
+pub trait Tablebase<T: Game> {
+    /// Returns the fitness of `state` from the perspective of `player` in case both players play optimally,
+    /// together with the best action of the active player if it is known, or `None` if `state` is unknown.
+    fn probe(&self, state: &T, player: T::Player) -> Option<(T::Fitness, Option<T::Action>)>;
+}
+
+/// A [`Tablebase`][tb] storing the result of each state in a `HashMap`.
+///
+/// States are identified using a hash, so in the rare case of a hash collision,
+/// the result of a different state is used.
+///
+/// [tb]: trait.Tablebase.html
+pub struct HashTablebase<T: Game> {
+    hash: fn(&T) -> u64,
+    entries: HashMap<(u64, T::Player), TablebaseEntry<T>>,
+}
+
+/// The fitness of a state and the best action of the active player.
+type TablebaseEntry<T> = (<T as Game>::Fitness, Option<<T as Game>::Action>);
+
+impl<T: Game> HashTablebase<T>
+where
+    T::Player: Eq + Hash,
+{
+    /// Creates an empty tablebase, identifying states using `hash`.
+    pub fn new(hash: fn(&T) -> u64) -> Self {
+        Self {
+            hash,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Stores the `fitness` of `state` from the perspective of `player` together
+    /// with the best action of the active player, replacing any previous entry.
+    pub fn insert(
+        &mut self,
+        state: &T,
+        player: T::Player,
+        fitness: T::Fitness,
+        action: Option<T::Action>,
+    ) {
+        self.entries
+            .insert(((self.hash)(state), player), (fitness, action));
+    }
+
+    /// Returns the amount of stored entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the tablebase does not contain any entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<T: Game> Tablebase<T> for HashTablebase<T>
+where
+    T::Player: Eq + Hash,
+{
+    fn probe(&self, state: &T, player: T::Player) -> Option<(T::Fitness, Option<T::Action>)> {
+        self.entries.get(&((self.hash)(state), player)).cloned()
+    }
+}
+
+/// Overflow-free arithmetic on fitness values, used by features which widen or narrow
+/// search windows by a margin.
+///
+/// All operations saturate at the bounds of the fitness type. Implementations should ensure
+/// that saturated values are still valid fitness values, a margin added to `i32::MAX`
+/// must not wrap around to a losing fitness.
+///
+/// This trait is implemented for all primitive integers.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::Margin;
+///
+/// assert_eq!(100i8.add_margin(50), i8::MAX);
+/// assert_eq!((-100i8).sub_margin(50), i8::MIN);
+/// assert_eq!(3u8.sub_margin(5), 0);
+/// assert_eq!(i32::MAX.successor(), i32::MAX);
+/// assert_eq!(7i32.predecessor(), 6);
+/// ```
+pub trait Margin: Ord + Copy {
+    /// Returns `self + margin`, saturating at the upper bound.
+    fn add_margin(self, margin: Self) -> Self;
+
+    /// Returns `self - margin`, saturating at the lower bound.
+    fn sub_margin(self, margin: Self) -> Self;
+
+    /// Returns the smallest fitness greater than `self`, or `self` if there is none.
+    fn successor(self) -> Self;
+
+    /// Returns the largest fitness smaller than `self`, or `self` if there is none.
+    fn predecessor(self) -> Self;
+}
+
+/// Fitness values which can be negated to change the perspective in zero-sum games.
+///
+/// By convention, the fitness returned by [`Game`][game] is always from the perspective of
+/// the queried player. For zero-sum games, the fitness of the opponent is the negated fitness.
+///
+/// Negating the minimum of a two's complement integer overflows, so `negate` saturates instead:
+/// `i32::MIN.negate()` is `i32::MAX`. Use `-i32::MAX` instead of `i32::MIN` as the worst fitness
+/// to keep negation symmetric.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::Negate;
+///
+/// assert_eq!(5i32.negate(), -5);
+/// assert_eq!(i32::MIN.negate(), i32::MAX);
+/// assert_eq!(i32::MAX.negate().negate(), i32::MAX);
+/// ```
+///
+/// [game]: trait.Game.html
+pub trait Negate: Margin {
+    /// Returns `-self`, saturating at the upper bound.
+    fn negate(self) -> Self;
+}
+
+macro_rules! impl_margin {
+    ($($t:ty),*) => {
+        $(
+            impl Margin for $t {
+                #[inline]
+                fn add_margin(self, margin: Self) -> Self {
+                    self.saturating_add(margin)
+                }
+
+                #[inline]
+                fn sub_margin(self, margin: Self) -> Self {
+                    self.saturating_sub(margin)
+                }
+
+                #[inline]
+                fn successor(self) -> Self {
+                    self.saturating_add(1)
+                }
+
+                #[inline]
+                fn predecessor(self) -> Self {
+                    self.saturating_sub(1)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_negate {
+    ($($t:ty),*) => {
+        $(
+            impl Negate for $t {
+                #[inline]
+                fn negate(self) -> Self {
+                    self.saturating_neg()
+                }
+            }
+        )*
+    };
+}
+
+impl_margin!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+impl_negate!(i8, i16, i32, i64, i128, isize);
+
+macro_rules! ordered_float {
+    ($($(#[$attr:meta])* $name:ident($t:ident, $serde:literal);)*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Clone, Copy, Default)]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+            #[cfg_attr(feature = "serde", serde(try_from = $serde, into = $serde))]
+            pub struct $name($t);
+
+            impl $name {
+                /// Wraps `value`, where `-0.0` is replaced by `0.0`.
+                ///
+                /// # Panics
+                ///
+                /// Panics if `value` is `NaN`.
+                pub fn new(value: $t) -> Self {
+                    assert!(!value.is_nan(), "fitness values must not be `NaN`");
+                    // `-0.0 == 0.0`, so adding `0.0` turns `-0.0` into `0.0` without changing other values.
+                    $name(value + 0.0)
+                }
+
+                /// Returns the wrapped value.
+                pub fn get(self) -> $t {
+                    self.0
+                }
+            }
+
+            impl PartialEq for $name {
+                fn eq(&self, other: &Self) -> bool {
+                    self.0 == other.0
+                }
+            }
+
+            impl Eq for $name {}
+
+            impl PartialOrd for $name {
+                fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                    Some(self.cmp(other))
+                }
+            }
+
+            impl Ord for $name {
+                fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                    // Neither value is `NaN`, so `total_cmp` only differs from `partial_cmp`
+                    // for `-0.0`, which is never stored.
+                    self.0.total_cmp(&other.0)
+                }
+            }
+
+            impl std::hash::Hash for $name {
+                fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                    self.0.to_bits().hash(state)
+                }
+            }
+
+            impl fmt::Debug for $name {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    fmt::Debug::fmt(&self.0, f)
+                }
+            }
+
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    fmt::Display::fmt(&self.0, f)
+                }
+            }
+
+            impl From<$name> for $t {
+                fn from(value: $name) -> $t {
+                    value.0
+                }
+            }
+
+            impl TryFrom<$t> for $name {
+                type Error = NanError;
+
+                fn try_from(value: $t) -> Result<Self, NanError> {
+                    if value.is_nan() {
+                        Err(NanError)
+                    } else {
+                        Ok($name::new(value))
+                    }
+                }
+            }
+
+            impl Margin for $name {
+                #[inline]
+                fn add_margin(self, margin: Self) -> Self {
+                    $name::new(self.0 + margin.0)
+                }
+
+                #[inline]
+                fn sub_margin(self, margin: Self) -> Self {
+                    $name::new(self.0 - margin.0)
+                }
+
+                #[inline]
+                fn successor(self) -> Self {
+                    $name::new(self.0.next_up())
+                }
+
+                #[inline]
+                fn predecessor(self) -> Self {
+                    $name::new(self.0.next_down())
+                }
+            }
+
+            impl Negate for $name {
+                #[inline]
+                fn negate(self) -> Self {
+                    $name::new(-self.0)
+                }
+            }
+        )*
+    };
+}
+
+ordered_float! {
+    /// A `f32` fitness value which implements `Ord`, see [`OrderedF64`][f64].
+    ///
+    /// [f64]: struct.OrderedF64.html
+    OrderedF32(f32, "f32");
+    /// A `f64` which is never `NaN`, so it implements `Ord` and can be used as [`Game::Fitness`][fitness].
+    ///
+    /// `-0.0` is stored as `0.0`, so equal values also have the same hash. Adding margins saturates at
+    /// the infinities, which are the best and worst possible fitness values. A `Game` which needs to
+    /// compute `NaN` values, e.g. by subtracting infinities, panics instead of silently mis-ordering states.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rubot::{Margin, Negate, OrderedF64};
+    /// use std::convert::TryFrom;
+    ///
+    /// let mut scores = vec![OrderedF64::new(0.5), OrderedF64::new(-1.25), OrderedF64::new(3.0)];
+    /// scores.sort();
+    /// assert_eq!(scores, [OrderedF64::new(-1.25), OrderedF64::new(0.5), OrderedF64::new(3.0)]);
+    ///
+    /// assert_eq!(OrderedF64::new(-0.0), OrderedF64::new(0.0));
+    /// assert_eq!(OrderedF64::new(0.5).negate().get(), -0.5);
+    /// assert!(OrderedF64::new(1.0).successor() > OrderedF64::new(1.0));
+    /// assert!(OrderedF64::try_from(f64::NAN).is_err());
+    /// ```
+    ///
+    /// [fitness]: trait.Game.html#associatedtype.Fitness
+    OrderedF64(f64, "f64");
+}
+
+impl From<OrderedF32> for f64 {
+    fn from(value: OrderedF32) -> f64 {
+        f64::from(value.0)
+    }
+}
+
+/// The error returned when converting `NaN` into an [`OrderedF64`][f64] or [`OrderedF32`][f32].
+///
+/// [f64]: struct.OrderedF64.html
+/// [f32]: struct.OrderedF32.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NanError;
+
+impl fmt::Display for NanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("fitness values must not be `NaN`")
+    }
+}
+
+impl Error for NanError {}
+
+/// An error which occurred during [`Bot::try_select`][try].
+///
+/// [try]: alpha_beta/struct.Bot.html#method.try_select
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SelectError {
+    /// A method of [`Game`][game] panicked during the search, contains the panic payload.
+    ///
+    /// [game]: trait.Game.html
+    Panicked(Box<dyn Any + Send + 'static>),
+}
+
+impl fmt::Display for SelectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectError::Panicked(payload) => {
+                if let Some(msg) = payload.downcast_ref::<&str>() {
+                    write!(f, "game panicked during search: {}", msg)
+                } else if let Some(msg) = payload.downcast_ref::<String>() {
+                    write!(f, "game panicked during search: {}", msg)
+                } else {
+                    write!(f, "game panicked during search")
+                }
+            }
+        }
+    }
+}
+
+impl Error for SelectError {}
+
+/// Converts a type into a [`RunCondition`][rc] used by [`Bot::select`][sel].
+/// It is recommended to mostly use [`Duration`][dur].
+///
+/// # Examples
+///
+/// ```rust
+/// # struct Game;
+/// # struct Bot;
+/// # impl Bot {
+/// #   fn select<U: rubot::IntoRunCondition>(&mut self, state: &Game, condition: U) -> Option<()> {
+/// #       Some(())
+/// #   }
+/// # }
+/// use std::time::Duration;
+///
+/// let available_time = Duration::from_secs(2);
+///
+/// let game: Game = // ...
+/// # Game;
+/// let mut bot: Bot = // ...
+/// # Bot;
+/// assert!(bot.select(&game, available_time).is_some())
+/// ```
+///
+/// # Implementations
+///
+/// - [`Duration`][dur]: `select` runs for the specified duration
+/// - [`ToCompletion`][complete]: `select` runs until it found the perfect action
+/// - [`Depth`][depth]: `select` analyses up the to given depth and returns to best action at that depth
+/// - [`Nodes`][nodes]: `select` searches at most the given amount of states
+/// - [`Instant`][instant]: `select` runs until the given `Instant` is in the past
+/// - [`Timeout`][timeout]: `select` runs for the specified duration according to a [`Clock`][clock_trait]
+/// - [`Logger`][logger]: takes another run condition and stores information about the last call to `select`
+/// - [`GameClock`][clock]: `select` runs for a part of the remaining time of the whole game
+/// - [`StopFlag`][stop]: `select` runs until the flag is set, possibly by another thread
+/// - [`And`][and]: `select` runs while both run conditions are true
+/// - [`Or`][or]: `select` runs while at least one of two run conditions is true
+///
+/// [rc]: trait.RunCondition.html
+/// [dur]: https://doc.rust-lang.org/std/time/struct.Duration.html
+/// [complete]: struct.ToCompletion.html
+/// [depth]: struct.Depth.html
+/// [nodes]: struct.Nodes.html
+/// [instant]: https://doc.rust-lang.org/std/time/struct.Instant.html
+/// [timeout]: struct.Timeout.html
+/// [clock_trait]: trait.Clock.html
+/// [logger]: struct.Logger.html
+/// [clock]: struct.GameClock.html
+/// [stop]: struct.StopFlag.html
+/// [and]: struct.And.html
+/// [or]: struct.Or.html
+/// [sel]: alpha_beta/struct.Bot.html#method.select
+///
+pub trait IntoRunCondition {
+    type RunCondition: RunCondition;
+
+    /// consumes `self` and returns a `RunCondition`.
+    ///
+    /// [rc]: trait.RunCondition.html
+    fn into_run_condition(self) -> Self::RunCondition;
+}
+
+impl<T> IntoRunCondition for T
+where
+    T: RunCondition,
+{
+    type RunCondition = Self;
+
+    fn into_run_condition(self) -> Self {
+        self
+    }
+}
+
+/// Can be converted into [`RunCondition`][rc] which returns `true` for the first `self.0` steps.
+/// This should only be used for debugging and testing as unlike `Duration`, `ToCompletion` or `Depth`,
+/// the total amount of steps needed is not directly indicative of search depth and can change between minor versions.
+///
+/// [rc]: trait.RunCondition.html
+#[derive(Clone, Copy, Debug)]
+pub struct Steps(pub u64);
+
+/// The [`RunCondition`][rc] created by [`Steps`][steps]`::into_run_condition`
+///
+/// [rc]: trait.RunCondition.html
+/// [steps]: struct.Steps.html
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct InnerSteps(u64, u64);
+
+impl IntoRunCondition for Steps {
+    type RunCondition = InnerSteps;
+
+    fn into_run_condition(self) -> InnerSteps {
+        InnerSteps(0, self.0)
+    }
+}
+
+impl RunCondition for InnerSteps {
+    #[inline]
+    fn step(&mut self) -> bool {
+        self.0 = self.0.saturating_add(1);
+        self.0 < self.1
+    }
+
+    #[inline]
+    fn depth(&mut self, _: u32) -> bool {
+        true
+    }
+}
+
+/// Creates a [`RunCondition`][rc] which returns `true` until this `Duration` has passed,
+/// according to the [`SystemClock`][clock].
+///
+/// [rc]: trait.RunCondition.html
+/// [clock]: struct.SystemClock.html
+impl IntoRunCondition for Duration {
+    type RunCondition = Timeout;
+
+    fn into_run_condition(self) -> Timeout {
+        Timeout::new(SystemClock::new(), self)
+    }
+}
+
+/// A condition which indicates if [`Bot::select`][sel] should keep on running.
+/// It is recommended to use [`Duration`][dur] for nearly all use cases.
+///
+/// A list of all already implemented `RunCondition`s can be found [here][into].
+///
+/// [sel]: alpha_beta/struct.Bot.html#method.select
+/// [dur]: https://doc.rust-lang.org/std/time/struct.Duration.html
+/// [into]: trait.IntoRunCondition.html#implementations-1
+pub trait RunCondition {
+    /// Called at each search step, instantly stops all calculations by returning `false`.
+    fn step(&mut self) -> bool;
+    /// Called after every finished search depth, instantly stops all calculations by returning `false`.
+    fn depth(&mut self, depth: u32) -> bool;
+    /// Called between potentially expensive operations during a single search step,
+    /// for example before each call to [`Game::execute`][exe]. Instantly stops all calculations by returning `false`.
+    ///
+    /// Unlike [`step`][step], this should not count as progress, so it is only useful for conditions
+    /// which have to stop as soon as possible, like deadlines. The default implementation always returns `true`.
+    ///
+    /// [exe]: trait.Game.html#tymethod.execute
+    /// [step]: trait.RunCondition.html#tymethod.step
+    #[inline]
+    fn check(&mut self) -> bool {
+        true
+    }
+
+    /// Returns the time left until this condition stops, if it is known.
+    ///
+    /// Bots using iterative deepening use this to avoid starting an iteration which is not expected
+    /// to finish in time, as an incomplete iteration is mostly wasted. The default implementation returns `None`.
+    #[inline]
+    fn remaining(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Returns `true` while the `Instant` is still in the future
+impl RunCondition for Instant {
+    #[inline]
+    fn step(&mut self) -> bool {
+        Instant::now() < *self
+    }
+
+    #[inline]
+    fn depth(&mut self, _: u32) -> bool {
+        Instant::now() < *self
+    }
+
+    #[inline]
+    fn check(&mut self) -> bool {
+        Instant::now() < *self
+    }
+
+    #[inline]
+    fn remaining(&self) -> Option<Duration> {
+        Some(self.saturating_duration_since(Instant::now()))
+    }
+}
+
+/// A source of time used by [`Timeout`][timeout], [`GameClock`][game], [`Logger`][logger] and
+/// [`alpha_beta::Bot::with_clock`][bot].
+///
+/// This is implemented for the [`SystemClock`][system] and for all closures returning a `Duration`,
+/// which allows using a custom clock on platforms without `std::time::Instant`, like `wasm32-unknown-unknown`,
+/// or a simulated clock to test timeouts deterministically. Without a clock set by [`with_clock`][bot],
+/// the statistics of a search are still measured using the `SystemClock`.
+///
+/// [timeout]: struct.Timeout.html
+/// [game]: struct.GameClock.html
+/// [logger]: struct.Logger.html
+/// [bot]: alpha_beta/struct.Bot.html#method.with_clock
+/// [system]: struct.SystemClock.html
+pub trait Clock {
+    /// Returns the time passed since an arbitrary but fixed point.
+    fn now(&self) -> Duration;
+}
+
+impl<F: Fn() -> Duration> Clock for F {
+    #[inline]
+    fn now(&self) -> Duration {
+        self()
+    }
+}
+
+/// The default [`Clock`][clock], using `std::time::Instant`.
+///
+/// All system clocks measure the time since the first one was read, so creating a `SystemClock`
+/// does not use `std::time::Instant` yet.
+///
+/// [clock]: trait.Clock.html
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    /// Creates a new `SystemClock`.
+    pub fn new() -> Self {
+        SystemClock
+    }
+}
+
+impl Clock for SystemClock {
+    #[inline]
+    fn now(&self) -> Duration {
+        static EPOCH: OnceLock<Instant> = OnceLock::new();
+        EPOCH.get_or_init(Instant::now).elapsed()
+    }
+}
+
+/// A [`RunCondition`][rc] which returns `true` until a duration has passed according to a [`Clock`][clock].
+///
+/// This is the run condition used for `Duration`, which uses the [`SystemClock`][system].
+/// Other clocks can be used on platforms without `std::time::Instant`, for example
+/// using `performance.now()` in browsers.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{Bot, Timeout, tree::Node};
+/// use std::time::Duration;
+///
+/// fn now() -> Duration {
+///     // On `wasm32`, this could convert the milliseconds of `performance.now()` instead.
+///     std::time::SystemTime::UNIX_EPOCH.elapsed().unwrap()
+/// }
+///
+/// # #[rustfmt::skip]
+/// let tree = Node::root().with_children(&[
+///     Node::new(false, 7).with_children(&[
+///         Node::new(true, 4),
+///         Node::new(true, 2),
+///     ]),
+///     Node::new(false, 5).with_children(&[
+///         Node::new(true, 8),
+///         Node::new(true, 9),
+///     ]),
+/// ]);
+///
+/// let mut bot = Bot::new(true);
+/// assert_eq!(bot.select(&tree, Timeout::new(now, Duration::from_secs(1))), Some(1));
+/// ```
+///
+/// [rc]: trait.RunCondition.html
+/// [clock]: trait.Clock.html
+/// [system]: struct.SystemClock.html
+#[derive(Clone, Copy, Debug)]
+pub struct Timeout<C: Clock = SystemClock> {
+    clock: C,
+    deadline: Duration,
+}
+
+impl<C: Clock> Timeout<C> {
+    /// Creates a `Timeout` which stops once `duration` has passed according to `clock`.
+    pub fn new(clock: C, duration: Duration) -> Self {
+        let deadline = clock.now() + duration;
+        Self { clock, deadline }
+    }
+
+    /// Returns the clock used by this timeout.
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+}
+
+impl<C: Clock> RunCondition for Timeout<C> {
+    #[inline]
+    fn step(&mut self) -> bool {
+        self.clock.now() < self.deadline
+    }
+
+    #[inline]
+    fn depth(&mut self, _: u32) -> bool {
+        self.clock.now() < self.deadline
+    }
+
+    #[inline]
+    fn check(&mut self) -> bool {
+        self.clock.now() < self.deadline
+    }
+
+    #[inline]
+    fn remaining(&self) -> Option<Duration> {
+        Some(self.deadline.saturating_sub(self.clock.now()))
+    }
+}
+
+/// A struct implementing [`RunCondition`][rc] which always returns `true`.
+///
+/// This means that the bot will always run until the best action was found.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rubot::{Bot, tree::Node, ToCompletion};
+/// let tree = Node::root().with_children(&[
+///     Node::new(false, 7).with_children(&[
+///         Node::new(true, 4),
+///         Node::new(true, 2),
+///     ]),
+///     Node::new(false, 5).with_children(&[
+///         Node::new(true, 8),
+///         Node::new(true, 9)
+///     ]),
+/// ]);
+///
+/// let mut bot = Bot::new(true);
+/// assert_eq!(bot.select(&tree, ToCompletion), Some(1));
+/// ```
+/// [rc]: trait.RunCondition.html
+#[derive(Clone, Copy, Debug)]
+pub struct ToCompletion;
+
+impl RunCondition for ToCompletion {
+    #[inline]
+    fn step(&mut self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn depth(&mut self, _: u32) -> bool {
+        true
+    }
+}
+
+/// A struct implementing [`RunCondition`][rc] returning cancelling the computation once the depth `self.0`
+/// is reached.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rubot::{Bot, tree::Node, Depth};
+/// let tree = Node::root().with_children(&[
+///     Node::new(false, 7).with_children(&[
+///         Node::new(true, 4),
+///         Node::new(true, 2),
+///     ]),
+///     Node::new(false, 5).with_children(&[
+///         Node::new(true, 8),
+///         Node::new(true, 9)
+///     ]),
+/// ]);
+///
+/// let mut bot = Bot::new(true);
+/// assert_eq!(bot.select(&tree, Depth(0)), Some(0));
+/// assert_eq!(bot.select(&tree, Depth(1)), Some(1));
+/// ```
+/// [rc]: trait.RunCondition.html
+#[derive(Clone, Copy, Debug)]
+pub struct Depth(pub u32);
+
+impl RunCondition for Depth {
+    #[inline]
+    fn step(&mut self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn depth(&mut self, depth: u32) -> bool {
+        self.0 > depth
+    }
+}
+
+/// A struct implementing [`RunCondition`][rc] which stops the search once `self.0` states were searched.
+///
+/// Unlike `Duration`, the result does not depend on the speed of the machine, which makes games
+/// between bots reproducible. Unlike [`Steps`][steps], the amount of searched states is stable between versions:
+/// for [`alpha_beta::Bot`][ab], this is the same as [`SearchStats::nodes`][stats], for
+/// [`mcts::Bot`][mcts] it is the amount of playouts.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rubot::{Bot, tree::Node, Nodes, ToCompletion};
+/// let tree = Node::root().with_children(&[
+///     Node::new(false, 7).with_children(&[
+///         Node::new(true, 4),
+///         Node::new(true, 2),
+///     ]),
+///     Node::new(false, 5).with_children(&[
+///         Node::new(true, 8),
+///         Node::new(true, 9)
+///     ]),
+/// ]);
+///
+/// let mut bot = Bot::new(true);
+/// assert_eq!(bot.select(&tree, Nodes(1)), Some(0));
+/// assert_eq!(bot.stats().nodes, 1);
+///
+/// assert_eq!(bot.select(&tree, ToCompletion), Some(1));
+/// let nodes = bot.stats().nodes;
+/// assert_eq!(bot.select(&tree, Nodes(nodes)), Some(1));
+/// ```
+/// [rc]: trait.RunCondition.html
+/// [steps]: struct.Steps.html
+/// [ab]: alpha_beta/struct.Bot.html
+/// [stats]: alpha_beta/struct.SearchStats.html#structfield.nodes
+/// [mcts]: mcts/struct.Bot.html
+#[derive(Clone, Copy, Debug)]
+pub struct Nodes(pub u64);
+
+impl RunCondition for Nodes {
+    #[inline]
+    fn step(&mut self) -> bool {
+        if self.0 == 0 {
+            false
+        } else {
+            self.0 -= 1;
+            true
+        }
+    }
+
+    #[inline]
+    fn depth(&mut self, _: u32) -> bool {
+        true
+    }
+}
+
+/// A struct implementing [`IntoRunCondition`] which can be used to log a call to [`select`][sel].
+/// For more details you can visit the individual methods.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rubot::{Bot, tree::Node, ToCompletion, Logger};
 /// # use std::time::Duration;
 /// let tree = Node::root().with_children(&[
 ///     Node::new(false, 7).with_children(&[
@@ -512,22 +1617,31 @@ impl RunCondition for Depth {
 /// // the total duration of `bot.select`
 /// assert!(logger.duration() < Duration::from_secs(1));
 /// ```
+///
+/// The duration is measured using the [`SystemClock`][system], use [`with_clock`][with_clock]
+/// to use a different [`Clock`][clock] instead.
+///
 /// [sel]: alpha_beta/struct.Bot.html#method.select
-pub struct Logger<T: IntoRunCondition> {
+/// [system]: struct.SystemClock.html
+/// [with_clock]: #method.with_clock
+/// [clock]: trait.Clock.html
+pub struct Logger<T: IntoRunCondition, C: Clock = SystemClock> {
     condition: T::RunCondition,
-    steps: u32,
+    clock: C,
+    steps: u64,
     depth: u32,
     completed: bool,
     duration: Duration,
 }
 
-impl<T: IntoRunCondition> Debug for Logger<T>
+impl<T: IntoRunCondition, C: Clock + Debug> Debug for Logger<T, C>
 where
     T::RunCondition: Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Logger")
             .field("condition", &self.condition)
+            .field("clock", &self.clock)
             .field("steps", &self.steps)
             .field("depth", &self.depth)
             .field("completed", &self.completed)
@@ -541,18 +1655,42 @@ impl<T: IntoRunCondition> Logger<T> {
     pub fn new(condition: T) -> Self {
         Self {
             condition: condition.into_run_condition(),
+            clock: SystemClock::new(),
             steps: 0,
             depth: 0,
             completed: true,
             duration: Duration::from_secs(0),
         }
     }
+}
+
+impl<T: IntoRunCondition, C: Clock> Logger<T, C> {
+    /// Measures the duration of each call to [`select`][sel] using `clock` instead of the current one.
+    ///
+    /// [sel]: alpha_beta/struct.Bot.html#method.select
+    pub fn with_clock<D: Clock>(self, clock: D) -> Logger<T, D> {
+        Logger {
+            condition: self.condition,
+            clock,
+            steps: self.steps,
+            depth: self.depth,
+            completed: self.completed,
+            duration: self.duration,
+        }
+    }
+
+    /// Returns the clock used by this logger.
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
 
     /// Returns the total amount of times [`step`][step] was called during the last call to [`select`][sel].
     ///
+    /// This saturates at `u64::MAX`.
+    ///
     /// [step]: trait.RunCondition.html#tymethod.step
     /// [sel]: alpha_beta/struct.Bot.html#method.select
-    pub fn steps(&self) -> u32 {
+    pub fn steps(&self) -> u64 {
         self.steps
     }
 
@@ -589,22 +1727,26 @@ impl<T: IntoRunCondition> Logger<T> {
 /// [rc]: trait.RunCondition.html
 /// [logger]: struct.Logger.html
 #[doc(hidden)]
-pub struct InnerLogger<'a, T: IntoRunCondition>(&'a mut Logger<T>, Instant);
+pub struct InnerLogger<'a, T: IntoRunCondition, C: Clock = SystemClock>(
+    &'a mut Logger<T, C>,
+    Duration,
+);
 
-impl<'a, T: IntoRunCondition> IntoRunCondition for &'a mut Logger<T> {
-    type RunCondition = InnerLogger<'a, T>;
+impl<'a, T: IntoRunCondition, C: Clock> IntoRunCondition for &'a mut Logger<T, C> {
+    type RunCondition = InnerLogger<'a, T, C>;
 
-    fn into_run_condition(self) -> InnerLogger<'a, T> {
+    fn into_run_condition(self) -> InnerLogger<'a, T, C> {
         self.steps = 0;
         self.depth = 0;
-        InnerLogger(self, Instant::now())
+        let start = self.clock.now();
+        InnerLogger(self, start)
     }
 }
 
-impl<'a, T: IntoRunCondition> RunCondition for InnerLogger<'a, T> {
+impl<'a, T: IntoRunCondition, C: Clock> RunCondition for InnerLogger<'a, T, C> {
     #[inline]
     fn step(&mut self) -> bool {
-        self.0.steps += 1;
+        self.0.steps = self.0.steps.saturating_add(1);
         if self.0.condition.step() {
             true
         } else {
@@ -623,11 +1765,445 @@ impl<'a, T: IntoRunCondition> RunCondition for InnerLogger<'a, T> {
             false
         }
     }
+
+    #[inline]
+    fn check(&mut self) -> bool {
+        if self.0.condition.check() {
+            true
+        } else {
+            self.0.completed = false;
+            false
+        }
+    }
+
+    #[inline]
+    fn remaining(&self) -> Option<Duration> {
+        self.0.condition.remaining()
+    }
+}
+
+impl<'a, T: IntoRunCondition, C: Clock> Drop for InnerLogger<'a, T, C> {
+    fn drop(&mut self) {
+        self.0.duration = self.0.clock.now().saturating_sub(self.1);
+    }
+}
+
+/// A time budget for an entire game, which allocates a part of the remaining time to each move.
+///
+/// Each call to [`select`][sel] using `&mut GameClock` as its run condition may use
+/// [`move_budget`][budget], after which the time actually spent is subtracted from the remaining time.
+/// The budget of a move is the remaining time divided by the amount of expected remaining moves plus one,
+/// so games which take longer than expected never run out of time while positions late in the game
+/// still get a fair share.
+///
+/// Time spent outside of calls to `select`, for example waiting for the opponent, is not counted.
+/// Use [`set_remaining`][set] to synchronize with an external clock.
+///
+/// Time is measured using the [`SystemClock`][system] by default, use [`with_clock`][with_clock]
+/// to use a different [`Clock`][clock] instead, like for [`Timeout`][timeout].
+///
+/// # Examples
+///
+/// ```rust
+/// # use rubot::{Bot, tree::Node, GameClock};
+/// use std::time::Duration;
+///
+/// let tree = Node::root().with_children(&[
+///     Node::new(false, 7).with_children(&[
+///         Node::new(true, 4),
+///         Node::new(true, 2),
+///     ]),
+///     Node::new(false, 5).with_children(&[
+///         Node::new(true, 8),
+///         Node::new(true, 9)
+///     ]),
+/// ]);
+///
+/// // Five minutes for a game which is expected to take 40 moves.
+/// let mut clock = GameClock::new(Duration::from_secs(300), 40);
+/// assert_eq!(clock.move_budget(), Duration::from_secs(300) / 41);
+///
+/// let mut bot = Bot::new(true);
+/// assert_eq!(bot.select(&tree, &mut clock), Some(1));
+/// assert_eq!(clock.moves(), 1);
+/// assert!(clock.remaining() < Duration::from_secs(300));
+/// ```
+/// [sel]: alpha_beta/struct.Bot.html#method.select
+/// [budget]: #method.move_budget
+/// [set]: #method.set_remaining
+/// [with_clock]: #method.with_clock
+/// [system]: struct.SystemClock.html
+/// [clock]: trait.Clock.html
+/// [timeout]: struct.Timeout.html
+#[derive(Clone, Copy, Debug)]
+pub struct GameClock<C: Clock = SystemClock> {
+    clock: C,
+    remaining: Duration,
+    expected_moves: u32,
+    moves: u32,
+}
+
+impl GameClock {
+    /// Creates a new `GameClock` with a total time of `budget` for a game expected to
+    /// take `expected_moves` moves of this bot.
+    pub fn new(budget: Duration, expected_moves: u32) -> Self {
+        Self {
+            clock: SystemClock::new(),
+            remaining: budget,
+            expected_moves,
+            moves: 0,
+        }
+    }
+}
+
+impl<C: Clock> GameClock<C> {
+    /// Measures the time spent for each move using `clock` instead of the current one.
+    pub fn with_clock<D: Clock>(self, clock: D) -> GameClock<D> {
+        GameClock {
+            clock,
+            remaining: self.remaining,
+            expected_moves: self.expected_moves,
+            moves: self.moves,
+        }
+    }
+
+    /// Returns the clock used by this game clock.
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Returns the remaining time for the rest of the game.
+    pub fn remaining(&self) -> Duration {
+        self.remaining
+    }
+
+    /// Sets the remaining time for the rest of the game, for example after receiving an increment.
+    pub fn set_remaining(&mut self, remaining: Duration) {
+        self.remaining = remaining;
+    }
+
+    /// Returns the amount of moves which have already been selected using this clock.
+    pub fn moves(&self) -> u32 {
+        self.moves
+    }
+
+    /// Returns the time available for the next move.
+    pub fn move_budget(&self) -> Duration {
+        let moves_left = self.expected_moves.saturating_sub(self.moves);
+        self.remaining / moves_left.saturating_add(1)
+    }
+}
+
+/// The [`RunCondition`][rc] created by [`GameClock`][clock]`::into_run_condition`
+///
+/// [rc]: trait.RunCondition.html
+/// [clock]: struct.GameClock.html
+#[doc(hidden)]
+pub struct InnerGameClock<'a, C: Clock = SystemClock>(&'a mut GameClock<C>, Duration, Duration);
+
+impl<'a, C: Clock> IntoRunCondition for &'a mut GameClock<C> {
+    type RunCondition = InnerGameClock<'a, C>;
+
+    fn into_run_condition(self) -> InnerGameClock<'a, C> {
+        let now = self.clock.now();
+        let deadline = now + self.move_budget();
+        InnerGameClock(self, now, deadline)
+    }
+}
+
+impl<'a, C: Clock> RunCondition for InnerGameClock<'a, C> {
+    #[inline]
+    fn step(&mut self) -> bool {
+        self.0.clock.now() < self.2
+    }
+
+    #[inline]
+    fn depth(&mut self, _: u32) -> bool {
+        self.0.clock.now() < self.2
+    }
+
+    #[inline]
+    fn check(&mut self) -> bool {
+        self.0.clock.now() < self.2
+    }
+
+    #[inline]
+    fn remaining(&self) -> Option<Duration> {
+        Some(self.2.saturating_sub(self.0.clock.now()))
+    }
 }
 
-impl<'a, T: IntoRunCondition> Drop for InnerLogger<'a, T> {
+impl<'a, C: Clock> Drop for InnerGameClock<'a, C> {
     fn drop(&mut self) {
-        self.0.duration = self.1.elapsed();
+        let spent = self.0.clock.now().saturating_sub(self.1);
+        self.0.remaining = self.0.remaining.saturating_sub(spent);
+        self.0.moves = self.0.moves.saturating_add(1);
+    }
+}
+
+/// A [`RunCondition`][rc] which returns `true` until [`stop`][stop] is called on any of its clones,
+/// which allows cancelling a search from another thread.
+///
+/// As `select` takes its run condition by value, pass a clone of the flag and keep the original.
+/// The flag stays set after `select` returned, use [`reset`][reset] to reuse it.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rubot::{Bot, tree::Node, StopFlag};
+/// use std::thread;
+///
+/// let tree = Node::root().with_children(&[
+///     Node::new(false, 7).with_children(&[
+///         Node::new(true, 4),
+///         Node::new(true, 2),
+///     ]),
+///     Node::new(false, 5).with_children(&[
+///         Node::new(true, 8),
+///         Node::new(true, 9)
+///     ]),
+/// ]);
+///
+/// let flag = StopFlag::new();
+/// let stop = flag.clone();
+/// // For example once the player resigns.
+/// thread::spawn(move || stop.stop()).join().unwrap();
+/// assert!(flag.is_stopped());
+///
+/// // The search is cancelled immediately, but `select` still returns an action.
+/// let mut bot = Bot::new(true);
+/// assert!(bot.select(&tree, flag.clone()).is_some());
+///
+/// flag.reset();
+/// assert_eq!(bot.select(&tree, flag), Some(1));
+/// ```
+/// [rc]: trait.RunCondition.html
+/// [stop]: #method.stop
+/// [reset]: #method.reset
+#[derive(Clone, Debug, Default)]
+pub struct StopFlag(Arc<AtomicBool>);
+
+impl StopFlag {
+    /// Creates a new flag which is not yet stopped.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stops all searches using this flag or any of its clones.
+    pub fn stop(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether [`stop`][stop] was called since the last call to [`reset`][reset].
+    ///
+    /// [stop]: #method.stop
+    /// [reset]: #method.reset
+    pub fn is_stopped(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Clears the flag, so it can be used for another search.
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+}
+
+impl From<Arc<AtomicBool>> for StopFlag {
+    /// Uses an existing `AtomicBool`, which stops the search once it is `true`.
+    fn from(flag: Arc<AtomicBool>) -> Self {
+        StopFlag(flag)
+    }
+}
+
+impl RunCondition for StopFlag {
+    #[inline]
+    fn step(&mut self) -> bool {
+        !self.is_stopped()
+    }
+
+    #[inline]
+    fn depth(&mut self, _: u32) -> bool {
+        !self.is_stopped()
+    }
+
+    #[inline]
+    fn check(&mut self) -> bool {
+        !self.is_stopped()
+    }
+}
+
+/// Can be converted into a [`RunCondition`][rc] which returns `true` while both
+/// `self.0` and `self.1` return `true`, e.g. to use both a time limit and a maximum depth.
+///
+/// Both conditions are always queried, so conditions counting their calls like [`Steps`][steps]
+/// behave the same as when used on their own.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rubot::{Bot, tree::Node, And, Depth};
+/// use std::time::Duration;
+///
+/// let tree = Node::root().with_children(&[
+///     Node::new(false, 7).with_children(&[
+///         Node::new(true, 4),
+///         Node::new(true, 2),
+///     ]),
+///     Node::new(false, 5).with_children(&[
+///         Node::new(true, 8),
+///         Node::new(true, 9)
+///     ]),
+/// ]);
+///
+/// let mut bot = Bot::new(true);
+/// // At most 2 seconds and at most depth 0.
+/// let condition = And(Duration::from_secs(2), Depth(0));
+/// assert_eq!(bot.select(&tree, condition), Some(0));
+/// ```
+/// [rc]: trait.RunCondition.html
+/// [steps]: struct.Steps.html
+#[derive(Clone, Copy, Debug)]
+pub struct And<A, B>(pub A, pub B);
+
+/// The [`RunCondition`][rc] created by [`And`][and]`::into_run_condition`
+///
+/// [rc]: trait.RunCondition.html
+/// [and]: struct.And.html
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct InnerAnd<A, B>(A, B);
+
+impl<A: IntoRunCondition, B: IntoRunCondition> IntoRunCondition for And<A, B> {
+    type RunCondition = InnerAnd<A::RunCondition, B::RunCondition>;
+
+    fn into_run_condition(self) -> Self::RunCondition {
+        InnerAnd(self.0.into_run_condition(), self.1.into_run_condition())
+    }
+}
+
+impl<A: RunCondition, B: RunCondition> RunCondition for InnerAnd<A, B> {
+    #[inline]
+    fn step(&mut self) -> bool {
+        let a = self.0.step();
+        let b = self.1.step();
+        a && b
+    }
+
+    #[inline]
+    fn depth(&mut self, depth: u32) -> bool {
+        let a = self.0.depth(depth);
+        let b = self.1.depth(depth);
+        a && b
+    }
+
+    #[inline]
+    fn check(&mut self) -> bool {
+        let a = self.0.check();
+        let b = self.1.check();
+        a && b
+    }
+
+    #[inline]
+    fn remaining(&self) -> Option<Duration> {
+        match (self.0.remaining(), self.1.remaining()) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+/// Can be converted into a [`RunCondition`][rc] which returns `true` while at least one of
+/// `self.0` and `self.1` returns `true`.
+///
+/// As [`Depth`][depth] only stops between iterations, `Or(Depth(4), duration)` searches at least
+/// 4 iterations and then keeps on searching until `duration` has passed.
+/// Once a condition stopped at the start of an iteration, only the other one is used to stop the search.
+///
+/// Both conditions are always queried, so conditions counting their calls like [`Steps`][steps]
+/// behave the same as when used on their own.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rubot::{Bot, tree::Node, Depth, Or};
+/// use std::time::Duration;
+///
+/// let tree = Node::root().with_children(&[
+///     Node::new(false, 7).with_children(&[
+///         Node::new(true, 4),
+///         Node::new(true, 2),
+///     ]),
+///     Node::new(false, 5).with_children(&[
+///         Node::new(true, 8),
+///         Node::new(true, 9)
+///     ]),
+/// ]);
+///
+/// let mut bot = Bot::new(true);
+/// // At least depth 1, even though no time is left.
+/// let condition = Or(Depth(1), Duration::from_secs(0));
+/// assert_eq!(bot.select(&tree, condition), Some(1));
+/// ```
+/// [rc]: trait.RunCondition.html
+/// [depth]: struct.Depth.html
+/// [steps]: struct.Steps.html
+#[derive(Clone, Copy, Debug)]
+pub struct Or<A, B>(pub A, pub B);
+
+/// The [`RunCondition`][rc] created by [`Or`][or]`::into_run_condition`
+///
+/// [rc]: trait.RunCondition.html
+/// [or]: struct.Or.html
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct InnerOr<A, B>(A, B, Stopped);
+
+/// Whether `depth` of the first and the second condition of `InnerOr` already returned `false`,
+/// after which the results of all of its other methods are ignored.
+#[derive(Debug, Default)]
+struct Stopped(bool, bool);
+
+impl<A: IntoRunCondition, B: IntoRunCondition> IntoRunCondition for Or<A, B> {
+    type RunCondition = InnerOr<A::RunCondition, B::RunCondition>;
+
+    fn into_run_condition(self) -> Self::RunCondition {
+        InnerOr(
+            self.0.into_run_condition(),
+            self.1.into_run_condition(),
+            Stopped::default(),
+        )
+    }
+}
+
+impl<A: RunCondition, B: RunCondition> RunCondition for InnerOr<A, B> {
+    #[inline]
+    fn step(&mut self) -> bool {
+        let a = self.0.step() && !self.2 .0;
+        let b = self.1.step() && !self.2 .1;
+        a || b
+    }
+
+    #[inline]
+    fn depth(&mut self, depth: u32) -> bool {
+        self.2 .0 |= !self.0.depth(depth);
+        self.2 .1 |= !self.1.depth(depth);
+        !self.2 .0 || !self.2 .1
+    }
+
+    #[inline]
+    fn check(&mut self) -> bool {
+        let a = self.0.check() && !self.2 .0;
+        let b = self.1.check() && !self.2 .1;
+        a || b
+    }
+
+    #[inline]
+    fn remaining(&self) -> Option<Duration> {
+        match self.2 {
+            Stopped(true, false) => self.1.remaining(),
+            Stopped(false, true) => self.0.remaining(),
+            _ => Some(self.0.remaining()?.max(self.1.remaining()?)),
+        }
     }
 }
 
@@ -0,0 +1,223 @@
+//! A game bot for games with more than two players, using max^n.
+use crate::{Game, GameBot, IntoRunCondition, RunCondition};
+
+/// A [`Game`][game] with any amount of players, each trying to maximize their own fitness.
+///
+/// The active player of a state is the player whose `Game::actions` returns `true`.
+/// The fitness returned by `Game::execute` is not used, all states are evaluated using [`evaluate`][eval] instead.
+///
+/// [game]: ../trait.Game.html
+/// [eval]: #tymethod.evaluate
+pub trait Multiplayer: Game {
+    /// Returns all players of this game, which must be the same for all states of a game.
+    fn players(&self) -> Vec<Self::Player>;
+
+    /// Returns the fitness of this state from the perspective of `player`.
+    fn evaluate(&self, player: Self::Player) -> Self::Fitness;
+}
+
+/// A game bot which expects each player to choose the action which maximizes their own fitness.
+///
+/// Unlike [`alpha_beta::Bot`][ab], which assumes that all other players only try to minimize the fitness
+/// of the bot, this computes the fitness of every player for each searched state, and each player
+/// chooses the action with the highest fitness for themselves. In games with two players whose
+/// fitness values are negations of each other, this is the same as minimax.
+///
+/// As the fitness of the other players is not bounded by the one of the bot, this bot is not able to
+/// prune any actions, which makes it a lot slower than `alpha_beta::Bot`. Like [`brute::Brute`][brute],
+/// [`Depth`][depth]`(n)` searches `n + 1` actions.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{Bot, Game, ToCompletion, maxn::{self, Multiplayer}};
+///
+/// /// Player `0` either takes a safe prize or lets player `1` decide
+/// /// how a larger one is split among all three players.
+/// #[derive(Clone)]
+/// struct Prize {
+///     turn: u8,
+///     fitness: [i8; 3],
+/// }
+///
+/// impl Game for Prize {
+///     type Player = u8;
+///     type Action = u8;
+///     type Fitness = i8;
+///     type Actions = Vec<u8>;
+///
+///     fn actions(&self, player: u8) -> (bool, Vec<u8>) {
+///         let actions = if self.turn < 2 { vec![0, 1] } else { Vec::new() };
+///         (self.turn == player, actions)
+///     }
+///
+///     fn execute(&mut self, &action: &u8, player: u8) -> i8 {
+///         match (self.turn, action) {
+///             (0, 0) => self.turn = 1,
+///             (0, _) => {
+///                 self.turn = 2;
+///                 self.fitness = [1, 1, 1];
+///             }
+///             (_, 0) => {
+///                 self.turn = 2;
+///                 self.fitness = [3, 2, 0];
+///             }
+///             (_, _) => {
+///                 self.turn = 2;
+///                 self.fitness = [0, 1, 5];
+///             }
+///         }
+///         self.fitness[player as usize]
+///     }
+/// }
+///
+/// impl Multiplayer for Prize {
+///     fn players(&self) -> Vec<u8> {
+///         vec![0, 1, 2]
+///     }
+///
+///     fn evaluate(&self, player: u8) -> i8 {
+///         self.fitness[player as usize]
+///     }
+/// }
+///
+/// let state = Prize { turn: 0, fitness: [0; 3] };
+/// // Player `1` prefers the first split, which is also good for player `0`.
+/// assert_eq!(maxn::Bot::new(0).select(&state, ToCompletion), Some(0));
+/// // `alpha_beta::Bot` expects player `1` to choose the worst split for player `0`.
+/// assert_eq!(Bot::new(0).select(&state, ToCompletion), Some(1));
+/// ```
+///
+/// [ab]: ../alpha_beta/struct.Bot.html
+/// [depth]: ../struct.Depth.html
+/// [brute]: ../brute/struct.Brute.html
+pub struct Bot<T: Game> {
+    player: T::Player,
+}
+
+impl<T: Multiplayer> Bot<T> {
+    /// Creates a new `Bot` for the given `player`.
+    pub fn new(player: T::Player) -> Self {
+        Self { player }
+    }
+
+    /// Returns the action with the highest fitness for the player of this bot, or `None` if no action
+    /// is possible or the bot is currently not the active player.
+    ///
+    /// `RunCondition::depth` is called before searching one additional action and
+    /// `RunCondition::step` before each searched state. In case the search is cancelled,
+    /// the best action of the last completed depth is returned.
+    pub fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action> {
+        let mut condition = condition.into_run_condition();
+
+        let (active, actions) = state.actions(self.player);
+        if !active {
+            return None;
+        }
+
+        let players = state.players();
+        let index = active_player(state, &players)?.0;
+        let actions: Vec<_> = actions.into_iter().collect();
+        let search = |depth, condition: &mut U::RunCondition| {
+            self.best_action(state, &players, index, &actions, depth, condition)
+        };
+        let (mut best, mut complete) = search(0, &mut condition)?;
+        let mut depth = 0;
+        while !complete && condition.depth(depth) {
+            depth += 1;
+            match search(depth, &mut condition) {
+                Some((action, now_complete)) => {
+                    best = action;
+                    complete = now_complete;
+                }
+                None => break,
+            }
+        }
+        Some(best)
+    }
+
+    /// Returns the best action of `actions` for the player at `index` and whether the complete game
+    /// was searched, or `None` if there are no actions or the search was cancelled.
+    fn best_action<U: RunCondition>(
+        &self,
+        state: &T,
+        players: &[T::Player],
+        index: usize,
+        actions: &[T::Action],
+        depth: u32,
+        condition: &mut U,
+    ) -> Option<(T::Action, bool)> {
+        let mut best: Option<(&T::Action, T::Fitness)> = None;
+        let mut complete = true;
+        for action in actions {
+            let (value, action_complete) = self.value(state, players, action, depth, condition)?;
+            complete &= action_complete;
+            if best.is_none_or(|(_, best)| value[index] > best) {
+                best = Some((action, value[index]));
+            }
+        }
+        best.map(|(action, _)| (action.clone(), complete))
+    }
+
+    /// Returns the fitness of each player after `action` when searching `depth` additional actions
+    /// and whether this is exact, or `None` if the search was cancelled.
+    fn value<U: RunCondition>(
+        &self,
+        state: &T,
+        players: &[T::Player],
+        action: &T::Action,
+        depth: u32,
+        condition: &mut U,
+    ) -> Option<(Vec<T::Fitness>, bool)> {
+        if !condition.step() {
+            return None;
+        }
+
+        let mut state = state.clone();
+        state.execute(action, self.player);
+        let evaluate = |state: &T| {
+            players
+                .iter()
+                .map(|&player| state.evaluate(player))
+                .collect()
+        };
+        if depth == 0 {
+            return Some((evaluate(&state), false));
+        }
+
+        let (index, actions) = match active_player(&state, players) {
+            Some((index, actions)) if !actions.is_empty() => (index, actions),
+            _ => return Some((evaluate(&state), true)),
+        };
+
+        let mut best: Option<Vec<T::Fitness>> = None;
+        let mut complete = true;
+        for action in actions {
+            let (value, action_complete) =
+                self.value(&state, players, &action, depth - 1, condition)?;
+            complete &= action_complete;
+            if best.as_ref().is_none_or(|best| value[index] > best[index]) {
+                best = Some(value);
+            }
+        }
+        best.map(|best| (best, complete))
+    }
+}
+
+/// Returns the index of the active player in `players` together with their actions.
+fn active_player<T: Game>(state: &T, players: &[T::Player]) -> Option<(usize, Vec<T::Action>)> {
+    players.iter().enumerate().find_map(|(index, &player)| {
+        let (active, actions) = state.actions(player);
+        if active {
+            Some((index, actions.into_iter().collect()))
+        } else {
+            None
+        }
+    })
+}
+
+impl<T: Multiplayer> GameBot<T> for Bot<T> {
+    fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action> {
+        Bot::select(self, state, condition)
+    }
+}
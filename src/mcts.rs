@@ -0,0 +1,665 @@
+//! A game bot using Monte Carlo tree search.
+use crate::rng::XorShiftRng;
+use crate::{Game, GameBot, IntoRunCondition, Policy, RunCondition};
+
+use std::cmp::Ordering;
+use std::mem;
+
+/// A game bot which uses [Monte Carlo tree search][mcts] with the UCT selection rule.
+///
+/// Instead of searching every action, this bot estimates the value of actions using random playouts
+/// and spends more time on actions which seem promising. This works well for games with a huge amount
+/// of possible actions or without a good evaluation function, but unlike [`alpha_beta::Bot`][ab],
+/// the result is only guaranteed to be optimal once the complete game tree was explored.
+///
+/// The reward of a playout is `1.0` if its final fitness is an upper bound, `0.0` if it is a lower bound
+/// and `0.5` otherwise. Use [`with_reward`][reward] to use the fitness values directly.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{ToCompletion, mcts, tree::Node};
+/// use std::time::Duration;
+///
+/// let tree = Node::root().with_children(&[
+///     Node::new(false, 7).with_children(&[
+///         Node::new(true, 4),
+///         Node::new(true, 2),
+///     ]),
+///     Node::new(false, 5).with_children(&[
+///         Node::new(true, 8),
+///         Node::new(true, 9)
+///     ]),
+///     Node::new(false, 6),
+/// ]);
+///
+/// let mut bot = mcts::Bot::new(true).with_reward(|fitness| f64::from(fitness) / 10.0);
+/// // The tree is small enough to be completely explored.
+/// assert_eq!(bot.select(&tree, ToCompletion), Some(1));
+/// assert_eq!(bot.select(&tree, Duration::from_secs(1)), Some(1));
+/// ```
+///
+/// [mcts]: https://en.wikipedia.org/wiki/Monte_Carlo_tree_search
+/// [ab]: ../alpha_beta/struct.Bot.html
+/// [reward]: #method.with_reward
+pub struct Bot<T: Game> {
+    player: T::Player,
+    exploration: f64,
+    playout_depth: u32,
+    reward: Option<fn(T::Fitness) -> f64>,
+    policy: Option<Box<dyn Policy<T>>>,
+    /// The constant and exponent of progressive widening.
+    widening: Option<(f64, f64)>,
+    /// The maximum amount of nodes of the search tree.
+    max_nodes: Option<usize>,
+    rng: XorShiftRng,
+    reuse_tree: bool,
+    /// The search tree of the previous call to `select`, only used with `reuse_tree`.
+    tree: Option<Vec<Node<T>>>,
+}
+
+/// A node of the search tree, the children of a node always have a larger index than the node itself.
+struct Node<T: Game> {
+    /// The action leading to this node, `None` for the root.
+    action: Option<T::Action>,
+    /// The fitness after executing `action`.
+    fitness: T::Fitness,
+    /// Whether the opponent had to pass after `action`.
+    passed: bool,
+    /// Whether the bot is active at this node.
+    active: bool,
+    /// Actions which do not yet have a node.
+    ///
+    /// With a policy, these are sorted by their prior in ascending order.
+    untried: Vec<T::Action>,
+    /// The normalized prior of each untried action, empty without a policy.
+    priors: Vec<f64>,
+    /// The normalized prior of `action`, `1.0` without a policy.
+    prior: f64,
+    children: Vec<usize>,
+    visits: u64,
+    reward: f64,
+    /// Whether the complete subtree of this node is part of the search tree.
+    exhausted: bool,
+}
+
+impl<T: Game> Bot<T> {
+    /// Creates a new `Bot` for the given `player`.
+    pub fn new(player: T::Player) -> Self {
+        Self {
+            player,
+            exploration: std::f64::consts::SQRT_2,
+            playout_depth: 64,
+            reward: None,
+            policy: None,
+            widening: None,
+            max_nodes: None,
+            rng: XorShiftRng::from_seed_u64(0),
+            reuse_tree: false,
+            tree: None,
+        }
+    }
+
+    /// Sets the exploration constant of UCT, which is `sqrt(2)` by default.
+    ///
+    /// Higher values cause the bot to spend more time on actions which currently seem bad.
+    pub fn with_exploration(mut self, exploration: f64) -> Self {
+        self.exploration = exploration;
+        self
+    }
+
+    /// Sets the maximum amount of random actions executed during a single playout, which is `64` by default.
+    ///
+    /// The fitness after the last action of a playout is used as its result, so for games with
+    /// a meaningful fitness before the end of the game, short playouts can be a lot faster.
+    /// The last action is not chosen randomly, instead all possible last actions are evaluated at once
+    /// using [`Game::look_ahead_batch`][batch] and their rewards are averaged.
+    ///
+    /// [batch]: ../trait.Game.html#method.look_ahead_batch
+    pub fn with_playout_depth(mut self, playout_depth: u32) -> Self {
+        self.playout_depth = playout_depth;
+        self
+    }
+
+    /// Uses `reward` to convert the fitness at the end of a playout to a reward, which should be in `[0, 1]`.
+    pub fn with_reward(mut self, reward: fn(T::Fitness) -> f64) -> Self {
+        self.reward = Some(reward);
+        self
+    }
+
+    /// Uses `policy` to guide the search towards actions with a high prior.
+    ///
+    /// The priors of each node are normalized to sum to `1`. Untried actions are then expanded in the order
+    /// of their priors instead of randomly, and children are selected using the [PUCT][puct] rule, which replaces
+    /// the exploration term of UCT with `exploration * prior * sqrt(parent visits) / (1 + visits)`.
+    /// A good policy lets the search concentrate on a few plausible actions, which is especially useful for
+    /// games with a lot of possible actions.
+    ///
+    /// ```rust
+    /// use rubot::{Policy, Steps, mcts, tree::Node};
+    ///
+    /// /// Prefers the last action.
+    /// struct Last;
+    ///
+    /// impl Policy<Node> for Last {
+    ///     fn priors(&self, _: &Node, actions: &[usize], _: bool, priors: &mut Vec<f64>) {
+    ///         priors.extend(actions.iter().map(|&a| if a + 1 == actions.len() { 1.0 } else { 0.0 }));
+    ///     }
+    /// }
+    ///
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 3),
+    ///     Node::new(false, 5),
+    ///     Node::new(false, 4),
+    /// ]);
+    ///
+    /// let mut bot = mcts::Bot::new(true).with_policy(Last);
+    /// // The action with the highest prior is expanded first.
+    /// assert_eq!(bot.select(&tree, Steps(2)), Some(2));
+    /// ```
+    ///
+    /// [puct]: https://en.wikipedia.org/wiki/Monte_Carlo_tree_search#Exploration_and_exploitation
+    pub fn with_policy(mut self, policy: impl Policy<T> + 'static) -> Self {
+        self.policy = Some(Box::new(policy));
+        self
+    }
+
+    /// Uses progressive widening, which limits the amount of children of each node to
+    /// `ceil(constant * visits^exponent)`, but always at least one.
+    ///
+    /// By default, all actions of a node are expanded before any of its children is searched further,
+    /// which degenerates to sampling each action of the initial state once for games with hundreds of actions.
+    /// With progressive widening, nodes only expand new actions once their existing children were visited often
+    /// enough, so the search gets deeper instead. Together with [`with_policy`][policy], actions with a high prior
+    /// are expanded first. Common values are a `constant` of `1.0` and an `exponent` between `0.25` and `0.5`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `constant` is not positive or `exponent` is not in `[0, 1]`.
+    ///
+    /// ```rust
+    /// use rubot::{Steps, mcts, tree::Node};
+    ///
+    /// let mut tree = Node::root();
+    /// for i in 0..300 {
+    ///     tree.push_child(Node::new(false, 0).with_children(&[
+    ///         Node::new(true, 2),
+    ///         Node::new(true, if i == 7 { 9 } else { 1 }),
+    ///     ]));
+    /// }
+    ///
+    /// let mut bot = mcts::Bot::new(true)
+    ///     .with_reward(|fitness| f64::from(fitness) / 10.0)
+    ///     .with_progressive_widening(1.0, 0.5);
+    /// // Only the action `7` is good against the best reply.
+    /// assert_eq!(bot.select(&tree, Steps(2000)), Some(7));
+    /// ```
+    ///
+    /// [policy]: #method.with_policy
+    pub fn with_progressive_widening(mut self, constant: f64, exponent: f64) -> Self {
+        assert!(constant > 0.0, "the widening constant must be positive");
+        assert!(
+            (0.0..=1.0).contains(&exponent),
+            "the widening exponent must be in `[0, 1]`"
+        );
+        self.widening = Some((constant, exponent));
+        self
+    }
+
+    /// Limits the search tree to at most `nodes` nodes, which bounds the memory used by the search.
+    ///
+    /// Once the tree is full, the search stops and returns the action with the most playouts, in the same way
+    /// as if it was stopped by the run condition. A tree kept by [`with_tree_reuse`][reuse] also counts towards
+    /// this limit, so [`advance`][advance] frees the nodes which are not part of the new subtree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nodes` is zero, as the tree always contains the initial state.
+    ///
+    /// ```rust
+    /// use rubot::{ToCompletion, mcts, tree::Node};
+    ///
+    /// let tree = Node::root().with_children(&[
+    ///     Node::new(false, 3).with_children(&[
+    ///         Node::new(true, 1),
+    ///         Node::new(true, 2),
+    ///     ]),
+    ///     Node::new(false, 5).with_children(&[
+    ///         Node::new(true, 6),
+    ///         Node::new(true, 8),
+    ///     ]),
+    /// ]);
+    ///
+    /// // Only the initial state and its children fit into the tree.
+    /// let mut bot = mcts::Bot::new(true)
+    ///     .with_reward(|fitness| f64::from(fitness) / 10.0)
+    ///     .with_max_nodes(3);
+    /// assert_eq!(bot.select(&tree, ToCompletion), Some(1));
+    /// ```
+    ///
+    /// [reuse]: #method.with_tree_reuse
+    /// [advance]: #method.advance
+    pub fn with_max_nodes(mut self, nodes: usize) -> Self {
+        assert!(nodes > 0, "the search tree must contain at least one node");
+        self.max_nodes = Some(nodes);
+        self
+    }
+
+    /// Sets the seed used to choose random actions during playouts, results are reproducible when
+    /// using the same seed and a deterministic run condition like [`Steps`][steps].
+    ///
+    /// [steps]: ../struct.Steps.html
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = XorShiftRng::from_seed_u64(seed);
+        self
+    }
+
+    /// Keeps the search tree after each call to `select`, so the playouts of the actions
+    /// which are actually played are reused by the following searches.
+    ///
+    /// Every executed action, both the ones of the bot and of its opponents, must be passed to
+    /// [`advance`][advance], which keeps the subtree of this action. Use [`clear_tree`][clear]
+    /// in case the next state is not reached using these actions, e.g. when starting a new game.
+    ///
+    /// ```rust
+    /// use rubot::{Game, Steps, ToCompletion, mcts, tree::Node};
+    ///
+    /// # #[rustfmt::skip]
+    /// let mut tree = Node::root().with_children(&[
+    ///     Node::new(false, 4).with_children(&[
+    ///         Node::new(true, 2).with_children(&[
+    ///             Node::new(false, 1),
+    ///             Node::new(false, 3),
+    ///         ]),
+    ///     ]),
+    ///     Node::new(false, 2),
+    /// ]);
+    ///
+    /// let mut bot = mcts::Bot::new(true).with_tree_reuse();
+    /// let action = bot.select(&tree, ToCompletion).unwrap();
+    /// assert_eq!(action, 0);
+    /// tree.execute(&action, true);
+    /// bot.advance(&action);
+    /// tree.execute(&0, false);
+    /// bot.advance(&0);
+    ///
+    /// // The subtree of the current state was already completely explored.
+    /// assert_eq!(bot.select(&tree, Steps(0)), Some(1));
+    /// ```
+    ///
+    /// [advance]: #method.advance
+    /// [clear]: #method.clear_tree
+    pub fn with_tree_reuse(mut self) -> Self {
+        self.reuse_tree = true;
+        self
+    }
+
+    /// Keeps only the subtree reached by executing `action` in the current root of the search tree,
+    /// which is discarded in case `action` was not yet searched. Does nothing if the tree is not kept.
+    ///
+    /// See [`with_tree_reuse`][reuse] for more details.
+    ///
+    /// [reuse]: #method.with_tree_reuse
+    pub fn advance(&mut self, action: &T::Action)
+    where
+        T::Action: PartialEq,
+    {
+        let mut tree = match self.tree.take() {
+            Some(tree) => tree,
+            None => return,
+        };
+        let child = tree[0]
+            .children
+            .iter()
+            .copied()
+            .find(|&child| tree[child].action.as_ref() == Some(action));
+        if let Some(child) = child {
+            tree[child].action = None;
+            self.tree = Some(Self::subtree(tree, child));
+        }
+    }
+
+    /// Discards the search tree kept by [`with_tree_reuse`][reuse].
+    ///
+    /// [reuse]: #method.with_tree_reuse
+    pub fn clear_tree(&mut self) {
+        self.tree = None;
+    }
+
+    /// Returns the action with the most playouts, or `None` if no action is possible or the bot is
+    /// currently not the active player.
+    ///
+    /// `RunCondition::step` is called before each playout and `RunCondition::depth` each time the search tree
+    /// grows deeper, with the new depth of the tree. Once the complete game tree was explored, the search stops
+    /// and the action with the best fitness is returned.
+    pub fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action> {
+        let mut condition = condition.into_run_condition();
+
+        let (active, actions) = state.actions(self.player);
+        if !active {
+            return None;
+        }
+
+        let actions: Vec<_> = actions.into_iter().collect();
+        if actions.is_empty() {
+            return None;
+        }
+
+        let mut tree = match self.tree.take() {
+            // The bot must be active at a kept root, which is not the case if an action was not advanced.
+            Some(tree) if tree[0].active => tree,
+            _ => {
+                // The fitness of the root is never used.
+                let fitness = state.look_ahead(&actions[0], self.player);
+                let (untried, priors) = self.priors(state, actions);
+                vec![Node {
+                    action: None,
+                    fitness,
+                    passed: false,
+                    active,
+                    untried,
+                    priors,
+                    prior: 1.0,
+                    children: Vec::new(),
+                    visits: 0,
+                    reward: 0.0,
+                    exhausted: false,
+                }]
+            }
+        };
+
+        let mut tree_depth = 0;
+        let mut path = Vec::new();
+        while !tree[0].exhausted
+            && self.max_nodes.is_none_or(|max| tree.len() < max)
+            && condition.step()
+        {
+            // Select a node which still has untried actions.
+            let mut game_state = state.clone();
+            path.clear();
+            path.push(0);
+            let mut idx = 0;
+            while !self.expandable(&tree, idx) {
+                idx = self.uct(&tree, idx);
+                let node = &tree[idx];
+                game_state.execute(node.action.as_ref().unwrap(), self.player);
+                if node.passed {
+                    game_state.pass(self.player);
+                }
+                path.push(idx);
+            }
+
+            if path.len() > tree_depth {
+                tree_depth = path.len();
+                if !condition.depth(tree_depth as u32) {
+                    break;
+                }
+            }
+
+            // Expand the untried action with the highest prior, or a random one without a policy.
+            let node = &mut tree[idx];
+            let (action, prior) = match node.priors.pop() {
+                Some(prior) => (node.untried.pop().unwrap(), prior),
+                None => {
+                    let random = self.rng.below(node.untried.len());
+                    (node.untried.swap_remove(random), 1.0)
+                }
+            };
+            let fitness = game_state.execute(&action, self.player);
+            let (passed, active, actions) = self.actions(&mut game_state);
+            let (untried, priors) = self.priors(&game_state, actions);
+            let child = tree.len();
+            tree[idx].children.push(child);
+            tree.push(Node {
+                action: Some(action),
+                fitness,
+                passed,
+                active,
+                exhausted: untried.is_empty(),
+                untried,
+                priors,
+                prior,
+                children: Vec::new(),
+                visits: 0,
+                reward: 0.0,
+            });
+            path.push(child);
+
+            let reward = match self.playout(&mut game_state, fitness, &mut condition) {
+                Some(reward) => reward,
+                None => break,
+            };
+
+            for &idx in path.iter().rev() {
+                let exhausted = tree[idx].untried.is_empty()
+                    && tree[idx]
+                        .children
+                        .iter()
+                        .all(|&child| tree[child].exhausted);
+                let node = &mut tree[idx];
+                node.visits += 1;
+                node.reward += reward;
+                node.exhausted = exhausted;
+            }
+        }
+
+        let selected = if tree[0].exhausted {
+            Self::best_fitness(&tree)
+        } else {
+            Self::most_visited(&tree)
+        };
+        if self.reuse_tree {
+            self.tree = Some(tree);
+        }
+        selected
+    }
+
+    /// Returns the subtree starting at `root`, keeping children at larger indices than their parent.
+    fn subtree(tree: Vec<Node<T>>, root: usize) -> Vec<Node<T>> {
+        let mut nodes: Vec<_> = tree.into_iter().map(Some).collect();
+        let mut subtree = vec![nodes[root].take().expect("node visited twice")];
+        let mut idx = 0;
+        while idx < subtree.len() {
+            let children = mem::take(&mut subtree[idx].children);
+            for child in children {
+                let new = subtree.len();
+                subtree[idx].children.push(new);
+                subtree.push(nodes[child].take().expect("node visited twice"));
+            }
+            idx += 1;
+        }
+        subtree
+    }
+
+    /// Returns whether the opponent passed, whether the bot is active and all possible actions.
+    fn actions(&self, game_state: &mut T) -> (bool, bool, Vec<T::Action>) {
+        let (active, actions) = game_state.actions(self.player);
+        let actions: Vec<_> = actions.into_iter().collect();
+        if !active && actions.is_empty() && game_state.pass(self.player) {
+            let (active, actions) = game_state.actions(self.player);
+            (true, active, actions.into_iter().collect())
+        } else {
+            (false, active, actions)
+        }
+    }
+
+    /// Returns whether an untried action of `idx` should be expanded instead of selecting one of its children.
+    fn expandable(&self, tree: &[Node<T>], idx: usize) -> bool {
+        let node = &tree[idx];
+        if node.untried.is_empty() {
+            return false;
+        }
+
+        match self.widening {
+            Some((constant, exponent)) => {
+                let limit = (constant * (node.visits as f64).powf(exponent))
+                    .ceil()
+                    .max(1.0);
+                // Nodes whose children are all exhausted must expand, as there is nothing left to select.
+                (node.children.len() as f64) < limit
+                    || node.children.iter().all(|&child| tree[child].exhausted)
+            }
+            None => true,
+        }
+    }
+
+    /// Returns `actions` sorted by their normalized priors in ascending order, together with these priors.
+    ///
+    /// Without a policy, `actions` are returned unchanged without any priors.
+    fn priors(&self, game_state: &T, actions: Vec<T::Action>) -> (Vec<T::Action>, Vec<f64>) {
+        let policy = match self.policy {
+            Some(ref policy) if !actions.is_empty() => policy,
+            _ => return (actions, Vec::new()),
+        };
+
+        let mut priors = Vec::with_capacity(actions.len());
+        policy.priors(game_state, &actions, self.player, &mut priors);
+        assert_eq!(
+            priors.len(),
+            actions.len(),
+            "expected one prior for each action"
+        );
+        let total: f64 = priors.iter().map(|&prior| prior.max(0.0)).sum();
+        let uniform = 1.0 / actions.len() as f64;
+        let mut actions: Vec<_> = actions
+            .into_iter()
+            .zip(priors)
+            .map(|(action, prior)| {
+                if total > 0.0 {
+                    (action, prior.max(0.0) / total)
+                } else {
+                    (action, uniform)
+                }
+            })
+            .collect();
+        // Stable, so actions with the same prior are expanded in their original order.
+        actions.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+        actions.reverse();
+        actions.into_iter().unzip()
+    }
+
+    /// Returns the child of `idx` with the highest upper confidence bound, ignoring exhausted children.
+    ///
+    /// With a policy, the PUCT rule is used instead of UCT.
+    fn uct(&self, tree: &[Node<T>], idx: usize) -> usize {
+        let node = &tree[idx];
+        let ln_visits = (node.visits as f64).ln();
+        let sqrt_visits = (node.visits as f64).sqrt();
+        let score = |child: &Node<T>| {
+            let mean = child.reward / child.visits as f64;
+            let mean = if node.active { mean } else { 1.0 - mean };
+            if self.policy.is_some() {
+                mean + self.exploration * child.prior * sqrt_visits / (1 + child.visits) as f64
+            } else {
+                mean + self.exploration * (ln_visits / child.visits as f64).sqrt()
+            }
+        };
+
+        node.children
+            .iter()
+            .copied()
+            .filter(|&child| !tree[child].exhausted)
+            .max_by(|&a, &b| {
+                score(&tree[a])
+                    .partial_cmp(&score(&tree[b]))
+                    .unwrap_or(Ordering::Equal)
+            })
+            .expect("selected exhausted node")
+    }
+
+    /// Executes random actions starting at `game_state`, returning the reward of the final fitness
+    /// or `None` if the search was cancelled.
+    ///
+    /// Instead of executing a random last action, all actions of the last state are evaluated using
+    /// `Game::look_ahead_batch` and the average of their rewards is returned.
+    fn playout<U: RunCondition>(
+        &mut self,
+        game_state: &mut T,
+        mut fitness: T::Fitness,
+        condition: &mut U,
+    ) -> Option<f64> {
+        for depth in 1..=self.playout_depth {
+            let (_, _, actions) = self.actions(game_state);
+            if actions.is_empty() {
+                break;
+            }
+
+            if !condition.check() {
+                return None;
+            }
+            if depth == self.playout_depth {
+                let mut leaves = Vec::with_capacity(actions.len());
+                game_state.look_ahead_batch(&actions, self.player, &mut leaves);
+                let total: f64 = leaves
+                    .into_iter()
+                    .map(|fitness| self.reward(game_state, fitness))
+                    .sum();
+                return Some(total / actions.len() as f64);
+            }
+            let action = &actions[self.rng.below(actions.len())];
+            fitness = game_state.execute(action, self.player);
+        }
+
+        Some(self.reward(game_state, fitness))
+    }
+
+    /// Converts the `fitness` of a playout ending at `game_state` to a reward.
+    fn reward(&self, game_state: &T, fitness: T::Fitness) -> f64 {
+        match self.reward {
+            Some(reward) => reward(fitness),
+            None if game_state.is_upper_bound(fitness, self.player) => 1.0,
+            None if game_state.is_lower_bound(fitness, self.player) => 0.0,
+            None => 0.5,
+        }
+    }
+
+    /// Returns the best action by computing the minimax value of the completely explored tree.
+    fn best_fitness(tree: &[Node<T>]) -> Option<T::Action> {
+        // Children have a larger index than their parent, so their value is always computed first.
+        let mut values: Vec<_> = tree.iter().map(|node| node.fitness).collect();
+        for (idx, node) in tree.iter().enumerate().rev() {
+            let children = node.children.iter().map(|&child| values[child]);
+            let value = if node.active {
+                children.max()
+            } else {
+                children.min()
+            };
+            if let Some(value) = value {
+                values[idx] = value;
+            }
+        }
+
+        let mut best: Option<usize> = None;
+        for &child in &tree[0].children {
+            if best.is_none_or(|best| values[child] > values[best]) {
+                best = Some(child);
+            }
+        }
+        best.and_then(|best| tree[best].action.clone())
+    }
+
+    /// Returns the action with the most playouts, preferring a higher average reward in case of ties.
+    fn most_visited(tree: &[Node<T>]) -> Option<T::Action> {
+        let mean = |node: &Node<T>| node.reward / node.visits as f64;
+        let best = tree[0].children.iter().copied().max_by(|&a, &b| {
+            let (a, b) = (&tree[a], &tree[b]);
+            a.visits
+                .cmp(&b.visits)
+                .then_with(|| mean(a).partial_cmp(&mean(b)).unwrap_or(Ordering::Equal))
+        });
+
+        match best {
+            Some(best) => tree[best].action.clone(),
+            // The search was cancelled before the first action was expanded,
+            // with a policy the untried action with the highest prior is the last one.
+            None if tree[0].priors.is_empty() => tree[0].untried.first().cloned(),
+            None => tree[0].untried.last().cloned(),
+        }
+    }
+}
+
+impl<T: Game> GameBot<T> for Bot<T> {
+    fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action> {
+        Bot::select(self, state, condition)
+    }
+}
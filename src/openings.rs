@@ -0,0 +1,233 @@
+//! An opening book, which selects well known actions without searching.
+use crate::rng::XorShiftRng;
+use crate::{Game, GameBot, IntoRunCondition};
+
+use std::collections::HashMap;
+
+/// A table mapping states to preferred actions, each with a weight.
+///
+/// States are identified using a hash, so it is the responsibility of the user to use a hash with
+/// few collisions. Actions which are not possible in the given state are never selected, so a
+/// collision only results in the book not being used for this state.
+///
+/// Books are usually built from recorded games using [`add_game`][add], where each action is given
+/// a weight of `1` each time it was played in a given state.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{Bot, ToCompletion, openings::{Book, WithBook}, tree::Node};
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::{Hash, Hasher};
+///
+/// fn hash(node: &Node) -> u64 {
+///     let mut hasher = DefaultHasher::new();
+///     node.hash(&mut hasher);
+///     hasher.finish()
+/// }
+///
+/// # #[rustfmt::skip]
+/// let tree = Node::root().with_children(&[
+///     Node::new(false, 4).with_children(&[
+///         Node::new(true, 2),
+///     ]),
+///     Node::new(false, 7).with_children(&[
+///         Node::new(true, 5),
+///         Node::new(true, 3),
+///     ]),
+/// ]);
+///
+/// // Both recorded games start with the action `0`.
+/// let book = Book::new(hash)
+///     .with_game(&tree, vec![(true, 0), (false, 0)])
+///     .with_game(&tree, vec![(true, 0)]);
+/// assert_eq!(book.actions(&tree), &[(0, 2)]);
+///
+/// let mut bot = WithBook::new(true, book, Bot::new(true));
+/// // The book overrides the search, which would select `1`.
+/// assert_eq!(bot.select(&tree, ToCompletion), Some(0));
+/// ```
+///
+/// [add]: #method.add_game
+pub struct Book<T: Game> {
+    hash: fn(&T) -> u64,
+    entries: HashMap<u64, Vec<(T::Action, u32)>>,
+    max_plies: Option<usize>,
+    rng: XorShiftRng,
+}
+
+impl<T: Game> Book<T>
+where
+    T::Action: PartialEq,
+{
+    /// Creates an empty book, identifying states using `hash`.
+    pub fn new(hash: fn(&T) -> u64) -> Self {
+        Self {
+            hash,
+            entries: HashMap::new(),
+            max_plies: None,
+            rng: XorShiftRng::from_seed_u64(0),
+        }
+    }
+
+    /// Only records the first `max_plies` actions of games added using [`add_game`][add].
+    ///
+    /// Recorded games usually leave known theory after some actions, and adding
+    /// their remaining actions wastes memory without being useful.
+    ///
+    /// [add]: #method.add_game
+    pub fn with_max_plies(mut self, max_plies: usize) -> Self {
+        self.max_plies = Some(max_plies);
+        self
+    }
+
+    /// Sets the seed used to choose between multiple actions of the same state.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = XorShiftRng::from_seed_u64(seed);
+        self
+    }
+
+    /// Adds a recorded game using [`add_game`][add].
+    ///
+    /// [add]: #method.add_game
+    pub fn with_game<I>(mut self, start: &T, game: I) -> Self
+    where
+        I: IntoIterator<Item = (T::Player, T::Action)>,
+    {
+        self.add_game(start, game);
+        self
+    }
+
+    /// Adds a game starting at `start`, consisting of each action together with the player who executed it.
+    ///
+    /// The weight of each action in the state it was played in is increased by `1`.
+    pub fn add_game<I>(&mut self, start: &T, game: I)
+    where
+        I: IntoIterator<Item = (T::Player, T::Action)>,
+    {
+        let mut state = start.clone();
+        let max_plies = self.max_plies.unwrap_or(usize::MAX);
+        for (player, action) in game.into_iter().take(max_plies) {
+            self.insert(&state, action.clone(), 1);
+            state.execute(&action, player);
+        }
+    }
+
+    /// Increases the weight of `action` in `state` by `weight`.
+    pub fn insert(&mut self, state: &T, action: T::Action, weight: u32) {
+        let actions = self.entries.entry((self.hash)(state)).or_default();
+        match actions.iter_mut().find(|(known, _)| *known == action) {
+            Some((_, known)) => *known = known.saturating_add(weight),
+            None => actions.push((action, weight)),
+        }
+    }
+
+    /// Returns all actions stored for `state` together with their weights.
+    pub fn actions(&self, state: &T) -> &[(T::Action, u32)] {
+        self.entries
+            .get(&(self.hash)(state))
+            .map_or(&[], |actions| actions)
+    }
+
+    /// Returns the amount of states with stored actions.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the book does not contain any actions.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns a random action stored for `state`, where each action is chosen with a probability
+    /// proportional to its weight.
+    ///
+    /// Returns `None` if `player` is not active or the book does not contain a possible
+    /// action with a non zero weight.
+    pub fn select(&mut self, state: &T, player: T::Player) -> Option<T::Action> {
+        let (active, possible) = state.actions(player);
+        if !active {
+            return None;
+        }
+
+        let random = self.rng.next_u64();
+        let possible: Vec<_> = possible.into_iter().collect();
+        let actions: Vec<_> = self
+            .actions(state)
+            .iter()
+            .filter(|&(action, weight)| *weight > 0 && possible.contains(action))
+            .collect();
+        let total: u64 = actions.iter().map(|&(_, weight)| u64::from(*weight)).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut remaining = random % total;
+        for (action, weight) in actions {
+            match remaining.checked_sub(u64::from(*weight)) {
+                Some(now) => remaining = now,
+                None => return Some(action.clone()),
+            }
+        }
+        unreachable!("weights changed during selection")
+    }
+}
+
+/// A [`GameBot`][bot] which first consults an opening [`Book`][book] and only searches
+/// using the wrapped bot in case the book does not contain the current state.
+///
+/// [bot]: ../trait.GameBot.html
+/// [book]: struct.Book.html
+pub struct WithBook<T: Game, B> {
+    player: T::Player,
+    book: Book<T>,
+    bot: B,
+}
+
+impl<T: Game, B: GameBot<T>> WithBook<T, B>
+where
+    T::Action: PartialEq,
+{
+    /// Uses `book` to select actions for `player` before searching with `bot`,
+    /// which should select actions for the same player.
+    pub fn new(player: T::Player, book: Book<T>, bot: B) -> Self {
+        Self { player, book, bot }
+    }
+
+    /// Returns the opening book.
+    pub fn book(&self) -> &Book<T> {
+        &self.book
+    }
+
+    /// Returns a mutable reference to the opening book, which allows adding games while playing.
+    pub fn book_mut(&mut self) -> &mut Book<T> {
+        &mut self.book
+    }
+
+    /// Returns the wrapped bot.
+    pub fn bot(&self) -> &B {
+        &self.bot
+    }
+
+    /// Returns a mutable reference to the wrapped bot.
+    pub fn bot_mut(&mut self) -> &mut B {
+        &mut self.bot
+    }
+
+    /// Returns a random action from the book, or the action selected by the wrapped bot
+    /// in case the book does not contain a possible action for `state`.
+    pub fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action> {
+        self.book
+            .select(state, self.player)
+            .or_else(|| self.bot.select(state, condition))
+    }
+}
+
+impl<T: Game, B: GameBot<T>> GameBot<T> for WithBook<T, B>
+where
+    T::Action: PartialEq,
+{
+    fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action> {
+        WithBook::select(self, state, condition)
+    }
+}
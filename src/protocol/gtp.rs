@@ -0,0 +1,288 @@
+//! An engine speaking the Go Text Protocol, which allows using [`alpha_beta::Bot`][ab]
+//! with frontends and test harnesses written for Go programs.
+//!
+//! The protocol only describes colors and moves as text, the conversion to and from the
+//! types of a [`Game`][game] is done using a user provided [`Codec`][codec].
+//!
+//! # Supported commands
+//!
+//! - `protocol_version`, `name`, `version`, `known_command`, `list_commands` and `quit`.
+//! - `boardsize <size>` and `clear_board`, which use [`Codec::new_game`][new].
+//! - `komi <komi>`, which is accepted but ignored.
+//! - `play <color> <move>`, `genmove <color>` and `undo`.
+//!
+//! [ab]: ../../alpha_beta/struct.Bot.html
+//! [game]: ../../trait.Game.html
+//! [codec]: trait.Codec.html
+//! [new]: trait.Codec.html#tymethod.new_game
+use crate::alpha_beta::Bot;
+use crate::Game;
+
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+/// All commands understood by [`Engine`][engine], in the order of `list_commands`.
+///
+/// [engine]: struct.Engine.html
+const COMMANDS: &[&str] = &[
+    "protocol_version",
+    "name",
+    "version",
+    "known_command",
+    "list_commands",
+    "quit",
+    "boardsize",
+    "clear_board",
+    "komi",
+    "play",
+    "genmove",
+    "undo",
+];
+
+/// Converts between the textual representation used by the protocol and the types of a game.
+pub trait Codec<T: Game> {
+    /// Returns the initial state of a game on a board of the given size, or the default size if `size` is `None`.
+    ///
+    /// Returns `None` if the size is not supported.
+    fn new_game(&self, size: Option<u32>) -> Option<T>;
+
+    /// Parses a color like `black`, `b`, `white` or `w`, returning `None` if it is invalid.
+    fn parse_color(&self, color: &str) -> Option<T::Player>;
+
+    /// Parses a move of `player` in `state`, like `D4` or `pass`, returning `None` if it is invalid.
+    fn parse_move(&self, state: &T, player: T::Player, vertex: &str) -> Option<T::Action>;
+
+    /// Formats a move of `player` in `state`.
+    fn format_move(&self, state: &T, player: T::Player, action: &T::Action) -> String;
+}
+
+/// A Go engine using the Go Text Protocol, see the [module level documentation][module].
+///
+/// Moves are checked against `Game::actions` before they are executed.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{Bot, Game, protocol::gtp::{Codec, Engine}, tree::Node};
+///
+/// /// Moves are the index of the chosen child, only boards of size `2` are supported.
+/// struct TreeCodec;
+///
+/// impl Codec<Node> for TreeCodec {
+///     fn new_game(&self, size: Option<u32>) -> Option<Node> {
+///         # #[rustfmt::skip]
+///         let tree = Node::root().with_children(&[
+///             Node::new(false, 7).with_children(&[
+///                 Node::new(true, 4),
+///                 Node::new(true, 2),
+///             ]),
+///             Node::new(false, 5).with_children(&[
+///                 Node::new(true, 8),
+///                 Node::new(true, 9),
+///             ]),
+///         ]);
+///         match size {
+///             None | Some(2) => Some(tree),
+///             Some(_) => None,
+///         }
+///     }
+///
+///     fn parse_color(&self, color: &str) -> Option<bool> {
+///         match color {
+///             "b" | "black" => Some(true),
+///             "w" | "white" => Some(false),
+///             _ => None,
+///         }
+///     }
+///
+///     fn parse_move(&self, _: &Node, _: bool, vertex: &str) -> Option<usize> {
+///         vertex.parse().ok()
+///     }
+///
+///     fn format_move(&self, _: &Node, _: bool, action: &usize) -> String {
+///         action.to_string()
+///     }
+/// }
+///
+/// let mut engine = Engine::new(TreeCodec, Bot::new(true)).with_name("tree");
+/// let input = "1 name\n2 boardsize 19\n3 play b 7\n4 play b 1\n5 genmove w\nquit\n";
+/// let mut output = Vec::new();
+/// engine.run(input.as_bytes(), &mut output).unwrap();
+/// assert_eq!(
+///     String::from_utf8(output).unwrap(),
+///     "=1 tree\n\n?2 unacceptable size\n\n?3 illegal move\n\n=4\n\n=5 1\n\n=\n\n"
+/// );
+/// ```
+///
+/// [module]: index.html
+pub struct Engine<T: Game, C> {
+    name: String,
+    codec: C,
+    bot: Bot<T>,
+    move_time: Duration,
+    size: Option<u32>,
+    state: T,
+    /// The states before each move, used by `undo`.
+    history: Vec<T>,
+}
+
+impl<T: Game, C: Codec<T>> Engine<T, C>
+where
+    T::Action: PartialEq,
+{
+    /// Creates a new engine using `codec` which selects its moves using `bot`.
+    ///
+    /// The player of `bot` is changed to the color given to `genmove`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `codec` does not support the default size.
+    pub fn new(codec: C, bot: Bot<T>) -> Self {
+        let state = codec
+            .new_game(None)
+            .expect("the default size must be supported");
+        Self {
+            name: String::from("rubot"),
+            codec,
+            bot,
+            move_time: Duration::from_secs(1),
+            size: None,
+            state,
+            history: Vec::new(),
+        }
+    }
+
+    /// Sets the name sent in response to `name`.
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// Sets the time used to select each move of `genmove`, which is one second by default.
+    pub fn with_move_time(mut self, move_time: Duration) -> Self {
+        self.move_time = move_time;
+        self
+    }
+
+    /// Returns the current state.
+    pub fn state(&self) -> &T {
+        &self.state
+    }
+
+    /// Returns a mutable reference to the bot, which allows changing its options between moves.
+    pub fn bot_mut(&mut self) -> &mut Bot<T> {
+        &mut self.bot
+    }
+
+    /// Processes the commands of `input` until `quit` or the end of `input`, writing all responses to `output`.
+    ///
+    /// Use `std::io::stdin().lock()` and `std::io::stdout()` to communicate with a frontend.
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, mut output: W) -> io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            // Everything after `#` is a comment.
+            let line = line.split('#').next().unwrap_or_default();
+            let mut words = line.split_whitespace().peekable();
+            let id = match words.peek() {
+                Some(word) if word.bytes().all(|byte| byte.is_ascii_digit()) => words.next(),
+                Some(_) => None,
+                None => continue,
+            };
+            let command = match words.next() {
+                Some(command) => command,
+                None => continue,
+            };
+            let arguments: Vec<_> = words.collect();
+
+            let (success, response) = match self.command(command, &arguments) {
+                Ok(response) => ('=', response),
+                Err(error) => ('?', error.to_string()),
+            };
+            let separator = if response.is_empty() { "" } else { " " };
+            write!(
+                output,
+                "{}{}{}{}\n\n",
+                success,
+                id.unwrap_or_default(),
+                separator,
+                response
+            )?;
+            output.flush()?;
+            if command == "quit" {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Executes a single command, returning its response or an error message.
+    fn command(&mut self, command: &str, arguments: &[&str]) -> Result<String, &'static str> {
+        match (command, arguments) {
+            ("protocol_version", []) => Ok(String::from("2")),
+            ("name", []) => Ok(self.name.clone()),
+            ("version", []) => Ok(String::from(env!("CARGO_PKG_VERSION"))),
+            ("known_command", [command]) => Ok(COMMANDS.contains(command).to_string()),
+            ("list_commands", []) => Ok(COMMANDS.join("\n")),
+            ("quit", []) => Ok(String::new()),
+            ("boardsize", [size]) => {
+                let size = size.parse().map_err(|_| "syntax error")?;
+                self.state = self.codec.new_game(Some(size)).ok_or("unacceptable size")?;
+                self.size = Some(size);
+                self.history.clear();
+                Ok(String::new())
+            }
+            ("clear_board", []) => {
+                self.state = self
+                    .codec
+                    .new_game(self.size)
+                    .expect("the current size must be supported");
+                self.history.clear();
+                Ok(String::new())
+            }
+            ("komi", [komi]) => komi
+                .parse::<f64>()
+                .map(|_| String::new())
+                .map_err(|_| "syntax error"),
+            ("play", [color, vertex]) => {
+                let player = self.codec.parse_color(color).ok_or("syntax error")?;
+                let action = self
+                    .codec
+                    .parse_move(&self.state, player, vertex)
+                    .filter(|action| {
+                        self.state
+                            .actions(player)
+                            .1
+                            .into_iter()
+                            .any(|a| a == *action)
+                    })
+                    .ok_or("illegal move")?;
+                self.execute(&action, player);
+                Ok(String::new())
+            }
+            ("genmove", [color]) => {
+                let player = self.codec.parse_color(color).ok_or("syntax error")?;
+                self.bot.set_player(player);
+                match self.bot.select(&self.state, self.move_time) {
+                    Some(action) => {
+                        let vertex = self.codec.format_move(&self.state, player, &action);
+                        self.execute(&action, player);
+                        Ok(vertex)
+                    }
+                    // The bot is not active or does not have any possible actions.
+                    None => Ok(String::from("pass")),
+                }
+            }
+            ("undo", []) => {
+                self.state = self.history.pop().ok_or("cannot undo")?;
+                Ok(String::new())
+            }
+            _ if COMMANDS.contains(&command) => Err("syntax error"),
+            _ => Err("unknown command"),
+        }
+    }
+
+    fn execute(&mut self, action: &T::Action, player: T::Player) {
+        self.history.push(self.state.clone());
+        self.state.execute(action, player);
+    }
+}
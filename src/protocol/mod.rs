@@ -0,0 +1,5 @@
+//! Adapters which drive bots using text protocols understood by existing frontends.
+//!
+//! Only available with the `protocol` feature.
+pub mod gtp;
+pub mod uci;
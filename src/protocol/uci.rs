@@ -0,0 +1,377 @@
+//! An engine speaking the Universal Chess Interface, which allows using [`alpha_beta::Bot`][ab]
+//! with graphical user interfaces and tools written for chess engines.
+//!
+//! The protocol only describes positions and actions as text, the conversion to and from the
+//! types of a [`Game`][game] is done using a user provided [`Codec`][codec].
+//!
+//! # Supported commands
+//!
+//! - `uci`, `isready`, `ucinewgame` and `quit`.
+//! - `position startpos [moves ...]` and `position fen <position> [moves ...]`,
+//!   where everything between `fen` and `moves` is passed to [`Codec::parse_position`][parse].
+//! - `go` with the optional arguments `depth`, `nodes`, `movetime`, `infinite` and the clock of a timed game,
+//!   `wtime`, `btime`, `winc`, `binc` and `movestogo`, which is split between moves using [`GameClock`][clock].
+//!   An `info` line is sent after each completed iteration, followed by `bestmove` once the search stopped.
+//! - `stop`, which stops the current search.
+//!
+//! Unknown commands and arguments are ignored. Commands other than `stop` and `quit` are only processed
+//! after the current search stopped, so `isready` is not answered while searching.
+//!
+//! [ab]: ../../alpha_beta/struct.Bot.html
+//! [game]: ../../trait.Game.html
+//! [codec]: trait.Codec.html
+//! [parse]: trait.Codec.html#tymethod.parse_position
+//! [clock]: ../../struct.GameClock.html
+use crate::alpha_beta::Bot;
+use crate::{Game, GameClock, RunCondition, StopFlag};
+
+use std::io::{self, BufRead, Write};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The amount of remaining moves assumed for a timed game in case `go` does not contain `movestogo`.
+const EXPECTED_MOVES: u32 = 30;
+
+/// Converts between the textual representation used by the protocol and the types of a game.
+pub trait Codec<T: Game> {
+    /// Returns the state used for `position startpos`.
+    fn start_position(&self) -> T;
+
+    /// Parses the position of `position fen <position>`, returning `None` if it is invalid.
+    fn parse_position(&self, position: &str) -> Option<T>;
+
+    /// Returns the player who is active in `state`.
+    fn active_player(&self, state: &T) -> T::Player;
+
+    /// Returns `true` if `player` is white, whose remaining time is sent as `wtime` and `winc`.
+    fn is_white(&self, player: T::Player) -> bool;
+
+    /// Parses an action of the active player in `state`, returning `None` if it is invalid.
+    fn parse_action(&self, state: &T, action: &str) -> Option<T::Action>;
+
+    /// Formats an action of the active player in `state`, for example `e2e4`.
+    fn format_action(&self, state: &T, action: &T::Action) -> String;
+
+    /// Formats the score of an `info` line, which should either be `cp <centipawns>` or `mate <moves>`.
+    fn format_score(&self, fitness: T::Fitness) -> String;
+}
+
+/// A chess engine using the Universal Chess Interface, see the [module level documentation][module].
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{Bot, Game, protocol::uci::{Codec, Engine}, tree::Node};
+///
+/// /// Actions are the index of the chosen child, positions are the bytes of `Node::from_bytes`.
+/// struct TreeCodec;
+///
+/// impl Codec<Node> for TreeCodec {
+///     fn start_position(&self) -> Node {
+///         Node::from_bytes(&[3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5])
+///     }
+///
+///     fn parse_position(&self, position: &str) -> Option<Node> {
+///         let bytes: Result<Vec<u8>, _> = position.split(',').map(str::parse).collect();
+///         bytes.ok().map(|bytes| Node::from_bytes(&bytes))
+///     }
+///
+///     fn active_player(&self, state: &Node) -> bool {
+///         state.actions(true).0
+///     }
+///
+///     fn is_white(&self, player: bool) -> bool {
+///         player
+///     }
+///
+///     fn parse_action(&self, _: &Node, action: &str) -> Option<usize> {
+///         action.parse().ok()
+///     }
+///
+///     fn format_action(&self, _: &Node, action: &usize) -> String {
+///         action.to_string()
+///     }
+///
+///     fn format_score(&self, fitness: i8) -> String {
+///         format!("cp {}", fitness)
+///     }
+/// }
+///
+/// let mut engine = Engine::new(TreeCodec, Bot::new(true)).with_id("tree", "rubot");
+/// let input = "uci\nposition startpos\ngo depth 1\nquit\n";
+/// let mut output = Vec::new();
+/// engine.run(input.as_bytes(), &mut output).unwrap();
+///
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.starts_with("id name tree\nid author rubot\nuciok\n"));
+/// assert!(output.lines().any(|line| line.starts_with("bestmove ")));
+/// ```
+///
+/// [module]: index.html
+pub struct Engine<T: Game, C> {
+    name: String,
+    author: String,
+    codec: C,
+    bot: Bot<T>,
+    state: T,
+}
+
+impl<T: Game, C: Codec<T>> Engine<T, C> {
+    /// Creates a new engine using `codec` which selects its actions using `bot`.
+    ///
+    /// The player of `bot` is changed to the active player before each search.
+    pub fn new(codec: C, bot: Bot<T>) -> Self {
+        Self {
+            name: String::from("rubot"),
+            author: String::new(),
+            state: codec.start_position(),
+            codec,
+            bot,
+        }
+    }
+
+    /// Sets the name and author sent in response to `uci`.
+    pub fn with_id(mut self, name: &str, author: &str) -> Self {
+        self.name = name.to_string();
+        self.author = author.to_string();
+        self
+    }
+
+    /// Returns the current position.
+    pub fn state(&self) -> &T {
+        &self.state
+    }
+
+    /// Returns a mutable reference to the bot, which allows changing its options between searches.
+    pub fn bot_mut(&mut self) -> &mut Bot<T> {
+        &mut self.bot
+    }
+
+    /// Processes the commands of `input` until `quit` or the end of `input`, writing all responses to `output`.
+    ///
+    /// `input` is read on a separate thread, so `stop` is able to interrupt a running search.
+    /// This thread is not joined, as it may still wait for input after `quit`.
+    /// Use `std::io::stdin().lock()` and `std::io::stdout()` to communicate with a frontend.
+    pub fn run<R, W>(&mut self, input: R, mut output: W) -> io::Result<()>
+    where
+        R: BufRead + Send + 'static,
+        W: Write,
+    {
+        let (sender, commands) = mpsc::channel();
+        thread::spawn(move || {
+            // Each `stop` stops the search started by the last `go`.
+            let mut stop = StopFlag::new();
+            for line in input.lines() {
+                let line = line.map(|line| {
+                    match line.split_whitespace().next() {
+                        Some("stop") | Some("quit") => stop.stop(),
+                        Some("go") => stop = StopFlag::new(),
+                        _ => (),
+                    }
+                    (line, stop.clone())
+                });
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+            stop.stop();
+        });
+
+        for command in commands {
+            let (line, stop) = command?;
+            if !self.command(&line, stop, &mut output)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Processes a single command, returning `false` for `quit`.
+    fn command<W: Write>(
+        &mut self,
+        line: &str,
+        stop: StopFlag,
+        output: &mut W,
+    ) -> io::Result<bool> {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("uci") => {
+                writeln!(output, "id name {}", self.name)?;
+                writeln!(output, "id author {}", self.author)?;
+                writeln!(output, "uciok")?;
+            }
+            Some("isready") => writeln!(output, "readyok")?,
+            Some("ucinewgame") => {
+                self.bot.clear_table();
+                self.state = self.codec.start_position();
+            }
+            Some("position") => {
+                if let Some(state) = self.position(words) {
+                    self.state = state;
+                }
+            }
+            Some("go") => self.go(words, stop, output)?,
+            Some("quit") => return Ok(false),
+            _ => (),
+        }
+        output.flush()?;
+        Ok(true)
+    }
+
+    /// Returns the position of a `position` command, or `None` if it is invalid.
+    fn position<'a>(&self, mut words: impl Iterator<Item = &'a str>) -> Option<T> {
+        let mut state = match words.next()? {
+            "startpos" => {
+                if let Some(word) = words.next() {
+                    if word != "moves" {
+                        return None;
+                    }
+                }
+                self.codec.start_position()
+            }
+            "fen" => {
+                let position: Vec<_> = words.by_ref().take_while(|&word| word != "moves").collect();
+                self.codec.parse_position(&position.join(" "))?
+            }
+            _ => return None,
+        };
+
+        for word in words {
+            let action = self.codec.parse_action(&state, word)?;
+            let player = self.codec.active_player(&state);
+            state.execute(&action, player);
+        }
+        Some(state)
+    }
+
+    /// Searches the current position and sends `info` lines and the final `bestmove`.
+    fn go<'a, W: Write>(
+        &mut self,
+        mut words: impl Iterator<Item = &'a str>,
+        stop: StopFlag,
+        output: &mut W,
+    ) -> io::Result<()> {
+        let mut condition = Go {
+            stop,
+            depth: u32::MAX,
+            nodes: u64::MAX,
+            deadline: None,
+        };
+        // The remaining time and the increment of white and black.
+        let mut clocks = [(None, Duration::from_secs(0)); 2];
+        let mut moves_to_go = None;
+        while let Some(word) = words.next() {
+            let mut value = || words.next().and_then(|value| value.parse::<u64>().ok());
+            match word {
+                "depth" => {
+                    if let Some(depth) = value() {
+                        condition.depth = depth.min(u64::from(u32::MAX)) as u32;
+                    }
+                }
+                "nodes" => {
+                    if let Some(nodes) = value() {
+                        condition.nodes = nodes;
+                    }
+                }
+                "movetime" => {
+                    if let Some(millis) = value() {
+                        condition.deadline = Some(Instant::now() + Duration::from_millis(millis));
+                    }
+                }
+                "wtime" => clocks[0].0 = value().map(Duration::from_millis),
+                "btime" => clocks[1].0 = value().map(Duration::from_millis),
+                "winc" => clocks[0].1 = value().map_or(clocks[0].1, Duration::from_millis),
+                "binc" => clocks[1].1 = value().map_or(clocks[1].1, Duration::from_millis),
+                "movestogo" => moves_to_go = value(),
+                _ => (),
+            }
+        }
+
+        let (codec, state) = (&self.codec, &self.state);
+        let player = codec.active_player(state);
+        let (remaining, increment) = clocks[if codec.is_white(player) { 0 } else { 1 }];
+        if let Some(remaining) = remaining {
+            let moves = moves_to_go.map_or(EXPECTED_MOVES, |moves| {
+                moves.min(u64::from(u32::MAX)) as u32
+            });
+            // The increment is only received after the move, so never plan to use all of the remaining time.
+            let budget =
+                (GameClock::new(remaining, moves).move_budget() + increment).min(remaining * 3 / 4);
+            let deadline = Instant::now() + budget;
+            condition.deadline = Some(
+                condition
+                    .deadline
+                    .map_or(deadline, |other| other.min(deadline)),
+            );
+        }
+        self.bot.set_player(player);
+        let mut result = Ok(());
+        let selected = self
+            .bot
+            .select_with_progress(state, condition, |iteration| {
+                if result.is_ok() {
+                    result = writeln!(
+                        output,
+                        "info depth {} score {} nodes {} time {} pv {}",
+                        // Iteration `depth` searches `depth + 2` plies.
+                        iteration.depth + 2,
+                        codec.format_score(iteration.fitness),
+                        iteration.nodes,
+                        iteration.elapsed.as_millis(),
+                        codec.format_action(state, &iteration.action)
+                    )
+                    .and_then(|()| output.flush());
+                }
+            });
+        result?;
+
+        match selected {
+            Some(action) => writeln!(output, "bestmove {}", codec.format_action(state, &action)),
+            // The protocol uses the null move in case there is no possible action.
+            None => writeln!(output, "bestmove 0000"),
+        }
+    }
+}
+
+/// The [`RunCondition`][rc] of a `go` command.
+///
+/// [rc]: ../../trait.RunCondition.html
+struct Go {
+    stop: StopFlag,
+    /// The maximum amount of plies, where `Bot::select` searches `depth + 2` plies during iteration `depth`.
+    ///
+    /// For a maximum of `1` ply, no iteration is started and each action is only evaluated using `Game::look_ahead`.
+    depth: u32,
+    nodes: u64,
+    deadline: Option<Instant>,
+}
+
+impl RunCondition for Go {
+    #[inline]
+    fn step(&mut self) -> bool {
+        if self.nodes == 0 {
+            return false;
+        }
+        self.nodes -= 1;
+        self.check()
+    }
+
+    #[inline]
+    fn depth(&mut self, depth: u32) -> bool {
+        depth.saturating_add(2) <= self.depth && self.check()
+    }
+
+    #[inline]
+    fn check(&mut self) -> bool {
+        !self.stop.is_stopped()
+            && self
+                .deadline
+                .is_none_or(|deadline| Instant::now() < deadline)
+    }
+
+    #[inline]
+    fn remaining(&self) -> Option<Duration> {
+        self.deadline.as_ref().and_then(RunCondition::remaining)
+    }
+}
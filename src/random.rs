@@ -0,0 +1,71 @@
+//! A game bot which chooses random actions.
+use crate::rng::XorShiftRng;
+use crate::{Game, GameBot, IntoRunCondition};
+
+/// A game bot which chooses a uniformly random possible action, without looking at the game at all.
+///
+/// This is useful as a trivial opponent when testing an implementation of [`Game`][game]
+/// or as the lowest difficulty of a game.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{random, tree::Node};
+///
+/// let tree = Node::root().with_children(&[
+///     Node::new(false, 7),
+///     Node::new(false, 5),
+///     Node::new(false, 6),
+/// ]);
+///
+/// let mut bot = random::Bot::new(true).with_seed(42);
+/// let action = bot.select(&tree).unwrap();
+/// assert!(action < 3);
+/// // The same seed always selects the same actions.
+/// assert_eq!(random::Bot::new(true).with_seed(42).select(&tree), Some(action));
+/// ```
+///
+/// [game]: ../trait.Game.html
+pub struct Bot<T: Game> {
+    player: T::Player,
+    rng: XorShiftRng,
+}
+
+impl<T: Game> Bot<T> {
+    /// Creates a new `Bot` for the given `player`.
+    pub fn new(player: T::Player) -> Self {
+        Self {
+            player,
+            rng: XorShiftRng::from_seed_u64(0),
+        }
+    }
+
+    /// Sets the seed used to choose actions.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = XorShiftRng::from_seed_u64(seed);
+        self
+    }
+
+    /// Returns a random possible action, or `None` if no action is possible or the bot is
+    /// currently not the active player.
+    pub fn select(&mut self, state: &T) -> Option<T::Action> {
+        let (active, actions) = state.actions(self.player);
+        if !active {
+            return None;
+        }
+
+        let mut actions: Vec<_> = actions.into_iter().collect();
+        if actions.is_empty() {
+            None
+        } else {
+            Some(actions.swap_remove(self.rng.below(actions.len())))
+        }
+    }
+}
+
+/// The run condition is ignored, as choosing a random action does not require a search.
+impl<T: Game> GameBot<T> for Bot<T> {
+    fn select<U: IntoRunCondition>(&mut self, state: &T, _: U) -> Option<T::Action> {
+        Bot::select(self, state)
+    }
+}
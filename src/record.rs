@@ -0,0 +1,268 @@
+//! A record of the actions played during a game, which can be exported for later analysis.
+use crate::alpha_beta::Bot;
+use crate::{Game, IntoRunCondition};
+
+use std::fmt::Display;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// A single action of a [`Record`][record].
+///
+/// [record]: struct.Record.html
+pub struct Entry<T: Game> {
+    /// The hash of the state in which `action` was played.
+    pub hash: u64,
+    /// The played action.
+    pub action: T::Action,
+    /// The fitness of `action`, either as expected by the search or as returned by `Game::execute`.
+    pub fitness: T::Fitness,
+    /// The depth of the last completed iteration of the search, or `None` if `action` was not searched.
+    pub depth: Option<u32>,
+    /// The time spent selecting `action`.
+    pub time: Duration,
+}
+
+impl<T: Game> Clone for Entry<T> {
+    fn clone(&self) -> Self {
+        Self {
+            hash: self.hash,
+            action: self.action.clone(),
+            fitness: self.fitness,
+            depth: self.depth,
+            time: self.time,
+        }
+    }
+}
+
+/// A list of all actions played during a game, together with the fitness, depth and time of each search.
+///
+/// States are identified using a hash, so records stay small and can be matched with other
+/// data like an opening [`Book`][book] or a [`Tablebase`][tb].
+///
+/// Records are exported using [`write`][write] as one line per action, containing the hash, action, fitness,
+/// depth and time in microseconds separated by tabs, where `-` is used for a missing depth.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{Bot, Game, ToCompletion, record::Record, tree::Node};
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::{Hash, Hasher};
+///
+/// fn hash(node: &Node) -> u64 {
+///     let mut hasher = DefaultHasher::new();
+///     node.hash(&mut hasher);
+///     hasher.finish()
+/// }
+///
+/// # #[rustfmt::skip]
+/// let mut tree = Node::root().with_children(&[
+///     Node::new(false, 7).with_children(&[
+///         Node::new(true, 4),
+///         Node::new(true, 2),
+///     ]),
+///     Node::new(false, 5).with_children(&[
+///         Node::new(true, 8),
+///         Node::new(true, 9),
+///     ]),
+/// ]);
+///
+/// let mut record = Record::new(hash);
+/// let mut bot = Bot::new(true);
+/// let action = record.select(&mut bot, &tree, ToCompletion).unwrap();
+/// tree.execute(&action, true);
+/// // The reply of the opponent is not searched.
+/// record.execute(&mut tree, 0, false);
+///
+/// let mut exported = Vec::new();
+/// record.write(&mut exported).unwrap();
+/// let imported: Record<Node> = Record::read(record.hash(), exported.as_slice()).unwrap();
+/// assert_eq!(imported.len(), 2);
+/// assert_eq!(imported.entries()[0].depth, Some(1));
+/// assert_eq!(imported.entries()[1].fitness, 8);
+/// ```
+///
+/// [book]: ../openings/struct.Book.html
+/// [tb]: ../trait.Tablebase.html
+/// [write]: #method.write
+pub struct Record<T: Game> {
+    hash: fn(&T) -> u64,
+    entries: Vec<Entry<T>>,
+}
+
+impl<T: Game> Record<T> {
+    /// Creates an empty record, identifying states using `hash`.
+    pub fn new(hash: fn(&T) -> u64) -> Self {
+        Self {
+            hash,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Returns the hash used to identify states.
+    pub fn hash(&self) -> fn(&T) -> u64 {
+        self.hash
+    }
+
+    /// Returns all recorded actions in the order they were played.
+    pub fn entries(&self) -> &[Entry<T>] {
+        &self.entries
+    }
+
+    /// Returns the amount of recorded actions.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no action was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Records `action` played in `state`.
+    pub fn push(
+        &mut self,
+        state: &T,
+        action: T::Action,
+        fitness: T::Fitness,
+        depth: Option<u32>,
+        time: Duration,
+    ) {
+        self.entries.push(Entry {
+            hash: (self.hash)(state),
+            action,
+            fitness,
+            depth,
+            time,
+        });
+    }
+
+    /// Selects an action using [`Bot::select_verbose`][verbose] and records it together with
+    /// its expected fitness, the depth of the search and the time spent according to [`Bot::with_clock`][clock].
+    ///
+    /// The action is not executed, as the caller usually has to update their own state as well.
+    ///
+    /// [verbose]: ../alpha_beta/struct.Bot.html#method.select_verbose
+    /// [clock]: ../alpha_beta/struct.Bot.html#method.with_clock
+    pub fn select<U: IntoRunCondition>(
+        &mut self,
+        bot: &mut Bot<T>,
+        state: &T,
+        condition: U,
+    ) -> Option<T::Action> {
+        let report = bot.select_verbose(state, condition)?;
+        self.push(
+            state,
+            report.action.clone(),
+            report.fitness,
+            report.depth,
+            report.stats.duration,
+        );
+        Some(report.action)
+    }
+
+    /// Executes `action` of `player`, for example an action of the opponent, and records it together with
+    /// the fitness returned by `Game::execute`.
+    pub fn execute(&mut self, state: &mut T, action: T::Action, player: T::Player) -> T::Fitness {
+        let hash = (self.hash)(state);
+        let fitness = state.execute(&action, player);
+        self.entries.push(Entry {
+            hash,
+            action,
+            fitness,
+            depth: None,
+            time: Duration::default(),
+        });
+        fitness
+    }
+
+    /// Writes all recorded actions to `output`, one line per action.
+    ///
+    /// The `Display` implementation of actions and fitness values must not contain tabs or newlines.
+    pub fn write<W: Write>(&self, mut output: W) -> io::Result<()>
+    where
+        T::Action: Display,
+        T::Fitness: Display,
+    {
+        for entry in &self.entries {
+            let depth = entry
+                .depth
+                .map_or_else(|| String::from("-"), |depth| depth.to_string());
+            writeln!(
+                output,
+                "{}\t{}\t{}\t{}\t{}",
+                entry.hash,
+                entry.action,
+                entry.fitness,
+                depth,
+                entry.time.as_micros()
+            )?;
+        }
+        output.flush()
+    }
+
+    /// Reads a record previously exported using [`write`][write], identifying new states using `hash`.
+    ///
+    /// Empty lines are ignored, invalid lines result in an error of kind `InvalidData`.
+    ///
+    /// [write]: #method.write
+    pub fn read<R: BufRead>(hash: fn(&T) -> u64, input: R) -> io::Result<Self>
+    where
+        T::Action: FromStr,
+        T::Fitness: FromStr,
+    {
+        let mut record = Self::new(hash);
+        for (i, line) in input.lines().enumerate() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+            let entry = parse_entry(&line).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid entry in line {}: {:?}", i + 1, line),
+                )
+            })?;
+            record.entries.push(entry);
+        }
+        Ok(record)
+    }
+}
+
+/// Parses a single line written by `Record::write`.
+fn parse_entry<T: Game>(line: &str) -> Option<Entry<T>>
+where
+    T::Action: FromStr,
+    T::Fitness: FromStr,
+{
+    let mut fields = line.split('\t');
+    let hash = fields.next()?.parse().ok()?;
+    let action = fields.next()?.parse().ok()?;
+    let fitness = fields.next()?.parse().ok()?;
+    let depth = match fields.next()? {
+        "-" => None,
+        depth => Some(depth.parse().ok()?),
+    };
+    let time = Duration::from_micros(fields.next()?.parse().ok()?);
+    if fields.next().is_some() {
+        return None;
+    }
+
+    Some(Entry {
+        hash,
+        action,
+        fitness,
+        depth,
+        time,
+    })
+}
+
+impl<T: Game> Clone for Record<T> {
+    fn clone(&self) -> Self {
+        Self {
+            hash: self.hash,
+            entries: self.entries.clone(),
+        }
+    }
+}
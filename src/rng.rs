@@ -0,0 +1,71 @@
+//! A small xorshift random number generator.
+//!
+//! This is used wherever a bot or tool needs reproducible randomness,
+//! without requiring an additional dependency.
+use std::num::Wrapping;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct XorShiftRng {
+    x: Wrapping<u32>,
+    y: Wrapping<u32>,
+    z: Wrapping<u32>,
+    w: Wrapping<u32>,
+}
+
+impl XorShiftRng {
+    /// Creates a new rng, a `seed` of `0` is replaced by a fixed non zero seed
+    /// as xorshift would otherwise only return zeros.
+    pub(crate) fn from_seed(seed: u32) -> Self {
+        let seed = if seed == 0 { 0xBAD_5EED } else { seed };
+        Self {
+            x: Wrapping(seed),
+            y: Wrapping(seed),
+            z: Wrapping(seed),
+            w: Wrapping(seed),
+        }
+    }
+
+    /// Creates a new rng using both halves of `seed`.
+    pub(crate) fn from_seed_u64(seed: u64) -> Self {
+        let mut rng = Self::from_seed((seed ^ (seed >> 32)) as u32);
+        rng.y ^= Wrapping((seed >> 32) as u32);
+        // Discard the first few values, as they strongly depend on the seed.
+        for _ in 0..8 {
+            rng.next_u32();
+        }
+        rng
+    }
+
+    pub(crate) fn next_u32(&mut self) -> u32 {
+        let x = self.x;
+        let t = x ^ (x << 11);
+        self.x = self.y;
+        self.y = self.z;
+        self.z = self.w;
+        let w = self.w;
+        self.w = w ^ (w >> 19) ^ (t ^ (t >> 8));
+        self.w.0
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        (u64::from(self.next_u32()) << 32) | u64::from(self.next_u32())
+    }
+
+    /// Returns a value in `0..n`, `n` must not be `0`.
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Returns a value in `[0, 1)`.
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a normally distributed value with mean `0` and standard deviation `1`.
+    pub(crate) fn next_gaussian(&mut self) -> f64 {
+        // Box-Muller transform, `1.0 - next_f64` is in `(0, 1]` so `ln` is finite.
+        let u = 1.0 - self.next_f64();
+        let v = self.next_f64();
+        (-2.0 * u.ln()).sqrt() * (2.0 * std::f64::consts::PI * v).cos()
+    }
+}
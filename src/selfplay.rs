@@ -0,0 +1,203 @@
+//! Self-play, which generates training data by letting a bot play against itself.
+use crate::tournament::Outcome;
+use crate::{Game, GameBot, IntoRunCondition};
+
+use std::fmt::{self, Debug};
+
+/// A single action played during [`SelfPlay`][selfplay], labeled with the final outcome of its game.
+///
+/// [selfplay]: struct.SelfPlay.html
+#[derive(Clone)]
+pub struct Sample<T: Game> {
+    /// The state in which `action` was played.
+    pub state: T,
+    /// The player who played `action`.
+    pub player: T::Player,
+    /// The action selected by the bot.
+    pub action: T::Action,
+    /// The outcome of the game from the perspective of `player`.
+    pub outcome: Outcome,
+}
+
+impl<T: Game + Debug> Debug for Sample<T>
+where
+    T::Player: Debug,
+    T::Action: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Sample")
+            .field("state", &self.state)
+            .field("player", &self.player)
+            .field("action", &self.action)
+            .field("outcome", &self.outcome)
+            .finish()
+    }
+}
+
+/// Repeatedly plays a bot against itself, passing the samples of each game to a user provided callback.
+///
+/// Each player is controlled by a new bot created at the start of every game, so a callback which updates
+/// the parameters of the bot, for example using [`tune::SharedWeights`][shared], influences all later games.
+/// Games end in the same way as the games of a [`Tournament`][tournament], once no player has a possible action
+/// or once the maximum amount of plies is reached, and the final state is then scored by `outcome`.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{Bot, Depth, Game, selfplay::SelfPlay, tournament::Outcome};
+///
+/// /// Both players take one to three stones, the player who takes the last stone wins.
+/// #[derive(Clone)]
+/// struct Stones { stones: u8, first: bool }
+///
+/// impl Game for Stones {
+///     type Player = bool;
+///     type Action = u8;
+///     type Fitness = i8;
+///     type Actions = Vec<u8>;
+///
+///     fn actions(&self, player: bool) -> (bool, Vec<u8>) {
+///         (player == self.first, (1..=self.stones.min(3)).collect())
+///     }
+///
+///     fn execute(&mut self, &take: &u8, player: bool) -> i8 {
+///         let mover = self.first;
+///         self.stones -= take;
+///         self.first = !self.first;
+///         match self.stones {
+///             0 if mover == player => 1,
+///             0 => -1,
+///             _ => 0,
+///         }
+///     }
+/// }
+///
+/// fn outcome(state: &Stones, player: bool) -> Outcome {
+///     if state.first == player { Outcome::Loss } else { Outcome::Win }
+/// }
+///
+/// let start = Stones { stones: 6, first: true };
+/// let mut samples = Vec::new();
+/// SelfPlay::new(vec![true, false], vec![start], outcome).run(
+///     Bot::new,
+///     Depth(10),
+///     |game| samples.extend_from_slice(game),
+/// );
+///
+/// // Perfect play takes two stones, leaving a multiple of four for the opponent.
+/// assert_eq!(samples[0].action, 2);
+/// assert_eq!(samples[0].outcome, Outcome::Win);
+/// assert_eq!(samples.last().unwrap().outcome, Outcome::Win);
+/// ```
+///
+/// [shared]: ../tune/struct.SharedWeights.html
+/// [tournament]: ../tournament/struct.Tournament.html
+pub struct SelfPlay<T: Game> {
+    players: Vec<T::Player>,
+    starts: Vec<T>,
+    outcome: fn(&T, T::Player) -> Outcome,
+    games: u32,
+    max_plies: u32,
+}
+
+impl<T: Game> SelfPlay<T> {
+    /// Creates a new self-play runner for a game with the given players, where `outcome` returns the result
+    /// of a final state for the given player.
+    ///
+    /// By default, each start state is played once and games end after `1000` plies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `players` or `starts` is empty.
+    pub fn new(
+        players: Vec<T::Player>,
+        starts: Vec<T>,
+        outcome: fn(&T, T::Player) -> Outcome,
+    ) -> Self {
+        assert!(!players.is_empty(), "at least one player is required");
+        assert!(!starts.is_empty(), "at least one start state is required");
+        Self {
+            players,
+            games: starts.len() as u32,
+            starts,
+            outcome,
+            max_plies: 1000,
+        }
+    }
+
+    /// Sets the total amount of games, cycling through the start states.
+    pub fn with_games(mut self, games: u32) -> Self {
+        self.games = games;
+        self
+    }
+
+    /// Sets the maximum amount of plies of each game, after which the current state is scored using `outcome`.
+    pub fn with_max_plies(mut self, max_plies: u32) -> Self {
+        self.max_plies = max_plies;
+        self
+    }
+
+    /// Plays all games, where `bot` creates a new bot for the given player at the start of each game
+    /// and `condition` is used for each action.
+    ///
+    /// After each game, `update` is called with one sample for each of its actions, in the order they were played.
+    pub fn run<B, U>(
+        &self,
+        mut bot: impl FnMut(T::Player) -> B,
+        condition: U,
+        mut update: impl FnMut(&[Sample<T>]),
+    ) where
+        B: GameBot<T>,
+        U: IntoRunCondition + Clone,
+    {
+        let mut samples = Vec::new();
+        for game in 0..self.games {
+            let start = &self.starts[game as usize % self.starts.len()];
+            let mut bots: Vec<_> = self.players.iter().map(|&player| bot(player)).collect();
+            samples.clear();
+            self.play(start, &mut bots, &condition, &mut samples);
+            update(&samples);
+        }
+    }
+
+    /// Plays a single game, adding a sample for each action to `samples`.
+    ///
+    /// The outcome of each sample is only known at the end of the game, so all samples
+    /// temporarily use `Outcome::Draw`.
+    fn play<B, U>(&self, start: &T, bots: &mut [B], condition: &U, samples: &mut Vec<Sample<T>>)
+    where
+        B: GameBot<T>,
+        U: IntoRunCondition + Clone,
+    {
+        let mut state = start.clone();
+        'plies: for _ in 0..self.max_plies {
+            for (bot, &player) in bots.iter_mut().zip(self.players.iter()) {
+                let (active, actions) = state.actions(player);
+                if active && actions.into_iter().next().is_some() {
+                    match bot.select(&state, condition.clone()) {
+                        Some(action) => {
+                            samples.push(Sample {
+                                state: state.clone(),
+                                player,
+                                action: action.clone(),
+                                outcome: Outcome::Draw,
+                            });
+                            state.execute(&action, player);
+                            continue 'plies;
+                        }
+                        // The bot gave up, so the game is scored as it is.
+                        None => break 'plies,
+                    }
+                }
+            }
+
+            if !self.players.iter().any(|&player| state.pass(player)) {
+                break;
+            }
+        }
+
+        for sample in samples.iter_mut() {
+            sample.outcome = (self.outcome)(&state, sample.player);
+        }
+    }
+}
@@ -0,0 +1,313 @@
+//! A game bot for games where both players choose their actions at the same time.
+use crate::{rng::XorShiftRng, IntoRunCondition, RunCondition};
+
+use std::fmt::{self, Debug};
+
+/// The tolerance used when solving the matrix game of a single turn.
+const EPSILON: f64 = 1e-9;
+
+/// A game with two players, who both choose an action each turn without knowing the choice of the other.
+///
+/// Unlike [`Game`][game], there is no active player, as each turn consists of one action of both
+/// players which are executed together.
+///
+/// [game]: ../trait.Game.html
+pub trait SimultaneousGame: Clone {
+    /// The player type.
+    type Player: Copy;
+    /// The action type, used by both players.
+    type Action: Clone;
+    /// The fitness of a state, which is converted to `f64` to compute mixed strategies.
+    type Fitness: Copy + Into<f64>;
+
+    /// Returns the actions of `player` and the actions of their opponent for the current turn.
+    ///
+    /// The game has ended once either of them is empty.
+    fn actions(&self, player: Self::Player) -> (Vec<Self::Action>, Vec<Self::Action>);
+
+    /// Executes `action` of `player` together with `reply` of their opponent and returns the fitness
+    /// of the resulting state from the perspective of `player`.
+    fn execute(
+        &mut self,
+        action: &Self::Action,
+        reply: &Self::Action,
+        player: Self::Player,
+    ) -> Self::Fitness;
+}
+
+/// The mixed strategy returned by [`Bot::strategy`][strategy].
+///
+/// [strategy]: struct.Bot.html#method.strategy
+pub struct Strategy<T: SimultaneousGame> {
+    /// Each action of the bot together with the probability with which it should be chosen.
+    pub actions: Vec<(T::Action, f64)>,
+    /// The expected fitness when following this strategy against an optimal opponent.
+    pub value: f64,
+}
+
+impl<T: SimultaneousGame> Debug for Strategy<T>
+where
+    T::Action: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Strategy")
+            .field("actions", &self.actions)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+/// A game bot for [`SimultaneousGame`][sg]s, which expects the opponent to play optimally.
+///
+/// Each turn is treated as a matrix game, where the entry for an action and a reply is the fitness after
+/// searching the rest of the game. This matrix is solved for the mixed strategy which maximizes the worst
+/// case expected fitness, after which [`select`][select] randomly chooses an action according to this strategy.
+/// The fitness of a turn which is not searched further is the one returned by `SimultaneousGame::execute`.
+///
+/// [`Depth`][depth]`(n)` searches `n + 1` turns.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{ToCompletion, simultaneous::{self, SimultaneousGame}};
+///
+/// /// Both players show a coin, the bot wins if both coins show the same side.
+/// #[derive(Clone)]
+/// struct Pennies {
+///     done: bool,
+/// }
+///
+/// impl SimultaneousGame for Pennies {
+///     type Player = ();
+///     type Action = bool;
+///     type Fitness = i8;
+///
+///     fn actions(&self, _: ()) -> (Vec<bool>, Vec<bool>) {
+///         if self.done {
+///             (Vec::new(), Vec::new())
+///         } else {
+///             (vec![true, false], vec![true, false])
+///         }
+///     }
+///
+///     fn execute(&mut self, &action: &bool, &reply: &bool, _: ()) -> i8 {
+///         self.done = true;
+///         if action == reply { 1 } else { -1 }
+///     }
+/// }
+///
+/// let strategy = simultaneous::Bot::new(()).strategy(&Pennies { done: false }, ToCompletion).unwrap();
+/// for (_, probability) in strategy.actions {
+///     assert!((probability - 0.5).abs() < 1e-6);
+/// }
+/// assert!(strategy.value.abs() < 1e-6);
+/// ```
+///
+/// [sg]: trait.SimultaneousGame.html
+/// [select]: #method.select
+/// [depth]: ../struct.Depth.html
+pub struct Bot<T: SimultaneousGame> {
+    player: T::Player,
+    rng: XorShiftRng,
+}
+
+impl<T: SimultaneousGame> Bot<T> {
+    /// Creates a new `Bot` for the given `player`.
+    pub fn new(player: T::Player) -> Self {
+        Self {
+            player,
+            rng: XorShiftRng::from_seed_u64(0),
+        }
+    }
+
+    /// Sets the seed used to sample actions from the mixed strategy.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = XorShiftRng::from_seed_u64(seed);
+        self
+    }
+
+    /// Returns an action sampled from the [`strategy`][strategy] of the current turn,
+    /// or `None` if the game has ended.
+    ///
+    /// [strategy]: #method.strategy
+    pub fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action> {
+        let strategy = self.strategy(state, condition)?;
+        let mut remaining = self.rng.next_f64();
+        let mut selected = None;
+        for (action, probability) in strategy.actions {
+            if probability > EPSILON {
+                selected = Some(action);
+                remaining -= probability;
+                if remaining < 0.0 {
+                    break;
+                }
+            }
+        }
+        selected
+    }
+
+    /// Returns the mixed strategy for the current turn which has the best expected fitness against
+    /// an optimal opponent, or `None` if the game has ended.
+    ///
+    /// `RunCondition::depth` is called before searching one additional turn and
+    /// `RunCondition::step` before each searched state. In case the search is cancelled,
+    /// the strategy of the last completed depth is returned.
+    pub fn strategy<U: IntoRunCondition>(&self, state: &T, condition: U) -> Option<Strategy<T>> {
+        let mut condition = condition.into_run_condition();
+
+        let (actions, replies) = state.actions(self.player);
+        if actions.is_empty() || replies.is_empty() {
+            return None;
+        }
+
+        let (matrix, mut complete) = self.matrix(state, &actions, &replies, 0, &mut condition)?;
+        let mut best = solve(&matrix);
+        let mut depth = 0;
+        while !complete && condition.depth(depth) {
+            depth += 1;
+            match self.matrix(state, &actions, &replies, depth, &mut condition) {
+                Some((matrix, now_complete)) => {
+                    best = solve(&matrix);
+                    complete = now_complete;
+                }
+                None => break,
+            }
+        }
+
+        let (value, probabilities) = best;
+        Some(Strategy {
+            actions: actions.into_iter().zip(probabilities).collect(),
+            value,
+        })
+    }
+
+    /// Returns the fitness of each combination of `actions` and `replies` when searching `depth`
+    /// additional turns and whether these are exact, or `None` if the search was cancelled.
+    fn matrix<U: RunCondition>(
+        &self,
+        state: &T,
+        actions: &[T::Action],
+        replies: &[T::Action],
+        depth: u32,
+        condition: &mut U,
+    ) -> Option<(Vec<Vec<f64>>, bool)> {
+        let mut complete = true;
+        let mut matrix = Vec::with_capacity(actions.len());
+        for action in actions {
+            let mut row = Vec::with_capacity(replies.len());
+            for reply in replies {
+                let (value, entry_complete) = self.value(state, action, reply, depth, condition)?;
+                complete &= entry_complete;
+                row.push(value);
+            }
+            matrix.push(row);
+        }
+        Some((matrix, complete))
+    }
+
+    /// Returns the fitness of `action` and `reply` when searching `depth` additional turns
+    /// and whether this is exact, or `None` if the search was cancelled.
+    fn value<U: RunCondition>(
+        &self,
+        state: &T,
+        action: &T::Action,
+        reply: &T::Action,
+        depth: u32,
+        condition: &mut U,
+    ) -> Option<(f64, bool)> {
+        if !condition.step() {
+            return None;
+        }
+
+        let mut state = state.clone();
+        let fitness = state.execute(action, reply, self.player).into();
+        let (actions, replies) = state.actions(self.player);
+        if actions.is_empty() || replies.is_empty() {
+            Some((fitness, true))
+        } else if depth == 0 {
+            Some((fitness, false))
+        } else {
+            let (matrix, complete) =
+                self.matrix(&state, &actions, &replies, depth - 1, condition)?;
+            Some((solve(&matrix).0, complete))
+        }
+    }
+}
+
+/// Solves the zero-sum matrix game where the bot chooses a row and the opponent a column,
+/// returning its value and the optimal probability of each row.
+///
+/// After shifting all entries to be at least `1`, the value is positive and the scaled strategy of the bot
+/// is the dual solution of maximizing the sum of `y` subject to `matrix * y <= 1` and `y >= 0`,
+/// which is solved using the simplex method with Bland's rule to prevent cycling.
+fn solve(matrix: &[Vec<f64>]) -> (f64, Vec<f64>) {
+    let rows = matrix.len();
+    let columns = matrix[0].len();
+    let min = matrix
+        .iter()
+        .flatten()
+        .fold(f64::INFINITY, |min, &entry| min.min(entry));
+    let shift = 1.0 - min;
+
+    // Each row contains the shifted matrix, followed by the slack variables and the right-hand side.
+    let width = columns + rows + 1;
+    let mut tableau: Vec<Vec<f64>> = matrix
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut line = vec![0.0; width];
+            for (entry, &value) in line.iter_mut().zip(row) {
+                *entry = value + shift;
+            }
+            line[columns + i] = 1.0;
+            line[width - 1] = 1.0;
+            line
+        })
+        .collect();
+    let mut objective = vec![0.0; width];
+    for entry in &mut objective[..columns] {
+        *entry = -1.0;
+    }
+    let mut basis: Vec<usize> = (columns..columns + rows).collect();
+
+    while let Some(entering) = (0..width - 1).find(|&j| objective[j] < -EPSILON) {
+        let ratio = |i: usize| tableau[i][width - 1] / tableau[i][entering];
+        let leaving = (0..rows)
+            .filter(|&i| tableau[i][entering] > EPSILON)
+            .min_by(|&a, &b| {
+                ratio(a)
+                    .partial_cmp(&ratio(b))
+                    .unwrap()
+                    .then(basis[a].cmp(&basis[b]))
+            })
+            .expect("the matrix game is bounded as all entries are positive");
+
+        let pivot = tableau[leaving][entering];
+        for entry in &mut tableau[leaving] {
+            *entry /= pivot;
+        }
+        let pivot_row = tableau[leaving].clone();
+        let eliminate = |row: &mut Vec<f64>| {
+            let factor = row[entering];
+            if factor != 0.0 {
+                for (entry, &pivot) in row.iter_mut().zip(&pivot_row) {
+                    *entry -= factor * pivot;
+                }
+            }
+        };
+        for (i, row) in tableau.iter_mut().enumerate() {
+            if i != leaving {
+                eliminate(row);
+            }
+        }
+        eliminate(&mut objective);
+        basis[leaving] = entering;
+    }
+
+    let total = objective[width - 1];
+    let probabilities = objective[columns..columns + rows]
+        .iter()
+        .map(|&dual| dual.max(0.0) / total)
+        .collect();
+    (1.0 / total - shift, probabilities)
+}
@@ -0,0 +1,246 @@
+//! A best-first game bot, using [MT-SSS*][sss].
+//!
+//! [sss]: https://en.wikipedia.org/wiki/SSS*
+use crate::{Game, GameBot, IntoRunCondition, RunCondition};
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A game bot which uses MT-SSS*, a best-first variant of alpha-beta pruning.
+///
+/// Instead of searching with a window like [`alpha_beta::Bot`][ab], each search only tests whether
+/// the fitness of the current state is at least as high as a given bound. Starting with an unreachable
+/// bound, the bound is lowered to the result of the previous test until a test succeeds, which
+/// means that the exact fitness was found. The bounds of all searched states are remembered,
+/// so each test only expands the states which are still relevant to it.
+///
+/// This explores the game tree in the same order as SSS*, which never expands states which are
+/// skipped by alpha-beta, and for some games expands far fewer. As the result of every searched state is
+/// stored, the required memory grows with the amount of searched states. Like [`brute::Brute`][brute],
+/// [`Depth`][depth]`(n)` searches `n + 1` actions.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{ToCompletion, sss, tree::Node};
+///
+/// # #[rustfmt::skip]
+/// let tree = Node::root().with_children(&[
+///     Node::new(false, 7).with_children(&[
+///         Node::new(true, 4),
+///         Node::new(true, 2),
+///     ]),
+///     Node::new(false, 5).with_children(&[
+///         Node::new(true, 8),
+///         Node::new(true, 9),
+///     ]),
+///     Node::new(false, 6),
+/// ]);
+///
+/// assert_eq!(sss::Bot::new(true).select(&tree, ToCompletion), Some(1));
+/// ```
+///
+/// [ab]: ../alpha_beta/struct.Bot.html
+/// [brute]: ../brute/struct.Brute.html
+/// [depth]: ../struct.Depth.html
+pub struct Bot<T: Game> {
+    player: T::Player,
+}
+
+/// The known bounds of a state at a given depth, `None` is unbounded.
+struct Entry<F> {
+    lower: Option<F>,
+    upper: Option<F>,
+    /// Whether the search of this state used `Game::look_ahead` for states with possible actions.
+    truncated: bool,
+}
+
+/// The state of a single call to `select`.
+struct Search<'a, T: Game, U> {
+    player: T::Player,
+    memory: HashMap<(T, u32), Entry<T::Fitness>>,
+    condition: &'a mut U,
+    truncated: bool,
+}
+
+/// Returns whether `fitness` passes the test of `gamma`, where `None` is an unreachable bound.
+fn passes<F: Ord>(fitness: F, gamma: Option<F>) -> bool {
+    gamma.is_some_and(|gamma| fitness >= gamma)
+}
+
+impl<T: Game + Hash + Eq> Bot<T> {
+    /// Creates a new `Bot` for the given `player`.
+    pub fn new(player: T::Player) -> Self {
+        Self { player }
+    }
+
+    /// Returns the action with the highest fitness, or `None` if no action is possible or the bot is
+    /// currently not the active player.
+    ///
+    /// `RunCondition::depth` is called before searching one additional action and
+    /// `RunCondition::step` before each searched state. In case the search is cancelled,
+    /// the best action of the last completed depth is returned.
+    pub fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action> {
+        let mut condition = condition.into_run_condition();
+
+        let (active, actions) = state.actions(self.player);
+        if !active {
+            return None;
+        }
+
+        let actions: Vec<_> = actions.into_iter().collect();
+        let mut search = Search {
+            player: self.player,
+            memory: HashMap::new(),
+            condition: &mut condition,
+            truncated: false,
+        };
+        let (mut best, mut complete) = search.best_action(state, &actions, 0)?;
+        let mut depth = 0;
+        while !complete && search.condition.depth(depth) {
+            depth += 1;
+            match search.best_action(state, &actions, depth) {
+                Some((action, now_complete)) => {
+                    best = action;
+                    complete = now_complete;
+                }
+                None => break,
+            }
+        }
+        Some(best)
+    }
+}
+
+impl<'a, T: Game + Hash + Eq, U: RunCondition> Search<'a, T, U> {
+    /// Returns the best action of `actions` and whether the complete game was searched,
+    /// or `None` if there are no actions or the search was cancelled.
+    fn best_action(
+        &mut self,
+        state: &T,
+        actions: &[T::Action],
+        depth: u32,
+    ) -> Option<(T::Action, bool)> {
+        if actions.is_empty() {
+            return None;
+        }
+
+        self.truncated = false;
+        let mut gamma = None;
+        let fitness = loop {
+            let fitness = self.test_state(state, true, actions, depth, gamma)?;
+            if passes(fitness, gamma) {
+                break fitness;
+            }
+            gamma = Some(fitness);
+        };
+
+        // The best action is the first one reaching the fitness of the state,
+        // which only requires remembered results.
+        for action in actions {
+            if passes(
+                self.test(state, action, depth, Some(fitness))?,
+                Some(fitness),
+            ) {
+                return Some((action.clone(), !self.truncated));
+            }
+        }
+        unreachable!("no action reaches the fitness of the state")
+    }
+
+    /// Tests whether the fitness of `state` with the given `actions` is at least `gamma`.
+    ///
+    /// The result is a lower bound of the fitness in case it passes the test and an upper bound otherwise,
+    /// or `None` if the search was cancelled.
+    fn test_state(
+        &mut self,
+        state: &T,
+        active: bool,
+        actions: &[T::Action],
+        depth: u32,
+        gamma: Option<T::Fitness>,
+    ) -> Option<T::Fitness> {
+        let mut best: Option<T::Fitness> = None;
+        for action in actions {
+            let fitness = self.test(state, action, depth, gamma)?;
+            let best = best.insert(match best {
+                Some(best) if active => best.max(fitness),
+                Some(best) => best.min(fitness),
+                None => fitness,
+            });
+            if active == passes(*best, gamma) {
+                break;
+            }
+        }
+        best
+    }
+
+    /// Tests whether the fitness of `action` when searching `depth` additional actions is at least `gamma`,
+    /// see `test_state`.
+    fn test(
+        &mut self,
+        state: &T,
+        action: &T::Action,
+        depth: u32,
+        gamma: Option<T::Fitness>,
+    ) -> Option<T::Fitness> {
+        if depth == 0 {
+            self.truncated = true;
+            return Some(state.look_ahead(action, self.player));
+        }
+
+        if !self.condition.step() {
+            return None;
+        }
+
+        let mut state = state.clone();
+        let fitness = state.execute(action, self.player);
+        let (mut active, actions) = state.actions(self.player);
+        let mut actions: Vec<_> = actions.into_iter().collect();
+        if !active && actions.is_empty() && state.pass(self.player) {
+            let (now_active, now_actions) = state.actions(self.player);
+            active = now_active;
+            actions = now_actions.into_iter().collect();
+        }
+
+        if actions.is_empty() {
+            return Some(fitness);
+        }
+
+        let key = (state, depth);
+        if let Some(entry) = self.memory.get(&key) {
+            match (entry.lower, entry.upper) {
+                (Some(lower), _) if passes(lower, gamma) => {
+                    self.truncated |= entry.truncated;
+                    return Some(lower);
+                }
+                (_, Some(upper)) if !passes(upper, gamma) => {
+                    self.truncated |= entry.truncated;
+                    return Some(upper);
+                }
+                _ => (),
+            }
+        }
+
+        let truncated = std::mem::replace(&mut self.truncated, false);
+        let fitness = self.test_state(&key.0, active, &actions, depth - 1, gamma)?;
+        let entry = self.memory.entry(key).or_insert(Entry {
+            lower: None,
+            upper: None,
+            truncated: false,
+        });
+        if passes(fitness, gamma) {
+            entry.lower = Some(entry.lower.map_or(fitness, |lower| lower.max(fitness)));
+        } else {
+            entry.upper = Some(entry.upper.map_or(fitness, |upper| upper.min(fitness)));
+        }
+        entry.truncated |= self.truncated;
+        self.truncated |= truncated;
+        Some(fitness)
+    }
+}
+
+impl<T: Game + Hash + Eq> GameBot<T> for Bot<T> {
+    fn select<U: IntoRunCondition>(&mut self, state: &T, condition: U) -> Option<T::Action> {
+        Bot::select(self, state, condition)
+    }
+}
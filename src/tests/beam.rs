@@ -0,0 +1,97 @@
+//! Tests for `beam::Bot`.
+use super::*;
+use crate::beam;
+use crate::brute::Brute;
+use crate::rng::XorShiftRng;
+use crate::Game;
+
+/// A single player moves through a grid from left to right, collecting the value of each visited cell.
+#[derive(Clone)]
+struct Path {
+    grid: Vec<Vec<i8>>,
+    column: usize,
+    row: usize,
+    sum: i16,
+}
+
+impl Game for Path {
+    type Player = ();
+    type Action = usize;
+    type Fitness = i16;
+    type Actions = Vec<usize>;
+
+    fn actions(&self, _: ()) -> (bool, Vec<usize>) {
+        if self.column + 1 < self.grid.len() {
+            let rows = self.grid[0].len();
+            let actions = (self.row.saturating_sub(1)..=self.row + 1)
+                .filter(|&row| row < rows)
+                .collect();
+            (true, actions)
+        } else {
+            (true, Vec::new())
+        }
+    }
+
+    fn execute(&mut self, &row: &usize, _: ()) -> i16 {
+        self.column += 1;
+        self.row = row;
+        self.sum += i16::from(self.grid[self.column][row]);
+        self.sum
+    }
+}
+
+fn path(rng: &mut XorShiftRng) -> Path {
+    Path {
+        grid: (0..6)
+            .map(|_| (0..4).map(|_| rng.below(19) as i8 - 9).collect())
+            .collect(),
+        column: 0,
+        row: 1,
+        sum: 0,
+    }
+}
+
+/// Keeping every state is an exhaustive search.
+#[test]
+fn wide() {
+    let mut rng = XorShiftRng::from_seed_u64(11);
+    for _ in 0..100 {
+        let state = path(&mut rng);
+        let selected = beam::Bot::new(())
+            .with_width(usize::MAX)
+            .select(&state, ToCompletion);
+        assert!(Brute::new(()).check_if_best(&state, selected.as_ref(), u32::MAX));
+    }
+}
+
+/// A width of `1` only looks at the best action at each depth.
+#[test]
+fn narrow() {
+    let state = Path {
+        grid: vec![vec![0, 0, 0], vec![1, 0, 0], vec![0, 0, 9]],
+        column: 0,
+        row: 0,
+        sum: 0,
+    };
+    let mut narrow = beam::Bot::new(()).with_width(1);
+    assert_eq!(narrow.select(&state, ToCompletion), Some(0));
+    let mut wide = beam::Bot::new(()).with_width(2);
+    assert_eq!(wide.select(&state, ToCompletion), Some(1));
+}
+
+/// Interrupted searches must still return a possible action.
+#[test]
+fn interrupted() {
+    let mut rng = XorShiftRng::from_seed_u64(5);
+    let state = path(&mut rng);
+    for steps in 0..20 {
+        let selected = beam::Bot::new(())
+            .with_width(3)
+            .select(&state, Steps(steps));
+        assert!(state.actions(()).1.contains(&selected.unwrap()));
+    }
+    for depth in 0..6 {
+        let selected = beam::Bot::new(()).select(&state, Depth(depth));
+        assert!(selected.is_some());
+    }
+}
@@ -0,0 +1,35 @@
+//! Tests for `bench::Suite`.
+use super::*;
+use crate::bench::Suite;
+
+/// Each depth searches at least as many nodes as the previous one, and deeper depths
+/// are skipped once the search completed.
+#[test]
+fn measurements() {
+    let small = Node::from_bytes(&[3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8]);
+    let suite = Suite::standard()
+        .with_state("small", small)
+        .with_max_depth(4);
+    assert_eq!(suite.states().len(), 4);
+
+    let measurements = suite.run(|| Bot::new(true));
+    for (name, _) in suite.states() {
+        let depths: Vec<_> = measurements
+            .iter()
+            .filter(|measurement| measurement.name == *name)
+            .collect();
+        assert_eq!(depths[0].depth, 0, "name: {}", name);
+        for pair in depths.windows(2) {
+            assert_eq!(pair[0].depth + 1, pair[1].depth, "name: {}", name);
+            assert!(pair[0].nodes <= pair[1].nodes, "name: {}", name);
+            assert!(!pair[0].completed, "name: {}", name);
+        }
+        let last = depths.last().unwrap();
+        assert!(last.completed || last.depth == 4, "name: {}", name);
+    }
+
+    let small = measurements.last().unwrap();
+    assert_eq!(small.name, "small");
+    assert!(small.completed);
+    assert!(small.nodes_per_second() > 0.0);
+}
@@ -1,6 +1,7 @@
 //! testing tests, deep!
 use super::*;
 use crate::brute::Brute;
+use std::cmp;
 
 #[test]
 fn allowed_actions_depth_zero() {
@@ -26,3 +27,134 @@ fn allowed_actions_depth_zero() {
         }
     }
 }
+
+/// Memoization must not change the result, even when reusing values of different depths.
+#[test]
+fn memoization() {
+    use crate::Game;
+
+    /// Players alternately increment either coordinate until their sum reaches 8,
+    /// reaching the same state using many different paths.
+    #[derive(Clone, PartialEq, Eq, Hash)]
+    struct Walk(u8, u8, bool);
+
+    impl Game for Walk {
+        type Player = bool;
+        type Action = bool;
+        type Fitness = i8;
+        type Actions = Vec<bool>;
+
+        fn actions(&self, player: bool) -> (bool, Vec<bool>) {
+            let actions = if self.0 + self.1 < 8 {
+                vec![false, true]
+            } else {
+                Vec::new()
+            };
+            (player == self.2, actions)
+        }
+
+        fn execute(&mut self, &action: &bool, _: bool) -> i8 {
+            if action {
+                self.1 += 1;
+            } else {
+                self.0 += 1;
+            }
+            self.2 = !self.2;
+            (self.0 * self.1 % 7) as i8 - 3
+        }
+    }
+
+    let swap = |walk: &Walk| Walk(cmp::min(walk.0, walk.1), cmp::max(walk.0, walk.1), walk.2);
+    let mut plain = Brute::new(true);
+    let mut memoized = Brute::new(true).with_memoization();
+    let mut symmetric = Brute::new(true)
+        .with_memoization()
+        .with_canonical_form(swap);
+    // `with_canonical_form` enables memoization and keeps working if it is enabled again.
+    let mut canonical = Brute::new(true)
+        .with_canonical_form(swap)
+        .with_memoization();
+    for &depth in [3, 0, u32::MAX, 1, 7, 5].iter() {
+        for start in [Walk(0, 0, true), Walk(1, 2, true), Walk(3, 0, true)].iter() {
+            for action in [false, true].iter() {
+                let expected = plain.fitness(start, action, depth);
+                assert_eq!(memoized.fitness(start, action, depth), expected);
+                assert_eq!(symmetric.fitness(start, action, depth), expected);
+                assert_eq!(canonical.fitness(start, action, depth), expected);
+            }
+        }
+    }
+}
+
+/// `Depth(n)` must search the same amount of actions as `Brute::select` with a depth of `n`.
+#[test]
+fn game_bot_depth() {
+    use crate::GameBot;
+
+    for seed in 0..50u32 {
+        let bytes: Vec<u8> = seed
+            .to_be_bytes()
+            .iter()
+            .copied()
+            .chain((0..30u8).map(|i| (seed as u8).wrapping_mul(5).wrapping_add(i)))
+            .collect();
+        let tree = Node::from_bytes(&bytes);
+        let mut brute = Brute::new(true);
+        for depth in 0..4 {
+            assert_eq!(
+                GameBot::select(&mut brute, &tree, Depth(depth)),
+                brute.select(&tree, depth),
+                "seed: {}, depth: {}",
+                seed,
+                depth
+            );
+        }
+        assert_eq!(
+            GameBot::select(&mut brute, &tree, ToCompletion),
+            brute.select(&tree, u32::MAX),
+            "seed: {}",
+            seed
+        );
+    }
+}
+
+/// Always selects the last possible action.
+struct Last;
+
+impl crate::GameBot<Node> for Last {
+    fn select<U: crate::IntoRunCondition>(&mut self, state: &Node, _: U) -> Option<usize> {
+        use crate::Game;
+
+        state.actions(true).1.last()
+    }
+}
+
+#[test]
+fn assert_bot() {
+    for seed in 0..50u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&[7, 3, 9, 1, 4, 2, 8, 6, 5, 0, 3, 7, 2, 9, 4, 4]);
+        let tree = Node::from_bytes(&bytes);
+        for depth in 0..5 {
+            Brute::new(true).assert_bot(&mut Bot::new(true), &tree, depth);
+        }
+        Brute::new(true).assert_bot(&mut Bot::new(true), &tree, u32::MAX);
+    }
+}
+
+#[test]
+#[should_panic(
+    expected = "selected Some(1) with fitness Some(1) at depth 0, but the best action is Some(0) with fitness Some(5)"
+)]
+fn assert_bot_worse() {
+    let tree = Node::root().with_children(&[Node::new(true, 5), Node::new(true, 1)]);
+    Brute::new(true).assert_bot(&mut Last, &tree, 0);
+}
+
+#[test]
+fn print_best() {
+    let tree = Node::root().with_children(&[Node::new(true, 1), Node::new(true, 5)]);
+    let mut out = Vec::new();
+    Brute::new(true).print_best(&mut out, &tree, 0).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "best: 1, fitness: 5\n");
+}
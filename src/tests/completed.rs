@@ -275,3 +275,563 @@ fn with_my_dying_breath() {
     ]);
     assert_eq!(Bot::new(true).select(&tree, ToCompletion), Some(1));
 }
+
+/// A token on a line of 5 positions, which both players move in alternating turns.
+/// The player `true` wins by moving the token to `4`, while `false` wins at `0`.
+///
+/// Playing optimally, neither player ever wins, so this game never terminates
+/// unless repeated states are detected.
+#[test]
+fn cycle_detection() {
+    use crate::Game;
+
+    #[derive(Clone, Hash)]
+    struct Token {
+        position: i8,
+        active: bool,
+    }
+
+    impl Game for Token {
+        type Player = bool;
+        type Action = i8;
+        type Fitness = i8;
+        type Actions = Vec<i8>;
+
+        fn actions(&self, player: bool) -> (bool, Vec<i8>) {
+            let actions = if self.position == 0 || self.position == 4 {
+                Vec::new()
+            } else {
+                vec![-1, 1]
+            };
+            (player == self.active, actions)
+        }
+
+        fn execute(&mut self, action: &i8, player: bool) -> i8 {
+            self.position += action;
+            self.active = !self.active;
+            match self.position {
+                0 if player => -1,
+                4 if player => 1,
+                0 => 1,
+                4 => -1,
+                _ => 0,
+            }
+        }
+    }
+
+    let token = Token {
+        position: 2,
+        active: true,
+    };
+    let action = Bot::new(true)
+        .with_cycle_detection(0)
+        .detailed_select(&token, ToCompletion)
+        .unwrap();
+    assert_eq!(action.fitness, 0);
+
+    let token = Token {
+        position: 3,
+        active: true,
+    };
+    let action = Bot::new(true)
+        .with_cycle_detection(0)
+        .detailed_select(&token, ToCompletion)
+        .unwrap();
+    assert_eq!((action.path[0], action.fitness), (1, 1));
+}
+
+/// Extended actions have longer paths than the depth of the next iteration,
+/// which must not cause problems once they are searched again.
+#[test]
+fn extensions() {
+    use crate::brute::Brute;
+
+    for seed in 0..200u32 {
+        let bytes: Vec<u8> = seed
+            .to_be_bytes()
+            .iter()
+            .copied()
+            .chain((0..40u8).map(|i| (seed as u8).wrapping_mul(i).wrapping_add(i.wrapping_mul(7))))
+            .collect();
+        let tree = Node::from_bytes(&bytes);
+        let selected = Bot::new(true)
+            .with_volatility_extension(0, 10)
+            .with_selective_deepening(0, 3)
+            .select(&tree, ToCompletion);
+        assert!(
+            Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+            "seed: {}",
+            seed
+        );
+    }
+}
+
+/// Completely searched results must be proven, while results of the endgame solver
+/// must be the same as the ones of a complete search.
+#[test]
+fn proven() {
+    use crate::brute::Brute;
+
+    for seed in 0..100u32 {
+        let bytes: Vec<u8> = seed
+            .to_be_bytes()
+            .iter()
+            .copied()
+            .chain((0..30u8).map(|i| (seed as u8).wrapping_add(i.wrapping_mul(13))))
+            .collect();
+        let tree = Node::from_bytes(&bytes);
+        if let Some(action) = Bot::new(true).detailed_select(&tree, ToCompletion) {
+            assert!(action.proven, "seed: {}", seed);
+        }
+
+        let solved = Bot::new(true)
+            .with_endgame_solver(u64::MAX)
+            .detailed_select(&tree, Depth(1));
+        if let Some(action) = solved {
+            assert!(action.proven, "seed: {}", seed);
+            assert!(
+                Brute::new(true).check_if_best(&tree, Some(&action.path[0]), u32::MAX),
+                "seed: {}",
+                seed
+            );
+        }
+    }
+}
+
+/// A hint must only change the order in which actions are searched.
+#[test]
+fn hints() {
+    use crate::brute::Brute;
+
+    for seed in 0..100u32 {
+        let bytes: Vec<u8> = seed
+            .to_be_bytes()
+            .iter()
+            .copied()
+            .chain((0..30u8).map(|i| (seed as u8).wrapping_mul(7).wrapping_add(i)))
+            .collect();
+        let tree = Node::from_bytes(&bytes);
+        for hint in 0..tree.child_count() {
+            let mut bot = Bot::new(true);
+            bot.set_hint(hint);
+            let selected = bot.select(&tree, ToCompletion);
+            assert!(
+                Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+                "seed: {}, hint: {}",
+                seed,
+                hint
+            );
+        }
+    }
+}
+
+/// Searching very long lines must not overflow the stack.
+#[test]
+fn deep_search() {
+    use crate::Game;
+
+    /// Players alternately remove a single token, the player who removes the last one wins.
+    #[derive(Clone)]
+    struct Countdown(u32);
+
+    impl Game for Countdown {
+        type Player = bool;
+        type Action = ();
+        type Fitness = i8;
+        type Actions = Option<()>;
+
+        fn actions(&self, player: bool) -> (bool, Self::Actions) {
+            let active = player == (self.0 & 1 == 0);
+            (active && self.0 > 0, Some(()).filter(|_| self.0 > 0))
+        }
+
+        fn execute(&mut self, _: &(), player: bool) -> i8 {
+            let mover = self.0 & 1 == 0;
+            self.0 -= 1;
+            match self.0 {
+                0 if mover == player => 1,
+                0 => -1,
+                _ => 0,
+            }
+        }
+    }
+
+    let mut bot = Bot::new(true);
+    let action = bot.detailed_select(&Countdown(2000), ToCompletion).unwrap();
+    assert_eq!(action.fitness, -1);
+    assert_eq!(action.path.len(), 2000);
+}
+
+/// Reusing results of transpositions must not change the selected action.
+#[test]
+fn transposition_table() {
+    use crate::brute::Brute;
+    use crate::Game;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn hash<T: Hash>(state: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        state.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Players alternately increment either coordinate until their sum reaches 10,
+    /// reaching the same state using many different paths.
+    #[derive(Clone, PartialEq, Eq, Hash)]
+    struct Walk(u8, u8, bool);
+
+    impl Game for Walk {
+        type Player = bool;
+        type Action = bool;
+        type Fitness = i8;
+        type Actions = Vec<bool>;
+
+        fn actions(&self, player: bool) -> (bool, Vec<bool>) {
+            let actions = if self.0 + self.1 < 10 {
+                vec![false, true]
+            } else {
+                Vec::new()
+            };
+            (player == self.2, actions)
+        }
+
+        fn execute(&mut self, &action: &bool, _: bool) -> i8 {
+            if action {
+                self.1 += 1;
+            } else {
+                self.0 += 1;
+            }
+            self.2 = !self.2;
+            (self.0 * 3 + self.1 * 5) as i8 % 11 - 5
+        }
+    }
+
+    for start in [Walk(0, 0, true), Walk(1, 2, true), Walk(4, 0, true)].iter() {
+        let mut plain = Bot::new(true);
+        let mut bot = Bot::new(true).with_transposition_table(1 << 8, hash);
+        let action = bot.detailed_select(start, ToCompletion).unwrap();
+        assert_eq!(
+            action.fitness,
+            plain.detailed_select(start, ToCompletion).unwrap().fitness
+        );
+        assert!(Brute::new(true).check_if_best(start, Some(&action.path[0]), u32::MAX));
+        assert!(bot.stats().transpositions > 0);
+        assert!(bot.stats().nodes < plain.stats().nodes);
+    }
+
+    for seed in 0..100u32 {
+        let bytes: Vec<u8> = seed
+            .to_be_bytes()
+            .iter()
+            .copied()
+            .chain((0..30u8).map(|i| (seed as u8).wrapping_mul(3).wrapping_add(i / 4)))
+            .collect();
+        let tree = Node::from_bytes(&bytes);
+        let selected = Bot::new(true)
+            .with_transposition_table(16, hash)
+            .select(&tree, ToCompletion);
+        assert!(
+            Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+            "seed: {}",
+            seed
+        );
+    }
+}
+
+/// Each ranked action must have its exact fitness.
+#[test]
+fn rank_actions() {
+    use crate::brute::Brute;
+
+    for seed in 0..100u32 {
+        let bytes: Vec<u8> = seed
+            .to_be_bytes()
+            .iter()
+            .copied()
+            .chain((0..30u8).map(|i| (seed as u8).wrapping_mul(17).wrapping_add(i)))
+            .collect();
+        let tree = Node::from_bytes(&bytes);
+        let ranked = Bot::new(true).rank_actions(&tree, ToCompletion);
+        assert_eq!(ranked.len(), tree.child_count(), "seed: {}", seed);
+        for (action, fitness) in ranked.iter() {
+            assert_eq!(
+                *fitness,
+                Brute::new(true).fitness(&tree, action, u32::MAX),
+                "seed: {}, action: {}",
+                seed,
+                action
+            );
+        }
+        assert!(
+            ranked.windows(2).all(|w| w[0].1 >= w[1].1),
+            "seed: {}",
+            seed
+        );
+    }
+}
+
+/// Aspiration windows must not change the fitness of the selected action.
+#[test]
+fn aspiration_windows() {
+    use crate::brute::Brute;
+    use crate::Depth;
+
+    for seed in 0..100u32 {
+        let bytes: Vec<u8> = seed
+            .to_be_bytes()
+            .iter()
+            .copied()
+            .chain((0..30u8).map(|i| (seed as u8).wrapping_mul(7).wrapping_add(i * 3)))
+            .collect();
+        let tree = Node::from_bytes(&bytes);
+        for &margin in [0, 1, 4].iter() {
+            let mut bot = Bot::new(true).with_aspiration_windows(margin);
+            let selected = bot.select(&tree, ToCompletion);
+            assert!(
+                Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+                "seed: {}, margin: {}",
+                seed,
+                margin
+            );
+
+            let expected = Bot::new(true).detailed_select(&tree, Depth(2));
+            let actual = bot.detailed_select(&tree, Depth(2));
+            assert_eq!(
+                expected.map(|act| act.fitness),
+                actual.map(|act| act.fitness),
+                "seed: {}, margin: {}",
+                seed,
+                margin
+            );
+        }
+    }
+}
+
+/// MTD(f) must not change the fitness of the selected action, both with and without a transposition table.
+#[test]
+fn mtdf() {
+    use crate::brute::Brute;
+    use crate::Depth;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn hash(node: &Node) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        node.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    for seed in 0..100u32 {
+        let bytes: Vec<u8> = seed
+            .to_be_bytes()
+            .iter()
+            .copied()
+            .chain((0..30u8).map(|i| (seed as u8).wrapping_mul(5).wrapping_add(i.wrapping_mul(9))))
+            .collect();
+        let tree = Node::from_bytes(&bytes);
+        for &table in [false, true].iter() {
+            let new_bot = || {
+                let bot = Bot::new(true).with_mtdf();
+                if table {
+                    bot.with_transposition_table(64, hash)
+                } else {
+                    bot
+                }
+            };
+            let selected = new_bot().select(&tree, ToCompletion);
+            assert!(
+                Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+                "seed: {}, table: {}",
+                seed,
+                table
+            );
+
+            let expected = Bot::new(true).detailed_select(&tree, Depth(2));
+            let actual = new_bot().detailed_select(&tree, Depth(2));
+            assert_eq!(
+                expected.map(|act| act.fitness),
+                actual.map(|act| act.fitness),
+                "seed: {}, table: {}",
+                seed,
+                table
+            );
+        }
+    }
+}
+
+/// Principal variation search must not change the fitness of the selected action.
+#[test]
+fn principal_variation_search() {
+    use crate::brute::Brute;
+    use crate::Depth;
+
+    let mut researches = 0;
+    for seed in 0..100u32 {
+        let bytes: Vec<u8> = seed
+            .to_be_bytes()
+            .iter()
+            .copied()
+            .chain((0..30u8).map(|i| {
+                (seed as u8)
+                    .wrapping_mul(11)
+                    .wrapping_add(i.wrapping_mul(13))
+            }))
+            .collect();
+        let tree = Node::from_bytes(&bytes);
+        let mut bot = Bot::new(true).with_principal_variation_search();
+        let selected = bot.select(&tree, ToCompletion);
+        researches += bot.stats().pvs_researches;
+        assert!(
+            Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+            "seed: {}",
+            seed
+        );
+
+        let expected = Bot::new(true).detailed_select(&tree, Depth(2));
+        let actual = bot.detailed_select(&tree, Depth(2));
+        assert_eq!(
+            expected.map(|act| act.fitness),
+            actual.map(|act| act.fitness),
+            "seed: {}",
+            seed
+        );
+    }
+    assert!(researches > 0);
+}
+
+/// Helper threads must not change the result of a complete search.
+#[test]
+fn threads() {
+    use crate::brute::Brute;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn hash(node: &Node) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        node.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    for seed in 0..50u32 {
+        let bytes: Vec<u8> = seed
+            .to_be_bytes()
+            .iter()
+            .copied()
+            .chain((0..40u8).map(|i| (seed as u8).wrapping_mul(3).wrapping_add(i / 3)))
+            .collect();
+        let tree = Node::from_bytes(&bytes);
+        let expected = Bot::new(true).detailed_select(&tree, ToCompletion);
+        let mut bot = Bot::new(true)
+            .with_transposition_table(64, hash)
+            .with_threads(4);
+        let actual = bot.detailed_select(&tree, ToCompletion);
+        assert_eq!(
+            expected.as_ref().map(|act| act.fitness),
+            actual.as_ref().map(|act| act.fitness),
+            "seed: {}",
+            seed
+        );
+        let selected = actual.map(|act| act.path[0]);
+        assert!(
+            Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+            "seed: {}",
+            seed
+        );
+    }
+}
+
+/// Deterministic helper threads must return the same action and statistics in every search,
+/// without changing the result of a complete search.
+#[test]
+fn deterministic_threads() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    use std::time::Duration;
+
+    fn hash(node: &Node) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        node.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    for seed in 0..50u32 {
+        let bytes: Vec<u8> = seed
+            .to_be_bytes()
+            .iter()
+            .copied()
+            .chain((0..40u8).map(|i| (seed as u8).wrapping_mul(7).wrapping_add(i / 3)))
+            .collect();
+        let tree = Node::from_bytes(&bytes);
+        let expected = Bot::new(true).detailed_select(&tree, ToCompletion);
+        let search = || {
+            let mut bot = Bot::new(true)
+                .with_transposition_table(64, hash)
+                .with_deterministic_threads(3);
+            let action = bot
+                .detailed_select(&tree, ToCompletion)
+                .map(|act| (act.fitness, act.path));
+            let mut stats = bot.stats().clone();
+            stats.duration = Duration::default();
+            (action, stats)
+        };
+        let (action, stats) = search();
+        assert_eq!(
+            expected.map(|act| act.fitness),
+            action.as_ref().map(|&(fitness, _)| fitness),
+            "seed: {}",
+            seed
+        );
+        for _ in 0..3 {
+            assert_eq!(search(), (action.clone(), stats.clone()), "seed: {}", seed);
+        }
+    }
+}
+
+/// Helper threads must use the policy of the bot instead of silently searching without one.
+#[test]
+fn threads_policy() {
+    use crate::Policy;
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashSet;
+    use std::hash::{Hash, Hasher};
+    use std::sync::{Arc, Mutex};
+    use std::thread::{self, ThreadId};
+    use std::time::Duration;
+
+    fn hash(node: &Node) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        node.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Records the threads using it, waiting until a second thread used it.
+    struct Recorded(Arc<Mutex<HashSet<ThreadId>>>);
+
+    impl Policy<Node> for Recorded {
+        fn priors(&self, _: &Node, actions: &[usize], _: bool, priors: &mut Vec<f64>) {
+            let mut threads = self.0.lock().unwrap();
+            threads.insert(thread::current().id());
+            if threads.len() == 1 {
+                drop(threads);
+                thread::sleep(Duration::from_millis(1));
+            }
+            priors.extend(actions.iter().map(|_| 1.0));
+        }
+    }
+
+    let bytes: Vec<u8> = (0..40u8).map(|i| i.wrapping_mul(37)).collect();
+    let tree = Node::from_bytes(&bytes);
+    let threads = Arc::new(Mutex::new(HashSet::new()));
+    let expected = Bot::new(true).detailed_select(&tree, ToCompletion);
+    let actual = Bot::new(true)
+        .with_policy(Recorded(Arc::clone(&threads)))
+        .with_transposition_table(64, hash)
+        .with_threads(2)
+        .detailed_select(&tree, ToCompletion);
+    assert_eq!(
+        expected.map(|act| act.fitness),
+        actual.map(|act| act.fitness)
+    );
+    assert_eq!(threads.lock().unwrap().len(), 2);
+}
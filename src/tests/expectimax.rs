@@ -0,0 +1,119 @@
+//! Tests for `expectimax::Bot`.
+use super::*;
+use crate::brute::Brute;
+use crate::expectimax::{self, Chance};
+use crate::Game;
+
+/// A tree without random events.
+#[derive(Clone)]
+struct Deterministic(Node);
+
+impl Game for Deterministic {
+    type Player = bool;
+    type Action = usize;
+    type Fitness = i8;
+    type Actions = std::ops::Range<usize>;
+
+    fn actions(&self, player: bool) -> (bool, Self::Actions) {
+        self.0.actions(player)
+    }
+
+    fn execute(&mut self, action: &usize, player: bool) -> i8 {
+        self.0.execute(action, player)
+    }
+}
+
+impl Chance for Deterministic {
+    fn outcomes(&self) -> Vec<(usize, f64)> {
+        Vec::new()
+    }
+}
+
+/// Without random events, the result must be the same as the one of minimax.
+#[test]
+fn deterministic() {
+    for seed in 0..100u32 {
+        let bytes: Vec<u8> = seed
+            .to_be_bytes()
+            .iter()
+            .copied()
+            .chain((0..30u8).map(|i| (seed as u8).wrapping_mul(13).wrapping_add(i)))
+            .collect();
+        let tree = Node::from_bytes(&bytes);
+        let mut bot = expectimax::Bot::new(true);
+        let selected = bot.select(&Deterministic(tree.clone()), ToCompletion);
+        assert!(
+            Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+            "seed: {}",
+            seed
+        );
+
+        for depth in 0..3 {
+            let selected = bot.select(&Deterministic(tree.clone()), Depth(depth));
+            assert!(
+                Brute::new(true).check_if_best(&tree, selected.as_ref(), depth),
+                "seed: {}, depth: {}",
+                seed,
+                depth
+            );
+        }
+    }
+}
+
+/// The opponent must still be expected to choose the worst action for the bot,
+/// while random events are weighted by their probability.
+#[test]
+fn opponent_and_chance() {
+    /// The bot chooses a coin, after which the opponent decides whether it is flipped.
+    /// A flipped coin either doubles or loses the stake of the chosen coin.
+    #[derive(Clone)]
+    enum Coin {
+        Choose,
+        Decide(i8, f64),
+        Flip(i8, f64),
+        Done,
+    }
+
+    impl Game for Coin {
+        type Player = bool;
+        type Action = usize;
+        type Fitness = i8;
+        type Actions = std::ops::Range<usize>;
+
+        fn actions(&self, player: bool) -> (bool, Self::Actions) {
+            match self {
+                Coin::Choose => (player, 0..2),
+                Coin::Decide(..) => (!player, 0..2),
+                _ => (false, 0..0),
+            }
+        }
+
+        fn execute(&mut self, &action: &usize, _: bool) -> i8 {
+            let (next, fitness) = match *self {
+                // A fair coin with a stake of `2` or a biased coin with a stake of `3`.
+                Coin::Choose if action == 0 => (Coin::Decide(2, 0.5), 0),
+                Coin::Choose => (Coin::Decide(3, 0.25), 0),
+                Coin::Decide(stake, _) if action == 0 => (Coin::Done, stake),
+                Coin::Decide(stake, p) => (Coin::Flip(stake, p), 0),
+                Coin::Flip(stake, _) if action == 0 => (Coin::Done, stake * 2),
+                Coin::Flip(..) | Coin::Done => (Coin::Done, 0),
+            };
+            *self = next;
+            fitness
+        }
+    }
+
+    impl Chance for Coin {
+        fn outcomes(&self) -> Vec<(usize, f64)> {
+            match *self {
+                Coin::Flip(_, p) => vec![(0, p), (1, 1.0 - p)],
+                _ => Vec::new(),
+            }
+        }
+    }
+
+    // Flipping the fair coin is expected to keep the stake of `2`, which is better than
+    // the expected fitness of `1.5` of flipping the biased coin.
+    let mut bot = expectimax::Bot::new(true);
+    assert_eq!(bot.select(&Coin::Choose, ToCompletion), Some(0));
+}
@@ -0,0 +1,36 @@
+//! Tests for `greedy::Bot`.
+use super::*;
+use crate::brute::Brute;
+use crate::greedy;
+
+/// The result must be the best action when only searching a single action.
+#[test]
+fn one_ply() {
+    for seed in 0..100u32 {
+        let bytes: Vec<u8> = seed
+            .to_be_bytes()
+            .iter()
+            .copied()
+            .chain((0..30u8).map(|i| (seed as u8).wrapping_mul(17).wrapping_add(i)))
+            .collect();
+        let tree = Node::from_bytes(&bytes);
+        let selected = greedy::Bot::new(true).select(&tree);
+        assert!(
+            Brute::new(true).check_if_best(&tree, selected.as_ref(), 0),
+            "seed: {}",
+            seed
+        );
+    }
+}
+
+#[test]
+fn first_of_equal() {
+    let tree = Node::root().with_children(&[
+        Node::new(false, 3),
+        Node::new(false, 5),
+        Node::new(false, 5),
+    ]);
+    assert_eq!(greedy::Bot::new(true).select(&tree), Some(1));
+    assert_eq!(greedy::Bot::new(false).select(&tree), None);
+    assert_eq!(greedy::Bot::new(true).select(&Node::root()), None);
+}
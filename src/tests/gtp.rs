@@ -0,0 +1,113 @@
+//! Tests for `protocol::gtp::Engine`.
+use super::*;
+use crate::protocol::gtp::{Codec, Engine};
+use crate::Game;
+
+/// Moves are the index of the chosen child, the size is the amount of bytes used by `Node::from_bytes`.
+struct TreeCodec;
+
+impl Codec<Node> for TreeCodec {
+    fn new_game(&self, size: Option<u32>) -> Option<Node> {
+        let bytes = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9, 3, 2, 3, 8, 4];
+        let size = size.unwrap_or(bytes.len() as u32) as usize;
+        bytes.get(..size).map(Node::from_bytes)
+    }
+
+    fn parse_color(&self, color: &str) -> Option<bool> {
+        match color {
+            "b" | "black" => Some(true),
+            "w" | "white" => Some(false),
+            _ => None,
+        }
+    }
+
+    fn parse_move(&self, _: &Node, _: bool, vertex: &str) -> Option<usize> {
+        vertex.parse().ok()
+    }
+
+    fn format_move(&self, _: &Node, _: bool, action: &usize) -> String {
+        action.to_string()
+    }
+}
+
+fn run(input: &str) -> (Engine<Node, TreeCodec>, Vec<String>) {
+    let mut engine = Engine::new(TreeCodec, Bot::new(true));
+    let mut output = Vec::new();
+    engine.run(input.as_bytes(), &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    let responses = output
+        .strip_suffix("\n\n")
+        .unwrap()
+        .split("\n\n")
+        .map(String::from)
+        .collect();
+    (engine, responses)
+}
+
+/// Commands may have an id and comments, empty lines are ignored.
+#[test]
+fn commands() {
+    let (_, responses) = run(concat!(
+        "protocol_version\n",
+        "\n",
+        "# a comment\n",
+        "7 known_command genmove # another comment\n",
+        "known_command showboard\n",
+        "list_commands\n",
+        "showboard\n",
+        "3 komi x\n",
+        "komi 6.5\n",
+        "quit\n",
+        "name\n",
+    ));
+    assert_eq!(
+        responses,
+        &[
+            "= 2",
+            "=7 true",
+            "= false",
+            "= protocol_version\nname\nversion\nknown_command\nlist_commands\nquit\nboardsize\nclear_board\nkomi\nplay\ngenmove\nundo",
+            "? unknown command",
+            "?3 syntax error",
+            "=",
+            "=",
+        ]
+    );
+}
+
+/// `genmove` selects the same move as `Bot::select`, executing it together with all played moves
+/// until they are undone. Once the game ended, the bot passes.
+#[test]
+fn moves() {
+    let mut expected = TreeCodec.new_game(None).unwrap();
+    let (engine, responses) = run("play b 1\nplay w 9\nundo\nundo\nundo\n");
+    assert_eq!(
+        responses,
+        &["=", "? illegal move", "=", "? cannot undo", "? cannot undo"]
+    );
+    assert_eq!(engine.state(), &expected);
+
+    let (engine, responses) = run("play b 1\ngenmove w\n");
+    expected.execute(&1, true);
+    let selected = Bot::new(false).select(&expected, ToCompletion).unwrap();
+    assert_eq!(responses[1], format!("= {}", selected));
+    expected.execute(&selected, false);
+    assert_eq!(engine.state(), &expected);
+
+    let mut input = String::new();
+    for _ in 0..20 {
+        input.push_str("genmove b\n");
+    }
+    let (engine, responses) = run(&input);
+    assert!(engine.state().actions(true).1.is_empty());
+    assert_eq!(responses.last().unwrap(), "= pass");
+}
+
+/// `boardsize` only changes the size if it is supported, which is then also used by `clear_board`.
+#[test]
+fn boardsize() {
+    let (engine, responses) = run("boardsize 30\nboardsize 4\ngenmove b\nclear_board\n");
+    assert_eq!(responses[..2], ["? unacceptable size", "="]);
+    assert_eq!(responses[3], "=");
+    assert_eq!(engine.state(), &TreeCodec.new_game(Some(4)).unwrap());
+}
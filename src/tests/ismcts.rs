@@ -0,0 +1,168 @@
+//! Tests for `ismcts::Bot`.
+use super::*;
+use crate::brute::Brute;
+use crate::ismcts::{self, HiddenInformationGame};
+use crate::{mcts, Game};
+
+/// A tree without hidden information.
+#[derive(Clone)]
+struct Visible(Node);
+
+impl Game for Visible {
+    type Player = bool;
+    type Action = usize;
+    type Fitness = i8;
+    type Actions = std::ops::Range<usize>;
+
+    fn actions(&self, player: bool) -> (bool, Self::Actions) {
+        self.0.actions(player)
+    }
+
+    fn execute(&mut self, action: &usize, player: bool) -> i8 {
+        self.0.execute(action, player)
+    }
+}
+
+impl HiddenInformationGame for Visible {
+    fn determinize(&self, _: bool, _: u64) -> Self {
+        self.clone()
+    }
+}
+
+/// Without hidden information, enough playouts must find the best action of small trees.
+#[test]
+fn visible() {
+    #[rustfmt::skip]
+    let tree = Node::root().with_children(&[
+        Node::new(false, 7).with_children(&[
+            Node::new(true, 4),
+            Node::new(true, 2),
+        ]),
+        Node::new(false, 5).with_children(&[
+            Node::new(true, 8),
+            Node::new(true, 9),
+        ]),
+        Node::new(false, 6),
+    ]);
+
+    let selected = ismcts::Bot::new(true)
+        .with_reward(|fitness| f64::from(fitness) / 10.0)
+        .select(&Visible(tree.clone()), Steps(2000));
+    assert!(Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX));
+}
+
+/// Interrupted searches must still return a possible action and be reproducible.
+#[test]
+fn interrupted() {
+    let tree = Node::from_bytes(&[3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9, 3, 2, 3, 8, 4]);
+    for steps in 0..8 {
+        let selected = ismcts::Bot::new(true)
+            .with_seed(7)
+            .select(&Visible(tree.clone()), Steps(steps));
+        assert!(selected.is_some(), "steps: {}", steps);
+        assert_eq!(
+            selected,
+            ismcts::Bot::new(true)
+                .with_seed(7)
+                .select(&Visible(tree.clone()), Steps(steps)),
+            "steps: {}",
+            steps
+        );
+    }
+}
+
+/// The bot either folds for `4` points or plays its card, after which the opponent reveals
+/// their hidden card. The bot gets `10` points if its card is higher and nothing otherwise.
+#[derive(Clone)]
+struct Cards {
+    card: u8,
+    hidden: u8,
+    played: bool,
+    done: bool,
+}
+
+impl Game for Cards {
+    type Player = bool;
+    type Action = u8;
+    type Fitness = i8;
+    type Actions = Vec<u8>;
+
+    fn actions(&self, player: bool) -> (bool, Vec<u8>) {
+        if self.done {
+            (false, Vec::new())
+        } else if self.played {
+            // The only action of the opponent depends on the hidden card.
+            (!player, vec![self.hidden])
+        } else {
+            // `0` folds, `1` plays the card.
+            (player, vec![0, 1])
+        }
+    }
+
+    fn execute(&mut self, &action: &u8, player: bool) -> i8 {
+        let fitness = if self.played {
+            self.done = true;
+            if self.card > self.hidden {
+                10
+            } else {
+                0
+            }
+        } else if action == 0 {
+            self.done = true;
+            4
+        } else {
+            self.played = true;
+            0
+        };
+        if player {
+            fitness
+        } else {
+            10 - fitness
+        }
+    }
+}
+
+impl HiddenInformationGame for Cards {
+    fn determinize(&self, _: bool, seed: u64) -> Self {
+        Cards {
+            hidden: (seed % 3) as u8,
+            ..self.clone()
+        }
+    }
+}
+
+/// The bot must not use the actual hidden card, which `mcts::Bot` is able to see.
+#[test]
+fn hidden_card() {
+    for &(card, expected) in &[(1, 0), (2, 1)] {
+        for hidden in 0..3 {
+            let state = Cards {
+                card,
+                hidden,
+                played: false,
+                done: false,
+            };
+            let selected = ismcts::Bot::new(true)
+                .with_reward(|fitness| f64::from(fitness) / 10.0)
+                .select(&state, Steps(2000));
+            assert_eq!(
+                selected,
+                Some(expected),
+                "card: {}, hidden: {}",
+                card,
+                hidden
+            );
+        }
+    }
+
+    let state = Cards {
+        card: 1,
+        hidden: 0,
+        played: false,
+        done: false,
+    };
+    let cheating = mcts::Bot::new(true)
+        .with_reward(|fitness| f64::from(fitness) / 10.0)
+        .select(&state, ToCompletion);
+    assert_eq!(cheating, Some(1));
+}
@@ -0,0 +1,153 @@
+//! Tests for `maxn::Bot`.
+use super::*;
+use crate::brute::Brute;
+use crate::maxn::{self, Multiplayer};
+use crate::Game;
+
+/// A tree with two players, where the fitness of the opponent is the negated fitness of the bot.
+#[derive(Clone)]
+struct ZeroSum {
+    node: Node,
+    fitness: i8,
+}
+
+impl Game for ZeroSum {
+    type Player = bool;
+    type Action = usize;
+    type Fitness = i16;
+    type Actions = std::ops::Range<usize>;
+
+    fn actions(&self, player: bool) -> (bool, Self::Actions) {
+        self.node.actions(player)
+    }
+
+    fn execute(&mut self, action: &usize, player: bool) -> i16 {
+        self.fitness = self.node.execute(action, player);
+        self.evaluate(player)
+    }
+}
+
+impl Multiplayer for ZeroSum {
+    fn players(&self) -> Vec<bool> {
+        vec![true, false]
+    }
+
+    fn evaluate(&self, player: bool) -> i16 {
+        if player {
+            i16::from(self.fitness)
+        } else {
+            -i16::from(self.fitness)
+        }
+    }
+}
+
+/// For zero-sum games with two players, the result must be the same as the one of minimax.
+#[test]
+fn zero_sum() {
+    for seed in 0..100u32 {
+        let bytes: Vec<u8> = seed
+            .to_be_bytes()
+            .iter()
+            .copied()
+            .chain((0..30u8).map(|i| (seed as u8).wrapping_mul(11).wrapping_add(i)))
+            .collect();
+        let tree = Node::from_bytes(&bytes);
+        let state = ZeroSum {
+            node: tree.clone(),
+            fitness: 0,
+        };
+        let mut bot = maxn::Bot::new(true);
+        let selected = bot.select(&state, ToCompletion);
+        assert!(
+            Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+            "seed: {}",
+            seed
+        );
+
+        for depth in 0..3 {
+            let selected = bot.select(&state, Depth(depth));
+            assert!(
+                Brute::new(true).check_if_best(&tree, selected.as_ref(), depth),
+                "seed: {}, depth: {}",
+                seed,
+                depth
+            );
+        }
+    }
+}
+
+/// Each player must choose the action which is best for themselves.
+#[test]
+fn three_players() {
+    /// Each player in turn adds `1` or `2` points to one of the other two players, the state is
+    /// the amount of executed actions and the points of each player.
+    #[derive(Clone)]
+    struct Gift(usize, [i8; 3]);
+
+    impl Game for Gift {
+        type Player = usize;
+        type Action = (usize, i8);
+        type Fitness = i8;
+        type Actions = Vec<(usize, i8)>;
+
+        fn actions(&self, player: usize) -> (bool, Self::Actions) {
+            let active = self.0 % 3;
+            let actions = if self.0 < 4 {
+                let others = (0..3).filter(|&other| other != active);
+                others
+                    .flat_map(|other| vec![(other, 1), (other, 2)])
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            (active == player, actions)
+        }
+
+        fn execute(&mut self, &(other, points): &(usize, i8), player: usize) -> i8 {
+            self.0 += 1;
+            self.1[other] += points;
+            self.evaluate(player)
+        }
+    }
+
+    impl Multiplayer for Gift {
+        fn players(&self) -> Vec<usize> {
+            vec![0, 1, 2]
+        }
+
+        // Each player wants to have more points than both other players.
+        fn evaluate(&self, player: usize) -> i8 {
+            let others = (0..3).filter(|&other| other != player);
+            self.1[player] - others.map(|other| self.1[other]).max().unwrap()
+        }
+    }
+
+    /// Returns the fitness of each player if all of them choose their best action,
+    /// preferring earlier actions in case of a tie, like `maxn::Bot`.
+    fn exhaustive(state: &Gift) -> Vec<i8> {
+        let active = state.0 % 3;
+        let (_, actions) = state.actions(active);
+        let values = actions.iter().map(|action| {
+            let mut state = state.clone();
+            state.execute(action, active);
+            exhaustive(&state)
+        });
+        let best = values.fold(None, |best: Option<Vec<i8>>, value| match best {
+            Some(best) if best[active] >= value[active] => Some(best),
+            _ => Some(value),
+        });
+        best.unwrap_or_else(|| (0..3).map(|player| state.evaluate(player)).collect())
+    }
+
+    for turn in 0..4 {
+        let state = Gift(turn, [0; 3]);
+        let active = turn % 3;
+        let selected = maxn::Bot::new(active).select(&state, ToCompletion).unwrap();
+        let mut after = state.clone();
+        after.execute(&selected, active);
+        assert_eq!(exhaustive(&after), exhaustive(&state), "turn: {}", turn);
+
+        let inactive = (active + 1) % 3;
+        assert_eq!(maxn::Bot::new(inactive).select(&state, ToCompletion), None);
+    }
+}
@@ -0,0 +1,293 @@
+//! Tests for `mcts::Bot`.
+use super::*;
+use crate::brute::Brute;
+use crate::mcts;
+
+/// Once the complete tree was explored, the result must be optimal.
+#[test]
+fn completed() {
+    for seed in 0..100u32 {
+        let bytes: Vec<u8> = seed
+            .to_be_bytes()
+            .iter()
+            .copied()
+            .chain((0..30u8).map(|i| (seed as u8).wrapping_mul(11).wrapping_add(i)))
+            .collect();
+        let tree = Node::from_bytes(&bytes);
+        let selected = mcts::Bot::new(true)
+            .with_seed(u64::from(seed))
+            .select(&tree, ToCompletion);
+        assert!(
+            Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+            "seed: {}",
+            seed
+        );
+    }
+}
+
+/// Interrupted searches must still return a possible action and be reproducible.
+#[test]
+fn interrupted() {
+    #[rustfmt::skip]
+    let tree = Node::root().with_children(&[
+        Node::new(false, 1).with_children(&[
+            Node::new(true, 4),
+            Node::new(true, 2),
+        ]),
+        Node::new(false, 5).with_children(&[
+            Node::new(true, 8),
+            Node::new(true, 9),
+        ]),
+        Node::new(false, 3),
+    ]);
+
+    for steps in 0..8 {
+        let selected = mcts::Bot::new(true)
+            .with_seed(7)
+            .select(&tree, Steps(steps));
+        assert!(selected.is_some(), "steps: {}", steps);
+        assert_eq!(
+            selected,
+            mcts::Bot::new(true)
+                .with_seed(7)
+                .select(&tree, Steps(steps)),
+            "steps: {}",
+            steps
+        );
+    }
+}
+
+/// After completely exploring the tree once, the kept subtrees must be enough to play optimally.
+#[test]
+fn reuse() {
+    use crate::Game;
+
+    for seed in 0..50u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..40u8).map(|i| (seed as u8).wrapping_mul(7).wrapping_add(i * 3)));
+        let mut tree = Node::from_bytes(&bytes);
+
+        let mut bot = mcts::Bot::new(true)
+            .with_seed(u64::from(seed))
+            .with_tree_reuse();
+        let mut searched = false;
+        loop {
+            let (active, mut actions) = tree.actions(true);
+            let action = if active {
+                let selected = if searched {
+                    bot.select(&tree, Steps(0))
+                } else {
+                    searched = true;
+                    bot.select(&tree, ToCompletion)
+                };
+                assert!(
+                    Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+                    "seed: {}",
+                    seed
+                );
+                selected
+            } else {
+                actions.next()
+            };
+            match action {
+                Some(action) => {
+                    tree.execute(&action, true);
+                    bot.advance(&action);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+/// Prefers actions with a larger index.
+struct Ascending;
+
+impl crate::Policy<Node> for Ascending {
+    fn priors(&self, _: &Node, actions: &[usize], _: bool, priors: &mut Vec<f64>) {
+        priors.extend(actions.iter().map(|&a| a as f64));
+    }
+}
+
+/// A policy must not change the result once the complete tree was explored.
+#[test]
+fn policy_completed() {
+    for seed in 0..100u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..30u8).map(|i| (seed as u8).wrapping_mul(13).wrapping_add(i * 5)));
+        let tree = Node::from_bytes(&bytes);
+        let selected = mcts::Bot::new(true)
+            .with_seed(u64::from(seed))
+            .with_policy(Ascending)
+            .select(&tree, ToCompletion);
+        assert!(
+            Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+            "seed: {}",
+            seed
+        );
+    }
+}
+
+/// Only prefers the action `17`.
+struct Oracle;
+
+impl crate::Policy<Node> for Oracle {
+    fn priors(&self, _: &Node, actions: &[usize], _: bool, priors: &mut Vec<f64>) {
+        priors.extend(actions.iter().map(|&a| if a == 17 { 1.0 } else { 0.0 }));
+    }
+}
+
+/// Actions with a high prior are searched first.
+#[test]
+fn policy_order() {
+    let mut tree = Node::root();
+    for i in 0..30 {
+        tree.push_child(Node::new(false, if i == 17 { 9 } else { 1 }));
+    }
+
+    for steps in 1..30 {
+        let selected = mcts::Bot::new(true)
+            .with_reward(|fitness| f64::from(fitness) / 10.0)
+            .with_policy(Oracle)
+            .select(&tree, Steps(steps));
+        assert_eq!(selected, Some(17), "steps: {}", steps);
+    }
+}
+
+/// A game with `300` actions at the initial state and `3` actions afterwards,
+/// which records all actions executed at the initial state.
+#[derive(Clone, Default)]
+struct Wide {
+    ply: u32,
+    expanded: std::rc::Rc<std::cell::RefCell<std::collections::HashSet<u32>>>,
+}
+
+impl crate::Game for Wide {
+    type Player = bool;
+    type Action = u32;
+    type Fitness = u32;
+    type Actions = std::ops::Range<u32>;
+
+    fn actions(&self, player: bool) -> (bool, Self::Actions) {
+        match self.ply {
+            0 => (player, 0..300),
+            1..=20 => (player == (self.ply & 1 == 0), 0..3),
+            _ => (player, 0..0),
+        }
+    }
+
+    fn execute(&mut self, &action: &u32, _: bool) -> u32 {
+        if self.ply == 0 {
+            self.expanded.borrow_mut().insert(action);
+        }
+        self.ply += 1;
+        action
+    }
+
+    fn look_ahead(&self, &action: &u32, _: bool) -> u32 {
+        action
+    }
+}
+
+/// With progressive widening, the amount of children of the initial state grows with its visits.
+#[test]
+fn progressive_widening() {
+    for &(widening, expected) in &[(None, 300), (Some((1.0, 0.5)), 32), (Some((2.0, 0.25)), 12)] {
+        let state = Wide::default();
+        let mut bot = mcts::Bot::new(true);
+        if let Some((constant, exponent)) = widening {
+            bot = bot.with_progressive_widening(constant, exponent);
+        }
+        bot.select(&state, Steps(1000));
+        assert_eq!(
+            state.expanded.borrow().len(),
+            expected,
+            "widening: {:?}",
+            widening
+        );
+    }
+}
+
+/// Progressive widening must not change the result once the complete tree was explored.
+#[test]
+fn progressive_widening_completed() {
+    for seed in 0..100u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..30u8).map(|i| (seed as u8).wrapping_mul(19).wrapping_add(i * 3)));
+        let tree = Node::from_bytes(&bytes);
+        let selected = mcts::Bot::new(true)
+            .with_seed(u64::from(seed))
+            .with_progressive_widening(1.0, 0.25)
+            .select(&tree, ToCompletion);
+        assert!(
+            Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+            "seed: {}",
+            seed
+        );
+    }
+}
+
+/// The search must stop once the search tree contains the maximum amount of nodes.
+#[test]
+fn max_nodes() {
+    for &nodes in &[1, 2, 21, 250] {
+        let state = Wide::default();
+        let selected = mcts::Bot::new(true)
+            .with_max_nodes(nodes)
+            .select(&state, Steps(1000));
+        assert!(selected.is_some(), "nodes: {}", nodes);
+        // All nodes except the initial state are children of the initial state.
+        assert_eq!(state.expanded.borrow().len(), nodes - 1, "nodes: {}", nodes);
+    }
+}
+
+/// The last action of each playout must be evaluated using `Game::look_ahead_batch`.
+#[test]
+fn look_ahead_batch() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Each player has three actions until ply `20`, counting the calls to `look_ahead_batch`.
+    #[derive(Clone, Default)]
+    struct Batched {
+        ply: u32,
+        batches: Rc<Cell<u32>>,
+    }
+
+    impl crate::Game for Batched {
+        type Player = bool;
+        type Action = u32;
+        type Fitness = u32;
+        type Actions = std::ops::Range<u32>;
+
+        fn actions(&self, player: bool) -> (bool, Self::Actions) {
+            let actions = if self.ply < 20 { 0..3 } else { 0..0 };
+            (player == (self.ply & 1 == 0), actions)
+        }
+
+        fn execute(&mut self, &action: &u32, _: bool) -> u32 {
+            self.ply += 1;
+            action
+        }
+
+        fn look_ahead_batch(&self, actions: &[u32], player: bool, fitness: &mut Vec<u32>) {
+            assert_eq!(actions, &[0, 1, 2]);
+            self.batches.set(self.batches.get() + 1);
+            fitness.extend(actions.iter().map(|a| self.look_ahead(a, player)));
+        }
+    }
+
+    for &(playout_depth, expected) in &[(0, 0), (4, 49), (64, 0)] {
+        let state = Batched::default();
+        mcts::Bot::new(true)
+            .with_playout_depth(playout_depth)
+            .select(&state, Steps(50));
+        // `Steps(50)` allows `49` playouts, each of which ends before ply `20` only with a short playout depth.
+        assert_eq!(
+            state.batches.get(),
+            expected,
+            "playout depth: {}",
+            playout_depth
+        );
+    }
+}
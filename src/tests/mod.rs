@@ -1,8 +1,28 @@
-use crate::{tree::Node, Bot, Depth, Logger, Steps, ToCompletion};
+use crate::{tree::Node, Bot, Depth, Logger, Nodes, Steps, ToCompletion};
 
+mod beam;
+mod bench;
 mod brute;
 mod completed;
+mod expectimax;
+mod greedy;
+#[cfg(feature = "protocol")]
+mod gtp;
+mod ismcts;
+mod maxn;
+mod mcts;
+mod openings;
 mod partial;
+mod random;
+mod record;
+mod selfplay;
+mod simultaneous;
+mod sss;
+mod tournament;
+mod training;
+mod tune;
+#[cfg(feature = "protocol")]
+mod uci;
 
 #[test]
 fn logger_eq() {
@@ -40,3 +60,1998 @@ fn logger_eq() {
     Bot::new(true).select(&logger_eq, &mut logger);
     assert_eq!(logger.depth(), 2);
 }
+
+/// States where the active player does not have any actions must be counted,
+/// while states where the game ended must not.
+#[test]
+fn dead_ends() {
+    for &(owner, expected) in &[(false, 0), (true, 1)] {
+        #[rustfmt::skip]
+        let tree = Node::root().with_children(&[
+            Node::new(false, 3).with_children(&[
+                Node::new(false, 4),
+                Node::new(owner, 2),
+            ]),
+            Node::new(false, 5).with_children(&[
+                Node::new(true, 1).with_children(&[
+                    Node::new(false, 0),
+                ]),
+            ]),
+        ]);
+
+        for depth in 1..4 {
+            let mut bot = Bot::new(true).with_depth_schedule(depth, 1);
+            assert_eq!(bot.select(&tree, Depth(depth + 1)), Some(0));
+            assert_eq!(
+                bot.stats().dead_ends,
+                expected,
+                "owner: {}, depth: {}",
+                owner,
+                depth
+            );
+        }
+    }
+}
+
+/// `Game::look_ahead_batch` should be used to evaluate leaf nodes.
+#[test]
+fn look_ahead_batch() {
+    use crate::Game;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[derive(Clone)]
+    struct Batched(Node, Rc<Cell<u32>>);
+
+    impl Game for Batched {
+        type Player = bool;
+        type Action = usize;
+        type Fitness = i8;
+        type Actions = std::ops::Range<usize>;
+
+        fn actions(&self, player: bool) -> (bool, Self::Actions) {
+            self.0.actions(player)
+        }
+
+        fn execute(&mut self, action: &usize, player: bool) -> i8 {
+            self.0.execute(action, player)
+        }
+
+        fn look_ahead_batch(&self, actions: &[usize], player: bool, fitness: &mut Vec<i8>) {
+            self.1.set(self.1.get() + 1);
+            fitness.extend(actions.iter().map(|a| self.0.look_ahead(a, player)));
+        }
+    }
+
+    #[rustfmt::skip]
+    let tree = Node::root().with_children(&[
+        Node::new(false, 7).with_children(&[
+            Node::new(true, 4),
+            Node::new(true, 2),
+        ]),
+        Node::new(false, 5).with_children(&[
+            Node::new(true, 8),
+            Node::new(true, 9),
+        ]),
+    ]);
+
+    let calls = Rc::new(Cell::new(0));
+    let batched = Batched(tree, Rc::clone(&calls));
+    assert_eq!(Bot::new(true).select(&batched, ToCompletion), Some(1));
+    assert!(calls.get() > 1);
+}
+
+/// A `Policy` must only change the order in which actions are searched.
+#[test]
+fn bad_policy() {
+    use crate::{brute::Brute, Policy};
+
+    /// Always prefers the worst action according to `tree::Node`.
+    struct Reversed;
+
+    impl Policy<Node> for Reversed {
+        fn priors(&self, _: &Node, actions: &[usize], _: bool, priors: &mut Vec<f64>) {
+            priors.extend(actions.iter().map(|&a| a as f64));
+        }
+    }
+
+    for seed in 0..100u32 {
+        let bytes: Vec<u8> = seed
+            .to_be_bytes()
+            .iter()
+            .copied()
+            .chain((0..20).map(|i| (seed as u8).wrapping_mul(i).wrapping_add(i)))
+            .collect();
+        let tree = Node::from_bytes(&bytes);
+        let selected = Bot::new(true)
+            .with_policy(Reversed)
+            .select(&tree, ToCompletion);
+        assert!(
+            Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+            "seed: {}",
+            seed
+        );
+    }
+}
+
+/// Actions returned in a different order by clones should be caught by `check::Checked`.
+#[test]
+#[should_panic(expected = "different order")]
+fn checked_action_order() {
+    use crate::{check::Checked, Game};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Reverses the order of its actions on every other call, similar to iterating a `HashSet`.
+    #[derive(Clone)]
+    struct Unordered(Node, Rc<Cell<bool>>);
+
+    impl Game for Unordered {
+        type Player = bool;
+        type Action = usize;
+        type Fitness = i8;
+        type Actions = Vec<usize>;
+
+        fn actions(&self, player: bool) -> (bool, Self::Actions) {
+            let (active, actions) = self.0.actions(player);
+            let mut actions: Vec<_> = actions.collect();
+            self.1.set(!self.1.get());
+            if self.1.get() {
+                actions.reverse();
+            }
+            (active, actions)
+        }
+
+        fn execute(&mut self, action: &usize, player: bool) -> i8 {
+            self.0.execute(action, player)
+        }
+    }
+
+    let tree = Node::root().with_children(&[Node::new(false, 4), Node::new(false, 7)]);
+    let game = Checked::new(Unordered(tree, Rc::new(Cell::new(false))));
+    Bot::new(true).select(&game, ToCompletion);
+}
+
+/// `check::check_game` reports violations together with the actions leading to them.
+#[test]
+fn check_game() {
+    use crate::{check::check_game, Game};
+
+    /// `look_ahead` is off by one after the first action and `update_hash` ignores the action.
+    #[derive(Clone)]
+    struct Broken(Node, u32);
+
+    impl Game for Broken {
+        type Player = bool;
+        type Action = usize;
+        type Fitness = i8;
+        type Actions = std::ops::Range<usize>;
+
+        fn actions(&self, player: bool) -> (bool, Self::Actions) {
+            self.0.actions(player)
+        }
+
+        fn execute(&mut self, action: &usize, player: bool) -> i8 {
+            self.1 += 1;
+            self.0.execute(action, player)
+        }
+
+        fn look_ahead(&self, action: &usize, player: bool) -> i8 {
+            let fitness = self.0.look_ahead(action, player);
+            if self.1 > 0 {
+                fitness.saturating_add(1)
+            } else {
+                fitness
+            }
+        }
+
+        fn state_hash(&self) -> Option<u64> {
+            Some(u64::from(self.1))
+        }
+
+        fn update_hash(&self, hash: u64, _: &usize, _: bool) -> Option<u64> {
+            Some(hash)
+        }
+    }
+
+    #[rustfmt::skip]
+    let tree = Node::root().with_children(&[
+        Node::new(false, 7).with_children(&[
+            Node::new(true, 4),
+        ]),
+    ]);
+    assert!(check_game(&tree, &[true, false], u32::MAX).is_empty());
+
+    let violations = check_game(&Broken(tree, 0), &[true, false], u32::MAX);
+    let found: Vec<_> = violations
+        .iter()
+        .map(|violation| {
+            let message = violation.message.split(' ').next().unwrap();
+            (violation.path.clone(), message.to_string())
+        })
+        .collect();
+    assert_eq!(
+        found,
+        &[
+            (vec![], String::from("`Game::update_hash`")),
+            (vec![0], String::from("`Game::look_ahead`")),
+            (vec![0], String::from("`Game::look_ahead_batch`")),
+            (vec![0], String::from("`Game::look_ahead`")),
+            (vec![0], String::from("`Game::look_ahead_batch`")),
+            (vec![0], String::from("`Game::update_hash`")),
+        ]
+    );
+    assert_eq!(
+        violations[1].to_string(),
+        "`Game::look_ahead` returned `5` for `0`, but `Game::execute` returned `4` after [0]"
+    );
+}
+
+/// A correctly implemented zero-sum game must not be rejected by `Checked::with_zero_sum_checks`.
+#[test]
+fn checked_zero_sum() {
+    use crate::{check::Checked, Game};
+
+    #[derive(Clone)]
+    struct ZeroSum(Node);
+
+    impl Game for ZeroSum {
+        type Player = bool;
+        type Action = usize;
+        type Fitness = i8;
+        type Actions = std::ops::Range<usize>;
+
+        fn actions(&self, player: bool) -> (bool, Self::Actions) {
+            self.0.actions(player)
+        }
+
+        fn execute(&mut self, action: &usize, player: bool) -> i8 {
+            let fitness = self.0.execute(action, player);
+            if player {
+                fitness
+            } else {
+                -fitness
+            }
+        }
+    }
+
+    for i in 0..50u32 {
+        let mut bytes = i.to_be_bytes().to_vec();
+        bytes.extend((0..30).map(|j| (i * 31 + j * 17) as u8 % 120));
+        let game = Checked::new(ZeroSum(Node::from_bytes(&bytes)))
+            .with_look_ahead_checks(0.5)
+            .with_zero_sum_checks(|player| !player);
+        Bot::new(true).select(&game, ToCompletion);
+    }
+}
+
+/// Each pruning rule should be recorded when using `Bot::with_pruning_trace`.
+#[test]
+fn pruning_trace() {
+    use crate::alpha_beta::PruningRule;
+    use crate::{Game, OpponentModel};
+
+    struct Greedy;
+
+    impl OpponentModel<Node> for Greedy {
+        fn is_plausible(&self, state: &Node, action: &usize, player: bool) -> bool {
+            state.look_ahead(action, player) < 5
+        }
+    }
+
+    #[rustfmt::skip]
+    let tree = Node::root().with_children(&[
+        Node::new(false, 0).with_children(&[
+            Node::new(true, 4),
+            Node::new(true, 6),
+            Node::new(true, 8),
+        ]),
+        Node::new(false, 0).with_children(&[
+            Node::new(true, 3).with_children(&[
+                Node::new(true, 3),
+                Node::new(true, 1),
+                Node::new(true, 2),
+            ]),
+        ]),
+    ]);
+
+    let mut bot = Bot::new(true)
+        .with_pruning_trace()
+        .with_opponent_model(Greedy)
+        .with_search_widths(vec![2, 1]);
+    bot.select(&tree, ToCompletion);
+    let model = bot.stats().pruning(PruningRule::OpponentModel);
+    assert_eq!((model.skipped, model.max_skipped), (model.states * 2, 2));
+    let widths = bot.stats().pruning(PruningRule::SearchWidth);
+    assert!(widths.states > 0);
+    assert_eq!(widths.max_skipped, 2);
+
+    // Nothing is recorded without `with_pruning_trace`.
+    let mut bot = Bot::new(true).with_search_widths(vec![2, 1]);
+    bot.select(&tree, ToCompletion);
+    assert!(bot.stats().pruned.is_empty());
+}
+
+/// Margins are never negative and transpositions are recorded as pruning rules.
+#[test]
+fn pruning_margins() {
+    use crate::alpha_beta::PruningRule;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn hash(node: &Node) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        node.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let (mut margins, mut transpositions) = (0, 0);
+    for seed in 0..50u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..40).map(|i| (seed.wrapping_mul(11) + i * 3) as u8));
+        let tree = Node::from_bytes(&bytes);
+        let mut bot = Bot::new(true)
+            .with_transposition_table(1 << 10, hash)
+            .with_pruning_margins(f64::from);
+        let selected = bot.select(&tree, ToCompletion);
+        assert_eq!(
+            selected,
+            Bot::new(true).select(&tree, ToCompletion),
+            "seed: {}",
+            seed
+        );
+
+        let stats = bot.stats();
+        for pruned in &stats.pruned {
+            assert!(
+                pruned.margin.is_none_or(|margin| margin >= 0.0),
+                "seed: {}",
+                seed
+            );
+        }
+        margins += stats.pruning(PruningRule::Cutoff).min_margin.is_some() as u32;
+        let table = stats.pruning(PruningRule::Transposition);
+        assert!(table.states <= stats.transpositions, "seed: {}", seed);
+        assert!(stats.transpositions <= stats.table_probes, "seed: {}", seed);
+        transpositions += table.states;
+    }
+    assert!(margins > 0 && transpositions > 0);
+
+    // Margins are only recorded using `with_pruning_margins`.
+    let tree = Node::from_bytes(&[3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9, 3, 2, 3, 8, 4]);
+    let mut bot = Bot::new(true).with_pruning_trace();
+    bot.select(&tree, ToCompletion);
+    assert!(bot
+        .stats()
+        .pruned
+        .iter()
+        .all(|pruned| pruned.margin.is_none()));
+}
+
+/// Vetoing all actions of a state must not change anything.
+#[test]
+fn veto_everything() {
+    use crate::Veto;
+
+    struct Everything;
+
+    impl Veto<Node> for Everything {
+        fn vetoes(&self, _: &Node, _: &usize, _: bool) -> bool {
+            true
+        }
+    }
+
+    for seed in 0..50u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..20).map(|i| (seed * 13 + i * 7) as u8));
+        let tree = Node::from_bytes(&bytes);
+        let expected = Bot::new(true).detailed_select(&tree, ToCompletion);
+        let actual = Bot::new(true)
+            .with_veto(Everything)
+            .detailed_select(&tree, ToCompletion);
+        assert_eq!(
+            expected.map(|act| (act.fitness, act.path)),
+            actual.map(|act| (act.fitness, act.path)),
+            "seed: {}",
+            seed
+        );
+    }
+}
+
+/// The bot chooses a number, after which the opponent can always win by choosing `7`,
+/// which is only noticed after the following action of the bot.
+#[derive(Clone)]
+struct Refute(u8, u8, bool);
+
+impl crate::Game for Refute {
+    type Player = bool;
+    type Action = u8;
+    type Fitness = i8;
+    type Actions = std::ops::Range<u8>;
+
+    fn actions(&self, player: bool) -> (bool, Self::Actions) {
+        match self.0 {
+            0 => (player, 0..8),
+            1 => (!player, 0..8),
+            2 => (player, 0..2),
+            _ => (false, 0..0),
+        }
+    }
+
+    fn execute(&mut self, &action: &u8, _: bool) -> i8 {
+        self.0 += 1;
+        match self.0 {
+            1 => self.1 = action,
+            2 => self.2 = action == 7,
+            _ if self.2 => return -10,
+            _ => self.1 += action,
+        }
+        self.1 as i8
+    }
+}
+
+/// Killer moves must only change the order in which actions are searched.
+#[test]
+fn killer_moves() {
+    use crate::brute::Brute;
+
+    let mut plain = Bot::new(true);
+    let mut killer = Bot::new(true).with_killer_moves();
+    let expected = plain
+        .detailed_select(&Refute(0, 0, false), ToCompletion)
+        .unwrap();
+    let actual = killer
+        .detailed_select(&Refute(0, 0, false), ToCompletion)
+        .unwrap();
+    assert_eq!(expected.fitness, actual.fitness);
+    assert!(killer.stats().nodes < plain.stats().nodes);
+
+    for seed in 0..100u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..30).map(|i| (seed * 7 + i * 3) as u8));
+        let tree = Node::from_bytes(&bytes);
+        let selected = Bot::new(true)
+            .with_killer_moves()
+            .select(&tree, ToCompletion);
+        assert!(
+            Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+            "seed: {}",
+            seed
+        );
+    }
+}
+
+/// The history heuristic must only change the order in which actions are searched.
+#[test]
+fn history_heuristic() {
+    use crate::brute::Brute;
+
+    let mut plain = Bot::new(true);
+    let mut history = Bot::new(true).with_history_heuristic(|&action: &u8| usize::from(action));
+    let expected = plain
+        .detailed_select(&Refute(0, 0, false), ToCompletion)
+        .unwrap();
+    let actual = history
+        .detailed_select(&Refute(0, 0, false), ToCompletion)
+        .unwrap();
+    assert_eq!(expected.fitness, actual.fitness);
+    assert!(history.stats().nodes < plain.stats().nodes);
+
+    for seed in 0..100u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..30).map(|i| (seed * 11 + i * 5) as u8));
+        let tree = Node::from_bytes(&bytes);
+        let selected = Bot::new(true)
+            .with_history_heuristic(|&action| action)
+            .select(&tree, ToCompletion);
+        assert!(
+            Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+            "seed: {}",
+            seed
+        );
+    }
+}
+
+/// Null moves must reduce the amount of searched states while not changing the result for games
+/// where skipping a turn is never better than executing an action.
+#[test]
+fn null_move_pruning() {
+    use crate::alpha_beta::PruningRule;
+    use crate::Game;
+
+    /// Players alternately take up to three coins, every coin being worth
+    /// the amount of coins which were left before taking it.
+    #[derive(Clone)]
+    struct Coins(u8, bool, i16);
+
+    impl Game for Coins {
+        type Player = ();
+        type Action = u8;
+        type Fitness = i16;
+        type Actions = std::ops::RangeInclusive<u8>;
+
+        fn actions(&self, _: ()) -> (bool, Self::Actions) {
+            (self.1, 1..=self.0.min(3))
+        }
+
+        fn execute(&mut self, &take: &u8, _: ()) -> i16 {
+            let value: i16 = (0..take).map(|i| i16::from(self.0 - i)).sum();
+            self.2 += if self.1 { value } else { -value };
+            self.0 -= take;
+            self.1 = !self.1;
+            self.2
+        }
+    }
+
+    fn skip_turn(coins: &mut Coins) -> bool {
+        coins.1 = !coins.1;
+        true
+    }
+
+    let coins = Coins(14, true, 0);
+    let mut plain = Bot::new(());
+    let expected = plain.detailed_select(&coins, ToCompletion).unwrap();
+    let mut null = Bot::new(())
+        .with_null_move_pruning(1, skip_turn)
+        .with_pruning_margins(f64::from);
+    let actual = null.detailed_select(&coins, ToCompletion).unwrap();
+    assert_eq!(expected.fitness, actual.fitness);
+    assert_eq!(expected.path[0], actual.path[0]);
+    assert!(!actual.proven);
+    let pruned = null.stats().pruning(PruningRule::NullMove);
+    assert!(pruned.states > 0);
+    assert!(pruned.min_margin.is_some_and(|margin| margin >= 0.0));
+
+    // Not skipping any turns must not change anything.
+    let mut never = Bot::new(()).with_null_move_pruning(1, |_| false);
+    let actual = never.detailed_select(&coins, ToCompletion).unwrap();
+    assert_eq!(expected.path, actual.path);
+    assert_eq!(plain.stats().nodes, never.stats().nodes);
+
+    // Deep searches skip enough states to make up for the additional searches after skipping a turn.
+    let coins = Coins(20, true, 0);
+    let mut plain = Bot::new(());
+    let expected = plain.detailed_select(&coins, Depth(8)).unwrap();
+    let mut null = Bot::new(()).with_null_move_pruning(2, skip_turn);
+    let actual = null.detailed_select(&coins, Depth(8)).unwrap();
+    assert_eq!(expected.fitness, actual.fitness);
+    assert!(null.stats().nodes < plain.stats().nodes);
+}
+
+/// Quiescence search must not change the result once the complete game tree was searched,
+/// its states are counted in `SearchStats::quiescence_nodes`.
+#[test]
+fn quiescence_search() {
+    use crate::brute::Brute;
+    use crate::Game;
+
+    /// Every action is a noisy action.
+    #[derive(Clone)]
+    struct Noisy(Node);
+
+    impl Game for Noisy {
+        type Player = bool;
+        type Action = usize;
+        type Fitness = i8;
+        type Actions = std::ops::Range<usize>;
+
+        fn actions(&self, player: bool) -> (bool, Self::Actions) {
+            self.0.actions(player)
+        }
+
+        fn execute(&mut self, action: &usize, player: bool) -> i8 {
+            self.0.execute(action, player)
+        }
+
+        fn is_quiet(&self, _: &usize, _: bool) -> bool {
+            false
+        }
+    }
+
+    let mut quiescence_nodes = 0;
+    for seed in 0..100u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..30).map(|i| (seed * 13 + i * 7) as u8));
+        let tree = Node::from_bytes(&bytes);
+        let mut bot = Bot::new(true).with_quiescence_search(2);
+        let selected = bot.select(&Noisy(tree.clone()), ToCompletion);
+        assert!(
+            Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+            "seed: {}",
+            seed
+        );
+        let stats = bot.stats();
+        assert!(stats.quiescence_nodes <= stats.nodes, "seed: {}", seed);
+        quiescence_nodes += stats.quiescence_nodes;
+
+        let mut bot = Bot::new(true);
+        bot.select(&Noisy(tree), ToCompletion);
+        assert_eq!(bot.stats().quiescence_nodes, 0, "seed: {}", seed);
+    }
+    assert!(quiescence_nodes > 0);
+}
+
+/// `Nodes` must bound the amount of searched states and give reproducible results.
+#[test]
+fn nodes_limit() {
+    for seed in 0..50u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..30).map(|i| (seed * 7 + i * 3) as u8));
+        let tree = Node::from_bytes(&bytes);
+        for &limit in [0, 1, 5, 20, 100].iter() {
+            let mut bot = Bot::new(true);
+            let selected = bot.select(&tree, Nodes(limit));
+            assert!(bot.stats().nodes <= limit, "seed: {}", seed);
+            let mut other = Bot::new(true);
+            assert_eq!(
+                selected,
+                other.select(&tree, Nodes(limit)),
+                "seed: {}",
+                seed
+            );
+            assert_eq!(bot.stats().nodes, other.stats().nodes, "seed: {}", seed);
+        }
+    }
+}
+
+/// The memory limit must shrink the transposition table and stop deepening once it is reached.
+#[test]
+fn memory_limit() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn hash(node: &Node) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        node.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    for seed in 0..50u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..30).map(|i| (seed * 5 + i * 7) as u8));
+        let tree = Node::from_bytes(&bytes);
+        let expected = Bot::new(true).detailed_select(&tree, ToCompletion);
+
+        // Allocating this table without the limit would take a lot of time and memory.
+        let mut bot = Bot::new(true)
+            .with_transposition_table(1 << 40, hash)
+            .with_memory_limit(1 << 20);
+        let actual = bot.detailed_select(&tree, ToCompletion);
+        assert_eq!(
+            expected.as_ref().map(|act| act.fitness),
+            actual.map(|act| act.fitness),
+            "seed: {}",
+            seed
+        );
+        assert!(bot.stats().table_shrunk, "seed: {}", seed);
+        assert!(!bot.stats().memory_limit_reached, "seed: {}", seed);
+
+        let mut bot = Bot::new(true).with_memory_limit(0);
+        let actual = bot.detailed_select(&tree, ToCompletion);
+        assert_eq!(expected.is_some(), actual.is_some(), "seed: {}", seed);
+        assert!(actual.is_none_or(|act| !act.proven), "seed: {}", seed);
+        assert_eq!(
+            bot.stats().memory_limit_reached,
+            expected.is_some(),
+            "seed: {}",
+            seed
+        );
+    }
+}
+
+/// Entries of a transposition table which is too small must be replaced and counted.
+#[test]
+fn table_replacements() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn hash(node: &Node) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        node.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let bytes: Vec<u8> = (0..40u8).map(|i| i.wrapping_mul(29)).collect();
+    let tree = Node::from_bytes(&bytes);
+    let mut small = Bot::new(true).with_transposition_table(1, hash);
+    let mut large = Bot::new(true).with_transposition_table(1 << 16, hash);
+    assert_eq!(
+        small.select(&tree, ToCompletion),
+        large.select(&tree, ToCompletion)
+    );
+    assert!(small.stats().table_replacements > large.stats().table_replacements);
+    assert!(!small.stats().table_shrunk);
+}
+
+/// Recording the search tree must not change the result and every recorded state must be
+/// reachable from the initial state.
+#[test]
+fn search_tree() {
+    for seed in 0..50u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..40).map(|i| (seed.wrapping_mul(13) + i * 5) as u8));
+        let tree = Node::from_bytes(&bytes);
+        for &max_nodes in [1, 10, 1000].iter() {
+            let mut bot = Bot::new(true).with_search_tree(max_nodes);
+            let selected = bot.select(&tree, ToCompletion);
+            assert_eq!(selected, Bot::new(true).select(&tree, ToCompletion));
+
+            let searched = bot.search_tree().unwrap();
+            let nodes = searched.nodes();
+            assert!(!nodes.is_empty() && nodes.len() <= max_nodes);
+            assert!(nodes[0].parent.is_none() && nodes[0].action.is_none());
+            for (idx, node) in nodes.iter().enumerate().skip(1) {
+                assert!(node.parent.unwrap() < idx, "seed: {}", seed);
+                assert!(node.action.is_some(), "seed: {}", seed);
+            }
+
+            let mut dot = Vec::new();
+            searched.write_dot(&mut dot).unwrap();
+            let dot = String::from_utf8(dot).unwrap();
+            assert_eq!(dot.matches("-> n").count(), nodes.len() - 1);
+        }
+    }
+}
+
+/// A deserialized bot must keep its player, hint and plain options.
+#[cfg(feature = "serde")]
+#[test]
+fn serde_roundtrip() {
+    use crate::alpha_beta::Parity;
+
+    for seed in 0..20u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..40).map(|i| (seed.wrapping_mul(11) + i * 7) as u8));
+        let tree = Node::from_bytes(&bytes);
+
+        let mut bot = Bot::new(true)
+            .with_max_depth(3)
+            .with_depth_parity(Parity::Odd)
+            .with_search_widths(vec![3, 2]);
+        bot.set_hint(1);
+        let json = serde_json::to_string(&bot).unwrap();
+        let mut restored: Bot<Node> = serde_json::from_str(&json).unwrap();
+        assert_eq!(json, serde_json::to_string(&restored).unwrap());
+        assert_eq!(
+            bot.detailed_select(&tree, ToCompletion).map(|act| act.path),
+            restored
+                .detailed_select(&tree, ToCompletion)
+                .map(|act| act.path),
+            "seed: {}",
+            seed
+        );
+    }
+}
+
+/// A game which keeps all previous states, so actions can be reverted, and counts its clones.
+#[derive(Debug)]
+struct Undoable {
+    node: Node,
+    previous: Vec<Node>,
+    clones: std::rc::Rc<std::cell::Cell<usize>>,
+}
+
+impl Clone for Undoable {
+    fn clone(&self) -> Self {
+        self.clones.set(self.clones.get() + 1);
+        Undoable {
+            node: self.node.clone(),
+            previous: self.previous.clone(),
+            clones: self.clones.clone(),
+        }
+    }
+}
+
+impl crate::Game for Undoable {
+    type Player = bool;
+    type Action = usize;
+    type Fitness = i8;
+    type Actions = std::ops::Range<usize>;
+
+    fn actions(&self, player: bool) -> (bool, Self::Actions) {
+        self.node.actions(player)
+    }
+
+    fn execute(&mut self, action: &usize, player: bool) -> i8 {
+        self.previous.push(self.node.clone());
+        self.node.execute(action, player)
+    }
+
+    fn look_ahead(&self, action: &usize, player: bool) -> i8 {
+        self.node.look_ahead(action, player)
+    }
+}
+
+impl crate::UnmakeGame for Undoable {
+    fn undo(&mut self, _: &usize, _: bool) {
+        self.node = self.previous.pop().unwrap();
+    }
+}
+
+/// Reverting actions must not change the result and must clone the state less often.
+#[test]
+fn undo() {
+    let (mut total, mut total_undo) = (0, 0);
+    for seed in 0..100u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..60).map(|i| (seed.wrapping_mul(17) + i * 3) as u8));
+        let tree = Node::from_bytes(&bytes);
+        let state = Undoable {
+            node: tree.clone(),
+            previous: Vec::new(),
+            clones: Default::default(),
+        };
+
+        let expected = Bot::new(true)
+            .detailed_select(&tree, ToCompletion)
+            .map(|act| (act.fitness, act.path));
+        let actual = Bot::new(true)
+            .detailed_select(&state, ToCompletion)
+            .map(|act| (act.fitness, act.path));
+        let clones = state.clones.replace(0);
+        let undone = Bot::new(true)
+            .with_undo()
+            .detailed_select(&state, ToCompletion)
+            .map(|act| (act.fitness, act.path));
+        let undo_clones = state.clones.get();
+
+        assert_eq!(expected, actual, "seed: {}", seed);
+        assert_eq!(expected, undone, "seed: {}", seed);
+        assert!(undo_clones <= clones, "seed: {}", seed);
+        total += clones;
+        total_undo += undo_clones;
+    }
+    assert!(total_undo < total);
+}
+
+/// With `Bot::with_undo`, the state is only cloned to search the top level actions,
+/// independent of the amount of searched states.
+#[test]
+fn undo_clones() {
+    for seed in 0..100u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..60).map(|i| (seed.wrapping_mul(31) + i * 7) as u8));
+        let tree = Node::from_bytes(&bytes);
+        let state = Undoable {
+            node: tree.clone(),
+            previous: Vec::new(),
+            clones: Default::default(),
+        };
+        let actions = crate::Game::actions(&tree, true).1.len();
+
+        for depth in 1..=4 {
+            let expected = Bot::new(true)
+                .with_principal_variation_search()
+                .detailed_select(&tree, Depth(depth))
+                .map(|act| (act.fitness, act.path));
+            state.clones.set(0);
+            let actual = Bot::new(true)
+                .with_undo()
+                .with_principal_variation_search()
+                .detailed_select(&state, Depth(depth))
+                .map(|act| (act.fitness, act.path));
+
+            assert_eq!(expected, actual, "seed: {}", seed);
+            // Each top level action is cloned once per iteration, the last clone is used to find the reply.
+            assert!(
+                state.clones.get() <= depth as usize * actions + 1,
+                "seed: {}",
+                seed
+            );
+        }
+    }
+}
+
+/// Both players take stones, the opponent can't take the last stone and has to pass instead.
+#[derive(Clone, Debug)]
+struct Passing {
+    stones: u8,
+    our_turn: bool,
+    score: i8,
+    previous: Vec<(u8, i8)>,
+}
+
+impl crate::Game for Passing {
+    type Player = ();
+    type Action = u8;
+    type Fitness = i8;
+    type Actions = Vec<u8>;
+
+    fn actions(&self, _: ()) -> (bool, Vec<u8>) {
+        let max = if self.our_turn {
+            self.stones
+        } else {
+            self.stones.saturating_sub(1)
+        };
+        (self.our_turn, (1..=max.min(3)).collect())
+    }
+
+    fn execute(&mut self, &take: &u8, _: ()) -> i8 {
+        self.previous.push((self.stones, self.score));
+        self.stones -= take;
+        self.score += if self.our_turn {
+            take as i8
+        } else {
+            -(take as i8)
+        };
+        self.our_turn = !self.our_turn;
+        self.score
+    }
+
+    fn pass(&mut self, _: ()) -> bool {
+        self.our_turn = self.stones > 0;
+        self.our_turn
+    }
+}
+
+impl crate::UnmakeGame for Passing {
+    fn undo(&mut self, _: &u8, _: ()) {
+        let (stones, score) = self.previous.pop().unwrap();
+        self.stones = stones;
+        self.our_turn = !self.our_turn;
+        self.score = score;
+    }
+
+    fn undo_pass(&mut self, _: ()) {
+        self.our_turn = false;
+    }
+}
+
+/// Passes are reverted using `UnmakeGame::undo_pass` with `Bot::with_undo`.
+#[test]
+fn undo_pass() {
+    for stones in 1..16 {
+        let state = Passing {
+            stones,
+            our_turn: true,
+            score: 0,
+            previous: Vec::new(),
+        };
+        for depth in 1..=6 {
+            let expected = Bot::new(())
+                .with_principal_variation_search()
+                .detailed_select(&state, Depth(depth))
+                .map(|act| (act.fitness, act.path));
+            let actual = Bot::new(())
+                .with_undo()
+                .with_principal_variation_search()
+                .detailed_select(&state, Depth(depth))
+                .map(|act| (act.fitness, act.path));
+            assert_eq!(expected, actual, "stones: {}", stones);
+        }
+    }
+}
+
+/// A game which supports incremental hashing and counts how often a state is hashed from scratch.
+#[derive(Clone, Debug)]
+struct Hashed {
+    node: Node,
+    hashed: std::rc::Rc<std::cell::Cell<usize>>,
+}
+
+fn hash_node(node: &Node) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    node.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl crate::Game for Hashed {
+    type Player = bool;
+    type Action = usize;
+    type Fitness = i8;
+    type Actions = std::ops::Range<usize>;
+
+    fn actions(&self, player: bool) -> (bool, Self::Actions) {
+        self.node.actions(player)
+    }
+
+    fn execute(&mut self, action: &usize, player: bool) -> i8 {
+        self.node.execute(action, player)
+    }
+
+    fn state_hash(&self) -> Option<u64> {
+        self.hashed.set(self.hashed.get() + 1);
+        Some(hash_node(&self.node))
+    }
+
+    fn update_hash(&self, _: u64, action: &usize, player: bool) -> Option<u64> {
+        let mut node = self.node.clone();
+        node.execute(action, player);
+        Some(hash_node(&node))
+    }
+}
+
+/// Incremental hashing must not change the result and must not hash states from scratch.
+#[test]
+fn incremental_hashing() {
+    for seed in 0..100u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..60).map(|i| (seed.wrapping_mul(19) + i * 7) as u8));
+        let state = Hashed {
+            node: Node::from_bytes(&bytes),
+            hashed: Default::default(),
+        };
+
+        let hash = |state: &Hashed| hash_node(&state.node);
+        let mut bot = Bot::new(true).with_transposition_table(1 << 8, hash);
+        let expected = bot
+            .detailed_select(&state, ToCompletion)
+            .map(|act| (act.fitness, act.path));
+        let nodes = bot.stats().nodes;
+        assert_eq!(state.hashed.get(), 0);
+
+        let mut bot = Bot::new(true)
+            .with_transposition_table(1 << 8, hash)
+            .with_incremental_hashing();
+        let actual = bot
+            .detailed_select(&state, ToCompletion)
+            .map(|act| (act.fitness, act.path));
+        assert_eq!(expected, actual, "seed: {}", seed);
+        assert_eq!(nodes, bot.stats().nodes, "seed: {}", seed);
+        assert!(state.hashed.get() <= 1, "seed: {}", seed);
+    }
+}
+
+/// Storing the exact result of every state after two actions must not change the result,
+/// and a known best action of the initial state is selected without searching.
+#[test]
+fn tablebase() {
+    use crate::{brute::Brute, Game, HashTablebase};
+
+    fn value(state: &Node) -> Option<i8> {
+        let (active, actions) = state.actions(true);
+        let fitness = actions.map(|action| Brute::new(true).fitness(state, &action, u32::MAX));
+        if active {
+            fitness.max()
+        } else {
+            fitness.min()
+        }
+    }
+
+    let mut hits = 0;
+    for seed in 0..100u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..40).map(|i| (seed.wrapping_mul(23) + i * 5) as u8));
+        let tree = Node::from_bytes(&bytes);
+
+        let mut tablebase = HashTablebase::new(hash_node);
+        for first in tree.actions(true).1 {
+            let mut child = tree.clone();
+            child.execute(&first, true);
+            for second in child.actions(true).1 {
+                let mut state = child.clone();
+                state.execute(&second, true);
+                if let Some(fitness) = value(&state) {
+                    tablebase.insert(&state, true, fitness, None);
+                }
+            }
+        }
+
+        let mut bot = Bot::new(true).with_tablebase(tablebase);
+        let selected = bot.select(&tree, ToCompletion);
+        assert!(
+            Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+            "seed: {}",
+            seed
+        );
+        hits += bot.stats().tablebase_hits;
+    }
+    assert!(hits > 0);
+
+    #[rustfmt::skip]
+    let tree = Node::root().with_children(&[
+        Node::new(false, 5),
+        Node::new(false, 3),
+    ]);
+    let mut tablebase = HashTablebase::new(hash_node);
+    tablebase.insert(&tree, true, 3, Some(1));
+    let mut bot = Bot::new(true).with_tablebase(tablebase);
+    let action = bot.detailed_select(&tree, ToCompletion).unwrap();
+    assert_eq!((action.fitness, action.path), (3, vec![1]));
+    assert_eq!(bot.stats().nodes, 0);
+    assert_eq!(bot.stats().tablebase_hits, 1);
+}
+
+/// A run condition which never stops on its own, but reports a fixed remaining time.
+struct Remaining(std::time::Duration);
+
+impl crate::RunCondition for Remaining {
+    fn step(&mut self) -> bool {
+        true
+    }
+
+    fn depth(&mut self, _: u32) -> bool {
+        true
+    }
+
+    fn remaining(&self) -> Option<std::time::Duration> {
+        Some(self.0)
+    }
+}
+
+/// An iteration which is not expected to finish in the remaining time is not started.
+#[test]
+fn predicted_iteration() {
+    use std::time::Duration;
+
+    let tree = Node::from_bytes(&[
+        3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9, 3, 2, 3, 8, 4, 6, 2, 6, 4,
+    ]);
+    let mut logger = Logger::new(Remaining(Duration::from_secs(0)));
+    let mut bot = Bot::new(true);
+    assert!(bot.select(&tree, &mut logger).is_some());
+    assert_eq!(logger.depth(), 1);
+
+    let mut logger = Logger::new(Remaining(Duration::from_secs(3600)));
+    let selected = bot.select(&tree, &mut logger);
+    assert!(logger.depth() > 1);
+    assert_eq!(selected, bot.select(&tree, ToCompletion));
+}
+
+/// `Timeout` only uses the given clock, which advances by one millisecond each time it is read.
+#[test]
+fn timeout() {
+    use crate::{RunCondition, Timeout};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::Duration;
+
+    static MILLIS: AtomicU64 = AtomicU64::new(0);
+    fn now() -> Duration {
+        Duration::from_millis(MILLIS.fetch_add(1, Ordering::Relaxed))
+    }
+
+    let tree = Node::from_bytes(&[
+        3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9, 3, 2, 3, 8, 4, 6, 2, 6, 4,
+    ]);
+    let start = MILLIS.load(Ordering::Relaxed);
+    let mut bot = Bot::new(true);
+    assert!(bot
+        .select(&tree, Timeout::new(now, Duration::from_millis(20)))
+        .is_some());
+    let reads = MILLIS.load(Ordering::Relaxed) - start;
+    assert!((20..25).contains(&reads), "reads: {}", reads);
+
+    let timeout = Timeout::new(now, Duration::from_millis(5));
+    assert_eq!(timeout.remaining(), Some(Duration::from_millis(4)));
+}
+
+/// A simulated clock which advances by one millisecond each time it is read.
+struct Simulated(std::cell::Cell<u64>);
+
+impl crate::Clock for Simulated {
+    fn now(&self) -> std::time::Duration {
+        let millis = self.0.get();
+        self.0.set(millis + 1);
+        std::time::Duration::from_millis(millis)
+    }
+}
+
+/// Timeouts using a simulated clock stop after the same amount of reads each time,
+/// as iterations which are predicted to exceed the deadline are also measured using the clock.
+#[test]
+fn simulated_clock() {
+    use crate::Timeout;
+    use std::cell::Cell;
+    use std::time::Duration;
+
+    let tree = Node::from_bytes(&[
+        2, 7, 1, 8, 2, 8, 1, 8, 2, 8, 4, 5, 9, 0, 4, 5, 2, 3, 5, 3, 6, 0, 2, 8, 7, 4,
+    ]);
+    let search = |millis| {
+        let timeout = Timeout::new(Simulated(Cell::new(0)), Duration::from_millis(millis));
+        let mut logger = Logger::new(timeout);
+        let selected = Bot::new(true).select(&tree, &mut logger);
+        (selected, logger.into_inner().clock().0.get())
+    };
+    for millis in 0..40 {
+        let (selected, reads) = search(millis);
+        assert!(selected.is_some(), "millis: {}", millis);
+        assert!(reads <= millis + 2, "millis: {}", millis);
+        assert_eq!(search(millis), (selected, reads), "millis: {}", millis);
+    }
+}
+
+/// `GameClock` only uses the given clock to measure the time spent on each move.
+#[test]
+fn game_clock_simulated() {
+    use crate::GameClock;
+    use std::cell::Cell;
+    use std::time::Duration;
+
+    let tree = Node::from_bytes(&[
+        2, 7, 1, 8, 2, 8, 1, 8, 2, 8, 4, 5, 9, 0, 4, 5, 2, 3, 5, 3, 6, 0, 2, 8, 7, 4,
+    ]);
+    let play = || {
+        let mut clock =
+            GameClock::new(Duration::from_millis(100), 4).with_clock(Simulated(Cell::new(0)));
+        assert_eq!(clock.move_budget(), Duration::from_millis(20));
+        let selected = Bot::new(true).select(&tree, &mut clock);
+        // The first read returns zero, the last one happens when the move is done.
+        let spent = Duration::from_millis(clock.clock().0.get() - 1);
+        assert_eq!(clock.moves(), 1);
+        assert_eq!(clock.remaining(), Duration::from_millis(100) - spent);
+        (selected, spent)
+    };
+    let (selected, spent) = play();
+    assert!(selected.is_some());
+    assert!(spent >= Duration::from_millis(20), "spent: {:?}", spent);
+    assert_eq!(play(), (selected, spent));
+}
+
+/// A game which never ends, where each player has two actions.
+#[derive(Clone)]
+struct Endless(bool);
+
+impl crate::Game for Endless {
+    type Player = bool;
+    type Action = bool;
+    type Fitness = i8;
+    type Actions = [bool; 2];
+
+    fn actions(&self, player: bool) -> (bool, Self::Actions) {
+        (player == self.0, [false, true])
+    }
+
+    fn execute(&mut self, &action: &bool, _: bool) -> i8 {
+        self.0 = !self.0;
+        action as i8
+    }
+}
+
+/// `Or` must stop at the time limit once the minimum depth is reached,
+/// instead of always finishing the current iteration.
+#[test]
+fn or_depth_timeout() {
+    use crate::{Depth, Or, Timeout};
+    use std::cell::Cell;
+    use std::time::Duration;
+
+    let search = |millis| {
+        // Advances by one millisecond each time it is read.
+        let reads = Cell::new(0);
+        let clock = || {
+            reads.set(reads.get() + 1);
+            Duration::from_millis(reads.get())
+        };
+        let mut logger = Logger::new(Or(
+            Depth(3),
+            Timeout::new(clock, Duration::from_millis(millis)),
+        ));
+        assert!(Bot::new(true).select(&Endless(true), &mut logger).is_some());
+        assert!(logger.depth() >= 3, "millis: {}", millis);
+        reads.get()
+    };
+    // Without any time, the search stops right after the minimum depth.
+    let minimum = search(0);
+    for millis in (0..1000).step_by(20) {
+        assert!(
+            search(millis) <= minimum.max(millis + 1),
+            "millis: {}",
+            millis
+        );
+    }
+}
+
+/// A tree where each fitness of `3` is replaced by `1`.
+#[derive(Clone)]
+struct Contempt(Node);
+
+impl crate::Game for Contempt {
+    type Player = bool;
+    type Action = usize;
+    type Fitness = i8;
+    type Actions = std::ops::Range<usize>;
+
+    fn actions(&self, player: bool) -> (bool, Self::Actions) {
+        self.0.actions(player)
+    }
+
+    fn execute(&mut self, action: &usize, player: bool) -> i8 {
+        match self.0.execute(action, player) {
+            3 => 1,
+            fitness => fitness,
+        }
+    }
+}
+
+/// Using contempt must be the same as changing the fitness of even states.
+#[test]
+fn contempt() {
+    use crate::brute::Brute;
+
+    let mut changed = 0;
+    for seed in 0..100u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..40).map(|i| (seed.wrapping_mul(31) + i * 7) as u8 % 6));
+        let tree = Node::from_bytes(&bytes);
+
+        let selected = Bot::new(true)
+            .with_contempt(3, 2)
+            .select(&tree, ToCompletion);
+        if selected != Bot::new(true).select(&tree, ToCompletion) {
+            changed += 1;
+        }
+        assert!(
+            Brute::new(true).check_if_best(&Contempt(tree.clone()), selected.as_ref(), u32::MAX),
+            "seed: {}",
+            seed
+        );
+
+        let selected = Bot::new(true)
+            .with_contempt(3, 0)
+            .select(&tree, ToCompletion);
+        assert!(
+            Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+            "seed: {}",
+            seed
+        );
+    }
+    assert!(changed > 0);
+}
+
+/// A low temperature must select the best action, and results must be reproducible.
+#[test]
+fn temperature() {
+    use crate::brute::Brute;
+
+    let tree = Node::from_bytes(&[
+        2, 7, 1, 8, 2, 8, 1, 8, 2, 8, 4, 5, 9, 0, 4, 5, 2, 3, 5, 3, 6,
+    ]);
+    let best = Bot::new(true).rank_actions(&tree, ToCompletion)[0].1;
+    let mut bot = Bot::new(true).with_seed(3);
+    for _ in 0..20 {
+        let selected = bot.select_with_temperature(&tree, 0.01, ToCompletion);
+        assert_eq!(
+            Brute::new(true).fitness(&tree, &selected.unwrap(), u32::MAX),
+            best
+        );
+    }
+
+    let select = |seed| {
+        let mut bot = Bot::new(true).with_seed(seed);
+        (0..20)
+            .map(|_| {
+                bot.select_with_temperature(&tree, 100.0, ToCompletion)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>()
+    };
+    let selected = select(5);
+    assert_eq!(selected, select(5));
+    assert!(selected.iter().any(|&action| action != selected[0]));
+
+    assert_eq!(
+        Bot::new(true).select_with_temperature(&Node::root(), 1.0, ToCompletion),
+        None
+    );
+}
+
+/// Random tie-breaking must still select a best action, and be reproducible for a given seed.
+#[test]
+fn random_ties() {
+    use crate::brute::Brute;
+
+    for seed in 0..50u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..30).map(|i| (seed.wrapping_mul(13) + i * 3) as u8 % 4));
+        let tree = Node::from_bytes(&bytes);
+
+        let select = |seed| {
+            let mut bot = Bot::new(true).with_random_ties().with_seed(seed);
+            (0..5)
+                .map(|_| bot.select(&tree, ToCompletion))
+                .collect::<Vec<_>>()
+        };
+        let selected = select(u64::from(seed));
+        assert_eq!(selected, select(u64::from(seed)), "seed: {}", seed);
+        for action in &selected {
+            assert!(
+                Brute::new(true).check_if_best(&tree, action.as_ref(), u32::MAX),
+                "seed: {}",
+                seed
+            );
+        }
+    }
+}
+
+/// Keeping the transposition table during a whole game must not change the result of any search.
+#[test]
+fn persistent_table() {
+    use crate::{brute::Brute, Game};
+
+    for seed in 0..50u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..60).map(|i| (seed.wrapping_mul(17) + i * 11) as u8));
+        let mut tree = Node::from_bytes(&bytes);
+
+        let mut bot = Bot::new(true)
+            .with_transposition_table(1 << 8, hash_node)
+            .with_persistent_table();
+        loop {
+            let (active, mut actions) = tree.actions(true);
+            let action = if active {
+                let selected = bot.select(&tree, ToCompletion);
+                assert!(
+                    Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+                    "seed: {}",
+                    seed
+                );
+                selected
+            } else {
+                actions.next()
+            };
+            match action {
+                Some(action) => tree.execute(&action, true),
+                None => break,
+            };
+        }
+    }
+}
+
+/// `progress` must be called with every iteration which is also part of the report.
+#[test]
+fn progress() {
+    let tree = Node::from_bytes(&[
+        1, 4, 1, 4, 2, 1, 3, 5, 6, 2, 3, 7, 3, 0, 9, 5, 0, 4, 8, 8, 1, 6,
+    ]);
+    for depth in 0..5 {
+        let mut considered = Vec::new();
+        let selected = Bot::new(true).select_with_progress(&tree, Depth(depth), |iteration| {
+            considered.push((iteration.depth, iteration.action, iteration.fitness));
+        });
+        let report = Bot::new(true).select_verbose(&tree, Depth(depth)).unwrap();
+        assert_eq!(selected, Some(report.action));
+        let iterations: Vec<_> = report
+            .iterations
+            .iter()
+            .map(|iteration| (iteration.depth, iteration.action, iteration.fitness))
+            .collect();
+        assert_eq!(considered, iterations, "depth: {}", depth);
+    }
+}
+
+#[test]
+fn spawn_select() {
+    use crate::Game;
+
+    for seed in 0..100u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&[7, 3, 9, 1, 4, 2, 8, 6, 5, 0, 3, 7, 2, 9]);
+        let tree = Node::from_bytes(&bytes);
+        let search = Bot::new(true).spawn_select(tree.clone(), ToCompletion);
+        assert_eq!(
+            search.join(),
+            Bot::new(true).select(&tree, ToCompletion),
+            "seed: {}",
+            seed
+        );
+
+        let search = Bot::new(true).spawn_select(tree.clone(), ToCompletion);
+        search.cancel();
+        assert_eq!(
+            search.join().is_some(),
+            !tree.actions(true).1.is_empty(),
+            "seed: {}",
+            seed
+        );
+    }
+
+    let tree = Node::from_bytes(&[3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9, 3, 2, 3, 8, 4]);
+    let search = Bot::new(true).spawn_select(tree.clone(), ToCompletion);
+    while !search.poll() {
+        std::thread::yield_now();
+    }
+    let (best, _) = search.best_so_far().unwrap();
+    assert_eq!(search.join(), Some(best));
+}
+
+/// Vetoes the second action of the initial state.
+struct VetoSecond;
+
+impl crate::Veto<Node> for VetoSecond {
+    fn vetoes(&self, state: &Node, &action: &usize, _: bool) -> bool {
+        state.child_count() == 2 && action == 1
+    }
+}
+
+/// Trait objects like vetoes are shared with the spawned search.
+#[test]
+fn spawn_select_veto() {
+    #[rustfmt::skip]
+    let tree = Node::root().with_children(&[
+        Node::new(true, 3),
+        Node::new(true, 5),
+    ]);
+    let mut bot = Bot::new(true).with_veto(VetoSecond);
+    assert_eq!(bot.select(&tree, ToCompletion), Some(0));
+    assert_eq!(bot.spawn_select(tree, ToCompletion).join(), Some(0));
+}
+
+/// The spawned search ignores the pruning trace and the search tree, keeping the ones of the previous search.
+#[test]
+fn spawn_select_search_tree() {
+    #[rustfmt::skip]
+    let tree = Node::root().with_children(&[
+        Node::new(false, 7).with_children(&[
+            Node::new(true, 4),
+            Node::new(true, 2),
+        ]),
+        Node::new(false, 5).with_children(&[
+            Node::new(true, 8),
+            Node::new(true, 9),
+        ]),
+    ]);
+
+    let mut bot = Bot::new(true).with_search_tree(100);
+    bot.select(&tree, ToCompletion);
+    let nodes = bot.search_tree().unwrap().nodes().len();
+    assert_eq!(bot.spawn_select(Node::root(), ToCompletion).join(), None);
+    assert_eq!(bot.search_tree().unwrap().nodes().len(), nodes);
+    assert_eq!(bot.spawn_select(tree, ToCompletion).join(), Some(1));
+}
+
+/// Pruning traces of the spawned search are not added to the statistics of the bot.
+#[test]
+fn spawn_select_pruning_trace() {
+    let tree = Node::from_bytes(&[3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9, 3, 2, 3, 8, 4]);
+    let mut bot = Bot::new(true).with_pruning_margins(f64::from);
+    bot.select(&tree, ToCompletion);
+    let stats = bot.stats().clone();
+    assert!(!stats.pruned.is_empty());
+    let expected = Bot::new(true).select(&tree, ToCompletion);
+    assert_eq!(bot.spawn_select(tree, ToCompletion).join(), expected);
+    assert_eq!(bot.stats(), &stats);
+}
+
+/// The spawned search neither uses nor modifies the persistent table of the bot.
+#[test]
+fn spawn_select_persistent_table() {
+    use crate::Game;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn hash(node: &Node) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        node.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let mut transpositions = 0;
+    for seed in 0..100u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&[7, 3, 9, 1, 4, 2, 8, 6, 5, 0, 3, 7, 2, 9]);
+        let tree = Node::from_bytes(&bytes);
+        let bot = || {
+            Bot::new(true)
+                .with_transposition_table(1 << 8, hash)
+                .with_persistent_table()
+        };
+
+        let mut kept = bot();
+        kept.select(&tree, Depth(2));
+        let mut spawned = bot();
+        spawned.select(&tree, Depth(2));
+        let first = tree.actions(true).1.next().map(|action| {
+            let mut child = tree.clone();
+            child.execute(&action, true);
+            child
+        });
+        if let Some(child) = first {
+            spawned.spawn_select(child, ToCompletion).join();
+        }
+
+        kept.select(&tree, ToCompletion);
+        spawned.select(&tree, ToCompletion);
+        let stats = |bot: &Bot<Node>| {
+            let stats = bot.stats();
+            (stats.nodes, stats.transpositions, stats.table_replacements)
+        };
+        assert_eq!(stats(&kept), stats(&spawned), "seed: {}", seed);
+        transpositions += kept.stats().transpositions;
+    }
+    // The kept table must actually be used by the following searches.
+    assert!(transpositions > 0);
+}
+
+/// Each spawned search must advance the seed of the bot, so consecutive searches make different random choices.
+#[test]
+fn spawn_select_random_ties() {
+    let tree = Node::root().with_children(&[
+        Node::new(false, 2),
+        Node::new(false, 5),
+        Node::new(false, 5),
+        Node::new(false, 5),
+    ]);
+
+    let mut bot = Bot::new(true).with_random_ties().with_seed(3);
+    let mut reference = Bot::new(true).with_random_ties().with_seed(3);
+    let first = bot.spawn_select(tree.clone(), ToCompletion).join();
+    assert_eq!(first, reference.select(&tree, ToCompletion));
+    let selected: Vec<_> = (0..20)
+        .map(|_| bot.spawn_select(tree.clone(), ToCompletion).join().unwrap())
+        .collect();
+    assert!(selected.iter().all(|&action| action != 0));
+    assert!(selected.iter().any(|&action| Some(action) != first));
+}
+
+/// Polling `select_async` until it is ready must return the same action as `select`,
+/// and dropping it before completion must not block.
+#[cfg(feature = "async")]
+#[test]
+fn select_async() {
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake};
+    use std::thread::{self, Thread};
+
+    struct Unpark(Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future + Unpin>(mut future: F) -> F::Output {
+        let waker = Arc::new(Unpark(thread::current())).into();
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match std::pin::Pin::new(&mut future).poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    for seed in 0..50u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&[7, 3, 9, 1, 4, 2, 8, 6, 5, 0, 3, 7, 2, 9]);
+        let tree = Node::from_bytes(&bytes);
+        assert_eq!(
+            block_on(Bot::new(true).select_async(tree.clone(), ToCompletion)),
+            Bot::new(true).select(&tree, ToCompletion),
+            "seed: {}",
+            seed
+        );
+    }
+
+    let tree = Node::from_bytes(&[3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9, 3, 2, 3, 8, 4]);
+    drop(Bot::new(true).select_async(tree, ToCompletion));
+}
+
+/// A tree with real-valued fitness.
+#[derive(Clone)]
+struct Real(Node);
+
+impl crate::Game for Real {
+    type Player = bool;
+    type Action = usize;
+    type Fitness = crate::OrderedF64;
+    type Actions = std::ops::Range<usize>;
+
+    fn actions(&self, player: bool) -> (bool, Self::Actions) {
+        self.0.actions(player)
+    }
+
+    fn execute(&mut self, action: &usize, player: bool) -> crate::OrderedF64 {
+        crate::OrderedF64::new(f64::from(self.0.execute(action, player)) / 3.0)
+    }
+}
+
+/// `OrderedF64` fitness values result in the same actions as the integers they were computed from.
+#[test]
+fn ordered_float() {
+    use crate::brute::Brute;
+
+    for seed in 0..100u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&[7, 3, 9, 1, 4, 2, 8, 6, 5, 0, 3, 7, 2, 9, 4, 4, 1, 8]);
+        let tree = Real(Node::from_bytes(&bytes));
+        for depth in 0..4 {
+            let selected = Bot::new(true).select(&tree, Depth(depth));
+            assert!(
+                Brute::new(true).check_if_best(&tree, selected.as_ref(), depth),
+                "seed: {}, depth: {}",
+                seed,
+                depth
+            );
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "require a transposition table")]
+fn threads_without_table() {
+    Bot::<Node>::new(true).with_threads(2);
+}
+
+#[test]
+#[should_panic(expected = "must not be `NaN`")]
+fn ordered_float_nan() {
+    crate::OrderedF32::new(f32::INFINITY - f32::INFINITY);
+}
+
+/// A tree where actions with the given index are searched first.
+#[derive(Clone)]
+struct Hinted(Node, usize);
+
+impl crate::Game for Hinted {
+    type Player = bool;
+    type Action = usize;
+    type Fitness = i8;
+    type Actions = std::ops::Range<usize>;
+
+    fn actions(&self, player: bool) -> (bool, Self::Actions) {
+        self.0.actions(player)
+    }
+
+    fn execute(&mut self, action: &usize, player: bool) -> i8 {
+        self.0.execute(action, player)
+    }
+
+    fn order_hint(&self, &action: &usize, _: bool) -> i32 {
+        i32::from(action == self.1)
+    }
+}
+
+/// Order hints must not change the fitness of the selected action.
+#[test]
+fn order_hint() {
+    use crate::brute::Brute;
+
+    for seed in 0..100u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..30u8).map(|i| (seed as u8).wrapping_mul(17).wrapping_add(i * 7)));
+        let tree = Node::from_bytes(&bytes);
+        for depth in 0..5 {
+            let selected =
+                Bot::new(true).select(&Hinted(tree.clone(), seed as usize % 3), Depth(depth));
+            assert!(
+                Brute::new(true).check_if_best(&tree, selected.as_ref(), depth),
+                "seed: {}, depth: {}",
+                seed,
+                depth
+            );
+        }
+    }
+
+    // Both actions are equally good, so the one searched first is selected.
+    let tree = Node::root().with_children(&[Node::new(false, 3), Node::new(false, 3)]);
+    assert_eq!(
+        Bot::new(true).select(&Hinted(tree.clone(), 0), ToCompletion),
+        Some(0)
+    );
+    assert_eq!(
+        Bot::new(true).select(&Hinted(tree, 1), ToCompletion),
+        Some(1)
+    );
+}
+
+/// Tic-tac-toe, where `true` places the first mark.
+#[derive(Clone, Hash)]
+struct TicTacToe {
+    /// `0` is empty, `1` belongs to `true` and `2` to `false`.
+    board: [u8; 9],
+    active: bool,
+}
+
+impl TicTacToe {
+    fn winner(&self) -> Option<u8> {
+        const LINES: [[usize; 3]; 8] = [
+            [0, 1, 2],
+            [3, 4, 5],
+            [6, 7, 8],
+            [0, 3, 6],
+            [1, 4, 7],
+            [2, 5, 8],
+            [0, 4, 8],
+            [2, 4, 6],
+        ];
+        LINES
+            .iter()
+            .map(|line| self.board[line[0]])
+            .zip(LINES.iter())
+            .find(|&(mark, line)| mark != 0 && line.iter().all(|&i| self.board[i] == mark))
+            .map(|(mark, _)| mark)
+    }
+
+    /// Hashes the smallest of all rotations and reflections of the board.
+    fn canonical_hash(&self) -> u64 {
+        const ROTATE: [usize; 9] = [6, 3, 0, 7, 4, 1, 8, 5, 2];
+        const REFLECT: [usize; 9] = [2, 1, 0, 5, 4, 3, 8, 7, 6];
+        let mut board = self.board;
+        let mut smallest = board;
+        for i in 0..8 {
+            let map = if i == 4 { REFLECT } else { ROTATE };
+            board = map.map(|j| board[j]);
+            smallest = smallest.min(board);
+        }
+        smallest.iter().fold(u64::from(self.active), |hash, &mark| {
+            hash * 3 + u64::from(mark)
+        })
+    }
+}
+
+impl crate::Game for TicTacToe {
+    type Player = bool;
+    type Action = usize;
+    type Fitness = i8;
+    type Actions = Vec<usize>;
+
+    fn actions(&self, player: bool) -> (bool, Vec<usize>) {
+        let actions = if self.winner().is_some() {
+            Vec::new()
+        } else {
+            (0..9).filter(|&i| self.board[i] == 0).collect()
+        };
+        (player == self.active, actions)
+    }
+
+    fn execute(&mut self, &action: &usize, player: bool) -> i8 {
+        self.board[action] = if self.active { 1 } else { 2 };
+        self.active = !self.active;
+        match self.winner() {
+            Some(mark) if (mark == 1) == player => 1,
+            Some(_) => -1,
+            None => 0,
+        }
+    }
+}
+
+/// Symmetric states may share transposition table entries without changing the result.
+#[test]
+fn canonical_hash() {
+    use crate::Game;
+
+    let mut state = TicTacToe {
+        board: [0; 9],
+        active: true,
+    };
+    let mut exact = Bot::new(true).with_transposition_table(1 << 12, |state: &TicTacToe| {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        std::hash::Hash::hash(state, &mut hasher);
+        std::hash::Hasher::finish(&hasher)
+    });
+    let mut symmetric = Bot::new(true).with_transposition_table(1 << 12, TicTacToe::canonical_hash);
+    for &opening in &[None, Some(0), Some(1), Some(4)] {
+        let mut state = state.clone();
+        if let Some(opening) = opening {
+            state.execute(&opening, true);
+            state.execute(&((opening + 3) % 9), false);
+        }
+        let expected = exact.detailed_select(&state, ToCompletion).unwrap();
+        let actual = symmetric.detailed_select(&state, ToCompletion).unwrap();
+        assert_eq!(actual.fitness, expected.fitness, "opening: {:?}", opening);
+        assert!(
+            symmetric.stats().nodes < exact.stats().nodes,
+            "opening: {:?}",
+            opening
+        );
+    }
+
+    // Perfect play results in a draw.
+    let mut bots = [Bot::new(true), Bot::new(false)]
+        .map(|bot| bot.with_transposition_table(1 << 12, TicTacToe::canonical_hash));
+    while let Some(action) = bots[usize::from(!state.active)].select(&state, ToCompletion) {
+        state.execute(&action, state.active);
+    }
+    assert_eq!(state.winner(), None);
+}
+
+/// A tree which panics when executing an action while `.1` is set.
+#[derive(Clone)]
+struct Panicking(Node, bool);
+
+impl crate::Game for Panicking {
+    type Player = bool;
+    type Action = usize;
+    type Fitness = i8;
+    type Actions = std::ops::Range<usize>;
+
+    fn actions(&self, player: bool) -> (bool, Self::Actions) {
+        self.0.actions(player)
+    }
+
+    fn execute(&mut self, action: &usize, player: bool) -> i8 {
+        assert!(!self.1, "panicking");
+        self.0.execute(action, player)
+    }
+}
+
+/// After a caught panic, the hint, the stats and the kept table are discarded.
+#[test]
+fn try_select_reset() {
+    use crate::SelectError;
+
+    #[rustfmt::skip]
+    let tree = Node::root().with_children(&[
+        Node::new(false, 5).with_children(&[
+            Node::new(true, 3),
+        ]),
+        Node::new(false, 4).with_children(&[
+            Node::new(true, 3),
+        ]),
+    ]);
+    let mut bot = Bot::new(true)
+        .with_transposition_table(64, |state: &Panicking| state.0.child_count() as u64)
+        .with_persistent_table();
+    assert_eq!(
+        bot.select(&Panicking(tree.clone(), false), Depth(1)),
+        Some(0)
+    );
+    bot.set_hint(1);
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| ()));
+    let result = bot.try_select(&Panicking(tree.clone(), true), ToCompletion);
+    std::panic::set_hook(hook);
+    assert!(matches!(result, Err(SelectError::Panicked(_))));
+    assert_eq!(bot.stats().nodes, 0);
+    assert!(bot.search_tree().is_none());
+    // The hint is no longer used.
+    assert_eq!(bot.select(&Panicking(tree, false), Steps(0)), Some(0));
+}
+
+/// With `with_clock`, all durations of the search are measured using the given clock.
+#[test]
+fn bot_clock() {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::Duration;
+
+    static MILLIS: AtomicU64 = AtomicU64::new(0);
+    fn now() -> Duration {
+        Duration::from_millis(MILLIS.fetch_add(1, Ordering::Relaxed))
+    }
+
+    let tree = Node::from_bytes(&[
+        3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9, 3, 2, 3, 8, 4, 6, 2, 6, 4,
+    ]);
+    let mut bot = Bot::new(true).with_clock(now);
+    let mut logger = Logger::new(Depth(3)).with_clock(now);
+    let before = MILLIS.load(Ordering::Relaxed);
+    let action = bot.detailed_select(&tree, &mut logger).unwrap();
+    let reads = MILLIS.load(Ordering::Relaxed) - before;
+
+    // The bot reads the clock at the start, after each iteration and at the end,
+    // while the logger reads it once it is created and once it is dropped.
+    assert_eq!(action.iterations.len(), 3);
+    assert_eq!(reads, 7);
+    assert_eq!(bot.stats().duration, Duration::from_millis(6));
+    assert_eq!(logger.duration(), Duration::from_millis(4));
+    for (i, iteration) in action.iterations.iter().enumerate() {
+        assert_eq!(iteration.elapsed, Duration::from_millis(i as u64 + 2));
+    }
+}
+
+/// The statistics of a search driven by a simulated clock only depend on that clock.
+#[test]
+fn stats_clock() {
+    use crate::Timeout;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::Duration;
+
+    static MILLIS: AtomicU64 = AtomicU64::new(0);
+    fn now() -> Duration {
+        Duration::from_millis(MILLIS.fetch_add(1, Ordering::Relaxed))
+    }
+
+    let tree = Node::from_bytes(&[
+        2, 7, 1, 8, 2, 8, 1, 8, 2, 8, 4, 5, 9, 0, 4, 5, 2, 3, 5, 3, 6, 0, 2, 8, 7, 4,
+    ]);
+    let mut bot = Bot::new(true).with_clock(now);
+    let timeout = Timeout::new(now, Duration::from_millis(30));
+    let before = MILLIS.load(Ordering::Relaxed);
+    assert!(bot.select(&tree, timeout).is_some());
+    let reads = MILLIS.load(Ordering::Relaxed) - before;
+
+    // The bot reads the clock at the very start and the very end of the search.
+    let stats = bot.stats();
+    let duration = Duration::from_millis(reads - 1);
+    assert_eq!(stats.duration, duration);
+    assert_eq!(
+        stats.nodes_per_second(),
+        stats.nodes as f64 / duration.as_secs_f64()
+    );
+}
@@ -0,0 +1,97 @@
+//! Tests for `openings::Book`.
+use super::*;
+use crate::openings::{Book, WithBook};
+use crate::{Game, GameBot};
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+fn hash(node: &Node) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    node.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn tree() -> Node {
+    #[rustfmt::skip]
+    let tree = Node::root().with_children(&[
+        Node::new(false, 1).with_children(&[
+            Node::new(true, 1),
+            Node::new(true, 2),
+        ]),
+        Node::new(false, 2).with_children(&[
+            Node::new(true, 3).with_children(&[
+                Node::new(false, 3),
+            ]),
+            Node::new(true, 4),
+        ]),
+        Node::new(false, 5),
+    ]);
+    tree
+}
+
+/// Actions are chosen proportional to their weights, and only from the book.
+#[test]
+fn weights() {
+    let tree = tree();
+    let mut book = Book::new(hash).with_seed(3);
+    book.insert(&tree, 0, 3);
+    book.insert(&tree, 1, 1);
+    book.insert(&tree, 0, 0);
+    assert_eq!(book.actions(&tree), &[(0, 3), (1, 1)]);
+
+    let mut counts = [0; 3];
+    for _ in 0..4000 {
+        counts[book.select(&tree, true).unwrap()] += 1;
+    }
+    assert_eq!(counts[2], 0);
+    assert!(counts[0] > 2 * counts[1], "counts: {:?}", counts);
+    assert!(counts[1] > 800, "counts: {:?}", counts);
+}
+
+#[test]
+fn recorded_games() {
+    let tree = tree();
+    let book = Book::new(hash)
+        .with_max_plies(2)
+        .with_game(&tree, vec![(true, 1), (false, 0), (true, 0)])
+        .with_game(&tree, vec![(true, 1), (false, 1)])
+        .with_game(&tree, vec![(true, 0)]);
+    assert_eq!(book.len(), 2);
+    assert_eq!(book.actions(&tree), &[(1, 2), (0, 1)]);
+
+    let mut after = tree.clone();
+    after.execute(&1, true);
+    assert_eq!(book.actions(&after), &[(0, 1), (1, 1)]);
+    // The third action of the first game exceeds the maximum amount of plies.
+    after.execute(&0, false);
+    assert!(book.actions(&after).is_empty());
+}
+
+/// Impossible actions, e.g. due to hash collisions, and inactive players are ignored.
+#[test]
+fn impossible() {
+    let tree = tree();
+    let mut book = Book::new(|_: &Node| 0);
+    book.insert(&tree, 7, 1);
+    assert_eq!(book.select(&tree, true), None);
+    book.insert(&tree, 2, 1);
+    assert_eq!(book.select(&tree, true), Some(2));
+    assert_eq!(book.select(&tree, false), None);
+}
+
+/// The wrapped bot is only used once the book does not contain the state.
+#[test]
+fn with_book() {
+    let tree = tree();
+    let book = Book::new(hash).with_game(&tree, vec![(true, 1), (false, 0)]);
+    let mut bot = WithBook::new(true, book, Bot::new(true));
+    assert_eq!(GameBot::select(&mut bot, &tree, ToCompletion), Some(1));
+    assert_eq!(bot.bot_mut().select(&tree, ToCompletion), Some(2));
+
+    let mut after = tree.clone();
+    after.execute(&1, true);
+    after.execute(&0, false);
+    assert!(bot.book().actions(&after).is_empty());
+    assert_eq!(bot.select(&after, ToCompletion), Some(0));
+}
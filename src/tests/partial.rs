@@ -54,3 +54,108 @@ fn fuzz_two() {
     let selected = Bot::new(true).select(&tree, Steps(7));
     assert_eq!(selected, Some(0));
 }
+
+/// `Bot::with_max_depth` should behave like `Depth`.
+#[test]
+fn max_depth() {
+    #[rustfmt::skip]
+    let tree = Node::root().with_children(&[
+        Node::new(true, 0).with_children(&[
+            Node::new(true, 127)
+        ]),
+        Node::new(true, 3).with_children(&[
+            Node::new(false, -6),
+        ]),
+        Node::new(true, 1),
+    ]);
+
+    for depth in 0..3 {
+        assert_eq!(
+            Bot::new(true)
+                .with_max_depth(depth)
+                .select(&tree, ToCompletion),
+            Bot::new(true).select(&tree, Depth(depth)),
+        );
+    }
+    assert_eq!(
+        Bot::new(true).with_max_depth(0).select(&tree, ToCompletion),
+        Some(1)
+    );
+}
+
+/// `select` should not overshoot the deadline by more than a single call to `execute`,
+/// even if expanding a node requires a lot of these calls.
+#[test]
+fn deadline() {
+    use crate::Game;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    /// Only executing actions below the root is slow.
+    #[derive(Clone)]
+    struct Slow(u8);
+
+    impl Game for Slow {
+        type Player = ();
+        type Action = u8;
+        type Fitness = u8;
+        type Actions = std::ops::Range<u8>;
+
+        fn actions(&self, _: ()) -> (bool, Self::Actions) {
+            (true, 0..50)
+        }
+
+        fn execute(&mut self, action: &u8, _: ()) -> u8 {
+            if self.0 > 0 {
+                thread::sleep(Duration::from_millis(2));
+            }
+            self.0 += 1;
+            *action
+        }
+
+        fn look_ahead(&self, action: &u8, _: ()) -> u8 {
+            *action
+        }
+    }
+
+    let start = Instant::now();
+    let selected = Bot::new(()).select(&Slow(0), Duration::from_millis(20));
+    assert!(selected.is_some());
+    // Expanding a single node below the root takes at least 100 milliseconds.
+    assert!(start.elapsed() < Duration::from_millis(80));
+}
+
+/// Top level actions which were completely searched before an iteration was
+/// cancelled should replace the best action of the previous iteration.
+#[test]
+fn interrupted_iteration() {
+    #[rustfmt::skip]
+    let tree = Node::root().with_children(&[
+        Node::new(false, 5).with_children(&[
+            Node::new(true, 6).with_children(&[
+                Node::new(false, 0),
+            ]),
+        ]),
+        Node::new(false, 4).with_children(&[
+            Node::new(true, 4).with_children(&[
+                Node::new(false, 4),
+            ]),
+        ]),
+        Node::new(false, 1).with_children(&[
+            Node::new(true, 1).with_children(&[
+                Node::new(false, 1),
+            ]),
+            Node::new(true, 1).with_children(&[
+                Node::new(false, 1),
+            ]),
+        ]),
+    ]);
+
+    // Steps after the second iteration searched `[1]` but before it completed.
+    for steps in 13..16 {
+        let mut logger = Logger::new(Steps(steps));
+        let selected = Bot::new(true).select(&tree, &mut logger);
+        assert_eq!(logger.depth(), 1, "steps: {}", steps);
+        assert_eq!(selected, Some(1), "steps: {}", steps);
+    }
+}
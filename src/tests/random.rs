@@ -0,0 +1,30 @@
+//! Tests for `random::Bot`.
+use super::*;
+use crate::random;
+
+#[test]
+fn uniform() {
+    let tree = Node::root().with_children(&[
+        Node::new(false, 7),
+        Node::new(false, 5),
+        Node::new(false, 6),
+        Node::new(false, 1),
+    ]);
+
+    let mut bot = random::Bot::new(true).with_seed(3);
+    let mut counts = [0; 4];
+    for _ in 0..4000 {
+        counts[bot.select(&tree).unwrap()] += 1;
+    }
+    for &count in &counts {
+        assert!(count > 800 && count < 1200, "counts: {:?}", counts);
+    }
+}
+
+#[test]
+fn impossible() {
+    let tree = Node::root().with_children(&[Node::new(false, 7)]);
+    assert_eq!(random::Bot::new(false).select(&tree), None);
+    assert_eq!(random::Bot::new(true).select(&Node::root()), None);
+    assert_eq!(random::Bot::new(true).select(&tree), Some(0));
+}
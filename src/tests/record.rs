@@ -0,0 +1,83 @@
+//! Tests for `record::Record`.
+use super::*;
+use crate::record::Record;
+use crate::Game;
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::ErrorKind;
+use std::time::Duration;
+
+fn hash(node: &Node) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    node.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Recording a whole game stores each action with the hash of the state it was played in,
+/// and exporting and importing the record keeps all entries.
+#[test]
+fn roundtrip() {
+    for seed in 0..50u32 {
+        let mut bytes = seed.to_be_bytes().to_vec();
+        bytes.extend((0..30).map(|i| (seed.wrapping_mul(7) + i * 13) as u8));
+        let mut tree = Node::from_bytes(&bytes);
+
+        let mut record = Record::new(hash);
+        let mut bot = Bot::new(true);
+        let mut states = Vec::new();
+        loop {
+            states.push(hash(&tree));
+            if let Some(action) = record.select(&mut bot, &tree, Depth(2)) {
+                assert_eq!(Some(action), Bot::new(true).select(&tree, Depth(2)));
+                tree.execute(&action, true);
+            } else if let Some(action) = tree.actions(false).1.next() {
+                record.execute(&mut tree, action, false);
+            } else {
+                states.pop();
+                break;
+            }
+        }
+
+        let mut exported = Vec::new();
+        record.write(&mut exported).unwrap();
+        let imported: Record<Node> = Record::read(hash, exported.as_slice()).unwrap();
+        assert_eq!(imported.len(), states.len(), "seed: {}", seed);
+        for ((entry, original), &state) in
+            imported.entries().iter().zip(record.entries()).zip(&states)
+        {
+            assert_eq!(entry.hash, state, "seed: {}", seed);
+            assert_eq!(entry.action, original.action, "seed: {}", seed);
+            assert_eq!(entry.fitness, original.fitness, "seed: {}", seed);
+            assert_eq!(entry.depth, original.depth, "seed: {}", seed);
+            assert_eq!(
+                entry.time.as_micros(),
+                original.time.as_micros(),
+                "seed: {}",
+                seed
+            );
+        }
+    }
+}
+
+#[test]
+fn invalid() {
+    let valid = "17\t2\t-3\t-\t5\n\n4\t0\t8\t1\t1200\n";
+    let record: Record<Node> = Record::read(hash, valid.as_bytes()).unwrap();
+    assert_eq!(record.len(), 2);
+    assert_eq!(record.entries()[0].fitness, -3);
+    assert_eq!(record.entries()[0].depth, None);
+    assert_eq!(record.entries()[1].time, Duration::from_micros(1200));
+
+    for &invalid in &[
+        "17\t2\t-3\t-",
+        "17\t2\t-3\t-\t5\t0",
+        "17\tx\t-3\t-\t5",
+        "17\t2\t300\t1\t5",
+    ] {
+        let error = Record::<Node>::read(hash, invalid.as_bytes())
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidData, "line: {:?}", invalid);
+    }
+}
@@ -0,0 +1,80 @@
+//! Tests for `selfplay::SelfPlay`.
+use super::tournament::{outcome, Stones};
+use crate::selfplay::SelfPlay;
+use crate::tournament::Outcome;
+use crate::{random, Bot, Depth, Game};
+
+use std::cell::Cell;
+
+fn starts() -> Vec<Stones> {
+    (5..=8)
+        .map(|stones| Stones {
+            stones,
+            first: true,
+        })
+        .collect()
+}
+
+/// Replaying the samples of a game must result in the same states,
+/// and the player of the last action wins all games which were played until the end.
+#[test]
+fn samples() {
+    let mut games = 0;
+    SelfPlay::new(vec![true, false], starts(), outcome)
+        .with_games(6)
+        .run(
+            |player| random::Bot::new(player).with_seed(u64::from(player)),
+            Depth(0),
+            |samples| {
+                let start = &starts()[games % 4];
+                games += 1;
+                let mut state = start.clone();
+                for sample in samples {
+                    assert_eq!(sample.state.stones, state.stones);
+                    assert_eq!(sample.player, state.first);
+                    state.execute(&sample.action, sample.player);
+                }
+                assert_eq!(state.stones, 0);
+
+                let last = samples.last().unwrap();
+                for sample in samples {
+                    let expected = if sample.player == last.player {
+                        Outcome::Win
+                    } else {
+                        Outcome::Loss
+                    };
+                    assert_eq!(sample.outcome, expected);
+                }
+            },
+        );
+    assert_eq!(games, 6);
+}
+
+/// Changes made by `update` are visible to the bots of later games.
+#[test]
+fn update() {
+    let depth = Cell::new(0);
+    let mut depths = Vec::new();
+    SelfPlay::new(vec![true, false], starts(), outcome).run(
+        |player| {
+            depths.push(depth.get());
+            Bot::new(player).with_max_depth(depth.get())
+        },
+        Depth(u32::MAX),
+        |_| depth.set(depth.get() + 1),
+    );
+    assert_eq!(depths, [0, 0, 1, 1, 2, 2, 3, 3]);
+}
+
+/// Games are scored once the maximum amount of plies is reached.
+#[test]
+fn max_plies() {
+    let mut lengths = Vec::new();
+    SelfPlay::new(vec![true, false], starts(), outcome)
+        .with_max_plies(2)
+        .run(Bot::new, Depth(10), |samples| {
+            assert!(samples.iter().all(|sample| sample.outcome == Outcome::Draw));
+            lengths.push(samples.len());
+        });
+    assert_eq!(lengths, [2, 2, 2, 2]);
+}
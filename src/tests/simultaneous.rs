@@ -0,0 +1,186 @@
+//! Tests for `simultaneous::Bot`.
+use super::*;
+use crate::rng::XorShiftRng;
+use crate::simultaneous::{self, SimultaneousGame};
+
+/// A single turn, where the fitness is the entry at the action of the bot and the reply of the opponent.
+///
+/// The opponent is the player `false`, to which the entries are transposed and negated.
+#[derive(Clone)]
+struct Matrix {
+    entries: Vec<Vec<i8>>,
+    done: bool,
+}
+
+impl SimultaneousGame for Matrix {
+    type Player = bool;
+    type Action = usize;
+    type Fitness = i8;
+
+    fn actions(&self, player: bool) -> (Vec<usize>, Vec<usize>) {
+        if self.done {
+            return (Vec::new(), Vec::new());
+        }
+
+        let rows = (0..self.entries.len()).collect();
+        let columns = (0..self.entries[0].len()).collect();
+        if player {
+            (rows, columns)
+        } else {
+            (columns, rows)
+        }
+    }
+
+    fn execute(&mut self, &action: &usize, &reply: &usize, player: bool) -> i8 {
+        self.done = true;
+        if player {
+            self.entries[action][reply]
+        } else {
+            -self.entries[reply][action]
+        }
+    }
+}
+
+fn matrix(entries: &[&[i8]]) -> Matrix {
+    Matrix {
+        entries: entries.iter().map(|row| row.to_vec()).collect(),
+        done: false,
+    }
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-6,
+        "actual: {}, expected: {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn rock_paper_scissors() {
+    let state = matrix(&[&[0, -1, 1], &[1, 0, -1], &[-1, 1, 0]]);
+    let strategy = simultaneous::Bot::new(true)
+        .strategy(&state, ToCompletion)
+        .unwrap();
+    assert_close(strategy.value, 0.0);
+    for (_, probability) in strategy.actions {
+        assert_close(probability, 1.0 / 3.0);
+    }
+}
+
+#[test]
+fn dominant_action() {
+    let state = matrix(&[&[1, 2, 0], &[3, 4, 2], &[2, 0, 1]]);
+    let mut bot = simultaneous::Bot::new(true);
+    let strategy = bot.strategy(&state, ToCompletion).unwrap();
+    assert_close(strategy.value, 2.0);
+    let probabilities: Vec<_> = strategy.actions.iter().map(|&(_, p)| p).collect();
+    assert_close(probabilities[0], 0.0);
+    assert_close(probabilities[1], 1.0);
+    assert_close(probabilities[2], 0.0);
+    for seed in 0..20 {
+        bot = bot.with_seed(seed);
+        assert_eq!(bot.select(&state, ToCompletion), Some(1));
+    }
+}
+
+#[test]
+fn ended() {
+    let mut state = matrix(&[&[1]]);
+    state.done = true;
+    assert!(simultaneous::Bot::new(true)
+        .strategy(&state, ToCompletion)
+        .is_none());
+    assert_eq!(simultaneous::Bot::new(true).select(&state, Depth(3)), None);
+}
+
+/// The strategy of the bot must guarantee its value against every reply, and solving the game
+/// for the opponent must result in the negated value.
+#[test]
+fn random_matrices() {
+    let mut rng = XorShiftRng::from_seed_u64(7);
+    for _ in 0..200 {
+        let rows = 1 + rng.below(4);
+        let columns = 1 + rng.below(4);
+        let state = Matrix {
+            entries: (0..rows)
+                .map(|_| (0..columns).map(|_| rng.below(11) as i8 - 5).collect())
+                .collect(),
+            done: false,
+        };
+
+        let strategy = simultaneous::Bot::new(true)
+            .strategy(&state, ToCompletion)
+            .unwrap();
+        assert_close(strategy.actions.iter().map(|&(_, p)| p).sum(), 1.0);
+        for reply in 0..columns {
+            let expected: f64 = strategy
+                .actions
+                .iter()
+                .map(|&(action, p)| p * f64::from(state.entries[action][reply]))
+                .sum();
+            assert!(expected > strategy.value - 1e-6);
+        }
+
+        let opponent = simultaneous::Bot::new(false)
+            .strategy(&state, ToCompletion)
+            .unwrap();
+        assert_close(opponent.value, -strategy.value);
+    }
+}
+
+/// The first turn decides which matrix is played during the second turn.
+#[derive(Clone)]
+enum Sequence {
+    First,
+    Second(Matrix),
+    Done,
+}
+
+impl SimultaneousGame for Sequence {
+    type Player = bool;
+    type Action = usize;
+    type Fitness = i8;
+
+    fn actions(&self, player: bool) -> (Vec<usize>, Vec<usize>) {
+        match self {
+            Sequence::First => (vec![0, 1], vec![0, 1]),
+            Sequence::Second(matrix) => matrix.actions(player),
+            Sequence::Done => (Vec::new(), Vec::new()),
+        }
+    }
+
+    fn execute(&mut self, &action: &usize, &reply: &usize, player: bool) -> i8 {
+        match self {
+            Sequence::First => {
+                *self = if action == reply {
+                    Sequence::Second(matrix(&[&[0, -1, 1], &[1, 0, -1], &[-1, 1, 0]]))
+                } else {
+                    Sequence::Second(matrix(&[&[2, 3], &[4, 2]]))
+                };
+                0
+            }
+            Sequence::Second(matrix) => {
+                let fitness = matrix.execute(&action, &reply, player);
+                *self = Sequence::Done;
+                fitness
+            }
+            Sequence::Done => unreachable!(),
+        }
+    }
+}
+
+#[test]
+fn recursion() {
+    let bot = simultaneous::Bot::new(true);
+    let shallow = bot.strategy(&Sequence::First, Depth(0)).unwrap();
+    assert_close(shallow.value, 0.0);
+
+    // The second matrix has a value of `8 / 3`, so the first turn is `[[0, 8 / 3], [8 / 3, 0]]`.
+    let strategy = bot.strategy(&Sequence::First, ToCompletion).unwrap();
+    assert_close(strategy.value, 4.0 / 3.0);
+    for (_, probability) in strategy.actions {
+        assert_close(probability, 0.5);
+    }
+}
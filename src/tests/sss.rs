@@ -0,0 +1,45 @@
+//! Tests for `sss::Bot`.
+use super::*;
+use crate::brute::Brute;
+use crate::sss;
+
+/// The result must always be one of the best actions at the searched depth.
+#[test]
+fn random_trees() {
+    for seed in 0..100u32 {
+        let bytes: Vec<u8> = seed
+            .to_be_bytes()
+            .iter()
+            .copied()
+            .chain((0..40u8).map(|i| (seed as u8).wrapping_mul(29).wrapping_add(i)))
+            .collect();
+        let tree = Node::from_bytes(&bytes);
+        let mut bot = sss::Bot::new(true);
+        let selected = bot.select(&tree, ToCompletion);
+        assert!(
+            Brute::new(true).check_if_best(&tree, selected.as_ref(), u32::MAX),
+            "seed: {}",
+            seed
+        );
+
+        for depth in 0..4 {
+            let selected = bot.select(&tree, Depth(depth));
+            assert!(
+                Brute::new(true).check_if_best(&tree, selected.as_ref(), depth),
+                "seed: {}, depth: {}",
+                seed,
+                depth
+            );
+        }
+    }
+}
+
+/// Interrupted searches must still return a possible action.
+#[test]
+fn interrupted() {
+    let tree = Node::from_bytes(&[2, 7, 1, 8, 2, 8, 1, 8, 2, 8, 4, 5, 9, 0, 4, 5, 2, 3, 5, 3]);
+    for steps in 0..20 {
+        let selected = sss::Bot::new(true).select(&tree, Steps(steps));
+        assert!(selected.is_some(), "steps: {}", steps);
+    }
+}
@@ -0,0 +1,124 @@
+//! Tests for `tournament::Tournament`.
+use crate::tournament::{Outcome, Results, Tournament};
+use crate::{random, Bot, Depth, Game};
+
+/// Both players take one to three stones, the player who takes the last stone wins.
+#[derive(Clone)]
+pub(super) struct Stones {
+    pub(super) stones: u8,
+    pub(super) first: bool,
+}
+
+impl Game for Stones {
+    type Player = bool;
+    type Action = u8;
+    type Fitness = i8;
+    type Actions = Vec<u8>;
+
+    fn actions(&self, player: bool) -> (bool, Vec<u8>) {
+        (player == self.first, (1..=self.stones.min(3)).collect())
+    }
+
+    fn execute(&mut self, &take: &u8, player: bool) -> i8 {
+        let mover = self.first;
+        self.stones -= take;
+        self.first = !self.first;
+        match self.stones {
+            0 if mover == player => 1,
+            0 => -1,
+            _ => 0,
+        }
+    }
+}
+
+pub(super) fn outcome(state: &Stones, player: bool) -> Outcome {
+    if state.stones > 0 {
+        Outcome::Draw
+    } else if state.first == player {
+        Outcome::Loss
+    } else {
+        Outcome::Win
+    }
+}
+
+/// The first player wins iff the amount of stones is not divisible by `4`,
+/// so equal bots win exactly the games where they start in a winning position.
+#[test]
+fn alternating() {
+    let starts = (1..=8)
+        .map(|stones| Stones {
+            stones,
+            first: true,
+        })
+        .collect();
+    let tournament = Tournament::new([true, false], starts, outcome);
+    let results = tournament.run(Bot::new, Bot::new, Depth(10));
+    assert_eq!(
+        results,
+        Results {
+            wins: 8,
+            draws: 0,
+            losses: 8
+        }
+    );
+    assert_eq!(results.score(), Some(0.5));
+
+    let results = tournament.with_games(40).run(
+        Bot::new,
+        |player| random::Bot::new(player).with_seed(3),
+        Depth(10),
+    );
+    assert_eq!(results.games(), 40);
+    assert!(results.wins >= 30, "results: {:?}", results);
+}
+
+/// Games are scored once the maximum amount of plies is reached.
+#[test]
+fn max_plies() {
+    let start = Stones {
+        stones: 30,
+        first: true,
+    };
+    let results = Tournament::new([true, false], vec![start], outcome)
+        .with_max_plies(4)
+        .with_games(5)
+        .run(Bot::new, Bot::new, Depth(2));
+    assert_eq!(
+        results,
+        Results {
+            wins: 0,
+            draws: 5,
+            losses: 0
+        }
+    );
+    assert_eq!(Results::default().score(), None);
+}
+
+#[test]
+fn elo() {
+    let results = |wins, draws, losses| Results {
+        wins,
+        draws,
+        losses,
+    };
+    assert_eq!(results(0, 0, 0).elo(), None);
+
+    let even = results(10, 5, 10).elo().unwrap();
+    assert!(even.difference.abs() < 1e-9);
+    assert!((results(10, 10, 0).elo().unwrap().difference - 190.849).abs() < 1e-3);
+    assert!((results(0, 10, 10).elo().unwrap().difference + 190.849).abs() < 1e-3);
+
+    // More games and draws reduce the error for the same score.
+    let more = results(100, 50, 100).elo().unwrap();
+    let draws = results(0, 25, 0).elo().unwrap();
+    assert!(more.error < even.error);
+    assert_eq!(draws.difference, 0.0);
+    assert_eq!(draws.error, 0.0);
+
+    let perfect = results(10, 0, 0).elo().unwrap();
+    assert_eq!(perfect.difference, f64::INFINITY);
+    assert_eq!(perfect.error, f64::INFINITY);
+    let hopeless = results(0, 0, 10).elo().unwrap();
+    assert_eq!(hopeless.difference, f64::NEG_INFINITY);
+    assert_eq!(hopeless.error, f64::INFINITY);
+}
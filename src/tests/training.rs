@@ -0,0 +1,80 @@
+//! Tests for `training::Training`.
+use super::tournament::{outcome, Stones};
+use crate::selfplay::SelfPlay;
+use crate::tournament::Tournament;
+use crate::training::Training;
+use crate::tune::SharedWeights;
+use crate::{Bot, Depth};
+
+use std::cell::RefCell;
+
+fn starts() -> Vec<Stones> {
+    (5..=10)
+        .map(|stones| Stones {
+            stones,
+            first: true,
+        })
+        .collect()
+}
+
+/// Stronger candidates replace the current weights, which are used by the following generations,
+/// while weaker candidates are rejected.
+#[test]
+fn gating() {
+    let training = Training::new(
+        SelfPlay::new(vec![true, false], starts(), outcome),
+        Tournament::new([true, false], starts(), outcome),
+    );
+    let weights = SharedWeights::new(vec![0.0]);
+    let trained = RefCell::new(Vec::new());
+    let generations = training.run(
+        &weights,
+        3,
+        |player, weights| Bot::new(player).with_max_depth(weights[0] as u32),
+        Depth(u32::MAX),
+        |weights, samples| {
+            trained.borrow_mut().push((weights.to_vec(), samples.len()));
+            match weights[0] as u32 {
+                0 => vec![10.0],
+                _ => vec![0.0],
+            }
+        },
+    );
+
+    let accepted: Vec<_> = generations.iter().map(|gen| gen.accepted).collect();
+    assert_eq!(accepted, [true, false, false]);
+    assert_eq!(&*weights.load(), &[10.0]);
+    let trained = trained.into_inner();
+    assert_eq!(trained[0].0, [0.0]);
+    assert_eq!(trained[1].0, [10.0]);
+    assert_eq!(trained[2].0, [10.0]);
+    for (generation, (_, samples)) in generations.iter().zip(&trained) {
+        assert_eq!(generation.samples, *samples);
+        assert!(generation.samples > 0);
+        assert_eq!(generation.results.games(), 12);
+    }
+    assert!(generations[1].results.score() < Some(0.5));
+}
+
+/// A candidate which is as strong as the current weights is only accepted with a threshold of at most `0.5`.
+#[test]
+fn threshold() {
+    let run = |threshold| {
+        let training = Training::new(
+            SelfPlay::new(vec![true, false], starts(), outcome),
+            Tournament::new([true, false], starts(), outcome),
+        )
+        .with_threshold(threshold);
+        let weights = SharedWeights::new(vec![10.0]);
+        training.run(
+            &weights,
+            1,
+            |player, weights| Bot::new(player).with_max_depth(weights[0] as u32),
+            Depth(u32::MAX),
+            |weights, _| weights.to_vec(),
+        )[0]
+        .accepted
+    };
+    assert!(run(0.5));
+    assert!(!run(0.55));
+}
@@ -0,0 +1,106 @@
+//! Tests for `tune::Texel` and `tune::Genetic`.
+use super::tournament::{outcome, Stones};
+use crate::rng::XorShiftRng;
+use crate::tournament::{Results, Tournament};
+use crate::tune::{Features, Genetic, Texel};
+use crate::{Bot, Depth};
+
+/// Uses the state itself as its features.
+struct Identity;
+
+impl Features<[f64; 3]> for Identity {
+    fn len(&self) -> usize {
+        3
+    }
+
+    fn features(&self, state: &[f64; 3], features: &mut Vec<f64>) {
+        features.extend_from_slice(state);
+    }
+}
+
+/// Returns random positions labeled with the outcome predicted by `weights`.
+fn positions(weights: &[f64; 3], amount: usize) -> Vec<([f64; 3], f64)> {
+    let mut rng = XorShiftRng::from_seed_u64(5);
+    (0..amount)
+        .map(|_| {
+            let state = [rng.next_f64() * 4.0 - 2.0, rng.next_f64() * 4.0 - 2.0, 1.0];
+            let eval: f64 = state.iter().zip(weights).map(|(s, w)| s * w).sum();
+            (state, 1.0 / (1.0 + (-eval).exp()))
+        })
+        .collect()
+}
+
+/// Tuning must recover the weights which were used to label the positions.
+#[test]
+fn recovers_weights() {
+    let expected = [1.5, -0.5, 0.25];
+    let texel = Texel::new(&Identity, &positions(&expected, 500));
+    assert_eq!(texel.len(), 500);
+
+    let tuned = texel.run(&[0.0; 3], 2000);
+    for (tuned, expected) in tuned.iter().zip(&expected) {
+        assert!((tuned - expected).abs() < 0.01, "tuned: {:?}", tuned);
+    }
+    assert!(texel.error(&tuned) <= texel.error(&expected) + 1e-9);
+}
+
+/// The error must never increase with a small enough learning rate.
+#[test]
+fn error_decreases() {
+    let texel = Texel::new(&Identity, &positions(&[3.0, 1.0, -1.0], 200)).with_scale(0.5);
+    let mut weights = vec![0.0; 3];
+    let mut error = texel.error(&weights);
+    for i in 0..2000 {
+        texel.step(&mut weights);
+        let now = texel.error(&weights);
+        assert!(now <= error, "iteration: {}", i);
+        error = now;
+    }
+    // The scale halves the evaluation, so the weights must be doubled.
+    assert!((weights[0] - 6.0).abs() < 0.1, "weights: {:?}", weights);
+}
+
+/// Extreme evaluations must not result in an infinite or `NaN` error.
+#[test]
+fn extreme() {
+    let texel = Texel::new(
+        &Identity,
+        &[([1.0, 0.0, 0.0], 1.0), ([-1.0, 0.0, 0.0], 1.0)],
+    );
+    let error = texel.error(&[1000.0, 0.0, 0.0]);
+    assert!(error.is_finite());
+    assert!((error - 500.0).abs() < 1e-6, "error: {}", error);
+}
+
+fn max_depth(params: &[f64]) -> u32 {
+    params[0].round() as u32
+}
+
+/// Plays with bots searching `candidate` and `opponent` plies deep.
+fn play(candidate: &[f64], opponent: &[f64]) -> Results {
+    let starts = (5..=12)
+        .map(|stones| Stones {
+            stones,
+            first: true,
+        })
+        .collect();
+    Tournament::new([true, false], starts, outcome).run(
+        |player| Bot::new(player).with_max_depth(max_depth(candidate)),
+        |player| Bot::new(player).with_max_depth(max_depth(opponent)),
+        Depth(u32::MAX),
+    )
+}
+
+/// Deeper searches never lose against shallower ones, so the tuned depth must play as well as a complete search.
+#[test]
+fn versus() {
+    assert!(play(&[0.0], &[12.0]).score() < Some(0.5));
+
+    let mut genetic = Genetic::new(&[0.0])
+        .with_bounds(&[(0.0, 12.0)])
+        .with_population(8)
+        .with_seed(11);
+    let best = genetic.run_versus(10, play);
+    assert_eq!(play(&best, &[12.0]).score(), Some(0.5), "best: {:?}", best);
+    assert_eq!(genetic.population().len(), 8);
+}
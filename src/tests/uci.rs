@@ -0,0 +1,254 @@
+//! Tests for `protocol::uci::Engine`.
+use super::*;
+use crate::protocol::uci::{Codec, Engine};
+use crate::Game;
+
+use std::io::{self, BufReader, Read, Write};
+use std::sync::{Arc, Condvar, Mutex};
+
+/// Actions are the index of the chosen child, positions are the comma separated bytes of `Node::from_bytes`.
+struct TreeCodec;
+
+impl Codec<Node> for TreeCodec {
+    fn start_position(&self) -> Node {
+        Node::from_bytes(&[3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9, 3, 2, 3, 8, 4])
+    }
+
+    fn parse_position(&self, position: &str) -> Option<Node> {
+        let bytes: Result<Vec<u8>, _> = position.split(',').map(str::parse).collect();
+        bytes.ok().map(|bytes| Node::from_bytes(&bytes))
+    }
+
+    fn active_player(&self, state: &Node) -> bool {
+        state.actions(true).0
+    }
+
+    fn is_white(&self, player: bool) -> bool {
+        player
+    }
+
+    fn parse_action(&self, state: &Node, action: &str) -> Option<usize> {
+        action
+            .parse()
+            .ok()
+            .filter(|&action| state.actions(true).1.contains(&action))
+    }
+
+    fn format_action(&self, _: &Node, action: &usize) -> String {
+        action.to_string()
+    }
+
+    fn format_score(&self, fitness: i8) -> String {
+        format!("cp {}", fitness)
+    }
+}
+
+/// Writes all output to a shared buffer, notifying `Script` after each write.
+#[derive(Clone, Default)]
+struct Output(Arc<(Mutex<Vec<u8>>, Condvar)>);
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let (output, written) = &*self.0;
+        output.lock().unwrap().extend_from_slice(buf);
+        written.notify_all();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The lines of a script, where `quit` is only read once each previous `go` sent its `bestmove`,
+/// as `quit` stops the current search.
+struct Script {
+    lines: std::vec::IntoIter<String>,
+    searches: usize,
+    output: Output,
+}
+
+impl Read for Script {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let line = match self.lines.next() {
+            Some(line) => line,
+            None => return Ok(0),
+        };
+        if line.starts_with("go") {
+            self.searches += 1;
+        } else if line == "quit" {
+            let (output, written) = &*self.output.0;
+            let mut output = output.lock().unwrap();
+            while String::from_utf8_lossy(&output).matches("bestmove").count() < self.searches {
+                output = written.wait(output).unwrap();
+            }
+        }
+        let line = format!("{}\n", line);
+        assert!(line.len() <= buf.len());
+        buf[..line.len()].copy_from_slice(line.as_bytes());
+        Ok(line.len())
+    }
+}
+
+fn run(script: &[&str]) -> (Engine<Node, TreeCodec>, Vec<String>) {
+    run_with(TreeCodec, script)
+}
+
+fn run_with<T: Game, C: Codec<T>>(codec: C, script: &[&str]) -> (Engine<T, C>, Vec<String>)
+where
+    T::Player: Default,
+{
+    let mut engine = Engine::new(codec, Bot::new(T::Player::default()));
+    let output = Output::default();
+    let input = Script {
+        lines: script
+            .iter()
+            .map(|&line| line.to_string())
+            .collect::<Vec<_>>()
+            .into_iter(),
+        searches: 0,
+        output: output.clone(),
+    };
+    engine.run(BufReader::new(input), output.clone()).unwrap();
+    let output = String::from_utf8(output.0 .0.lock().unwrap().clone()).unwrap();
+    (engine, output.lines().map(String::from).collect())
+}
+
+/// `go depth` sends one `info` line for each ply, followed by the action selected by `Bot::select`.
+#[test]
+fn depth() {
+    for depth in 1..5 {
+        let go = format!("go depth {}", depth);
+        let (engine, output) = run(&["position startpos", &go, "quit"]);
+        let expected = Bot::new(true).select(engine.state(), Depth(depth - 1));
+        assert_eq!(
+            output.last(),
+            Some(&format!("bestmove {}", expected.unwrap())),
+            "depth: {}",
+            depth
+        );
+
+        let info: Vec<_> = output
+            .iter()
+            .filter(|line| line.starts_with("info "))
+            .collect();
+        // The first iteration already searches two plies.
+        assert!(info.len() < depth as usize, "depth: {}", depth);
+        for (i, line) in info.iter().enumerate() {
+            assert!(line.starts_with(&format!("info depth {} score cp ", i + 2)));
+        }
+    }
+}
+
+/// Positions are built from `startpos` or `fen` followed by the given moves, invalid positions are ignored.
+#[test]
+fn position() {
+    let mut expected = TreeCodec.start_position();
+    expected.execute(&1, true);
+    let (engine, _) = run(&[
+        "position startpos moves 1",
+        "position startpos moves 1 7",
+        "quit",
+    ]);
+    assert_eq!(engine.state(), &expected);
+
+    let expected = TreeCodec.parse_position("2,7,1,8,2,8").unwrap();
+    let (engine, output) = run(&[
+        "position fen 2,7,1,8,2,8",
+        "position fen invalid",
+        "go depth 1",
+        "quit",
+    ]);
+    assert_eq!(engine.state(), &expected);
+    assert!(output.last().unwrap().starts_with("bestmove "));
+}
+
+/// `stop` interrupts an infinite search, and commands received during the search are processed afterwards.
+#[test]
+fn stop() {
+    let (_, output) = run(&["uci", "go infinite", "isready", "stop", "quit"]);
+    assert_eq!(&output[..3], &["id name rubot", "id author ", "uciok"]);
+    let bestmove = output.iter().position(|line| line.starts_with("bestmove "));
+    let readyok = output.iter().position(|line| line == "readyok");
+    assert!(bestmove.unwrap() < readyok.unwrap(), "output: {:?}", output);
+}
+
+/// A game which never ends, where white is active after an even amount of plies.
+#[derive(Clone)]
+struct Endless(u32);
+
+impl Game for Endless {
+    type Player = bool;
+    type Action = u32;
+    type Fitness = i8;
+    type Actions = std::ops::Range<u32>;
+
+    fn actions(&self, player: bool) -> (bool, Self::Actions) {
+        (player == (self.0 & 1 == 0), 0..2)
+    }
+
+    fn execute(&mut self, &action: &u32, _: bool) -> i8 {
+        self.0 += 1;
+        action as i8
+    }
+}
+
+struct EndlessCodec;
+
+impl Codec<Endless> for EndlessCodec {
+    fn start_position(&self) -> Endless {
+        Endless(0)
+    }
+
+    fn parse_position(&self, _: &str) -> Option<Endless> {
+        None
+    }
+
+    fn active_player(&self, state: &Endless) -> bool {
+        state.0 & 1 == 0
+    }
+
+    fn is_white(&self, player: bool) -> bool {
+        player
+    }
+
+    fn parse_action(&self, _: &Endless, action: &str) -> Option<u32> {
+        action.parse().ok()
+    }
+
+    fn format_action(&self, _: &Endless, action: &u32) -> String {
+        action.to_string()
+    }
+
+    fn format_score(&self, fitness: i8) -> String {
+        format!("cp {}", fitness)
+    }
+}
+
+/// The clock of a timed game limits searches which would otherwise never end,
+/// using the time of the active player.
+#[test]
+fn clock() {
+    use std::time::{Duration, Instant};
+
+    for &(go, moves) in &[
+        ("go wtime 1000 btime 1000", ""),
+        ("go wtime 1000 btime 100000 winc 10 binc 10 movestogo 5", ""),
+        ("go wtime 100000 btime 1000", "1"),
+    ] {
+        let start = Instant::now();
+        let position = format!("position startpos moves {}", moves);
+        let (_, output) = run_with(EndlessCodec, &[&position, go, "quit"]);
+        assert!(
+            start.elapsed() < Duration::from_millis(750),
+            "go: {}, elapsed: {:?}",
+            go,
+            start.elapsed()
+        );
+        assert!(
+            output.last().unwrap().starts_with("bestmove "),
+            "go: {}",
+            go
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! A runner for matches between two bots, used to compare different configurations.
+use crate::{Game, GameBot, IntoRunCondition};
+
+/// The result of a single game from the perspective of a player.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    /// The player won.
+    Win,
+    /// Neither player won.
+    Draw,
+    /// The opponent won.
+    Loss,
+}
+
+/// The combined results of all games of a [`Tournament`][tournament], from the perspective of the first bot.
+///
+/// [tournament]: struct.Tournament.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Results {
+    /// The amount of games won by the first bot.
+    pub wins: u32,
+    /// The amount of games won by neither bot.
+    pub draws: u32,
+    /// The amount of games won by the second bot.
+    pub losses: u32,
+}
+
+impl Results {
+    /// Adds a single game to these results.
+    pub fn add(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Win => self.wins += 1,
+            Outcome::Draw => self.draws += 1,
+            Outcome::Loss => self.losses += 1,
+        }
+    }
+
+    /// Returns the amount of games.
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Returns the average score of the first bot, where a win counts as `1` and a draw as `0.5`,
+    /// or `None` if no games were played.
+    pub fn score(&self) -> Option<f64> {
+        match self.games() {
+            0 => None,
+            games => Some((f64::from(self.wins) + f64::from(self.draws) / 2.0) / f64::from(games)),
+        }
+    }
+
+    /// Estimates the Elo difference between the first and the second bot together with its error margin,
+    /// or returns `None` if no games were played.
+    ///
+    /// The error margin is the half width of the 95% confidence interval of the average score. In case one bot
+    /// won all games or the interval touches a score of `0` or `1`, the difference or the margin is infinite.
+    ///
+    /// ```rust
+    /// use rubot::tournament::Results;
+    ///
+    /// let results = Results { wins: 60, draws: 20, losses: 20 };
+    /// let elo = results.elo().unwrap();
+    /// assert_eq!(elo.difference.round(), 147.0);
+    /// assert!(elo.error > 50.0 && elo.error < 100.0);
+    /// // The improvement is significant if the difference is larger than the error.
+    /// assert!(elo.difference > elo.error);
+    /// ```
+    pub fn elo(&self) -> Option<Elo> {
+        let score = self.score()?;
+        let games = f64::from(self.games());
+        let deviation = |result: f64, count: u32| f64::from(count) * (result - score).powi(2);
+        let variance =
+            (deviation(1.0, self.wins) + deviation(0.5, self.draws) + deviation(0.0, self.losses))
+                / games;
+        let margin = CONFIDENCE * (variance / games).sqrt();
+        let (low, high) = (elo(score - margin), elo(score + margin));
+        let error = if score <= 0.0 || score >= 1.0 {
+            // Both bounds are the same infinity, so their difference would be `NaN`.
+            f64::INFINITY
+        } else {
+            (high - low) / 2.0
+        };
+        Some(Elo {
+            difference: elo(score),
+            error,
+        })
+    }
+}
+
+/// The z-score of a two-sided 95% confidence interval.
+const CONFIDENCE: f64 = 1.959_964;
+
+/// Returns the Elo difference for a given average score, which is infinite for scores outside of `(0, 1)`.
+fn elo(score: f64) -> f64 {
+    if score <= 0.0 {
+        f64::NEG_INFINITY
+    } else if score >= 1.0 {
+        f64::INFINITY
+    } else {
+        -400.0 * (1.0 / score - 1.0).log10()
+    }
+}
+
+/// An estimated Elo difference, returned by [`Results::elo`][elo].
+///
+/// [elo]: struct.Results.html#method.elo
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Elo {
+    /// The estimated difference, which is positive if the first bot is stronger.
+    pub difference: f64,
+    /// The error margin of the 95% confidence interval, which contains the true difference
+    /// between `difference - error` and `difference + error` in most cases.
+    pub error: f64,
+}
+
+/// Plays games between two bots, alternating the player controlled by each bot.
+///
+/// Each start state is played twice in a row, once with the first bot controlling the first player
+/// and once with the second bot controlling it, until the given amount of games is reached.
+/// A game ends once no player has a possible action, after trying to [`pass`][pass] for each player, or once
+/// the maximum amount of plies is reached. The final state is then scored by a user provided function.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{Bot, Depth, Game, random, tournament::{Outcome, Tournament}};
+///
+/// /// Both players take one to three stones, the player who takes the last stone wins.
+/// #[derive(Clone)]
+/// struct Stones { stones: u8, first: bool }
+///
+/// impl Game for Stones {
+///     type Player = bool;
+///     type Action = u8;
+///     type Fitness = i8;
+///     type Actions = Vec<u8>;
+///
+///     fn actions(&self, player: bool) -> (bool, Vec<u8>) {
+///         (player == self.first, (1..=self.stones.min(3)).collect())
+///     }
+///
+///     fn execute(&mut self, &take: &u8, player: bool) -> i8 {
+///         let mover = self.first;
+///         self.stones -= take;
+///         self.first = !self.first;
+///         match self.stones {
+///             0 if mover == player => 1,
+///             0 => -1,
+///             _ => 0,
+///         }
+///     }
+/// }
+///
+/// fn outcome(state: &Stones, player: bool) -> Outcome {
+///     // The player who is active after the last stone was taken lost.
+///     if state.first == player { Outcome::Loss } else { Outcome::Win }
+/// }
+///
+/// let start = Stones { stones: 10, first: true };
+/// let results = Tournament::new([true, false], vec![start], outcome)
+///     .with_games(20)
+///     .run(|player| Bot::new(player), |player| random::Bot::new(player), Depth(10));
+/// assert_eq!(results.games(), 20);
+/// // The first player wins with perfect play, so the random bot wins at most half of the games.
+/// assert!(results.losses <= 10);
+/// ```
+///
+/// [pass]: ../trait.Game.html#method.pass
+pub struct Tournament<T: Game> {
+    players: [T::Player; 2],
+    starts: Vec<T>,
+    outcome: fn(&T, T::Player) -> Outcome,
+    games: u32,
+    max_plies: u32,
+}
+
+impl<T: Game> Tournament<T> {
+    /// Creates a new tournament for a game with the two given players, where `outcome` returns the result
+    /// of a final state for the given player.
+    ///
+    /// By default, each start state is played once with both colors and games end after `1000` plies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `starts` is empty.
+    pub fn new(
+        players: [T::Player; 2],
+        starts: Vec<T>,
+        outcome: fn(&T, T::Player) -> Outcome,
+    ) -> Self {
+        assert!(!starts.is_empty(), "at least one start state is required");
+        Self {
+            players,
+            games: 2 * starts.len() as u32,
+            starts,
+            outcome,
+            max_plies: 1000,
+        }
+    }
+
+    /// Sets the total amount of games.
+    pub fn with_games(mut self, games: u32) -> Self {
+        self.games = games;
+        self
+    }
+
+    /// Sets the maximum amount of plies of each game, after which the current state is scored using `outcome`.
+    pub fn with_max_plies(mut self, max_plies: u32) -> Self {
+        self.max_plies = max_plies;
+        self
+    }
+
+    /// Plays all games, where `first` and `second` create a new bot for the given player at the start of each game
+    /// and `condition` is used for each action of both bots.
+    pub fn run<A, B, U>(
+        &self,
+        mut first: impl FnMut(T::Player) -> A,
+        mut second: impl FnMut(T::Player) -> B,
+        condition: U,
+    ) -> Results
+    where
+        A: GameBot<T>,
+        B: GameBot<T>,
+        U: IntoRunCondition + Clone,
+    {
+        let mut results = Results::default();
+        for game in 0..self.games {
+            let start = &self.starts[(game / 2) as usize % self.starts.len()];
+            let [player, opponent] = if game % 2 == 0 {
+                self.players
+            } else {
+                [self.players[1], self.players[0]]
+            };
+            let mut bots = (first(player), second(opponent));
+            let end = self.play(start, [player, opponent], &mut bots, &condition);
+            results.add((self.outcome)(&end, player));
+        }
+        results
+    }
+
+    /// Plays a single game, where `players[0]` is controlled by `bots.0`, and returns the final state.
+    fn play<A, B, U>(
+        &self,
+        start: &T,
+        players: [T::Player; 2],
+        bots: &mut (A, B),
+        condition: &U,
+    ) -> T
+    where
+        A: GameBot<T>,
+        B: GameBot<T>,
+        U: IntoRunCondition + Clone,
+    {
+        let mut state = start.clone();
+        'plies: for _ in 0..self.max_plies {
+            for (i, &player) in players.iter().enumerate() {
+                let (active, actions) = state.actions(player);
+                if active && actions.into_iter().next().is_some() {
+                    let action = match i {
+                        0 => bots.0.select(&state, condition.clone()),
+                        _ => bots.1.select(&state, condition.clone()),
+                    };
+                    match action {
+                        Some(action) => {
+                            state.execute(&action, player);
+                            continue 'plies;
+                        }
+                        // The bot gave up, so the game is scored as it is.
+                        None => break 'plies,
+                    }
+                }
+            }
+
+            if !players.iter().any(|&player| state.pass(player)) {
+                break;
+            }
+        }
+        state
+    }
+}
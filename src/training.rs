@@ -0,0 +1,166 @@
+//! A reinforcement learning loop, which improves the weights of a bot using self-play and gating matches.
+use crate::selfplay::{Sample, SelfPlay};
+use crate::tournament::{Results, Tournament};
+use crate::tune::SharedWeights;
+use crate::{Game, GameBot, IntoRunCondition};
+
+/// The result of a single generation of [`Training::run`][run].
+///
+/// [run]: struct.Training.html#method.run
+#[derive(Clone, Debug, PartialEq)]
+pub struct Generation {
+    /// The weights returned by the training callback.
+    pub candidate: Vec<f64>,
+    /// The amount of self-play samples the candidate was trained on.
+    pub samples: usize,
+    /// The results of the gating match, from the perspective of the candidate.
+    pub results: Results,
+    /// Whether the candidate replaced the previous weights.
+    pub accepted: bool,
+}
+
+/// Repeatedly generates training data using [`SelfPlay`][selfplay], trains new weights using
+/// a user provided callback and only keeps them if they win a gating [`Tournament`][tournament]
+/// against the current weights.
+///
+/// The weights are stored as [`SharedWeights`][shared], so evaluation functions using
+/// [`Linear::with_shared_weights`][linear] pick up accepted weights without being recreated.
+/// The actual training, for example of a neural network, happens in the callback, which
+/// can also export the samples of each generation.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::{Bot, Depth, Game, selfplay::SelfPlay, tournament::{Outcome, Tournament}};
+/// use rubot::{training::Training, tune::SharedWeights};
+///
+/// /// Both players take one to three stones, the player who takes the last stone wins.
+/// #[derive(Clone)]
+/// struct Stones { stones: u8, first: bool }
+///
+/// impl Game for Stones {
+///     type Player = bool;
+///     type Action = u8;
+///     type Fitness = i8;
+///     type Actions = Vec<u8>;
+///
+///     fn actions(&self, player: bool) -> (bool, Vec<u8>) {
+///         (player == self.first, (1..=self.stones.min(3)).collect())
+///     }
+///
+///     fn execute(&mut self, &take: &u8, player: bool) -> i8 {
+///         let mover = self.first;
+///         self.stones -= take;
+///         self.first = !self.first;
+///         match self.stones {
+///             0 if mover == player => 1,
+///             0 => -1,
+///             _ => 0,
+///         }
+///     }
+/// }
+///
+/// fn outcome(state: &Stones, player: bool) -> Outcome {
+///     if state.first == player { Outcome::Loss } else { Outcome::Win }
+/// }
+///
+/// let starts: Vec<_> = (5..=10).map(|stones| Stones { stones, first: true }).collect();
+/// let training = Training::new(
+///     SelfPlay::new(vec![true, false], starts.clone(), outcome),
+///     Tournament::new([true, false], starts, outcome),
+/// );
+///
+/// // The only weight is the search depth of the bot, which "training" increases.
+/// let weights = SharedWeights::new(vec![0.0]);
+/// let generations = training.run(
+///     &weights,
+///     2,
+///     |player, weights| Bot::new(player).with_max_depth(weights[0] as u32),
+///     Depth(u32::MAX),
+///     |weights, _samples| vec![weights[0] + 4.0],
+/// );
+///
+/// assert!(generations[0].accepted);
+/// // Searching four plies already results in perfect play, so searching deeper is not better.
+/// assert!(!generations[1].accepted);
+/// assert_eq!(&*weights.load(), &[4.0]);
+/// ```
+///
+/// [selfplay]: ../selfplay/struct.SelfPlay.html
+/// [tournament]: ../tournament/struct.Tournament.html
+/// [shared]: ../tune/struct.SharedWeights.html
+/// [linear]: ../tune/struct.Linear.html#method.with_shared_weights
+pub struct Training<T: Game> {
+    selfplay: SelfPlay<T>,
+    gating: Tournament<T>,
+    threshold: f64,
+}
+
+impl<T: Game> Training<T> {
+    /// Creates a new training loop, which generates samples using `selfplay` and plays the
+    /// candidate against the current weights using `gating`.
+    ///
+    /// By default, a candidate is accepted if its average score in the gating match is at least `0.55`.
+    pub fn new(selfplay: SelfPlay<T>, gating: Tournament<T>) -> Self {
+        Self {
+            selfplay,
+            gating,
+            threshold: 0.55,
+        }
+    }
+
+    /// Sets the minimum average score of the candidate in the gating match for it to be accepted.
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Runs `generations` generations and returns their results.
+    ///
+    /// Each generation plays all self-play games using bots created by `bot` with the current `weights`,
+    /// calls `train` with the current weights and all samples of these games and then plays the gating match
+    /// between bots using the returned candidate and the current weights. `condition` is used for each action
+    /// of both self-play and gating games. If the candidate reaches the threshold, it is stored in `weights`.
+    pub fn run<B, U>(
+        &self,
+        weights: &SharedWeights,
+        generations: u32,
+        bot: impl Fn(T::Player, &[f64]) -> B,
+        condition: U,
+        mut train: impl FnMut(&[f64], &[Sample<T>]) -> Vec<f64>,
+    ) -> Vec<Generation>
+    where
+        B: GameBot<T>,
+        U: IntoRunCondition + Clone,
+    {
+        let mut results = Vec::with_capacity(generations as usize);
+        let mut samples = Vec::new();
+        for _ in 0..generations {
+            let current = weights.load();
+            samples.clear();
+            self.selfplay.run(
+                |player| bot(player, &current),
+                condition.clone(),
+                |game| samples.extend_from_slice(game),
+            );
+
+            let candidate = train(&current, &samples);
+            let gating = self.gating.run(
+                |player| bot(player, &candidate),
+                |player| bot(player, &current),
+                condition.clone(),
+            );
+            let accepted = gating.score().is_some_and(|score| score >= self.threshold);
+            if accepted {
+                weights.store(candidate.clone());
+            }
+            results.push(Generation {
+                candidate,
+                samples: samples.len(),
+                results: gating,
+                accepted,
+            });
+        }
+        results
+    }
+}
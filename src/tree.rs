@@ -1,9 +1,9 @@
 //! A tree implementation used in examples and tests.
 
+use crate::rng::XorShiftRng;
 use crate::Game;
 use std::convert::TryInto;
 use std::fmt::Debug;
-use std::num::Wrapping;
 use std::ops::Range;
 
 /// A tree node, implements [`Game`][game].
@@ -26,7 +26,7 @@ use std::ops::Range;
 /// assert_eq!(bot.select(&tree, ToCompletion), Some(0));
 /// ```
 /// [game]: ../trait.Game.html
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Node {
     player: bool,
     // always from the perspective of the tested player
@@ -86,37 +86,7 @@ impl Node {
     pub fn from_bytes(bytes: &[u8]) -> Self {
         match bytes[0..4].try_into() {
             Ok(seed) => {
-                let mut seed = u32::from_be_bytes(seed);
-                if seed == 0 {
-                    seed = 0xBAD_5EED;
-                }
-
-                struct XorShiftRng {
-                    x: Wrapping<u32>,
-                    y: Wrapping<u32>,
-                    z: Wrapping<u32>,
-                    w: Wrapping<u32>,
-                }
-
-                impl XorShiftRng {
-                    fn next_u32(&mut self) -> u32 {
-                        let x = self.x;
-                        let t = x ^ (x << 11);
-                        self.x = self.y;
-                        self.y = self.z;
-                        self.z = self.w;
-                        let w = self.w;
-                        self.w = w ^ (w >> 19) ^ (t ^ (t >> 8));
-                        self.w.0
-                    }
-                }
-
-                let mut rng = XorShiftRng {
-                    x: Wrapping(seed),
-                    y: Wrapping(seed),
-                    z: Wrapping(seed),
-                    w: Wrapping(seed),
-                };
+                let mut rng = XorShiftRng::from_seed(u32::from_be_bytes(seed));
 
                 let mut root = Node::new(true, 0);
                 for &i in bytes[4..].iter() {
@@ -124,15 +94,15 @@ impl Node {
                     let mut next =
                         rng.next_u32() as usize % (pos.as_ref().unwrap().children.len() + 1);
                     while next != pos.as_ref().unwrap().children.len() {
-                        pos.take().map(|node| {
+                        if let Some(node) = pos.take() {
                             pos = Some(&mut node.children[next]);
-                        });
+                        }
                         next = rng.next_u32() as usize % (pos.as_ref().unwrap().children.len() + 1);
                     }
 
                     pos.unwrap()
                         .children
-                        .push(Node::new(rng.next_u32() % 2 == 0, i as i8));
+                        .push(Node::new(rng.next_u32() & 1 == 0, i as i8));
                 }
 
                 root
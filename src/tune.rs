@@ -0,0 +1,573 @@
+//! Tools to tune the parameters of bots and evaluation functions.
+//!
+//! The optimizers in this module treat the parameters as a slice of `f64` and only require a
+//! function which returns how good a given set of parameters is. This function is allowed to be noisy,
+//! for example by playing a few games using bots with the given parameters.
+use crate::rng::XorShiftRng;
+use crate::tournament::Results;
+
+use std::cmp::Ordering;
+use std::mem;
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// A set of parameters together with the fitness they had when they were last evaluated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    /// The parameters of this candidate.
+    pub params: Vec<f64>,
+    /// The result of the fitness function for `params`, higher is better.
+    pub fitness: f64,
+}
+
+/// A population based optimizer using evolution strategies.
+///
+/// Each generation, all candidates are evaluated. The best candidates are kept without changes,
+/// while the rest of the population is replaced by children of good candidates. Children are created using
+/// uniform crossover followed by a gaussian mutation of each parameter.
+///
+/// As all candidates, including the unchanged ones, are reevaluated each generation,
+/// this also works with noisy fitness functions.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::tune::Genetic;
+///
+/// let mut genetic = Genetic::new(&[0.0, 0.0])
+///     .with_bounds(&[(-10.0, 10.0), (-10.0, 10.0)])
+///     .with_seed(7);
+///
+/// // The best parameters are `[3.0, -1.0]`.
+/// let best = genetic.run(100, |p| -(p[0] - 3.0).powi(2) - (p[1] + 1.0).powi(2));
+/// assert!((best[0] - 3.0).abs() < 0.2);
+/// assert!((best[1] + 1.0).abs() < 0.2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Genetic {
+    population: Vec<Candidate>,
+    bounds: Option<Vec<(f64, f64)>>,
+    population_size: usize,
+    elite: usize,
+    mutation: f64,
+    rng: XorShiftRng,
+}
+
+impl Genetic {
+    /// Creates a new optimizer starting at `initial`.
+    ///
+    /// The first generation consists of mutations of `initial`, while keeping `initial` itself.
+    pub fn new(initial: &[f64]) -> Self {
+        Self {
+            population: vec![Candidate {
+                params: initial.to_vec(),
+                fitness: f64::NEG_INFINITY,
+            }],
+            bounds: None,
+            population_size: 16,
+            elite: 2,
+            mutation: 0.1,
+            rng: XorShiftRng::from_seed_u64(0),
+        }
+    }
+
+    /// Restricts each parameter to the inclusive range `(min, max)` at the same index.
+    ///
+    /// When bounds are given, the mutation strength is relative to the size of each range.
+    pub fn with_bounds(mut self, bounds: &[(f64, f64)]) -> Self {
+        assert_eq!(
+            bounds.len(),
+            self.population[0].params.len(),
+            "expected one bound for each parameter"
+        );
+        self.bounds = Some(bounds.to_vec());
+        for candidate in self.population.iter_mut() {
+            clamp(&mut candidate.params, bounds);
+        }
+        self
+    }
+
+    /// Sets the amount of candidates per generation, the default is `16`.
+    pub fn with_population(mut self, size: usize) -> Self {
+        assert!(size > 0, "the population must not be empty");
+        self.population_size = size;
+        self
+    }
+
+    /// Sets the amount of best candidates which are kept unchanged in the next generation, the default is `2`.
+    pub fn with_elite(mut self, elite: usize) -> Self {
+        self.elite = elite;
+        self
+    }
+
+    /// Sets the standard deviation used when mutating parameters, the default is `0.1`.
+    ///
+    /// If bounds were given, this is multiplied by the size of the range of each parameter.
+    pub fn with_mutation(mut self, mutation: f64) -> Self {
+        self.mutation = mutation;
+        self
+    }
+
+    /// Sets the seed of the random number generator, making the optimization reproducible.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = XorShiftRng::from_seed_u64(seed);
+        self
+    }
+
+    /// Returns the candidates of the current generation.
+    ///
+    /// After a call to [`generation`][gen] these are sorted by their fitness in descending order.
+    ///
+    /// [gen]: struct.Genetic.html#method.generation
+    pub fn population(&self) -> &[Candidate] {
+        &self.population
+    }
+
+    /// Evaluates the next generation using `fitness`, returning its best candidate.
+    pub fn generation<F: FnMut(&[f64]) -> f64>(&mut self, mut fitness: F) -> &Candidate {
+        self.populate();
+        for candidate in self.population.iter_mut() {
+            candidate.fitness = fitness(&candidate.params);
+        }
+
+        self.population
+            .sort_by(|a, b| b.fitness.partial_cmp(&a.fitness).unwrap_or(Ordering::Equal));
+        &self.population[0]
+    }
+
+    /// Runs `generations` generations and returns the parameters of the best candidate of the last one.
+    pub fn run<F: FnMut(&[f64]) -> f64>(&mut self, generations: u32, mut fitness: F) -> Vec<f64> {
+        for _ in 0..generations {
+            self.generation(&mut fitness);
+        }
+        self.population[0].params.clone()
+    }
+
+    /// Evaluates the next generation by letting each candidate play against the best candidate
+    /// of the previous generation, returning its new best candidate.
+    ///
+    /// `play` is called with the parameters of a candidate and of its opponent and should return the results
+    /// from the perspective of the candidate, usually by running a [`Tournament`][tournament] between bots
+    /// using these parameters. The fitness of each candidate is its [`score`][score], where candidates
+    /// without any games have a score of `0.5`.
+    ///
+    /// Before the first generation, the opponent is the initial set of parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rubot::tune::Genetic;
+    /// use rubot::tournament::{Outcome, Results};
+    ///
+    /// /// Each parameter wins against all smaller parameters.
+    /// fn play(candidate: &[f64], opponent: &[f64]) -> Results {
+    ///     let mut results = Results::default();
+    ///     results.add(match candidate[0].partial_cmp(&opponent[0]).unwrap() {
+    ///         std::cmp::Ordering::Greater => Outcome::Win,
+    ///         std::cmp::Ordering::Equal => Outcome::Draw,
+    ///         std::cmp::Ordering::Less => Outcome::Loss,
+    ///     });
+    ///     results
+    /// }
+    ///
+    /// let mut genetic = Genetic::new(&[0.0]).with_bounds(&[(0.0, 1.0)]).with_seed(3);
+    /// let best = genetic.run_versus(20, play);
+    /// assert!(best[0] > 0.5);
+    /// ```
+    ///
+    /// [tournament]: ../tournament/struct.Tournament.html
+    /// [score]: ../tournament/struct.Results.html#method.score
+    pub fn generation_versus<F>(&mut self, mut play: F) -> &Candidate
+    where
+        F: FnMut(&[f64], &[f64]) -> Results,
+    {
+        let opponent = self.population[0].params.clone();
+        self.generation(|params| play(params, &opponent).score().unwrap_or(0.5))
+    }
+
+    /// Runs `generations` generations using [`generation_versus`][versus] and returns
+    /// the parameters of the best candidate of the last one.
+    ///
+    /// [versus]: struct.Genetic.html#method.generation_versus
+    pub fn run_versus<F>(&mut self, generations: u32, mut play: F) -> Vec<f64>
+    where
+        F: FnMut(&[f64], &[f64]) -> Results,
+    {
+        for _ in 0..generations {
+            self.generation_versus(&mut play);
+        }
+        self.population[0].params.clone()
+    }
+
+    /// Replaces all but the elite of the current population with new children.
+    fn populate(&mut self) {
+        // The population is sorted by fitness, except before the first generation,
+        // where it only contains the initial parameters.
+        let previous = mem::take(&mut self.population);
+        let elite = self.elite.min(previous.len()).min(self.population_size);
+        // Only the better half of the previous generation is used for reproduction.
+        let pool = &previous[..previous.len().div_ceil(2)];
+
+        let mut next = previous[..elite].to_vec();
+        while next.len() < self.population_size {
+            let a = &pool[self.rng.below(pool.len())];
+            let b = &pool[self.rng.below(pool.len())];
+            let mut params: Vec<f64> = a
+                .params
+                .iter()
+                .zip(b.params.iter())
+                .map(|(&a, &b)| if self.rng.next_u32() & 1 == 0 { a } else { b })
+                .collect();
+
+            for (i, param) in params.iter_mut().enumerate() {
+                let scale = self
+                    .bounds
+                    .as_ref()
+                    .map_or(1.0, |bounds| bounds[i].1 - bounds[i].0);
+                *param += self.rng.next_gaussian() * self.mutation * scale;
+            }
+
+            if let Some(ref bounds) = self.bounds {
+                clamp(&mut params, bounds);
+            }
+
+            next.push(Candidate {
+                params,
+                fitness: f64::NEG_INFINITY,
+            });
+        }
+
+        self.population = next;
+    }
+}
+
+fn clamp(params: &mut [f64], bounds: &[(f64, f64)]) {
+    for (param, &(min, max)) in params.iter_mut().zip(bounds) {
+        *param = param.max(min).min(max);
+    }
+}
+
+/// Maps game states to numeric feature vectors.
+///
+/// Features are used by [`Linear`][lin] to evaluate states, and are the input
+/// of tuners optimizing the weights of such an evaluation function.
+///
+/// [lin]: struct.Linear.html
+pub trait Features<T> {
+    /// The amount of features returned by [`features`][feat].
+    ///
+    /// [feat]: trait.Features.html#tymethod.features
+    fn len(&self) -> usize;
+
+    /// Returns `true` if there are no features.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends exactly [`len`][len] features of `state` to `features`.
+    ///
+    /// [len]: trait.Features.html#tymethod.len
+    fn features(&self, state: &T, features: &mut Vec<f64>);
+}
+
+/// A set of weights which can be atomically replaced while it is being used.
+///
+/// Cloning `SharedWeights` creates a new handle to the same weights, so replacing
+/// the weights using one handle updates all of its clones.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::tune::SharedWeights;
+///
+/// let weights = SharedWeights::new(vec![1.0, 2.0]);
+/// let handle = weights.clone();
+///
+/// std::thread::spawn(move || handle.store(vec![3.0, 4.0]))
+///     .join()
+///     .unwrap();
+///
+/// assert_eq!(&*weights.load(), &[3.0, 4.0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedWeights(Arc<RwLock<Arc<[f64]>>>);
+
+impl SharedWeights {
+    /// Creates new shared weights.
+    pub fn new(weights: Vec<f64>) -> Self {
+        SharedWeights(Arc::new(RwLock::new(weights.into())))
+    }
+
+    /// Returns the current weights.
+    ///
+    /// The returned weights do not change, even if new weights are stored in the meantime.
+    pub fn load(&self) -> Arc<[f64]> {
+        Arc::clone(&self.0.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Replaces the current weights of all handles.
+    pub fn store(&self, weights: Vec<f64>) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = weights.into();
+    }
+}
+
+/// A linear evaluation function over some [`Features`][feat].
+///
+/// The weights of a `Linear` are stored as [`SharedWeights`][shared], so they can be updated
+/// while bots are using clones of this evaluation function, for example to pick up newly tuned weights
+/// without restarting a long running server.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::tune::{Features, Linear};
+///
+/// struct Material;
+///
+/// /// counts pawns and queens of both players: `[my_pawns, my_queens, their_pawns, their_queens]`
+/// impl Features<[u32; 4]> for Material {
+///     fn len(&self) -> usize {
+///         4
+///     }
+///
+///     fn features(&self, state: &[u32; 4], features: &mut Vec<f64>) {
+///         features.extend(state.iter().map(|&count| f64::from(count)));
+///     }
+/// }
+///
+/// let eval = Linear::new(Material, vec![1.0, 9.0, -1.0, -9.0]);
+/// assert_eq!(eval.evaluate(&[8, 1, 7, 0]), 10.0);
+///
+/// // Update the weights of all clones of `eval`.
+/// eval.shared_weights().store(vec![1.0, 10.0, -1.0, -10.0]);
+/// assert_eq!(eval.evaluate(&[8, 1, 7, 0]), 11.0);
+/// ```
+///
+/// [feat]: trait.Features.html
+/// [shared]: struct.SharedWeights.html
+#[derive(Debug, Clone)]
+pub struct Linear<F> {
+    features: F,
+    weights: SharedWeights,
+}
+
+impl<F> Linear<F> {
+    /// Creates a new linear evaluation function with the given `weights`.
+    pub fn new<T>(features: F, weights: Vec<f64>) -> Self
+    where
+        F: Features<T>,
+    {
+        Self::with_shared_weights(features, SharedWeights::new(weights))
+    }
+
+    /// Creates a new linear evaluation function using already existing shared weights.
+    pub fn with_shared_weights<T>(features: F, weights: SharedWeights) -> Self
+    where
+        F: Features<T>,
+    {
+        assert_eq!(
+            features.len(),
+            weights.load().len(),
+            "expected one weight for each feature"
+        );
+        Self { features, weights }
+    }
+
+    /// Returns the wrapped features.
+    pub fn features(&self) -> &F {
+        &self.features
+    }
+
+    /// Returns the current weights.
+    pub fn weights(&self) -> Arc<[f64]> {
+        self.weights.load()
+    }
+
+    /// Returns a handle to the weights of this evaluation function.
+    pub fn shared_weights(&self) -> &SharedWeights {
+        &self.weights
+    }
+
+    /// Replaces the current weights, for example with the result of a tuner.
+    ///
+    /// This also changes the weights of all clones of `self`.
+    pub fn set_weights(&self, weights: &[f64]) {
+        assert_eq!(self.weights.load().len(), weights.len());
+        self.weights.store(weights.to_vec());
+    }
+
+    /// Returns the weighted sum of the features of `state`.
+    pub fn evaluate<T>(&self, state: &T) -> f64
+    where
+        F: Features<T>,
+    {
+        let weights = self.weights.load();
+        let mut features = Vec::with_capacity(weights.len());
+        self.features.features(state, &mut features);
+        debug_assert_eq!(features.len(), weights.len());
+        features
+            .iter()
+            .zip(weights.iter())
+            .map(|(f, w)| f * w)
+            .sum()
+    }
+}
+
+/// Tunes the weights of a [`Linear`][lin] evaluation function using labeled positions.
+///
+/// Each position is labeled with the final outcome of the game it was taken from, `1.0` for a win,
+/// `0.5` for a draw and `0.0` for a loss, always from the perspective of the player whose evaluation is tuned.
+/// The predicted outcome of a state is `sigmoid(scale * evaluation)`, and the weights are
+/// tuned using gradient descent on the mean cross entropy between the predictions and the labels,
+/// which is logistic regression over the features of each position.
+///
+/// The features of all positions are computed once by [`new`][new], so the states themselves
+/// do not have to be kept in memory while tuning.
+///
+/// # Examples
+///
+/// ```rust
+/// use rubot::tune::{Features, Linear, Texel};
+///
+/// /// The material difference between both players.
+/// struct Material;
+///
+/// impl Features<i32> for Material {
+///     fn len(&self) -> usize {
+///         1
+///     }
+///
+///     fn features(&self, &state: &i32, features: &mut Vec<f64>) {
+///         features.push(f64::from(state));
+///     }
+/// }
+///
+/// // Games where the bot was ahead were usually won.
+/// let positions = [(2, 1.0), (1, 1.0), (1, 0.5), (0, 0.5), (-1, 0.0), (-2, 0.5), (-3, 0.0)];
+/// let texel = Texel::new(&Material, &positions);
+///
+/// let eval = Linear::new(Material, vec![0.0]);
+/// let tuned = texel.run(&eval.weights(), 100);
+/// assert!(texel.error(&tuned) < texel.error(&eval.weights()));
+/// assert!(tuned[0] > 0.0);
+/// eval.set_weights(&tuned);
+/// ```
+///
+/// [lin]: struct.Linear.html
+/// [new]: #method.new
+#[derive(Debug, Clone)]
+pub struct Texel {
+    positions: Vec<(Vec<f64>, f64)>,
+    scale: f64,
+    learning_rate: f64,
+}
+
+impl Texel {
+    /// Computes the features of all `positions`, each of which is a state together with the outcome of its game.
+    pub fn new<T, F: Features<T>>(features: &F, positions: &[(T, f64)]) -> Self {
+        let positions = positions
+            .iter()
+            .map(|(state, outcome)| {
+                assert!(
+                    (0.0..=1.0).contains(outcome),
+                    "outcomes must be in `[0, 1]`"
+                );
+                let mut values = Vec::with_capacity(features.len());
+                features.features(state, &mut values);
+                debug_assert_eq!(values.len(), features.len());
+                (values, *outcome)
+            })
+            .collect();
+
+        Self {
+            positions,
+            scale: 1.0,
+            learning_rate: 1.0,
+        }
+    }
+
+    /// Sets the factor by which the evaluation is multiplied before computing the predicted outcome,
+    /// the default is `1.0`.
+    ///
+    /// Evaluations in large units, e.g. hundredths of a pawn, should use a small scale, so that
+    /// weights of a reasonable size are not always predicting a certain outcome.
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Sets the step size of gradient descent, the default is `1.0`.
+    pub fn with_learning_rate(mut self, learning_rate: f64) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Returns the amount of labeled positions.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns `true` if there are no labeled positions.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Returns the mean cross entropy between the predicted and the actual outcomes using `weights`, lower is better.
+    pub fn error(&self, weights: &[f64]) -> f64 {
+        let total: f64 = self
+            .positions
+            .iter()
+            .map(|(features, outcome)| {
+                // `ln(sigmoid(x)) = -ln(1 + e^-x)`, computed without overflowing for large `|x|`.
+                let x = self.scale * dot(features, weights);
+                let log_win = -softplus(-x);
+                let log_loss = -softplus(x);
+                -(outcome * log_win + (1.0 - outcome) * log_loss)
+            })
+            .sum();
+        total / self.positions.len().max(1) as f64
+    }
+
+    /// Executes a single step of gradient descent, updating `weights` in place.
+    pub fn step(&self, weights: &mut [f64]) {
+        let mut gradient = vec![0.0; weights.len()];
+        for (features, outcome) in self.positions.iter() {
+            let predicted = sigmoid(self.scale * dot(features, weights));
+            let delta = (predicted - outcome) * self.scale;
+            for (g, f) in gradient.iter_mut().zip(features) {
+                *g += delta * f;
+            }
+        }
+
+        let factor = self.learning_rate / self.positions.len().max(1) as f64;
+        for (weight, g) in weights.iter_mut().zip(gradient) {
+            *weight -= factor * g;
+        }
+    }
+
+    /// Runs `iterations` steps of gradient descent starting at `initial` and returns the tuned weights.
+    pub fn run(&self, initial: &[f64], iterations: u32) -> Vec<f64> {
+        let mut weights = initial.to_vec();
+        for _ in 0..iterations {
+            self.step(&mut weights);
+        }
+        weights
+    }
+}
+
+fn dot(features: &[f64], weights: &[f64]) -> f64 {
+    assert_eq!(
+        features.len(),
+        weights.len(),
+        "expected one weight for each feature"
+    );
+    features.iter().zip(weights).map(|(f, w)| f * w).sum()
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+/// `ln(1 + e^x)`
+fn softplus(x: f64) -> f64 {
+    x.max(0.0) + (-x.abs()).exp().ln_1p()
+}